@@ -2,4 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod metrics;
+pub mod postgres;
+pub mod slo;
 pub mod util;
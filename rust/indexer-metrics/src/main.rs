@@ -1,22 +1,37 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use anyhow::{ensure, Context, Result};
 use chrono::NaiveDateTime;
 use clap::Parser;
 use indexer_metrics::{
     metrics::{
+        BACKFILL_LATEST_VERSION, BACKFILL_PROGRESS_PERCENT,
         HASURA_API_LATEST_TRANSACTION_LATENCY_IN_SECS, HASURA_API_LATEST_TRANSACTION_TIMESTAMP,
         HASURA_API_LATEST_VERSION, HASURA_API_LATEST_VERSION_TIMESTAMP, PFN_LEDGER_TIMESTAMP,
-        PFN_LEDGER_VERSION, TASK_FAILURE_COUNT,
+        PFN_LEDGER_VERSION, PROCESSOR_LAG_VERSIONS, SLO_ALERT_FIRING, SLO_BURN_RATE,
+        SLO_WITHIN_TARGET, TASK_FAILURE_COUNT,
+    },
+    postgres::{load_backfill_status, load_processor_status},
+    slo::{AlertEvent, AlertStatus, ProcessorObservation, ProcessorSloConfig, SloTracker},
+    util::{
+        deserialize_from_string, fetch_processor_status_with_timeout, get_url_with_timeout,
+        post_url_with_timeout,
     },
-    util::{deserialize_from_string, fetch_processor_status_with_timeout, get_url_with_timeout},
 };
+use processor::utils::database::{new_db_pool, ArcDbPool};
 use serde::{Deserialize, Serialize};
 use server_framework::{RunnableConfig, ServerArgs};
-use tokio::time::Duration;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use tokio::time::{timeout, Duration};
 
 const QUERY_TIMEOUT_MS: u64 = 500;
+const ALERT_WEBHOOK_TIMEOUT_MS: u64 = 5000;
+const POSTGRES_QUERY_TIMEOUT_MS: u64 = 2000;
+const POSTGRES_POOL_SIZE: u32 = 2;
 const MIN_TIME_QUERIES_MS: u64 = 500;
 const MICROSECONDS_MULTIPLIER: f64 = 1_000_000.0;
 
@@ -50,8 +65,26 @@ struct ProcessorsResponse {
 #[serde(deny_unknown_fields)]
 pub struct PostProcessorConfig {
     pub hasura_graphql_endpoint: Option<String>,
+    /// Reads processor status from the processor database instead of Hasura, and exports the
+    /// progress of backfills.
+    #[serde(default)]
+    pub postgres_connection_string: Option<String>,
     pub fullnode_rest_api_endpoint: Option<String>,
     pub chain_name: String,
+    /// Freshness SLOs of the processors reported by `hasura_graphql_endpoint` or
+    /// `postgres_connection_string`.
+    #[serde(default)]
+    pub slos: Vec<ProcessorSloConfig>,
+    /// Receives a JSON POST when an SLO alert starts or stops firing.
+    #[serde(default)]
+    pub alert_webhook_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct AlertNotification<'a> {
+    chain_name: &'a str,
+    #[serde(flatten)]
+    event: &'a AlertEvent,
 }
 
 #[async_trait::async_trait]
@@ -61,15 +94,39 @@ impl RunnableConfig for PostProcessorConfig {
         let hasura_graphql_endpoint = self.hasura_graphql_endpoint.clone();
         let fullnode_rest_api_endpoint = self.fullnode_rest_api_endpoint.clone();
         let chain_name = self.chain_name.clone();
+        let slo_tracker = SloTracker::new(self.slos.clone())?;
+        ensure!(
+            !slo_tracker.requires_ledger_version() || fullnode_rest_api_endpoint.is_some(),
+            "SLOs with max_lag_versions require fullnode_rest_api_endpoint"
+        );
+        // 0 until the fullnode is reached
+        let latest_ledger_version = Arc::new(AtomicU64::new(0));
+
+        ensure!(
+            hasura_graphql_endpoint.is_none() || self.postgres_connection_string.is_none(),
+            "Only one of hasura_graphql_endpoint and postgres_connection_string can be set"
+        );
+        let recorder = ProcessorStatusRecorder {
+            chain_name: chain_name.clone(),
+            slo_tracker,
+            latest_ledger_version: latest_ledger_version.clone(),
+            alert_webhook_url: self.alert_webhook_url.clone(),
+        };
 
         if let Some(endpoint) = hasura_graphql_endpoint {
-            tasks.push(tokio::spawn(start_processor_status_fetch(
-                endpoint,
-                chain_name.clone(),
-            )));
+            tasks.push(tokio::spawn(start_processor_status_fetch(endpoint, recorder)));
+        } else if let Some(connection_string) = &self.postgres_connection_string {
+            let pool = new_db_pool(connection_string, Some(POSTGRES_POOL_SIZE))
+                .await
+                .context("Failed to create connection pool")?;
+            tasks.push(tokio::spawn(start_postgres_status_fetch(pool, recorder)));
         }
         if let Some(fullnode) = fullnode_rest_api_endpoint {
-            tasks.push(tokio::spawn(start_fn_fetch(fullnode, chain_name)));
+            tasks.push(tokio::spawn(start_fn_fetch(
+                fullnode,
+                chain_name,
+                latest_ledger_version,
+            )));
         }
 
         let _ = futures::future::join_all(tasks).await;
@@ -89,7 +146,7 @@ async fn main() -> Result<()> {
         .await
 }
 
-async fn start_fn_fetch(url: String, chain_name: String) {
+async fn start_fn_fetch(url: String, chain_name: String, latest_ledger_version: Arc<AtomicU64>) {
     loop {
         let result = get_url_with_timeout(&url, QUERY_TIMEOUT_MS).await;
         let time_now = tokio::time::Instant::now();
@@ -102,6 +159,7 @@ async fn start_fn_fetch(url: String, chain_name: String) {
                     PFN_LEDGER_VERSION
                         .with_label_values(&[&chain_name])
                         .set(resp.ledger_version as i64);
+                    latest_ledger_version.store(resp.ledger_version, Ordering::Relaxed);
                     PFN_LEDGER_TIMESTAMP
                         .with_label_values(&[&chain_name])
                         .set(resp.ledger_timestamp as f64 / MICROSECONDS_MULTIPLIER);
@@ -136,7 +194,7 @@ async fn start_fn_fetch(url: String, chain_name: String) {
     }
 }
 
-async fn start_processor_status_fetch(url: String, chain_name: String) {
+async fn start_processor_status_fetch(url: String, mut recorder: ProcessorStatusRecorder) {
     loop {
         let result = fetch_processor_status_with_timeout(&url, QUERY_TIMEOUT_MS).await;
         let time_now = tokio::time::Instant::now();
@@ -146,33 +204,7 @@ async fn start_processor_status_fetch(url: String, chain_name: String) {
             Ok(Ok(response)) => match response.json::<ProcessorsResponse>().await {
                 Ok(resp) => {
                     tracing::info!(url = &url, response = ?resp, "Request succeeded");
-                    // Process the data as needed
-                    let system_time_now = chrono::Utc::now().naive_utc();
-                    for processor in resp.data.processor_status {
-                        // If the last_transaction_timestamp is None, then the processor has not processed any transactions.
-                        // Skip.
-                        let last_transaction_timestamp = match processor.last_transaction_timestamp
-                        {
-                            Some(timestamp) => timestamp,
-                            None => continue,
-                        };
-                        HASURA_API_LATEST_VERSION
-                            .with_label_values(&[&processor.processor, &chain_name])
-                            .set(processor.last_success_version as i64);
-                        HASURA_API_LATEST_VERSION_TIMESTAMP
-                            .with_label_values(&[&processor.processor, &chain_name])
-                            .set(processor.last_updated.and_utc().timestamp_micros() as f64 * 1e-6);
-                        HASURA_API_LATEST_TRANSACTION_TIMESTAMP
-                            .with_label_values(&[&processor.processor, &chain_name])
-                            .set(
-                                last_transaction_timestamp.and_utc().timestamp_micros() as f64
-                                    * 1e-6,
-                            );
-                        let latency = system_time_now - last_transaction_timestamp;
-                        HASURA_API_LATEST_TRANSACTION_LATENCY_IN_SECS
-                            .with_label_values(&[&processor.processor, &chain_name])
-                            .set(latency.num_milliseconds() as f64 * 1e-3);
-                    }
+                    recorder.record(resp.data.processor_status);
                 },
                 Err(err) => {
                     tracing::error!(url = &url, error = ?err, "Parsing error");
@@ -199,6 +231,187 @@ async fn start_processor_status_fetch(url: String, chain_name: String) {
     }
 }
 
+/// Same as `start_processor_status_fetch`, reading `processor_status` from the database, plus the
+/// progress of the backfills in `backfill_processor_status`.
+async fn start_postgres_status_fetch(pool: ArcDbPool, mut recorder: ProcessorStatusRecorder) {
+    loop {
+        let time_now = tokio::time::Instant::now();
+        let chain_name = recorder.chain_name.clone();
+
+        match timeout(
+            Duration::from_millis(POSTGRES_QUERY_TIMEOUT_MS),
+            load_processor_status(&pool),
+        )
+        .await
+        {
+            Ok(Ok(rows)) => {
+                tracing::info!(num_processors = rows.len(), "Loaded processor status");
+                recorder.record(
+                    rows.into_iter()
+                        .map(|row| ProcessorStatus {
+                            processor: row.processor,
+                            last_updated: row.last_updated,
+                            last_success_version: row.last_success_version as u64,
+                            last_transaction_timestamp: row.last_transaction_timestamp,
+                        })
+                        .collect(),
+                );
+            },
+            Ok(Err(err)) => {
+                tracing::error!(error = ?err, "Failed to load processor status");
+                TASK_FAILURE_COUNT
+                    .with_label_values(&["postgres", &chain_name])
+                    .inc();
+            },
+            Err(_) => {
+                tracing::error!("Loading processor status timed out");
+                TASK_FAILURE_COUNT
+                    .with_label_values(&["postgres", &chain_name])
+                    .inc();
+            },
+        }
+
+        match timeout(
+            Duration::from_millis(POSTGRES_QUERY_TIMEOUT_MS),
+            load_backfill_status(&pool),
+        )
+        .await
+        {
+            Ok(Ok(rows)) => {
+                for backfill in rows {
+                    BACKFILL_LATEST_VERSION
+                        .with_label_values(&[&backfill.backfill_alias, &chain_name])
+                        .set(backfill.last_success_version);
+                    BACKFILL_PROGRESS_PERCENT
+                        .with_label_values(&[&backfill.backfill_alias, &chain_name])
+                        .set(backfill.progress_percent());
+                }
+            },
+            Ok(Err(err)) => {
+                tracing::error!(error = ?err, "Failed to load backfill status");
+                TASK_FAILURE_COUNT
+                    .with_label_values(&["postgres", &chain_name])
+                    .inc();
+            },
+            Err(_) => {
+                tracing::error!("Loading backfill status timed out");
+                TASK_FAILURE_COUNT
+                    .with_label_values(&["postgres", &chain_name])
+                    .inc();
+            },
+        }
+
+        let elapsed = time_now.elapsed().as_millis() as u64;
+        if elapsed < MIN_TIME_QUERIES_MS {
+            tokio::time::sleep(Duration::from_millis(MIN_TIME_QUERIES_MS - elapsed)).await;
+        }
+    }
+}
+
+/// Exports the lag metrics of processors and evaluates their SLOs, whatever the status source.
+struct ProcessorStatusRecorder {
+    chain_name: String,
+    slo_tracker: SloTracker,
+    latest_ledger_version: Arc<AtomicU64>,
+    alert_webhook_url: Option<String>,
+}
+
+impl ProcessorStatusRecorder {
+    fn record(&mut self, processor_status: Vec<ProcessorStatus>) {
+        let chain_name = &self.chain_name;
+        let system_time_now = chrono::Utc::now().naive_utc();
+        for processor in processor_status {
+            // If the last_transaction_timestamp is None, then the processor has not processed any transactions.
+            // Skip.
+            let last_transaction_timestamp = match processor.last_transaction_timestamp {
+                Some(timestamp) => timestamp,
+                None => continue,
+            };
+            HASURA_API_LATEST_VERSION
+                .with_label_values(&[&processor.processor, chain_name])
+                .set(processor.last_success_version as i64);
+            HASURA_API_LATEST_VERSION_TIMESTAMP
+                .with_label_values(&[&processor.processor, chain_name])
+                .set(processor.last_updated.and_utc().timestamp_micros() as f64 * 1e-6);
+            HASURA_API_LATEST_TRANSACTION_TIMESTAMP
+                .with_label_values(&[&processor.processor, chain_name])
+                .set(last_transaction_timestamp.and_utc().timestamp_micros() as f64 * 1e-6);
+            let latency = system_time_now - last_transaction_timestamp;
+            HASURA_API_LATEST_TRANSACTION_LATENCY_IN_SECS
+                .with_label_values(&[&processor.processor, chain_name])
+                .set(latency.num_milliseconds() as f64 * 1e-3);
+
+            let lag_versions = match self.latest_ledger_version.load(Ordering::Relaxed) {
+                0 => None,
+                ledger_version => {
+                    Some(ledger_version as i64 - processor.last_success_version as i64)
+                },
+            };
+            if let Some(lag_versions) = lag_versions {
+                PROCESSOR_LAG_VERSIONS
+                    .with_label_values(&[&processor.processor, chain_name])
+                    .set(lag_versions);
+            }
+            let observation = ProcessorObservation {
+                last_success_version: processor.last_success_version,
+                lag_secs: latency.num_milliseconds() as f64 * 1e-3,
+                lag_versions,
+            };
+            let Some(evaluation) = self.slo_tracker.observe(
+                &processor.processor,
+                &observation,
+                std::time::Instant::now(),
+            ) else {
+                continue;
+            };
+            SLO_WITHIN_TARGET
+                .with_label_values(&[&processor.processor, chain_name])
+                .set(evaluation.within_slo as i64);
+            SLO_BURN_RATE
+                .with_label_values(&[&processor.processor, chain_name, "5m"])
+                .set(evaluation.short_burn_rate);
+            SLO_BURN_RATE
+                .with_label_values(&[&processor.processor, chain_name, "1h"])
+                .set(evaluation.long_burn_rate);
+            for alert in evaluation.alerts {
+                tracing::warn!(alert = ?alert, "SLO alert changed state");
+                SLO_ALERT_FIRING
+                    .with_label_values(&[&processor.processor, chain_name, alert.alert.as_str()])
+                    .set((alert.status == AlertStatus::Firing) as i64);
+                if let Some(webhook_url) = &self.alert_webhook_url {
+                    tokio::spawn(send_alert(webhook_url.clone(), chain_name.clone(), alert));
+                }
+            }
+        }
+    }
+}
+
+async fn send_alert(url: String, chain_name: String, alert: AlertEvent) {
+    let notification = AlertNotification {
+        chain_name: &chain_name,
+        event: &alert,
+    };
+    let data = match serde_json::to_value(&notification) {
+        Ok(data) => data,
+        Err(err) => {
+            tracing::error!(error = ?err, "Failed to serialize alert");
+            return;
+        },
+    };
+    let result = post_url_with_timeout(&url, data, ALERT_WEBHOOK_TIMEOUT_MS).await;
+    let error = match result {
+        Ok(Ok(response)) => response.error_for_status().err().map(|err| err.to_string()),
+        Ok(Err(err)) => Some(err.to_string()),
+        Err(_) => Some("Request timed out".to_string()),
+    };
+    if let Some(error) = error {
+        tracing::error!(url = &url, error = %error, "Failed to send alert");
+        TASK_FAILURE_COUNT
+            .with_label_values(&["alert_webhook", &chain_name])
+            .inc();
+    }
+}
+
 #[allow(clippy::needless_return)]
 #[cfg(test)]
 mod test {
@@ -70,3 +70,57 @@ pub static PFN_LEDGER_TIMESTAMP: Lazy<GaugeVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static PROCESSOR_LAG_VERSIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_metrics_processor_lag_versions",
+        "Number of versions the processor is behind the fullnode ledger version",
+        &["processor_name", "chain_name"],
+    )
+    .unwrap()
+});
+
+pub static SLO_WITHIN_TARGET: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_metrics_slo_within_target",
+        "1 if the latest check of the processor was within its freshness SLO limits, 0 otherwise",
+        &["processor_name", "chain_name"],
+    )
+    .unwrap()
+});
+
+pub static SLO_BURN_RATE: Lazy<GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "indexer_metrics_slo_burn_rate",
+        "Rate at which the processor spends its freshness error budget over the window",
+        &["processor_name", "chain_name", "window"],
+    )
+    .unwrap()
+});
+
+pub static SLO_ALERT_FIRING: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_metrics_slo_alert_firing",
+        "1 if the alert of the processor is firing, 0 otherwise",
+        &["processor_name", "chain_name", "alert"],
+    )
+    .unwrap()
+});
+
+pub static BACKFILL_LATEST_VERSION: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_metrics_backfill_latest_version",
+        "Latest version processed by the backfill, from backfill_processor_status",
+        &["backfill_alias", "chain_name"],
+    )
+    .unwrap()
+});
+
+pub static BACKFILL_PROGRESS_PERCENT: Lazy<GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "indexer_metrics_backfill_progress_percent",
+        "Percentage of the version range of the backfill that has been processed",
+        &["backfill_alias", "chain_name"],
+    )
+    .unwrap()
+});
@@ -0,0 +1,95 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reads processor status straight from the processor database, for deployments without Hasura.
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use diesel::{QueryDsl, Queryable, Selectable, SelectableHelper};
+use diesel_async::RunQueryDsl;
+use processor::{
+    schema::{backfill_processor_status, processor_status},
+    utils::database::ArcDbPool,
+};
+
+const BACKFILL_COMPLETE: &str = "complete";
+
+/// Parquet processors have a row per table, named `<processor>.<table>`.
+#[derive(Debug, Queryable, Selectable)]
+#[diesel(table_name = processor_status)]
+pub struct ProcessorStatusRow {
+    pub processor: String,
+    pub last_success_version: i64,
+    pub last_updated: NaiveDateTime,
+    pub last_transaction_timestamp: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Queryable, Selectable)]
+#[diesel(table_name = backfill_processor_status)]
+pub struct BackfillStatusRow {
+    pub backfill_alias: String,
+    pub backfill_status: String,
+    pub last_success_version: i64,
+    pub last_updated: NaiveDateTime,
+    pub last_transaction_timestamp: Option<NaiveDateTime>,
+    pub backfill_start_version: i64,
+    pub backfill_end_version: i64,
+}
+
+impl BackfillStatusRow {
+    /// Share of the versions between `backfill_start_version` and `backfill_end_version`,
+    /// inclusive, that have been processed.
+    pub fn progress_percent(&self) -> f64 {
+        if self.backfill_status == BACKFILL_COMPLETE {
+            return 100.0;
+        }
+        let total_versions = self.backfill_end_version - self.backfill_start_version + 1;
+        if total_versions <= 0 {
+            return 0.0;
+        }
+        let processed_versions =
+            (self.last_success_version - self.backfill_start_version + 1).clamp(0, total_versions);
+        processed_versions as f64 * 100.0 / total_versions as f64
+    }
+}
+
+pub async fn load_processor_status(pool: &ArcDbPool) -> Result<Vec<ProcessorStatusRow>> {
+    let mut conn = pool.get().await?;
+    Ok(processor_status::table
+        .select(ProcessorStatusRow::as_select())
+        .load(&mut conn)
+        .await?)
+}
+
+pub async fn load_backfill_status(pool: &ArcDbPool) -> Result<Vec<BackfillStatusRow>> {
+    let mut conn = pool.get().await?;
+    Ok(backfill_processor_status::table
+        .select(BackfillStatusRow::as_select())
+        .load(&mut conn)
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backfill(backfill_status: &str, last_success_version: i64) -> BackfillStatusRow {
+        BackfillStatusRow {
+            backfill_alias: "events_processor_1".to_string(),
+            backfill_status: backfill_status.to_string(),
+            last_success_version,
+            last_updated: NaiveDateTime::default(),
+            last_transaction_timestamp: None,
+            backfill_start_version: 100,
+            backfill_end_version: 199,
+        }
+    }
+
+    #[test]
+    fn test_progress_percent() {
+        assert_eq!(backfill("in_progress", 99).progress_percent(), 0.0);
+        assert_eq!(backfill("in_progress", 149).progress_percent(), 50.0);
+        assert_eq!(backfill("in_progress", 250).progress_percent(), 100.0);
+        assert_eq!(backfill("complete", 120).progress_percent(), 100.0);
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Freshness SLOs of processors. Every poll of `processor_status` is a check that is good when the
+//! processor is within its lag limits. The burn rate of a window is the fraction of bad checks in
+//! it divided by the error budget `1 - target`, so a burn rate of 1 spends the budget exactly at
+//! the rate the target allows. A processor is alerted on when both the short and the long window
+//! burn faster than its threshold, or when its version stops advancing.
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+pub const SHORT_WINDOW: Duration = Duration::from_secs(5 * 60);
+pub const LONG_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProcessorSloConfig {
+    pub processor: String,
+    /// Maximum number of versions the processor can be behind the fullnode. Requires
+    /// `fullnode_rest_api_endpoint`.
+    #[serde(default)]
+    pub max_lag_versions: Option<u64>,
+    /// Maximum number of seconds between now and the timestamp of the latest processed transaction.
+    #[serde(default)]
+    pub max_lag_secs: Option<u64>,
+    /// Fraction of checks that should be within the lag limits.
+    #[serde(default = "ProcessorSloConfig::default_target")]
+    pub target: f64,
+    /// Burn rate above which the processor is alerted on as behind.
+    #[serde(default = "ProcessorSloConfig::default_burn_rate_threshold")]
+    pub burn_rate_threshold: f64,
+    /// Alert when the version of the processor hasn't advanced for this many seconds.
+    #[serde(default)]
+    pub max_stall_secs: Option<u64>,
+}
+
+impl ProcessorSloConfig {
+    pub const fn default_target() -> f64 {
+        0.99
+    }
+
+    /// Spends 2% of a 30 day error budget in an hour.
+    pub const fn default_burn_rate_threshold() -> f64 {
+        14.4
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.max_lag_versions.is_some()
+                || self.max_lag_secs.is_some()
+                || self.max_stall_secs.is_some(),
+            "SLO of {} has no limit",
+            self.processor
+        );
+        ensure!(
+            self.target > 0.0 && self.target < 1.0,
+            "SLO target of {} must be between 0 and 1",
+            self.processor
+        );
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ProcessorObservation {
+    pub last_success_version: u64,
+    pub lag_secs: f64,
+    /// None if the fullnode version is unknown.
+    pub lag_versions: Option<i64>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    ProcessorBehind,
+    ProcessorStalled,
+}
+
+impl AlertKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ProcessorBehind => "processor_behind",
+            Self::ProcessorStalled => "processor_stalled",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertStatus {
+    Firing,
+    Resolved,
+}
+
+/// A change of the state of an alert, sent to the alert webhook.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AlertEvent {
+    pub status: AlertStatus,
+    pub alert: AlertKind,
+    pub processor: String,
+    pub message: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct SloEvaluation {
+    pub within_slo: bool,
+    pub short_burn_rate: f64,
+    pub long_burn_rate: f64,
+    pub alerts: Vec<AlertEvent>,
+}
+
+struct ProcessorSloState {
+    /// Time and result of the checks of the long window, oldest first.
+    checks: VecDeque<(Instant, bool)>,
+    last_success_version: u64,
+    last_advanced_at: Instant,
+    behind: bool,
+    stalled: bool,
+}
+
+impl ProcessorSloState {
+    fn burn_rate(&self, window: Duration, now: Instant, error_budget: f64) -> f64 {
+        let (total, bad) = self
+            .checks
+            .iter()
+            .rev()
+            .take_while(|(time, _)| now.duration_since(*time) <= window)
+            .fold((0, 0), |(total, bad), (_, good)| {
+                (total + 1, if *good { bad } else { bad + 1 })
+            });
+        if total == 0 {
+            return 0.0;
+        }
+        bad as f64 / total as f64 / error_budget
+    }
+}
+
+pub struct SloTracker {
+    slos: HashMap<String, ProcessorSloConfig>,
+    states: HashMap<String, ProcessorSloState>,
+}
+
+impl SloTracker {
+    pub fn new(slos: Vec<ProcessorSloConfig>) -> Result<Self> {
+        let mut slos_by_processor = HashMap::new();
+        for slo in slos {
+            slo.validate()?;
+            let processor = slo.processor.clone();
+            ensure!(
+                slos_by_processor.insert(processor.clone(), slo).is_none(),
+                "Processor {} has more than one SLO",
+                processor
+            );
+        }
+        Ok(Self {
+            slos: slos_by_processor,
+            states: HashMap::new(),
+        })
+    }
+
+    pub fn requires_ledger_version(&self) -> bool {
+        self.slos.values().any(|slo| slo.max_lag_versions.is_some())
+    }
+
+    /// Records a check of the processor and returns the alerts that changed state. Returns None
+    /// if the processor has no SLO.
+    pub fn observe(
+        &mut self,
+        processor: &str,
+        observation: &ProcessorObservation,
+        now: Instant,
+    ) -> Option<SloEvaluation> {
+        let slo = self.slos.get(processor)?;
+        let state = self
+            .states
+            .entry(processor.to_string())
+            .or_insert_with(|| ProcessorSloState {
+                checks: VecDeque::new(),
+                last_success_version: observation.last_success_version,
+                last_advanced_at: now,
+                behind: false,
+                stalled: false,
+            });
+
+        let within_lag_secs = slo.max_lag_secs.map_or(true, |max_lag_secs| {
+            observation.lag_secs <= max_lag_secs as f64
+        });
+        let within_lag_versions = match (slo.max_lag_versions, observation.lag_versions) {
+            (Some(max_lag_versions), Some(lag_versions)) => lag_versions <= max_lag_versions as i64,
+            _ => true,
+        };
+        let within_slo = within_lag_secs && within_lag_versions;
+        state.checks.push_back((now, within_slo));
+        while let Some((time, _)) = state.checks.front() {
+            if now.duration_since(*time) <= LONG_WINDOW {
+                break;
+            }
+            state.checks.pop_front();
+        }
+        if observation.last_success_version > state.last_success_version {
+            state.last_success_version = observation.last_success_version;
+            state.last_advanced_at = now;
+        }
+
+        let error_budget = 1.0 - slo.target;
+        let short_burn_rate = state.burn_rate(SHORT_WINDOW, now, error_budget);
+        let long_burn_rate = state.burn_rate(LONG_WINDOW, now, error_budget);
+
+        let mut alerts = vec![];
+        let behind =
+            short_burn_rate >= slo.burn_rate_threshold && long_burn_rate >= slo.burn_rate_threshold;
+        if behind != state.behind {
+            state.behind = behind;
+            alerts.push(AlertEvent {
+                status: if behind {
+                    AlertStatus::Firing
+                } else {
+                    AlertStatus::Resolved
+                },
+                alert: AlertKind::ProcessorBehind,
+                processor: processor.to_string(),
+                message: format!(
+                    "{} is burning its freshness error budget {:.1}x over 5m and {:.1}x over 1h \
+                     (lag: {:.0}s, {} versions)",
+                    processor,
+                    short_burn_rate,
+                    long_burn_rate,
+                    observation.lag_secs,
+                    observation
+                        .lag_versions
+                        .map_or("unknown".to_string(), |lag| lag.to_string()),
+                ),
+            });
+        }
+        let stalled_for = now.duration_since(state.last_advanced_at);
+        let stalled = slo
+            .max_stall_secs
+            .is_some_and(|max_stall_secs| stalled_for.as_secs() >= max_stall_secs);
+        if stalled != state.stalled {
+            state.stalled = stalled;
+            alerts.push(AlertEvent {
+                status: if stalled {
+                    AlertStatus::Firing
+                } else {
+                    AlertStatus::Resolved
+                },
+                alert: AlertKind::ProcessorStalled,
+                processor: processor.to_string(),
+                message: format!(
+                    "{} has been at version {} for {}s",
+                    processor,
+                    state.last_success_version,
+                    stalled_for.as_secs()
+                ),
+            });
+        }
+
+        Some(SloEvaluation {
+            within_slo,
+            short_burn_rate,
+            long_burn_rate,
+            alerts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> SloTracker {
+        SloTracker::new(vec![ProcessorSloConfig {
+            processor: "token_v2_processor".to_string(),
+            max_lag_versions: Some(1000),
+            max_lag_secs: Some(10),
+            target: 0.9,
+            burn_rate_threshold: 5.0,
+            max_stall_secs: Some(60),
+        }])
+        .unwrap()
+    }
+
+    fn observation(last_success_version: u64, lag_secs: f64) -> ProcessorObservation {
+        ProcessorObservation {
+            last_success_version,
+            lag_secs,
+            lag_versions: Some(10),
+        }
+    }
+
+    #[test]
+    fn test_burn_rate_alert() {
+        let mut tracker = tracker();
+        let start = Instant::now();
+        for i in 0..10 {
+            let evaluation = tracker
+                .observe(
+                    "token_v2_processor",
+                    &observation(i, 1.0),
+                    start + Duration::from_secs(i),
+                )
+                .unwrap();
+            assert!(evaluation.within_slo);
+            assert!(evaluation.alerts.is_empty());
+        }
+
+        // 10 bad checks out of 20 is a burn rate of 0.5 / 0.1 = 5
+        let mut alerts = vec![];
+        for i in 10..20 {
+            let evaluation = tracker
+                .observe(
+                    "token_v2_processor",
+                    &observation(i, 30.0),
+                    start + Duration::from_secs(i),
+                )
+                .unwrap();
+            assert!(!evaluation.within_slo);
+            alerts.extend(evaluation.alerts);
+        }
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].status, AlertStatus::Firing);
+        assert_eq!(alerts[0].alert, AlertKind::ProcessorBehind);
+
+        let evaluation = tracker
+            .observe(
+                "token_v2_processor",
+                &observation(20, 1.0),
+                start + Duration::from_secs(20),
+            )
+            .unwrap();
+        assert_eq!(evaluation.alerts.len(), 1);
+        assert_eq!(evaluation.alerts[0].status, AlertStatus::Resolved);
+    }
+
+    #[test]
+    fn test_stall_alert() {
+        let mut tracker = tracker();
+        let start = Instant::now();
+        let evaluation = tracker
+            .observe("token_v2_processor", &observation(5, 1.0), start)
+            .unwrap();
+        assert!(evaluation.alerts.is_empty());
+
+        let evaluation = tracker
+            .observe(
+                "token_v2_processor",
+                &observation(5, 1.0),
+                start + Duration::from_secs(60),
+            )
+            .unwrap();
+        assert_eq!(evaluation.alerts.len(), 1);
+        assert_eq!(evaluation.alerts[0].alert, AlertKind::ProcessorStalled);
+        assert_eq!(evaluation.alerts[0].status, AlertStatus::Firing);
+
+        let evaluation = tracker
+            .observe(
+                "token_v2_processor",
+                &observation(6, 1.0),
+                start + Duration::from_secs(61),
+            )
+            .unwrap();
+        assert_eq!(evaluation.alerts[0].status, AlertStatus::Resolved);
+    }
+
+    #[test]
+    fn test_processor_without_slo() {
+        let mut tracker = tracker();
+        assert!(tracker
+            .observe("ans_processor", &observation(1, 1.0), Instant::now())
+            .is_none());
+    }
+}
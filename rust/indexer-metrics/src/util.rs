@@ -38,7 +38,7 @@ pub async fn fetch_processor_status_with_timeout(
     post_url_with_timeout(url, data, timeout_ms).await
 }
 
-async fn post_url_with_timeout(
+pub async fn post_url_with_timeout(
     url: &str,
     data: serde_json::Value,
     timeout_ms: u64,
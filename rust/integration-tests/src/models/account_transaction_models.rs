@@ -32,6 +32,7 @@ pub struct AccountTransaction {
     pub transaction_version: i64,
     pub account_address: String,
     pub inserted_at: chrono::NaiveDateTime,
+    pub label: Option<String>,
 }
 
 impl AccountTransaction {
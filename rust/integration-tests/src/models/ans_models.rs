@@ -23,7 +23,7 @@ use serde::{Deserialize, Serialize};
     Eq,
     Queryable,
 )]
-#[diesel(primary_key(domain, subdomain, token_standard))]
+#[diesel(primary_key(domain, subdomain, token_standard, namespace))]
 #[diesel(table_name = current_ans_lookup_v2)]
 #[diesel(treat_none_as_null = true)]
 pub struct CurrentAnsLookupV2 {
@@ -37,6 +37,7 @@ pub struct CurrentAnsLookupV2 {
     pub is_deleted: bool,
     pub inserted_at: chrono::NaiveDateTime,
     pub subdomain_expiration_policy: Option<i64>,
+    pub namespace: String,
 }
 
 #[derive(
@@ -57,6 +58,7 @@ pub struct AnsLookupV2 {
     pub is_deleted: bool,
     pub inserted_at: chrono::NaiveDateTime,
     pub subdomain_expiration_policy: Option<i64>,
+    pub namespace: String,
 }
 
 #[derive(
@@ -72,7 +74,7 @@ pub struct AnsLookupV2 {
     Eq,
     Queryable,
 )]
-#[diesel(primary_key(registered_address, token_standard))]
+#[diesel(primary_key(registered_address, token_standard, namespace))]
 #[diesel(table_name = current_ans_primary_name_v2)]
 #[diesel(treat_none_as_null = true)]
 pub struct CurrentAnsPrimaryNameV2 {
@@ -84,6 +86,7 @@ pub struct CurrentAnsPrimaryNameV2 {
     pub is_deleted: bool,
     pub last_transaction_version: i64,
     pub inserted_at: chrono::NaiveDateTime,
+    pub namespace: String,
 }
 
 #[derive(
@@ -102,4 +105,5 @@ pub struct AnsPrimaryNameV2 {
     pub token_name: Option<String>,
     pub is_deleted: bool,
     pub inserted_at: chrono::NaiveDateTime,
+    pub namespace: String,
 }
@@ -33,6 +33,7 @@ pub struct FungibleAssetActivity {
     pub transaction_timestamp: chrono::NaiveDateTime,
     pub inserted_at: chrono::NaiveDateTime,
     pub storage_refund_amount: BigDecimal,
+    pub label: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize, Queryable)]
@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod tests {
+    use crate::sdk_tests::setup_test_database;
+    use aptos_indexer_testing_framework::database::TestDatabase;
+    use diesel::{
+        sql_query,
+        sql_types::{BigInt, Nullable, Text},
+        Connection, PgConnection, RunQueryDsl,
+    };
+    use processor::db::common::models::account_transaction_models::raw_account_summaries::{
+        RawAccountActivity, RawAccountCounterparty,
+    };
+    use sdk_processor::steps::account_transactions_processor::account_transactions_storer::update_account_summaries;
+
+    #[derive(Debug, PartialEq, diesel::QueryableByName)]
+    struct Summary {
+        #[diesel(sql_type = Text)]
+        account_address: String,
+        #[diesel(sql_type = BigInt)]
+        first_transaction_version: i64,
+        #[diesel(sql_type = BigInt)]
+        last_transaction_version: i64,
+        #[diesel(sql_type = BigInt)]
+        num_transactions: i64,
+        #[diesel(sql_type = BigInt)]
+        num_counterparties: i64,
+        #[diesel(sql_type = Nullable<Text>)]
+        last_entry_function_id_str: Option<String>,
+    }
+
+    fn activity(
+        account_address: &str,
+        version: i64,
+        entry_function: Option<&str>,
+    ) -> RawAccountActivity {
+        RawAccountActivity {
+            account_address: account_address.to_string(),
+            transaction_version: version,
+            transaction_timestamp: chrono::DateTime::from_timestamp(version, 0)
+                .unwrap()
+                .naive_utc(),
+            entry_function_id_str: entry_function.map(|f| f.to_string()),
+        }
+    }
+
+    /// A transfer from `sender` to `receiver`, who are each other's counterparties.
+    fn transfer(
+        sender: &str,
+        receiver: &str,
+        version: i64,
+    ) -> (Vec<RawAccountActivity>, Vec<RawAccountCounterparty>) {
+        let counterparty =
+            |account_address: &str, counterparty_address: &str| RawAccountCounterparty {
+                account_address: account_address.to_string(),
+                counterparty_address: counterparty_address.to_string(),
+                transaction_version: version,
+            };
+        (
+            vec![
+                activity(sender, version, Some("0x1::aptos_account::transfer")),
+                activity(receiver, version, None),
+            ],
+            vec![
+                counterparty(sender, receiver),
+                counterparty(receiver, sender),
+            ],
+        )
+    }
+
+    fn batch(
+        transfers: &[(&str, &str, i64)],
+    ) -> (Vec<RawAccountActivity>, Vec<RawAccountCounterparty>) {
+        let mut activities = vec![];
+        let mut counterparties = vec![];
+        for (sender, receiver, version) in transfers {
+            let (a, c) = transfer(sender, receiver, *version);
+            activities.extend(a);
+            counterparties.extend(c);
+        }
+        (activities, counterparties)
+    }
+
+    fn load_summary(conn: &mut PgConnection, account_address: &str) -> Summary {
+        sql_query(
+            "SELECT account_address, first_transaction_version, last_transaction_version, num_transactions, num_counterparties, last_entry_function_id_str FROM account_summaries WHERE account_address = $1",
+        )
+        .bind::<Text, _>(account_address)
+        .get_result::<Summary>(conn)
+        .unwrap()
+    }
+
+    fn summary(
+        account_address: &str,
+        versions: (i64, i64),
+        num_transactions: i64,
+        num_counterparties: i64,
+        last_entry_function_id_str: Option<&str>,
+    ) -> Summary {
+        Summary {
+            account_address: account_address.to_string(),
+            first_transaction_version: versions.0,
+            last_transaction_version: versions.1,
+            num_transactions,
+            num_counterparties,
+            last_entry_function_id_str: last_entry_function_id_str.map(|f| f.to_string()),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_update_account_summaries() {
+        let (db, conn_pool) = setup_test_database().await;
+        let mut conn = PgConnection::establish(&db.get_db_url()).unwrap();
+        let transfer_function = Some("0x1::aptos_account::transfer");
+
+        let (activities, counterparties) = batch(&[("0xa", "0xb", 20), ("0xa", "0xc", 21)]);
+        update_account_summaries(
+            conn_pool.clone(),
+            activities.clone(),
+            counterparties.clone(),
+        )
+        .await
+        .unwrap();
+        let expected_a = summary("0xa", (20, 21), 2, 2, transfer_function);
+        assert_eq!(load_summary(&mut conn, "0xa"), expected_a);
+        assert_eq!(
+            load_summary(&mut conn, "0xb"),
+            summary("0xb", (20, 20), 1, 1, None)
+        );
+
+        // Replaying a batch leaves the summaries as they are
+        update_account_summaries(conn_pool.clone(), activities, counterparties)
+            .await
+            .unwrap();
+        assert_eq!(load_summary(&mut conn, "0xa"), expected_a);
+
+        // An older batch is still counted, and lowers the first activity without changing the last
+        // one. 0xb is already a counterparty of 0xa, and 0xd is a new one.
+        let (mut activities, counterparties) = batch(&[("0xb", "0xa", 10), ("0xd", "0xa", 11)]);
+        activities.push(activity("0xa", 12, Some("0x1::coin::transfer")));
+        update_account_summaries(conn_pool.clone(), activities, counterparties)
+            .await
+            .unwrap();
+        assert_eq!(
+            load_summary(&mut conn, "0xa"),
+            summary("0xa", (10, 21), 5, 3, transfer_function)
+        );
+        assert_eq!(
+            load_summary(&mut conn, "0xb"),
+            summary("0xb", (10, 20), 2, 1, transfer_function)
+        );
+
+        // The counterparties keep the first version they interacted at
+        #[derive(diesel::QueryableByName)]
+        struct Counterparty {
+            #[diesel(sql_type = BigInt)]
+            first_transaction_version: i64,
+        }
+        let first_version = sql_query(
+            "SELECT first_transaction_version FROM account_counterparties WHERE account_address = '0xa' AND counterparty_address = '0xb'",
+        )
+        .get_result::<Counterparty>(&mut conn)
+        .unwrap()
+        .first_transaction_version;
+        assert_eq!(first_version, 10);
+    }
+}
@@ -29,10 +29,12 @@ pub fn setup_ans_processor_config(
             "0x21a0fd41330f3a0a38173c7c0e4ac59cd51505f0594f64d3d637c12425c3c155".to_string(),
         ans_v2_contract_address:
             "0x867ed1f6bf916171b1de3ee92849b8978b7d1b9e0a8cc982a3d19d535dfd9c0c".to_string(),
+        additional_naming_services: vec![],
         default: DefaultProcessorConfig {
             per_table_chunk_sizes: AHashMap::new(),
             channel_size: 100,
             deprecated_tables: HashSet::new(),
+            cdc_sink: None,
         },
     };
 
@@ -52,6 +54,8 @@ pub fn setup_ans_processor_config(
             backfill_config: None,
             bootstrap_config: None,
             testing_config: Some(testing_config),
+            gap_repair_config: None,
+            address_labels_config: None,
             mode: ProcessorMode::Testing,
         },
         processor_name,
@@ -74,7 +78,12 @@ mod tests {
         IMPORTED_MAINNET_TXNS_438536688_ANS_CURRENT_ANS_LOOKUP_V2,
     };
     use aptos_indexer_testing_framework::{cli_parser::get_test_config, database::TestDatabase};
-    use sdk_processor::processors::ans_processor::AnsProcessor;
+    use diesel::{sql_query, sql_types::Text, Connection, PgConnection, RunQueryDsl};
+    use processor::db::postgres::models::ans_models::ans_utils::NamingServiceConfig;
+    use sdk_processor::{
+        config::processor_config::ProcessorConfig, processors::ans_processor::AnsProcessor,
+    };
+    use serde_json::Value;
 
     /**
      * This test includes processing for the following:
@@ -132,6 +141,112 @@ mod tests {
         .await;
     }
 
+    /// Indexes the name with a second naming service deployed at the same address as ANS. The
+    /// names of the second service are stored in their own namespace, and leave the ANS rows and
+    /// `current_aptos_names` as they are.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn mainnet_current_ans_lookup_v2_second_naming_service() {
+        let (db, mut test_context) =
+            setup_test_environment(&[IMPORTED_MAINNET_TXNS_438536688_ANS_CURRENT_ANS_LOOKUP_V2])
+                .await;
+        let db_url = db.get_db_url();
+        let (mut indexer_processor_config, processor_name) =
+            setup_ans_processor_config(&test_context, &db_url);
+        if let ProcessorConfig::AnsProcessor(config) =
+            &mut indexer_processor_config.processor_config
+        {
+            let mut naming_service =
+                NamingServiceConfig::aptos_names(&config.ans_v2_contract_address);
+            naming_service.namespace = "example".to_string();
+            config.additional_naming_services = vec![naming_service];
+        }
+        let ans_processor = AnsProcessor::new(indexer_processor_config)
+            .await
+            .expect("Failed to create AnsProcessor");
+
+        let db_values = run_processor_test(
+            &mut test_context,
+            ans_processor,
+            load_data,
+            db_url.clone(),
+            false,
+            format!("{}/imported_mainnet_txns", DEFAULT_OUTPUT_FOLDER),
+            Some("test_current_ans_lookup_v2".to_string()),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("Test failed on {} due to {}", processor_name, e));
+
+        // Each namespace has its own copy of the name, and the ANS one is the name indexed without
+        // the second service
+        let lookups = db_values["current_ans_lookup_v2"].as_array().unwrap();
+        let in_namespace = |namespace: &str| -> Vec<Value> {
+            lookups
+                .iter()
+                .filter(|lookup| lookup["namespace"] == namespace)
+                .map(|lookup| {
+                    let mut lookup = lookup.clone();
+                    let fields = lookup.as_object_mut().unwrap();
+                    fields.remove("namespace");
+                    fields.remove("inserted_at");
+                    lookup
+                })
+                .collect()
+        };
+        let ans_lookups = in_namespace("ans");
+        assert_eq!(ans_lookups.len(), 1);
+        assert_eq!(ans_lookups[0]["token_name"], "003.apt");
+        assert_eq!(
+            ans_lookups[0]["registered_address"],
+            "0x761e4e45d8c6d56fb9fab4ebd865ce4a3dfe3a7bea3956158b4be07f268bcff5"
+        );
+        assert_eq!(
+            ans_lookups[0]["expiration_timestamp"],
+            "2024-11-19T13:31:40"
+        );
+        assert_eq!(in_namespace("example"), ans_lookups);
+
+        // The name token is in the mainnet ANS v2 domain collection
+        let mut conn = PgConnection::establish(&db_url).unwrap();
+        sql_query(
+            "INSERT INTO current_token_datas_v2 (token_data_id, collection_id, token_name, token_uri, description, token_properties, token_standard, last_transaction_version, last_transaction_timestamp) VALUES
+            ('0xa', '0x30fbc956f0f38db2d314bd9c018d34be3e047a804a71e30a4e5d43d8b7c539eb', '003.apt', '', '', '{}', 'v2', 438536688, '2024-01-01 00:00:00')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+        sql_query(
+            "INSERT INTO current_token_ownerships_v2 (token_data_id, property_version_v1, owner_address, storage_id, amount, token_standard, last_transaction_version, last_transaction_timestamp) VALUES
+            ('0xa', 0, '0x761e4e45d8c6d56fb9fab4ebd865ce4a3dfe3a7bea3956158b4be07f268bcff5', '0xa', 1, 'v2', 438536688, '2024-01-01 00:00:00')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+
+        #[derive(diesel::QueryableByName)]
+        struct AptosName {
+            #[diesel(sql_type = Text)]
+            domain_with_suffix: String,
+            #[diesel(sql_type = diesel::sql_types::Nullable<Text>)]
+            registered_address: Option<String>,
+        }
+        let names = sql_query(
+            "SELECT domain_with_suffix, registered_address FROM current_aptos_names WHERE token_name = '003.apt'",
+        )
+        .load::<AptosName>(&mut conn)
+        .unwrap()
+        .into_iter()
+        .map(|name| (name.domain_with_suffix, name.registered_address))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![(
+                "003.apt".to_string(),
+                Some(
+                    "0x761e4e45d8c6d56fb9fab4ebd865ce4a3dfe3a7bea3956158b4be07f268bcff5"
+                        .to_string()
+                )
+            )]
+        );
+    }
+
     // Helper function to abstract out the single transaction processing
     async fn process_single_mainnet_event_txn(txn: &[u8], test_case_name: Option<String>) {
         let (diff_flag, custom_output_path) = get_test_config();
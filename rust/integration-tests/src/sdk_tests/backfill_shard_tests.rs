@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use crate::sdk_tests::setup_test_database;
+    use sdk_processor::{
+        db::common::models::backfill_shard::{BackfillShard, BackfillShardQuery},
+        utils::backfill_shards::{hold_lease, record_shards},
+    };
+    use std::time::Duration;
+
+    const BACKFILL_ALIAS: &str = "events_processor_sharded";
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_backfill_shard_resume() {
+        let (_db, conn_pool) = setup_test_database().await;
+        let shards = BackfillShard::split_range(BACKFILL_ALIAS, 0, 199, 100);
+        record_shards(conn_pool.clone(), &shards).await.unwrap();
+
+        let mut conn = conn_pool.get().await.unwrap();
+        let shard = BackfillShardQuery::claim(BACKFILL_ALIAS, "worker_a", 300, &mut conn)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(shard.shard_index, 0);
+
+        // Another worker starting up records the same shards again, which keeps worker_a's lease
+        record_shards(conn_pool.clone(), &shards).await.unwrap();
+        let shard = BackfillShardQuery::claim(BACKFILL_ALIAS, "worker_b", 300, &mut conn)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(shard.shard_index, 1);
+
+        // After a restart worker_a resumes its own shard
+        let shard = BackfillShardQuery::claim(BACKFILL_ALIAS, "worker_a", 300, &mut conn)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(shard.shard_index, 0);
+        assert_eq!(shard.shard_start_version, 0);
+        assert_eq!(shard.shard_end_version, 99);
+
+        // Every shard is leased
+        let shard = BackfillShardQuery::claim(BACKFILL_ALIAS, "worker_c", 300, &mut conn)
+            .await
+            .unwrap();
+        assert!(shard.is_none());
+
+        let num_marked =
+            BackfillShardQuery::mark_complete(BACKFILL_ALIAS, 0, "worker_a", &mut conn)
+                .await
+                .unwrap();
+        assert_eq!(num_marked, 1);
+        let shard = BackfillShardQuery::claim(BACKFILL_ALIAS, "worker_a", 300, &mut conn)
+            .await
+            .unwrap();
+        assert!(shard.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_backfill_shard_lease_takeover() {
+        let (_db, conn_pool) = setup_test_database().await;
+        let shards = BackfillShard::split_range(BACKFILL_ALIAS, 0, 99, 100);
+        record_shards(conn_pool.clone(), &shards).await.unwrap();
+
+        let mut conn = conn_pool.get().await.unwrap();
+        let shard = BackfillShardQuery::claim(BACKFILL_ALIAS, "worker_a", 1, &mut conn)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(shard.shard_index, 0);
+
+        // worker_a stops renewing its lease, so worker_b takes the shard over once it expires
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        let shard = BackfillShardQuery::claim(BACKFILL_ALIAS, "worker_b", 300, &mut conn)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(shard.shard_index, 0);
+        assert_eq!(shard.worker_id.as_deref(), Some("worker_b"));
+
+        // worker_a stops processing the shard on its next renewal, and can't mark it complete
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            hold_lease(conn_pool.clone(), BACKFILL_ALIAS, 0, "worker_a", 1),
+        )
+        .await
+        .expect("hold_lease should return once the lease is taken over");
+        let num_marked =
+            BackfillShardQuery::mark_complete(BACKFILL_ALIAS, 0, "worker_a", &mut conn)
+                .await
+                .unwrap();
+        assert_eq!(num_marked, 0);
+
+        // worker_b keeps its lease
+        let renew_result = tokio::time::timeout(
+            Duration::from_secs(3),
+            hold_lease(conn_pool.clone(), BACKFILL_ALIAS, 0, "worker_b", 3),
+        )
+        .await;
+        assert!(renew_result.is_err());
+        let num_marked =
+            BackfillShardQuery::mark_complete(BACKFILL_ALIAS, 0, "worker_b", &mut conn)
+                .await
+                .unwrap();
+        assert_eq!(num_marked, 1);
+    }
+}
@@ -0,0 +1,275 @@
+#[cfg(test)]
+mod tests {
+    use crate::sdk_tests::setup_test_database;
+    use aptos_indexer_testing_framework::database::TestDatabase;
+    use bigdecimal::BigDecimal;
+    use diesel::{
+        sql_query,
+        sql_types::{BigInt, Text, Timestamp},
+        Connection, PgConnection, RunQueryDsl,
+    };
+    use processor::db::postgres::models::token_v2_models::{
+        v2_token_activities::TokenActivityV2, v2_token_ownerships::CurrentTokenOwnershipV2,
+    };
+    use sdk_processor::steps::token_v2_processor::token_v2_storer::update_collection_stats;
+
+    const COLLECTION_ID: &str = "0xc0";
+
+    #[derive(Debug, PartialEq, diesel::QueryableByName)]
+    struct CollectionStats {
+        #[diesel(sql_type = BigInt)]
+        num_holders: i64,
+        #[diesel(sql_type = BigInt)]
+        num_holders_1: i64,
+        #[diesel(sql_type = BigInt)]
+        num_holders_2_to_5: i64,
+        #[diesel(sql_type = BigInt)]
+        num_holders_6_to_20: i64,
+        #[diesel(sql_type = BigInt)]
+        num_holders_21_plus: i64,
+        #[diesel(sql_type = BigInt)]
+        num_burned: i64,
+    }
+
+    #[derive(diesel::QueryableByName)]
+    struct Holder {
+        #[diesel(sql_type = Text)]
+        owner_address: String,
+        #[diesel(sql_type = BigInt)]
+        num_tokens: i64,
+    }
+
+    #[derive(diesel::QueryableByName)]
+    struct HourlyActivity {
+        #[diesel(sql_type = Timestamp)]
+        hour: chrono::NaiveDateTime,
+        #[diesel(sql_type = BigInt)]
+        num_transfers: i64,
+        #[diesel(sql_type = BigInt)]
+        num_burns: i64,
+    }
+
+    fn timestamp(time: &str) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::parse_from_str(&format!("2025-01-01 {}", time), "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+    }
+
+    fn activity(
+        version: i64,
+        event_index: i64,
+        token_data_id: &str,
+        type_: &str,
+        time: &str,
+    ) -> TokenActivityV2 {
+        TokenActivityV2 {
+            transaction_version: version,
+            event_index,
+            event_account_address: token_data_id.to_string(),
+            token_data_id: token_data_id.to_string(),
+            property_version_v1: BigDecimal::from(0),
+            type_: type_.to_string(),
+            from_address: None,
+            to_address: None,
+            token_amount: BigDecimal::from(1),
+            before_value: None,
+            after_value: None,
+            entry_function_id_str: None,
+            token_standard: "v2".to_string(),
+            is_fungible_v2: None,
+            transaction_timestamp: timestamp(time),
+            label: None,
+        }
+    }
+
+    fn ownership(
+        token_data_id: &str,
+        owner_address: &str,
+        amount: i64,
+        version: i64,
+    ) -> CurrentTokenOwnershipV2 {
+        CurrentTokenOwnershipV2 {
+            token_data_id: token_data_id.to_string(),
+            property_version_v1: BigDecimal::from(0),
+            owner_address: owner_address.to_string(),
+            storage_id: token_data_id.to_string(),
+            amount: BigDecimal::from(amount),
+            table_type_v1: None,
+            token_properties_mutated_v1: None,
+            is_soulbound_v2: None,
+            token_standard: "v2".to_string(),
+            is_fungible_v2: None,
+            last_transaction_version: version,
+            last_transaction_timestamp: timestamp("00:00:00"),
+            non_transferrable_by_owner: None,
+        }
+    }
+
+    /// Writes the ownerships the way the storer does before updating the stats
+    fn store_ownerships(conn: &mut PgConnection, ownerships: &[CurrentTokenOwnershipV2]) {
+        for ownership in ownerships {
+            sql_query(
+                "INSERT INTO current_token_ownerships_v2 (token_data_id, property_version_v1, owner_address, storage_id, amount, token_standard, last_transaction_version, last_transaction_timestamp) VALUES ($1, 0, $2, $1, $3, 'v2', $4, '2025-01-01 00:00:00')
+                ON CONFLICT (token_data_id, property_version_v1, owner_address, storage_id) DO UPDATE SET amount = EXCLUDED.amount, last_transaction_version = EXCLUDED.last_transaction_version",
+            )
+            .bind::<Text, _>(&ownership.token_data_id)
+            .bind::<Text, _>(&ownership.owner_address)
+            .bind::<diesel::sql_types::Numeric, _>(&ownership.amount)
+            .bind::<BigInt, _>(ownership.last_transaction_version)
+            .execute(conn)
+            .unwrap();
+        }
+    }
+
+    fn load_stats(conn: &mut PgConnection) -> CollectionStats {
+        sql_query(
+            "SELECT num_holders, num_holders_1, num_holders_2_to_5, num_holders_6_to_20, num_holders_21_plus, num_burned FROM current_collection_stats WHERE collection_id = $1",
+        )
+        .bind::<Text, _>(COLLECTION_ID)
+        .get_result::<CollectionStats>(conn)
+        .unwrap()
+    }
+
+    fn load_holders(conn: &mut PgConnection) -> Vec<(String, i64)> {
+        sql_query(
+            "SELECT owner_address, num_tokens::BIGINT AS num_tokens FROM current_collection_holders WHERE collection_id = $1 ORDER BY owner_address",
+        )
+        .bind::<Text, _>(COLLECTION_ID)
+        .load::<Holder>(conn)
+        .unwrap()
+        .into_iter()
+        .map(|h| (h.owner_address, h.num_tokens))
+        .collect()
+    }
+
+    fn load_hourly_activity(conn: &mut PgConnection) -> Vec<(chrono::NaiveDateTime, i64, i64)> {
+        sql_query(
+            "SELECT hour, num_transfers, num_burns FROM collection_activity_hourly WHERE collection_id = $1 ORDER BY hour",
+        )
+        .bind::<Text, _>(COLLECTION_ID)
+        .load::<HourlyActivity>(conn)
+        .unwrap()
+        .into_iter()
+        .map(|a| (a.hour, a.num_transfers, a.num_burns))
+        .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_update_collection_stats() {
+        let (db, conn_pool) = setup_test_database().await;
+        let mut conn = PgConnection::establish(&db.get_db_url()).unwrap();
+
+        sql_query(
+            "INSERT INTO current_token_datas_v2 (token_data_id, collection_id, token_name, token_uri, description, token_properties, token_standard, last_transaction_version, last_transaction_timestamp) VALUES
+            ('0xa', '0xc0', 'a', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00'),
+            ('0xb', '0xc0', 'b', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00'),
+            ('0xc', '0xc0', 'c', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+
+        // 0xa and 0xb are transferred to alice and 0xc to bob
+        let first_activities = vec![
+            activity(10, 0, "0xa", "0x1::object::TransferEvent", "10:15:00"),
+            activity(10, 1, "0xb", "0x1::object::TransferEvent", "10:15:00"),
+            activity(11, 0, "0xc", "0x1::object::TransferEvent", "10:45:00"),
+        ];
+        let first_ownerships = vec![
+            ownership("0xa", "0xalice", 1, 10),
+            ownership("0xb", "0xalice", 1, 10),
+            ownership("0xc", "0xbob", 1, 11),
+        ];
+        store_ownerships(&mut conn, &first_ownerships);
+        update_collection_stats(
+            conn_pool.clone(),
+            &first_activities,
+            first_ownerships.iter(),
+            11,
+        )
+        .await
+        .unwrap();
+        let expected_stats = CollectionStats {
+            num_holders: 2,
+            num_holders_1: 1,
+            num_holders_2_to_5: 1,
+            num_holders_6_to_20: 0,
+            num_holders_21_plus: 0,
+            num_burned: 0,
+        };
+        assert_eq!(load_stats(&mut conn), expected_stats);
+        assert_eq!(load_holders(&mut conn), vec![
+            ("0xalice".to_string(), 2),
+            ("0xbob".to_string(), 1),
+        ]);
+        assert_eq!(load_hourly_activity(&mut conn), vec![(timestamp("10:00:00"), 3, 0)]);
+
+        // Replaying a batch leaves the stats and the rollup as they are
+        update_collection_stats(
+            conn_pool.clone(),
+            &first_activities,
+            first_ownerships.iter(),
+            11,
+        )
+        .await
+        .unwrap();
+        assert_eq!(load_stats(&mut conn), expected_stats);
+        assert_eq!(load_hourly_activity(&mut conn), vec![(timestamp("10:00:00"), 3, 0)]);
+
+        // In the next hour alice transfers 0xb to bob and burns 0xa, so she holds nothing anymore
+        let second_activities = vec![
+            activity(20, 0, "0xb", "0x1::object::TransferEvent", "11:05:00"),
+            activity(21, 0, "0xa", "0x4::collection::BurnEvent", "11:10:00"),
+        ];
+        let second_ownerships = vec![
+            ownership("0xb", "0xalice", 0, 20),
+            ownership("0xb", "0xbob", 1, 20),
+            ownership("0xa", "0xalice", 0, 21),
+        ];
+        store_ownerships(&mut conn, &second_ownerships);
+        update_collection_stats(
+            conn_pool.clone(),
+            &second_activities,
+            second_ownerships.iter(),
+            21,
+        )
+        .await
+        .unwrap();
+        let expected_stats = CollectionStats {
+            num_holders: 1,
+            num_holders_1: 0,
+            num_holders_2_to_5: 1,
+            num_holders_6_to_20: 0,
+            num_holders_21_plus: 0,
+            num_burned: 1,
+        };
+        assert_eq!(load_stats(&mut conn), expected_stats);
+        assert_eq!(load_holders(&mut conn), vec![("0xbob".to_string(), 2)]);
+        assert_eq!(load_hourly_activity(&mut conn), vec![
+            (timestamp("10:00:00"), 3, 0),
+            (timestamp("11:00:00"), 1, 1),
+        ]);
+
+        // Replaying both batches out of order changes nothing either
+        update_collection_stats(
+            conn_pool.clone(),
+            &first_activities,
+            first_ownerships.iter(),
+            11,
+        )
+        .await
+        .unwrap();
+        update_collection_stats(
+            conn_pool.clone(),
+            &second_activities,
+            second_ownerships.iter(),
+            21,
+        )
+        .await
+        .unwrap();
+        assert_eq!(load_stats(&mut conn), expected_stats);
+        assert_eq!(load_holders(&mut conn), vec![("0xbob".to_string(), 2)]);
+        assert_eq!(load_hourly_activity(&mut conn), vec![
+            (timestamp("10:00:00"), 3, 0),
+            (timestamp("11:00:00"), 1, 1),
+        ]);
+    }
+}
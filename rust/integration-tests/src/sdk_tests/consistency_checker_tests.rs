@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use crate::sdk_tests::setup_test_database;
+    use aptos_indexer_testing_framework::database::TestDatabase;
+    use diesel::{sql_query, Connection, PgConnection, RunQueryDsl};
+    use sdk_processor::consistency_checker::{
+        check_table,
+        fungible_asset_checks::{
+            CurrentFungibleAssetBalancesV1Check, CurrentFungibleAssetBalancesV2Check,
+        },
+        ScanMode,
+    };
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_fungible_asset_balances_fixup() {
+        let (db, conn_pool) = setup_test_database().await;
+        let mut conn = PgConnection::establish(&db.get_db_url()).unwrap();
+
+        // 0xa has a v1 and a v2 balance, 0xb only a v2 balance
+        sql_query(
+            "INSERT INTO fungible_asset_balances (transaction_version, write_set_change_index, storage_id, owner_address, asset_type, is_primary, is_frozen, amount, transaction_timestamp, token_standard) VALUES
+            (1, 0, '0xa', '0x1', '0x1::aptos_coin::AptosCoin', true, false, 100, '2025-01-01 00:00:00', 'v1'),
+            (2, 0, '0xa', '0x1', '0x1::aptos_coin::AptosCoin', true, false, 80, '2025-01-01 00:00:01', 'v1'),
+            (3, 0, '0xa', '0x1', '0xfa', true, false, 20, '2025-01-01 00:00:02', 'v2'),
+            (4, 0, '0xb', '0x2', '0xfa', true, false, 5, '2025-01-01 00:00:03', 'v2')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+        // The v1 balance of 0xa is stale and 0xb is missing
+        sql_query(
+            "INSERT INTO current_fungible_asset_balances (storage_id, owner_address, asset_type_v1, asset_type_v2, is_primary, is_frozen, amount_v1, amount_v2, last_transaction_version_v1, last_transaction_version_v2, last_transaction_timestamp_v1, last_transaction_timestamp_v2) VALUES
+            ('0xa', '0x1', '0x1::aptos_coin::AptosCoin', '0xfa', true, false, 100, 20, 1, 3, '2025-01-01 00:00:00', '2025-01-01 00:00:02')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+
+        let v1_report = check_table::<CurrentFungibleAssetBalancesV1Check>(
+            conn_pool.clone(),
+            ScanMode::Sample(10),
+        )
+        .await
+        .unwrap();
+        assert_eq!(v1_report.mismatches.len(), 1);
+        assert_eq!(
+            v1_report.mismatches[0].mismatched_columns(v1_report.columns),
+            vec![
+                "amount_v1",
+                "last_transaction_version_v1",
+                "last_transaction_timestamp_v1"
+            ]
+        );
+        let v2_report = check_table::<CurrentFungibleAssetBalancesV2Check>(
+            conn_pool.clone(),
+            ScanMode::Full { page_size: 1 },
+        )
+        .await
+        .unwrap();
+        assert_eq!(v2_report.mismatches.len(), 1);
+        assert_eq!(v2_report.mismatches[0].pk, "\"0xb\"");
+
+        // The fix-up statements only write the v1 or v2 columns of the rows
+        for statement in v1_report
+            .fixup_statements
+            .iter()
+            .chain(v2_report.fixup_statements.iter())
+        {
+            sql_query(statement).execute(&mut conn).unwrap();
+        }
+
+        for report in [
+            check_table::<CurrentFungibleAssetBalancesV1Check>(
+                conn_pool.clone(),
+                ScanMode::Sample(10),
+            )
+            .await
+            .unwrap(),
+            check_table::<CurrentFungibleAssetBalancesV2Check>(
+                conn_pool.clone(),
+                ScanMode::Full { page_size: 1 },
+            )
+            .await
+            .unwrap(),
+        ] {
+            assert!(report.mismatches.is_empty());
+        }
+
+        #[derive(diesel::QueryableByName)]
+        struct Balance {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            storage_id: String,
+            #[diesel(sql_type = diesel::sql_types::Numeric)]
+            amount: bigdecimal::BigDecimal,
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            token_standard: String,
+        }
+        let balances = sql_query(
+            "SELECT storage_id, amount, token_standard FROM current_fungible_asset_balances ORDER BY storage_id",
+        )
+        .load::<Balance>(&mut conn)
+        .unwrap();
+        let balances = balances
+            .into_iter()
+            .map(|b| (b.storage_id, b.amount.to_string(), b.token_standard))
+            .collect::<Vec<_>>();
+        assert_eq!(balances, vec![
+            ("0xa".to_string(), "100".to_string(), "v1".to_string()),
+            ("0xb".to_string(), "5".to_string(), "v2".to_string()),
+        ]);
+    }
+}
@@ -22,6 +22,7 @@ pub fn setup_fa_processor_config(
         per_table_chunk_sizes: AHashMap::new(),
         channel_size: 100,
         deprecated_tables: HashSet::new(),
+        cdc_sink: None,
     };
 
     let processor_config = ProcessorConfig::FungibleAssetProcessor(default_processor_config);
@@ -40,6 +41,8 @@ pub fn setup_fa_processor_config(
             backfill_config: None,
             bootstrap_config: None,
             testing_config: Some(testing_config),
+            gap_repair_config: None,
+            address_labels_config: None,
             mode: ProcessorMode::Testing,
         },
         processor_name,
@@ -6,6 +6,7 @@ use aptos_indexer_testing_framework::{
 };
 use assert_json_diff::assert_json_eq;
 use diesel::{Connection, PgConnection};
+use sdk_processor::utils::database::{new_db_pool, run_migrations, ArcDbPool};
 use serde_json::Value;
 use std::{
     collections::HashMap,
@@ -25,6 +26,8 @@ pub mod token_v2_processor_tests;
 
 #[cfg(test)]
 pub mod account_transaction_processor_tests;
+#[cfg(test)]
+pub mod account_summaries_tests;
 
 #[cfg(test)]
 pub mod default_processor_tests;
@@ -36,6 +39,21 @@ pub mod stake_processor_tests;
 
 #[cfg(test)]
 pub mod account_restoration_processor_tests;
+
+#[cfg(test)]
+pub mod backfill_shard_tests;
+#[cfg(test)]
+pub mod collection_stats_tests;
+#[cfg(test)]
+pub mod consistency_checker_tests;
+#[cfg(test)]
+pub mod object_root_owners_tests;
+#[cfg(test)]
+pub mod token_compositions_tests;
+#[cfg(test)]
+pub mod token_properties_tests;
+#[cfg(test)]
+pub mod transaction_metadata_processor_tests;
 // #[cfg(test)]
 // pub mod user_transaction_processor_tests;
 
@@ -75,6 +93,20 @@ pub async fn setup_test_environment(
     (db, test_context)
 }
 
+/// A database with every migration run, for tests that call the queries of the processors directly
+/// instead of running a processor over transactions.
+#[allow(dead_code)]
+pub async fn setup_test_database() -> (PostgresTestDatabase, ArcDbPool) {
+    let mut db = PostgresTestDatabase::new();
+    db.setup().await.unwrap();
+
+    let db_url = db.get_db_url();
+    let conn_pool = new_db_pool(&db_url, Some(10)).await.unwrap();
+    run_migrations(db_url, conn_pool.clone()).await;
+
+    (db, conn_pool)
+}
+
 #[allow(dead_code)]
 pub fn validate_json(
     db_values: &mut HashMap<String, Value>,
@@ -0,0 +1,225 @@
+#[cfg(test)]
+mod tests {
+    use crate::sdk_tests::setup_test_database;
+    use aptos_indexer_testing_framework::database::TestDatabase;
+    use bigdecimal::BigDecimal;
+    use diesel::{
+        sql_query,
+        sql_types::{Array, BigInt, Bool, Integer, Text},
+        Connection, PgConnection, RunQueryDsl,
+    };
+    use processor::db::postgres::models::object_models::v2_objects::CurrentObject;
+    use sdk_processor::{
+        steps::objects_processor::objects_storer::{
+            update_current_object_root_owners, MAX_OBJECT_OWNERSHIP_DEPTH,
+        },
+        utils::database::ArcDbPool,
+    };
+
+    #[derive(diesel::QueryableByName)]
+    struct RootOwner {
+        #[diesel(sql_type = Text)]
+        object_address: String,
+        #[diesel(sql_type = Text)]
+        root_owner_address: String,
+        #[diesel(sql_type = Integer)]
+        depth: i32,
+    }
+
+    fn object(
+        object_address: &str,
+        owner_address: &str,
+        version: i64,
+        is_deleted: bool,
+    ) -> CurrentObject {
+        CurrentObject {
+            object_address: object_address.to_string(),
+            owner_address: owner_address.to_string(),
+            state_key_hash: object_address.to_string(),
+            allow_ungated_transfer: true,
+            last_guid_creation_num: BigDecimal::from(0),
+            last_transaction_version: version,
+            is_deleted,
+            untransferrable: false,
+        }
+    }
+
+    /// Writes the objects to `current_objects`, as the storer does before updating the root owners,
+    /// then updates the root owners.
+    async fn store_objects(
+        conn: &mut PgConnection,
+        conn_pool: ArcDbPool,
+        objects: Vec<CurrentObject>,
+        version: i64,
+    ) {
+        for o in &objects {
+            sql_query(
+                "INSERT INTO current_objects (object_address, owner_address, state_key_hash, allow_ungated_transfer, last_guid_creation_num, last_transaction_version, is_deleted, untransferrable)
+                VALUES ($1, $2, $3, TRUE, 0, $4, $5, FALSE)
+                ON CONFLICT (object_address) DO UPDATE SET
+                    owner_address = EXCLUDED.owner_address,
+                    last_transaction_version = EXCLUDED.last_transaction_version,
+                    is_deleted = EXCLUDED.is_deleted",
+            )
+            .bind::<Text, _>(&o.object_address)
+            .bind::<Text, _>(&o.owner_address)
+            .bind::<Text, _>(&o.state_key_hash)
+            .bind::<BigInt, _>(o.last_transaction_version)
+            .bind::<Bool, _>(o.is_deleted)
+            .execute(conn)
+            .unwrap();
+        }
+        update_current_object_root_owners(conn_pool, &objects, version)
+            .await
+            .unwrap();
+    }
+
+    fn load_root_owners(
+        conn: &mut PgConnection,
+        object_addresses: &[&str],
+    ) -> Vec<(String, String, i32)> {
+        sql_query(
+            "SELECT object_address, root_owner_address, depth FROM current_object_root_owners WHERE object_address = ANY($1) ORDER BY object_address",
+        )
+        .bind::<Array<Text>, _>(object_addresses)
+        .load::<RootOwner>(conn)
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.object_address, r.root_owner_address, r.depth))
+        .collect()
+    }
+
+    fn root_owner(
+        object_address: &str,
+        root_owner_address: &str,
+        depth: i32,
+    ) -> (String, String, i32) {
+        (
+            object_address.to_string(),
+            root_owner_address.to_string(),
+            depth,
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_update_current_object_root_owners_cascade() {
+        let (db, conn_pool) = setup_test_database().await;
+        let mut conn = PgConnection::establish(&db.get_db_url()).unwrap();
+
+        // Account 0x1 owns 0xa, which owns 0xb, which owns 0xc
+        store_objects(
+            &mut conn,
+            conn_pool.clone(),
+            vec![
+                object("0xa", "0x1", 1, false),
+                object("0xb", "0xa", 1, false),
+                object("0xc", "0xb", 1, false),
+            ],
+            1,
+        )
+        .await;
+        assert_eq!(load_root_owners(&mut conn, &["0xa", "0xb", "0xc"]), vec![
+            root_owner("0xa", "0x1", 1),
+            root_owner("0xb", "0x1", 2),
+            root_owner("0xc", "0x1", 3),
+        ]);
+
+        // Transferring 0xa moves everything it owns with it, although only 0xa is in the batch
+        store_objects(
+            &mut conn,
+            conn_pool.clone(),
+            vec![object("0xa", "0x2", 2, false)],
+            2,
+        )
+        .await;
+        assert_eq!(load_root_owners(&mut conn, &["0xa", "0xb", "0xc"]), vec![
+            root_owner("0xa", "0x2", 1),
+            root_owner("0xb", "0x2", 2),
+            root_owner("0xc", "0x2", 3),
+        ]);
+
+        // Un-nesting 0xc to an account makes it its own chain
+        store_objects(
+            &mut conn,
+            conn_pool.clone(),
+            vec![object("0xc", "0x3", 3, false)],
+            3,
+        )
+        .await;
+        assert_eq!(load_root_owners(&mut conn, &["0xa", "0xb", "0xc"]), vec![
+            root_owner("0xa", "0x2", 1),
+            root_owner("0xb", "0x2", 2),
+            root_owner("0xc", "0x3", 1),
+        ]);
+
+        // A deleted object has no root owner anymore
+        store_objects(
+            &mut conn,
+            conn_pool.clone(),
+            vec![object("0xc", "0x3", 4, true)],
+            4,
+        )
+        .await;
+        assert_eq!(load_root_owners(&mut conn, &["0xa", "0xb", "0xc"]), vec![
+            root_owner("0xa", "0x2", 1),
+            root_owner("0xb", "0x2", 2),
+        ]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_update_current_object_root_owners_cycle() {
+        let (db, conn_pool) = setup_test_database().await;
+        let mut conn = PgConnection::establish(&db.get_db_url()).unwrap();
+
+        // 0xd and 0xe own each other. The update terminates, and following the chain stops at the
+        // depth cap.
+        store_objects(
+            &mut conn,
+            conn_pool.clone(),
+            vec![
+                object("0xd", "0xe", 1, false),
+                object("0xe", "0xd", 1, false),
+            ],
+            1,
+        )
+        .await;
+        let max_depth = MAX_OBJECT_OWNERSHIP_DEPTH as i32;
+        assert_eq!(load_root_owners(&mut conn, &["0xd", "0xe"]), vec![
+            root_owner("0xd", "0xd", max_depth),
+            root_owner("0xe", "0xe", max_depth),
+        ]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_update_current_object_root_owners_depth_cap() {
+        let (db, conn_pool) = setup_test_database().await;
+        let mut conn = PgConnection::establish(&db.get_db_url()).unwrap();
+
+        // A chain of 70 objects under account 0x1, where object i owns object i + 1
+        let addresses: Vec<String> = (1..=70).map(|i| format!("0x{:x}", 0x1000 + i)).collect();
+        let objects = addresses
+            .iter()
+            .enumerate()
+            .map(|(i, address)| {
+                let owner_address = if i == 0 {
+                    "0x1"
+                } else {
+                    addresses[i - 1].as_str()
+                };
+                object(address, owner_address, 1, false)
+            })
+            .collect();
+        store_objects(&mut conn, conn_pool.clone(), objects, 1).await;
+
+        // Objects up to 64 owners deep reach the account. Deeper ones stop at the object 64 owners
+        // above them.
+        let max_depth = MAX_OBJECT_OWNERSHIP_DEPTH as i32;
+        assert_eq!(
+            load_root_owners(&mut conn, &[addresses[63].as_str(), addresses[69].as_str()]),
+            vec![
+                root_owner(&addresses[63], "0x1", max_depth),
+                root_owner(&addresses[69], &addresses[5], max_depth),
+            ]
+        );
+    }
+}
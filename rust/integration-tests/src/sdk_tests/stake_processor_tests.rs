@@ -26,6 +26,7 @@ pub fn setup_stake_processor_config(
             per_table_chunk_sizes: AHashMap::new(),
             channel_size: 100,
             deprecated_tables: HashSet::new(),
+            cdc_sink: None,
         },
         // Avoid doing long lookups in tests
         query_retries: 1,
@@ -47,6 +48,8 @@ pub fn setup_stake_processor_config(
             backfill_config: None,
             bootstrap_config: None,
             testing_config: Some(testing_config),
+            gap_repair_config: None,
+            address_labels_config: None,
             mode: ProcessorMode::Testing,
         },
         processor_name,
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use crate::sdk_tests::setup_test_database;
+    use aptos_indexer_testing_framework::database::TestDatabase;
+    use diesel::{
+        sql_query,
+        sql_types::{BigInt, Nullable, Text},
+        Connection, PgConnection, RunQueryDsl,
+    };
+    use processor::db::postgres::models::token_v2_models::v2_token_compositions::TokenOwnerChange;
+    use sdk_processor::steps::token_v2_processor::token_v2_storer::update_token_compositions;
+
+    #[derive(diesel::QueryableByName)]
+    struct Composition {
+        #[diesel(sql_type = Text)]
+        child_token_data_id: String,
+        #[diesel(sql_type = BigInt)]
+        compose_transaction_version: i64,
+        #[diesel(sql_type = Text)]
+        parent_token_data_id: String,
+        #[diesel(sql_type = Nullable<BigInt>)]
+        decompose_transaction_version: Option<i64>,
+    }
+
+    fn change(token_data_id: &str, version: i64, owner_address: Option<&str>) -> TokenOwnerChange {
+        TokenOwnerChange {
+            token_data_id: token_data_id.to_string(),
+            transaction_version: version,
+            owner_address: owner_address.map(str::to_string),
+            transaction_timestamp: chrono::DateTime::from_timestamp(version, 0)
+                .unwrap()
+                .naive_utc(),
+        }
+    }
+
+    fn load_compositions(conn: &mut PgConnection) -> Vec<(String, i64, String, Option<i64>)> {
+        sql_query(
+            "SELECT child_token_data_id, compose_transaction_version, parent_token_data_id, decompose_transaction_version FROM token_compositions ORDER BY child_token_data_id, compose_transaction_version",
+        )
+        .load::<Composition>(conn)
+        .unwrap()
+        .into_iter()
+        .map(|c| {
+            (
+                c.child_token_data_id,
+                c.compose_transaction_version,
+                c.parent_token_data_id,
+                c.decompose_transaction_version,
+            )
+        })
+        .collect()
+    }
+
+    fn composition(
+        child_token_data_id: &str,
+        compose_version: i64,
+        parent_token_data_id: &str,
+        decompose_version: Option<i64>,
+    ) -> (String, i64, String, Option<i64>) {
+        (
+            child_token_data_id.to_string(),
+            compose_version,
+            parent_token_data_id.to_string(),
+            decompose_version,
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_update_token_compositions() {
+        let (db, conn_pool) = setup_test_database().await;
+        let mut conn = PgConnection::establish(&db.get_db_url()).unwrap();
+
+        sql_query(
+            "INSERT INTO current_token_datas_v2 (token_data_id, collection_id, token_name, token_uri, description, token_properties, token_standard, last_transaction_version, last_transaction_timestamp) VALUES
+            ('0xa', '0xc0', 'a', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00'),
+            ('0xb', '0xc0', 'b', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00'),
+            ('0xc', '0xc0', 'c', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+
+        // 0xc is equipped on 0xb, which is equipped on 0xa, owned by an account. Owners that aren't
+        // tokens don't make a composition.
+        let first_batch = vec![
+            change("0xa", 10, Some("0x1")),
+            change("0xb", 10, Some("0xa")),
+            change("0xc", 10, Some("0xb")),
+        ];
+        update_token_compositions(conn_pool.clone(), first_batch.clone())
+            .await
+            .unwrap();
+        let expected = vec![
+            composition("0xb", 10, "0xa", None),
+            composition("0xc", 10, "0xb", None),
+        ];
+        assert_eq!(load_compositions(&mut conn), expected);
+
+        // Replaying a batch doesn't add the compositions twice
+        update_token_compositions(conn_pool.clone(), first_batch)
+            .await
+            .unwrap();
+        assert_eq!(load_compositions(&mut conn), expected);
+
+        // 0xc is taken off 0xb, which closes its composition. 0xb is taken off 0xa and equipped on
+        // it again within the batch, which closes the open composition and adds another.
+        update_token_compositions(conn_pool.clone(), vec![
+            change("0xb", 20, Some("0x2")),
+            change("0xb", 30, Some("0xa")),
+            change("0xc", 20, Some("0x1")),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(load_compositions(&mut conn), vec![
+            composition("0xb", 10, "0xa", Some(20)),
+            composition("0xb", 30, "0xa", None),
+            composition("0xc", 10, "0xb", Some(20)),
+        ]);
+
+        // 0xc is equipped on 0xa and burned in the same transaction, so its composition opens and
+        // closes at the same version. Burning 0xb closes its open composition.
+        update_token_compositions(conn_pool.clone(), vec![
+            change("0xb", 40, None),
+            change("0xc", 40, Some("0xa")),
+            change("0xc", 40, None),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(load_compositions(&mut conn), vec![
+            composition("0xb", 10, "0xa", Some(20)),
+            composition("0xb", 30, "0xa", Some(40)),
+            composition("0xc", 10, "0xb", Some(20)),
+            composition("0xc", 40, "0xa", Some(40)),
+        ]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_update_token_compositions_replay_after_decompose() {
+        let (db, conn_pool) = setup_test_database().await;
+        let mut conn = PgConnection::establish(&db.get_db_url()).unwrap();
+
+        sql_query(
+            "INSERT INTO current_token_datas_v2 (token_data_id, collection_id, token_name, token_uri, description, token_properties, token_standard, last_transaction_version, last_transaction_timestamp) VALUES
+            ('0xa', '0xc0', 'a', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00'),
+            ('0xb', '0xc0', 'b', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+
+        // 0xb is equipped on 0xa at version 10 and moved to an account at version 20, in two batches
+        update_token_compositions(conn_pool.clone(), vec![change("0xb", 10, Some("0xa"))])
+            .await
+            .unwrap();
+        update_token_compositions(conn_pool.clone(), vec![change("0xb", 20, Some("0x1"))])
+            .await
+            .unwrap();
+        assert_eq!(
+            load_compositions(&mut conn),
+            vec![composition("0xb", 10, "0xa", Some(20))]
+        );
+
+        // Replaying the first batch afterwards leaves the closed composition as it is
+        update_token_compositions(conn_pool.clone(), vec![change("0xb", 10, Some("0xa"))])
+            .await
+            .unwrap();
+        assert_eq!(
+            load_compositions(&mut conn),
+            vec![composition("0xb", 10, "0xa", Some(20))]
+        );
+    }
+}
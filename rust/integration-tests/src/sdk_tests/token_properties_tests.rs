@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use crate::sdk_tests::setup_test_database;
+    use aptos_indexer_testing_framework::database::TestDatabase;
+    use diesel::{sql_query, sql_types::Text, Connection, PgConnection, RunQueryDsl};
+    use processor::db::postgres::models::token_v2_models::v2_token_properties::{
+        CurrentTokenProperty, TokenProperties,
+    };
+    use sdk_processor::steps::token_v2_processor::token_v2_storer::sync_token_properties;
+
+    const COLLECTION_ID: &str = "0xc0";
+
+    #[derive(diesel::QueryableByName)]
+    struct TraitCount {
+        #[diesel(sql_type = Text)]
+        property_key: String,
+        #[diesel(sql_type = Text)]
+        property_value: String,
+        #[diesel(sql_type = diesel::sql_types::BigInt)]
+        num_tokens: i64,
+    }
+
+    fn token(token_data_id: &str, version: i64, properties: &[(&str, &str)]) -> TokenProperties {
+        TokenProperties {
+            token_data_id: token_data_id.to_string(),
+            last_transaction_version: version,
+            properties: properties
+                .iter()
+                .map(|(key, value)| CurrentTokenProperty {
+                    token_data_id: token_data_id.to_string(),
+                    property_key: key.to_string(),
+                    property_type: "0x1::string::String".to_string(),
+                    property_value: value.to_string(),
+                    last_transaction_version: version,
+                })
+                .collect(),
+        }
+    }
+
+    fn load_trait_counts(conn: &mut PgConnection) -> Vec<(String, String, i64)> {
+        sql_query(
+            "SELECT property_key, property_value, num_tokens FROM current_collection_trait_counts WHERE collection_id = $1 ORDER BY property_key, property_value",
+        )
+        .bind::<Text, _>(COLLECTION_ID)
+        .load::<TraitCount>(conn)
+        .unwrap()
+        .into_iter()
+        .map(|c| (c.property_key, c.property_value, c.num_tokens))
+        .collect()
+    }
+
+    fn trait_count(key: &str, value: &str, num_tokens: i64) -> (String, String, i64) {
+        (key.to_string(), value.to_string(), num_tokens)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_sync_token_properties() {
+        let (db, conn_pool) = setup_test_database().await;
+        let mut conn = PgConnection::establish(&db.get_db_url()).unwrap();
+
+        sql_query(
+            "INSERT INTO current_token_datas_v2 (token_data_id, collection_id, token_name, token_uri, description, token_properties, token_standard, last_transaction_version, last_transaction_timestamp) VALUES
+            ('0xa', '0xc0', 'a', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00'),
+            ('0xb', '0xc0', 'b', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00'),
+            ('0xc', '0xc0', 'c', '', '', '{}', 'v2', 1, '2025-01-01 00:00:00')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+
+        // Values as long as the column, of multibyte characters, are too long for a B-tree index on
+        // the values themselves
+        let long_value = "🙂".repeat(1000);
+        let first_batch = vec![
+            token("0xa", 10, &[("Background", "Blue"), ("Eyes", "Green")]),
+            token("0xb", 10, &[("Background", "Blue")]),
+            token(
+                "0xc",
+                10,
+                &[("Background", "Red"), ("Motto", long_value.as_str())],
+            ),
+        ];
+        sync_token_properties(conn_pool.clone(), first_batch.clone())
+            .await
+            .unwrap();
+        let expected = vec![
+            trait_count("Background", "Blue", 2),
+            trait_count("Background", "Red", 1),
+            trait_count("Eyes", "Green", 1),
+            trait_count("Motto", &long_value, 1),
+        ];
+        assert_eq!(load_trait_counts(&mut conn), expected);
+
+        // Replaying a batch leaves the counts as they are
+        sync_token_properties(conn_pool.clone(), first_batch)
+            .await
+            .unwrap();
+        assert_eq!(load_trait_counts(&mut conn), expected);
+
+        // 0xa changes background and loses its eyes, and 0xb's property map is deleted. Traits no
+        // token has anymore are removed.
+        sync_token_properties(conn_pool.clone(), vec![
+            token("0xa", 20, &[("Background", "Red")]),
+            token("0xb", 20, &[]),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(load_trait_counts(&mut conn), vec![
+            trait_count("Background", "Red", 2),
+            trait_count("Motto", &long_value, 1),
+        ]);
+
+        // Properties older than the ones stored are ignored
+        let stale_batch = vec![token("0xa", 15, &[("Background", "Green")])];
+        sync_token_properties(conn_pool.clone(), stale_batch)
+            .await
+            .unwrap();
+        assert_eq!(load_trait_counts(&mut conn), vec![
+            trait_count("Background", "Red", 2),
+            trait_count("Motto", &long_value, 1),
+        ]);
+
+        // Tokens with a trait are looked up by the hashes of the key and value
+        #[derive(diesel::QueryableByName)]
+        struct Token {
+            #[diesel(sql_type = Text)]
+            token_data_id: String,
+        }
+        let token_data_ids = sql_query(
+            "SELECT token_data_id FROM current_token_properties WHERE collection_id = $1 AND property_key_hash = md5('Background') AND property_value_hash = md5('Red') ORDER BY token_data_id",
+        )
+        .bind::<Text, _>(COLLECTION_ID)
+        .load::<Token>(&mut conn)
+        .unwrap()
+        .into_iter()
+        .map(|t| t.token_data_id)
+        .collect::<Vec<_>>();
+        assert_eq!(token_data_ids, vec!["0xa", "0xc"]);
+    }
+}
@@ -0,0 +1,212 @@
+use ahash::AHashMap;
+use aptos_indexer_testing_framework::sdk_test_context::SdkTestContext;
+use sdk_processor::config::{
+    db_config::{DbConfig, PostgresConfig},
+    indexer_processor_config::{IndexerProcessorConfig, ProcessorMode, TestingConfig},
+    processor_config::{DefaultProcessorConfig, ProcessorConfig},
+};
+use std::collections::HashSet;
+
+pub fn setup_transaction_metadata_processor_config(
+    test_context: &SdkTestContext,
+    db_url: &str,
+) -> (IndexerProcessorConfig, &'static str) {
+    let transaction_stream_config = test_context.create_transaction_stream_config();
+    let postgres_config = PostgresConfig {
+        connection_string: db_url.to_string(),
+        db_pool_size: 100,
+    };
+
+    let db_config = DbConfig::PostgresConfig(postgres_config);
+    let default_processor_config = DefaultProcessorConfig {
+        per_table_chunk_sizes: AHashMap::new(),
+        channel_size: 100,
+        deprecated_tables: HashSet::new(),
+        cdc_sink: None,
+    };
+
+    let processor_config = ProcessorConfig::TransactionMetadataProcessor(default_processor_config);
+    let processor_name = processor_config.name();
+    let testing_config: TestingConfig = TestingConfig {
+        override_starting_version: transaction_stream_config.starting_version.unwrap(),
+        ending_version: transaction_stream_config.request_ending_version.unwrap(),
+    };
+
+    (
+        IndexerProcessorConfig {
+            processor_config,
+            transaction_stream_config,
+            db_config,
+            backfill_config: None,
+            bootstrap_config: None,
+            testing_config: Some(testing_config),
+            gap_repair_config: None,
+            address_labels_config: None,
+            mode: ProcessorMode::Testing,
+        },
+        processor_name,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sdk_tests::{
+        run_processor_test, setup_test_environment,
+        transaction_metadata_processor_tests::setup_transaction_metadata_processor_config,
+        DEFAULT_OUTPUT_FOLDER,
+    };
+    use aptos_indexer_test_transactions::json_transactions::generated_transactions::{
+        IMPORTED_TESTNET_TXNS_2_NEW_BLOCK_EVENT, IMPORTED_TESTNET_TXNS_5992795934_FA_ACTIVITIES,
+    };
+    use aptos_indexer_testing_framework::database::TestDatabase;
+    use aptos_protos::transaction::v1::Transaction;
+    use diesel::{
+        sql_query,
+        sql_types::{Array, BigInt, Text},
+        PgConnection, QueryableByName, RunQueryDsl,
+    };
+    use processor::db::postgres::models::transaction_metadata_model::transaction_gas_breakdown::TransactionGasBreakdown;
+    use sdk_processor::processors::transaction_metadata_processor::TransactionMetadataProcessor;
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    #[derive(QueryableByName)]
+    struct JsonRow {
+        #[diesel(sql_type = Text)]
+        row: String,
+    }
+
+    /// Loads the gas breakdown rows of the versions, and the hourly rollup rows, as json.
+    fn load_gas_data(
+        conn: &mut PgConnection,
+        txn_versions: Vec<i64>,
+    ) -> anyhow::Result<HashMap<String, Value>> {
+        let gas_breakdowns = sql_query(
+            "SELECT to_jsonb(g)::text AS row FROM transaction_gas_breakdown g WHERE transaction_version = ANY($1) ORDER BY transaction_version",
+        )
+        .bind::<Array<BigInt>, _>(txn_versions)
+        .load::<JsonRow>(conn)?;
+        let hourly = sql_query(
+            "SELECT to_jsonb(h)::text AS row FROM entry_function_gas_hourly h ORDER BY entry_function_id_str, hour",
+        )
+        .load::<JsonRow>(conn)?;
+
+        let to_json = |rows: Vec<JsonRow>| -> anyhow::Result<Value> {
+            Ok(Value::Array(
+                rows.into_iter()
+                    .map(|r| serde_json::from_str(&r.row))
+                    .collect::<Result<_, _>>()?,
+            ))
+        };
+        let mut result_map = HashMap::new();
+        result_map.insert(
+            "transaction_gas_breakdown".to_string(),
+            to_json(gas_breakdowns)?,
+        );
+        result_map.insert("entry_function_gas_hourly".to_string(), to_json(hourly)?);
+        Ok(result_map)
+    }
+
+    async fn run_transaction_metadata_processor(txn: &[u8]) -> HashMap<String, Value> {
+        let (db, mut test_context) = setup_test_environment(&[txn]).await;
+        let db_url = db.get_db_url();
+        let (indexer_processor_config, processor_name) =
+            setup_transaction_metadata_processor_config(&test_context, &db_url);
+        let transaction_metadata_processor =
+            TransactionMetadataProcessor::new(indexer_processor_config)
+                .await
+                .expect("Failed to create TransactionMetadataProcessor");
+
+        run_processor_test(
+            &mut test_context,
+            transaction_metadata_processor,
+            load_gas_data,
+            db_url,
+            false,
+            format!("{}/imported_testnet_txns", DEFAULT_OUTPUT_FOLDER),
+            Some(processor_name.to_string()),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("Test failed on {} due to {}", processor_name, e))
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn testnet_transaction_metadata_processor_gas_breakdown() {
+        let txn: Transaction =
+            serde_json::from_slice(IMPORTED_TESTNET_TXNS_5992795934_FA_ACTIVITIES).unwrap();
+        let expected = TransactionGasBreakdown::from_transaction(&txn)
+            .expect("The transaction should have a FeeStatement event");
+
+        let db_values =
+            run_transaction_metadata_processor(IMPORTED_TESTNET_TXNS_5992795934_FA_ACTIVITIES)
+                .await;
+
+        let gas_breakdowns = db_values["transaction_gas_breakdown"].as_array().unwrap();
+        assert_eq!(gas_breakdowns.len(), 1);
+        let row = &gas_breakdowns[0];
+        assert_eq!(row["transaction_version"], expected.transaction_version);
+        assert_eq!(row["sender"], expected.sender.as_str());
+        assert_eq!(
+            row["entry_function_id_str"],
+            expected.entry_function_id_str.as_str()
+        );
+        assert_eq!(
+            row["is_transaction_success"],
+            expected.is_transaction_success
+        );
+        for (column, value) in [
+            ("gas_unit_price", &expected.gas_unit_price),
+            ("gas_used", &expected.gas_used),
+            ("total_charge_gas_units", &expected.total_charge_gas_units),
+            ("execution_gas_units", &expected.execution_gas_units),
+            ("io_gas_units", &expected.io_gas_units),
+            ("storage_fee_octas", &expected.storage_fee_octas),
+            (
+                "storage_fee_refund_octas",
+                &expected.storage_fee_refund_octas,
+            ),
+        ] {
+            assert_eq!(row[column].to_string(), value.to_string(), "{}", column);
+        }
+
+        // The hourly rollup of the entry function is recomputed from the single breakdown row
+        let hourly = db_values["entry_function_gas_hourly"].as_array().unwrap();
+        assert_eq!(hourly.len(), 1);
+        assert_eq!(
+            hourly[0]["entry_function_id_str"],
+            expected.entry_function_id_str.as_str()
+        );
+        assert_eq!(hourly[0]["num_transactions"], 1);
+        assert_eq!(
+            hourly[0]["num_failed_transactions"],
+            i64::from(!expected.is_transaction_success)
+        );
+        assert_eq!(
+            hourly[0]["total_gas_used"].to_string(),
+            expected.gas_used.to_string()
+        );
+        assert_eq!(
+            hourly[0]["total_gas_fee_octas"].to_string(),
+            (&expected.gas_used * &expected.gas_unit_price).to_string()
+        );
+        assert_eq!(
+            hourly[0]["last_transaction_version"],
+            expected.transaction_version
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn testnet_transaction_metadata_processor_no_user_txn() {
+        let db_values =
+            run_transaction_metadata_processor(IMPORTED_TESTNET_TXNS_2_NEW_BLOCK_EVENT).await;
+
+        assert!(db_values["transaction_gas_breakdown"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+        assert!(db_values["entry_function_gas_hourly"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -39,6 +39,10 @@ pub struct IndexerGrpcProcessorConfig {
     // Maximum number of batches "missing" before we assume we have an issue with gaps and abort
     #[serde(default = "IndexerGrpcProcessorConfig::default_gap_detection_batch_size")]
     pub parquet_gap_detection_batch_size: u64,
+    // Record gaps in `processor_gaps` and skip past them instead of waiting for the missing batches.
+    // The skipped gaps are repaired by the gap repair of the sdk processor.
+    #[serde(default)]
+    pub skip_gaps: bool,
     // Number of protobuff transactions to send per chunk to the processor tasks
     #[serde(default = "IndexerGrpcProcessorConfig::default_pb_channel_txn_chunk_size")]
     pub pb_channel_txn_chunk_size: usize,
@@ -97,6 +101,7 @@ impl RunnableConfig for IndexerGrpcProcessorConfig {
             self.db_pool_size,
             self.gap_detection_batch_size,
             self.parquet_gap_detection_batch_size,
+            self.skip_gaps,
             self.pb_channel_txn_chunk_size,
             self.per_table_chunk_sizes.clone(),
             self.enable_verbose_logging,
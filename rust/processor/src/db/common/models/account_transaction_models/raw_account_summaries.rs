@@ -0,0 +1,233 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::raw_account_transactions::RawAccountTransaction;
+use crate::utils::util::{
+    get_entry_function_from_user_request, parse_timestamp, standardize_address,
+};
+use ahash::{AHashMap, AHashSet};
+use aptos_protos::transaction::v1::{transaction::TxnData, Transaction};
+use serde::{Deserialize, Serialize};
+
+/// Accounts at or below 0xa are framework accounts that most transactions touch, so they are
+/// nobody's counterparty.
+const MAX_FRAMEWORK_ADDRESS: &str =
+    "0x000000000000000000000000000000000000000000000000000000000000000a";
+
+/// An account touched by a transaction. The entry function is only set for the sender.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RawAccountActivity {
+    pub account_address: String,
+    pub transaction_version: i64,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+    pub entry_function_id_str: Option<String>,
+}
+
+/// The sender of a user transaction and another account it touched are each other's
+/// counterparties.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct RawAccountCounterparty {
+    pub account_address: String,
+    pub counterparty_address: String,
+    pub transaction_version: i64,
+}
+
+impl RawAccountActivity {
+    pub fn from_transaction(transaction: &Transaction) -> (Vec<Self>, Vec<RawAccountCounterparty>) {
+        let transaction_version = transaction.version as i64;
+        let accounts = RawAccountTransaction::get_accounts(transaction);
+        if accounts.is_empty() {
+            return (vec![], vec![]);
+        }
+        let transaction_timestamp = parse_timestamp(
+            transaction
+                .timestamp
+                .as_ref()
+                .expect("Transaction timestamp doesn't exist!"),
+            transaction_version,
+        );
+        let (sender, entry_function_id_str) = match transaction.txn_data.as_ref() {
+            Some(TxnData::User(user_txn)) => match user_txn.request.as_ref() {
+                Some(request) => (
+                    Some(standardize_address(&request.sender)),
+                    get_entry_function_from_user_request(request),
+                ),
+                None => (None, None),
+            },
+            _ => (None, None),
+        };
+
+        let mut counterparties = vec![];
+        if let Some(sender) = &sender {
+            for account_address in accounts.iter() {
+                if account_address == sender || account_address.as_str() <= MAX_FRAMEWORK_ADDRESS {
+                    continue;
+                }
+                counterparties.push(RawAccountCounterparty {
+                    account_address: sender.clone(),
+                    counterparty_address: account_address.clone(),
+                    transaction_version,
+                });
+                counterparties.push(RawAccountCounterparty {
+                    account_address: account_address.clone(),
+                    counterparty_address: sender.clone(),
+                    transaction_version,
+                });
+            }
+        }
+        let activities = accounts
+            .into_iter()
+            .map(|account_address| Self {
+                entry_function_id_str: if sender.as_ref() == Some(&account_address) {
+                    entry_function_id_str.clone()
+                } else {
+                    None
+                },
+                account_address,
+                transaction_version,
+                transaction_timestamp,
+            })
+            .collect();
+        (activities, counterparties)
+    }
+}
+
+/// Activity of an account within a batch of transactions.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RawAccountSummary {
+    pub account_address: String,
+    pub first_transaction_version: i64,
+    pub first_transaction_timestamp: chrono::NaiveDateTime,
+    pub last_transaction_version: i64,
+    pub last_transaction_timestamp: chrono::NaiveDateTime,
+    pub num_transactions: i64,
+    pub num_counterparties: i64,
+    pub last_entry_function_id_str: Option<String>,
+    pub last_entry_function_version: Option<i64>,
+}
+
+pub trait AccountSummaryConvertible {
+    fn from_raw(raw_item: RawAccountSummary) -> Self;
+}
+
+impl RawAccountSummary {
+    /// Summaries of the accounts of a batch, ordered by account. Counterparties are only counted
+    /// once within the batch.
+    pub fn from_activities(
+        activities: &[RawAccountActivity],
+        counterparties: &[RawAccountCounterparty],
+    ) -> Vec<Self> {
+        let mut summaries: AHashMap<&str, Self> = AHashMap::new();
+        for activity in activities {
+            let summary = summaries
+                .entry(activity.account_address.as_str())
+                .or_insert_with(|| Self {
+                    account_address: activity.account_address.clone(),
+                    first_transaction_version: activity.transaction_version,
+                    first_transaction_timestamp: activity.transaction_timestamp,
+                    last_transaction_version: activity.transaction_version,
+                    last_transaction_timestamp: activity.transaction_timestamp,
+                    num_transactions: 0,
+                    num_counterparties: 0,
+                    last_entry_function_id_str: None,
+                    last_entry_function_version: None,
+                });
+            summary.num_transactions += 1;
+            if activity.transaction_version < summary.first_transaction_version {
+                summary.first_transaction_version = activity.transaction_version;
+                summary.first_transaction_timestamp = activity.transaction_timestamp;
+            }
+            if activity.transaction_version > summary.last_transaction_version {
+                summary.last_transaction_version = activity.transaction_version;
+                summary.last_transaction_timestamp = activity.transaction_timestamp;
+            }
+            if activity.entry_function_id_str.is_some()
+                && summary.last_entry_function_version < Some(activity.transaction_version)
+            {
+                summary.last_entry_function_id_str = activity.entry_function_id_str.clone();
+                summary.last_entry_function_version = Some(activity.transaction_version);
+            }
+        }
+        let distinct_counterparties: AHashSet<(&str, &str)> = counterparties
+            .iter()
+            .map(|c| (c.account_address.as_str(), c.counterparty_address.as_str()))
+            .collect();
+        for (account_address, _) in distinct_counterparties {
+            if let Some(summary) = summaries.get_mut(account_address) {
+                summary.num_counterparties += 1;
+            }
+        }
+
+        let mut summaries: Vec<Self> = summaries.into_values().collect();
+        summaries.sort_by(|a, b| a.account_address.cmp(&b.account_address));
+        summaries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(
+        account_address: &str,
+        version: i64,
+        entry_function: Option<&str>,
+    ) -> RawAccountActivity {
+        RawAccountActivity {
+            account_address: account_address.to_string(),
+            transaction_version: version,
+            transaction_timestamp: chrono::DateTime::from_timestamp(version, 0)
+                .unwrap()
+                .naive_utc(),
+            entry_function_id_str: entry_function.map(|f| f.to_string()),
+        }
+    }
+
+    fn counterparty(
+        account_address: &str,
+        counterparty_address: &str,
+        version: i64,
+    ) -> RawAccountCounterparty {
+        RawAccountCounterparty {
+            account_address: account_address.to_string(),
+            counterparty_address: counterparty_address.to_string(),
+            transaction_version: version,
+        }
+    }
+
+    #[test]
+    fn test_summaries_from_activities() {
+        let activities = vec![
+            activity("0xa1", 10, Some("0x1::aptos_account::transfer")),
+            activity("0xb2", 10, None),
+            activity("0xa1", 12, None),
+            activity("0xa1", 11, Some("0x1::coin::transfer")),
+        ];
+        let counterparties = vec![
+            counterparty("0xa1", "0xb2", 10),
+            counterparty("0xb2", "0xa1", 10),
+            counterparty("0xa1", "0xb2", 11),
+            counterparty("0xb2", "0xa1", 11),
+        ];
+        let summaries = RawAccountSummary::from_activities(&activities, &counterparties);
+
+        assert_eq!(summaries.len(), 2);
+        let a1 = &summaries[0];
+        assert_eq!(a1.account_address, "0xa1");
+        assert_eq!(
+            (a1.first_transaction_version, a1.last_transaction_version),
+            (10, 12)
+        );
+        assert_eq!(a1.num_transactions, 3);
+        assert_eq!(a1.num_counterparties, 1);
+        assert_eq!(
+            a1.last_entry_function_id_str.as_deref(),
+            Some("0x1::coin::transfer")
+        );
+        assert_eq!(a1.last_entry_function_version, Some(11));
+        let b2 = &summaries[1];
+        assert_eq!(b2.num_transactions, 1);
+        assert_eq!(b2.num_counterparties, 1);
+        assert_eq!(b2.last_entry_function_id_str, None);
+    }
+}
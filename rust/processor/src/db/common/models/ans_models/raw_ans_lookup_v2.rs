@@ -10,7 +10,10 @@ use crate::{
         common::models::token_v2_models::v2_token_utils::TokenStandard,
         postgres::models::ans_models::{
             ans_lookup::{AnsLookup, CurrentAnsLookup},
-            ans_utils::{get_token_name, NameRecordV2, SubdomainExtV2},
+            ans_utils::{
+                get_token_name, NameRecordV2, NamingServiceConfig, SubdomainExtV2,
+                DEFAULT_NAMESPACE,
+            },
         },
     },
     utils::util::standardize_address,
@@ -19,11 +22,12 @@ use ahash::AHashMap;
 use aptos_protos::transaction::v1::WriteResource;
 use serde::{Deserialize, Serialize};
 
+type Namespace = String;
 type Domain = String;
 type Subdomain = String;
 pub type TokenStandardType = String;
 // PK of current_ans_lookup_v2
-type CurrentAnsLookupV2PK = (Domain, Subdomain, TokenStandardType);
+type CurrentAnsLookupV2PK = (Namespace, Domain, Subdomain, TokenStandardType);
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RawAnsLookupV2 {
@@ -37,6 +41,7 @@ pub struct RawAnsLookupV2 {
     pub token_name: String,
     pub is_deleted: bool,
     pub subdomain_expiration_policy: Option<i64>,
+    pub namespace: String,
 }
 
 pub trait AnsLookupV2Convertible {
@@ -54,12 +59,14 @@ pub struct RawCurrentAnsLookupV2 {
     pub token_name: String,
     pub is_deleted: bool,
     pub subdomain_expiration_policy: Option<i64>,
+    pub namespace: String,
 }
 
 impl Ord for RawCurrentAnsLookupV2 {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.domain
-            .cmp(&other.domain)
+        self.namespace
+            .cmp(&other.namespace)
+            .then(self.domain.cmp(&other.domain))
             .then(self.subdomain.cmp(&other.subdomain))
     }
 }
@@ -73,6 +80,7 @@ impl PartialOrd for RawCurrentAnsLookupV2 {
 impl RawCurrentAnsLookupV2 {
     pub fn pk(&self) -> CurrentAnsLookupV2PK {
         (
+            self.namespace.clone(),
             self.domain.clone(),
             self.subdomain.clone(),
             self.token_standard.clone(),
@@ -94,6 +102,7 @@ impl RawCurrentAnsLookupV2 {
                 token_name: v1_current_ans_lookup.token_name,
                 is_deleted: v1_current_ans_lookup.is_deleted,
                 subdomain_expiration_policy: None,
+                namespace: DEFAULT_NAMESPACE.to_string(),
             },
             RawAnsLookupV2 {
                 transaction_version: v1_ans_lookup.transaction_version,
@@ -106,20 +115,20 @@ impl RawCurrentAnsLookupV2 {
                 token_name: v1_ans_lookup.token_name,
                 is_deleted: v1_ans_lookup.is_deleted,
                 subdomain_expiration_policy: None,
+                namespace: DEFAULT_NAMESPACE.to_string(),
             },
         )
     }
 
     pub fn parse_name_record_from_write_resource_v2(
         write_resource: &WriteResource,
-        ans_v2_contract_address: &str,
+        naming_service: &NamingServiceConfig,
         txn_version: i64,
         write_set_change_index: i64,
         address_to_subdomain_ext: &AHashMap<String, SubdomainExtV2>,
     ) -> anyhow::Result<Option<(Self, RawAnsLookupV2)>> {
         if let Some(inner) =
-            NameRecordV2::from_write_resource(write_resource, ans_v2_contract_address, txn_version)
-                .unwrap()
+            NameRecordV2::from_write_resource(write_resource, naming_service, txn_version).unwrap()
         {
             // If this resource account has a SubdomainExt, then it's a subdomain
             let (subdomain_name, subdomain_expiration_policy) = match address_to_subdomain_ext
@@ -145,6 +154,7 @@ impl RawCurrentAnsLookupV2 {
                     last_transaction_version: txn_version,
                     is_deleted: false,
                     subdomain_expiration_policy,
+                    namespace: naming_service.namespace.clone(),
                 },
                 RawAnsLookupV2 {
                     transaction_version: txn_version,
@@ -157,6 +167,7 @@ impl RawCurrentAnsLookupV2 {
                     token_name,
                     is_deleted: false,
                     subdomain_expiration_policy,
+                    namespace: naming_service.namespace.clone(),
                 },
             )));
         }
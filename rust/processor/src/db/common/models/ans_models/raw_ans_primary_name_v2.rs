@@ -9,14 +9,15 @@ use crate::db::{
     common::models::token_v2_models::v2_token_utils::TokenStandard,
     postgres::models::ans_models::{
         ans_lookup::{AnsPrimaryName, CurrentAnsPrimaryName},
-        ans_utils::SetReverseLookupEvent,
+        ans_utils::{NamingServiceConfig, SetReverseLookupEvent, DEFAULT_NAMESPACE},
     },
 };
 use aptos_protos::transaction::v1::Event;
 use serde::{Deserialize, Serialize};
+type Namespace = String;
 type RegisteredAddress = String;
 // PK of current_ans_primary_nameTokenStandard
-type CurrentAnsPrimaryNameV2PK = (RegisteredAddress, TokenStandardType);
+type CurrentAnsPrimaryNameV2PK = (Namespace, RegisteredAddress, TokenStandardType);
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RawAnsPrimaryNameV2 {
@@ -29,6 +30,7 @@ pub struct RawAnsPrimaryNameV2 {
     pub token_name: Option<String>,
     pub is_deleted: bool,
     pub transaction_timestamp: chrono::NaiveDateTime,
+    pub namespace: String,
 }
 
 pub trait AnsPrimaryNameV2Convertible {
@@ -44,11 +46,14 @@ pub struct RawCurrentAnsPrimaryNameV2 {
     pub token_name: Option<String>,
     pub is_deleted: bool,
     pub last_transaction_version: i64,
+    pub namespace: String,
 }
 
 impl Ord for RawCurrentAnsPrimaryNameV2 {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.registered_address.cmp(&other.registered_address)
+        self.namespace
+            .cmp(&other.namespace)
+            .then(self.registered_address.cmp(&other.registered_address))
     }
 }
 
@@ -60,7 +65,11 @@ impl PartialOrd for RawCurrentAnsPrimaryNameV2 {
 
 impl RawCurrentAnsPrimaryNameV2 {
     pub fn pk(&self) -> CurrentAnsPrimaryNameV2PK {
-        (self.registered_address.clone(), self.token_standard.clone())
+        (
+            self.namespace.clone(),
+            self.registered_address.clone(),
+            self.token_standard.clone(),
+        )
     }
 
     pub fn get_v2_from_v1(
@@ -77,6 +86,7 @@ impl RawCurrentAnsPrimaryNameV2 {
                 token_name: v1_current_primary_name.token_name,
                 is_deleted: v1_current_primary_name.is_deleted,
                 last_transaction_version: v1_current_primary_name.last_transaction_version,
+                namespace: DEFAULT_NAMESPACE.to_string(),
             },
             RawAnsPrimaryNameV2 {
                 transaction_version: v1_primary_name.transaction_version,
@@ -88,6 +98,7 @@ impl RawCurrentAnsPrimaryNameV2 {
                 token_name: v1_primary_name.token_name,
                 is_deleted: v1_primary_name.is_deleted,
                 transaction_timestamp: txn_timestamp,
+                namespace: DEFAULT_NAMESPACE.to_string(),
             },
         )
     }
@@ -97,11 +108,11 @@ impl RawCurrentAnsPrimaryNameV2 {
         event: &Event,
         txn_version: i64,
         event_index: i64,
-        ans_v2_contract_address: &str,
+        naming_service: &NamingServiceConfig,
         txn_timestamp: chrono::NaiveDateTime,
     ) -> anyhow::Result<Option<(Self, RawAnsPrimaryNameV2)>> {
         if let Some(set_reverse_lookup_event) =
-            SetReverseLookupEvent::from_event(event, ans_v2_contract_address, txn_version).unwrap()
+            SetReverseLookupEvent::from_event(event, naming_service, txn_version).unwrap()
         {
             if set_reverse_lookup_event.get_curr_domain_trunc().is_empty() {
                 // Handle case where the address's primary name is unset
@@ -114,6 +125,7 @@ impl RawCurrentAnsPrimaryNameV2 {
                         token_name: None,
                         last_transaction_version: txn_version,
                         is_deleted: true,
+                        namespace: naming_service.namespace.clone(),
                     },
                     RawAnsPrimaryNameV2 {
                         transaction_version: txn_version,
@@ -125,6 +137,7 @@ impl RawCurrentAnsPrimaryNameV2 {
                         token_name: None,
                         is_deleted: true,
                         transaction_timestamp: txn_timestamp,
+                        namespace: naming_service.namespace.clone(),
                     },
                 )));
             } else {
@@ -138,6 +151,7 @@ impl RawCurrentAnsPrimaryNameV2 {
                         token_name: Some(set_reverse_lookup_event.get_curr_token_name()),
                         last_transaction_version: txn_version,
                         is_deleted: false,
+                        namespace: naming_service.namespace.clone(),
                     },
                     RawAnsPrimaryNameV2 {
                         transaction_version: txn_version,
@@ -149,6 +163,7 @@ impl RawCurrentAnsPrimaryNameV2 {
                         token_name: Some(set_reverse_lookup_event.get_curr_token_name()),
                         is_deleted: false,
                         transaction_timestamp: txn_timestamp,
+                        namespace: naming_service.namespace.clone(),
                     },
                 )));
             }
@@ -39,6 +39,9 @@ pub struct RawTokenActivityV2 {
     pub token_standard: String,
     pub is_fungible_v2: Option<bool>,
     pub transaction_timestamp: chrono::NaiveDateTime,
+    /// Collection of the token, when the event or the transaction tells it. This isn't stored with
+    /// the activity, it's used to roll up the activities of each collection.
+    pub collection_id: Option<String>,
 }
 
 /// A simplified TokenActivity (excluded common fields) to reduce code duplication
@@ -85,6 +88,16 @@ impl RawTokenActivityV2 {
                 V2TokenEvent::TransferEvent(inner) => inner.get_object_address(),
                 _ => event_account_address.clone(),
             };
+            let collection_id = match token_event {
+                V2TokenEvent::Burn(inner) => Some(inner.get_collection_address()),
+                V2TokenEvent::MintEvent(_) | V2TokenEvent::BurnEvent(_) => {
+                    Some(event_account_address.clone())
+                },
+                _ => token_v2_metadata
+                    .get(&token_data_id)
+                    .and_then(|metadata| metadata.token.as_ref())
+                    .map(|token| token.get_collection_address()),
+            };
 
             if let Some(metadata) = token_v2_metadata.get(&token_data_id) {
                 let object_core = &metadata.object.object_core;
@@ -162,6 +175,7 @@ impl RawTokenActivityV2 {
                     token_standard: TokenStandard::V2.to_string(),
                     is_fungible_v2: None,
                     transaction_timestamp: txn_timestamp,
+                    collection_id,
                 }));
             } else {
                 // If the object metadata isn't found in the transaction, then the token was burnt.
@@ -190,6 +204,7 @@ impl RawTokenActivityV2 {
                     token_standard: TokenStandard::V2.to_string(),
                     is_fungible_v2: None,
                     transaction_timestamp: txn_timestamp,
+                    collection_id,
                 }));
             }
         }
@@ -349,6 +364,7 @@ impl RawTokenActivityV2 {
                 token_standard: TokenStandard::V1.to_string(),
                 is_fungible_v2: None,
                 transaction_timestamp: txn_timestamp,
+                collection_id: Some(token_data_id_struct.get_collection_id()),
             }));
         }
         Ok(None)
@@ -60,6 +60,40 @@ impl FromStr for TokenStandard {
     }
 }
 
+/// Token activities counted in the collection stats. v1 transfers are counted by their withdrawal,
+/// since v1 mints deposit the new token as well.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CollectionActivityType {
+    Transfer,
+    Burn,
+}
+
+impl CollectionActivityType {
+    pub fn from_activity_type(activity_type: &str) -> Option<Self> {
+        match activity_type {
+            "0x1::object::TransferEvent"
+            | "0x1::object::Transfer"
+            | "0x3::token::WithdrawEvent"
+            | "0x3::token::TokenWithdraw" => Some(Self::Transfer),
+            "0x4::collection::BurnEvent"
+            | "0x4::collection::Burn"
+            | "0x3::token::BurnTokenEvent"
+            | "0x3::token::Burn" => Some(Self::Burn),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CollectionActivityType {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let res = match self {
+            CollectionActivityType::Transfer => "transfer",
+            CollectionActivityType::Burn => "burn",
+        };
+        write!(f, "{}", res)
+    }
+}
+
 /* Section on Collection / Token */
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Collection {
@@ -0,0 +1,67 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::{
+    bq_analytics::generic_parquet_processor::{GetTimeStamp, HasVersion, NamedTable},
+    db::common::models::account_transaction_models::raw_account_summaries::{
+        AccountSummaryConvertible, RawAccountSummary,
+    },
+};
+use allocative_derive::Allocative;
+use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
+use serde::{Deserialize, Serialize};
+
+/// Activity of an account within a single batch of transactions. Consumers sum the counts of an
+/// account's rows and take the first and last activity across them. Counterparties are distinct
+/// within a batch only.
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct AccountSummary {
+    pub account_address: String,
+    pub first_transaction_version: i64,
+    #[allocative(skip)]
+    pub first_block_timestamp: chrono::NaiveDateTime,
+    pub last_transaction_version: i64,
+    #[allocative(skip)]
+    pub last_block_timestamp: chrono::NaiveDateTime,
+    pub num_transactions: i64,
+    pub num_counterparties: i64,
+    pub last_entry_function_id_str: Option<String>,
+    pub last_entry_function_version: Option<i64>,
+}
+
+impl NamedTable for AccountSummary {
+    const TABLE_NAME: &'static str = "account_summaries";
+}
+
+impl HasVersion for AccountSummary {
+    fn version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
+impl GetTimeStamp for AccountSummary {
+    fn get_timestamp(&self) -> chrono::NaiveDateTime {
+        self.last_block_timestamp
+    }
+}
+
+impl AccountSummaryConvertible for AccountSummary {
+    fn from_raw(raw_item: RawAccountSummary) -> Self {
+        Self {
+            account_address: raw_item.account_address,
+            first_transaction_version: raw_item.first_transaction_version,
+            first_block_timestamp: raw_item.first_transaction_timestamp,
+            last_transaction_version: raw_item.last_transaction_version,
+            last_block_timestamp: raw_item.last_transaction_timestamp,
+            num_transactions: raw_item.num_transactions,
+            num_counterparties: raw_item.num_counterparties,
+            last_entry_function_id_str: raw_item.last_entry_function_id_str,
+            last_entry_function_version: raw_item.last_entry_function_version,
+        }
+    }
+}
@@ -5,7 +5,10 @@
 #![allow(clippy::extra_unused_lifetimes)]
 #![allow(clippy::unused_unit)]
 
-use crate::bq_analytics::generic_parquet_processor::{GetTimeStamp, HasVersion, NamedTable};
+use crate::{
+    bq_analytics::generic_parquet_processor::{GetTimeStamp, HasVersion, NamedTable},
+    utils::address_labels::Labeled,
+};
 use allocative_derive::Allocative;
 use field_count::FieldCount;
 use parquet_derive::ParquetRecordWriter;
@@ -21,6 +24,7 @@ pub struct AccountTransaction {
     pub account_address: String,
     #[allocative(skip)]
     pub block_timestamp: chrono::NaiveDateTime,
+    pub label: Option<String>,
 }
 
 impl NamedTable for AccountTransaction {
@@ -38,3 +42,13 @@ impl GetTimeStamp for AccountTransaction {
         self.block_timestamp
     }
 }
+
+impl Labeled for AccountTransaction {
+    fn labeled_address(&self) -> Option<&str> {
+        Some(&self.account_address)
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+}
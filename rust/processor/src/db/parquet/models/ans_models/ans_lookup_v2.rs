@@ -29,6 +29,7 @@ pub struct AnsLookupV2 {
     pub token_name: String,
     pub is_deleted: bool,
     pub subdomain_expiration_policy: Option<i64>,
+    pub namespace: String,
 }
 
 impl NamedTable for AnsLookupV2 {
@@ -61,6 +62,7 @@ impl AnsLookupV2Convertible for AnsLookupV2 {
             token_name: raw_item.token_name,
             is_deleted: raw_item.is_deleted,
             subdomain_expiration_policy: raw_item.subdomain_expiration_policy,
+            namespace: raw_item.namespace,
         }
     }
 }
@@ -77,6 +79,7 @@ pub struct CurrentAnsLookupV2 {
     pub token_name: String,
     pub is_deleted: bool,
     pub subdomain_expiration_policy: Option<i64>,
+    pub namespace: String,
 }
 
 impl NamedTable for CurrentAnsLookupV2 {
@@ -108,6 +111,7 @@ impl CurrentAnsLookupV2Convertible for CurrentAnsLookupV2 {
             token_name: raw_item.token_name,
             is_deleted: raw_item.is_deleted,
             subdomain_expiration_policy: raw_item.subdomain_expiration_policy,
+            namespace: raw_item.namespace,
         }
     }
 }
@@ -27,6 +27,7 @@ pub struct AnsPrimaryNameV2 {
     pub is_deleted: bool,
     #[allocative(skip)]
     pub block_timestamp: chrono::NaiveDateTime,
+    pub namespace: String,
 }
 
 impl NamedTable for AnsPrimaryNameV2 {
@@ -57,6 +58,7 @@ impl AnsPrimaryNameV2Convertible for AnsPrimaryNameV2 {
             token_name: raw_item.token_name,
             is_deleted: raw_item.is_deleted,
             block_timestamp: raw_item.transaction_timestamp,
+            namespace: raw_item.namespace,
         }
     }
 }
@@ -70,6 +72,7 @@ pub struct CurrentAnsPrimaryNameV2 {
     pub token_name: Option<String>,
     pub is_deleted: bool,
     pub last_transaction_version: i64,
+    pub namespace: String,
 }
 
 impl NamedTable for CurrentAnsPrimaryNameV2 {
@@ -99,6 +102,7 @@ impl CurrentAnsPrimaryNameV2Convertible for CurrentAnsPrimaryNameV2 {
             token_name: raw_item.token_name,
             is_deleted: raw_item.is_deleted,
             last_transaction_version: raw_item.last_transaction_version,
+            namespace: raw_item.namespace,
         }
     }
 }
@@ -13,7 +13,7 @@ use crate::{
         },
         postgres::models::coin_models::coin_utils::EventGuidResource,
     },
-    utils::util::bigdecimal_to_u64,
+    utils::{address_labels::Labeled, util::bigdecimal_to_u64},
 };
 use ahash::AHashMap;
 use allocative::Allocative;
@@ -53,6 +53,7 @@ pub struct FungibleAssetActivity {
     #[allocative(skip)]
     pub block_timestamp: chrono::NaiveDateTime,
     pub storage_refund_octa: u64,
+    pub label: Option<String>,
 }
 
 impl NamedTable for FungibleAssetActivity {
@@ -90,6 +91,17 @@ impl FungibleAssetActivityConvertible for FungibleAssetActivity {
             token_standard: raw_item.token_standard,
             block_timestamp: raw_item.transaction_timestamp,
             storage_refund_octa: bigdecimal_to_u64(&raw_item.storage_refund_amount),
+            label: None,
         }
     }
 }
+
+impl Labeled for FungibleAssetActivity {
+    fn labeled_address(&self) -> Option<&str> {
+        self.owner_address.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+}
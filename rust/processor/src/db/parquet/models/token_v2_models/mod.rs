@@ -1,5 +1,6 @@
 pub mod token_claims;
 pub mod v1_token_royalty;
+pub mod v2_collection_activity_hourly;
 pub mod v2_token_activities;
 pub mod v2_token_datas;
 pub mod v2_token_metadata;
@@ -0,0 +1,185 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::extra_unused_lifetimes)]
+
+use super::v2_token_datas::CurrentTokenDataV2;
+use crate::{
+    bq_analytics::generic_parquet_processor::{GetTimeStamp, HasVersion, NamedTable},
+    db::common::models::token_v2_models::{
+        raw_v2_token_activities::RawTokenActivityV2, v2_token_utils::CollectionActivityType,
+    },
+};
+use ahash::AHashMap;
+use allocative_derive::Allocative;
+use chrono::Timelike;
+use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
+use serde::{Deserialize, Serialize};
+
+/// Token transfers and burns per collection per hour, aggregated over a single batch of
+/// transactions. An hour can span several batches and so several rows; consumers sum the rows of a
+/// (collection, hour) to get its totals.
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct CollectionActivityHourly {
+    pub collection_id: String,
+    #[allocative(skip)]
+    pub hour: chrono::NaiveDateTime,
+    pub num_transfers: u64,
+    pub num_burns: u64,
+    pub first_transaction_version: i64,
+    pub last_transaction_version: i64,
+}
+
+impl NamedTable for CollectionActivityHourly {
+    const TABLE_NAME: &'static str = "collection_activity_hourly";
+}
+
+impl HasVersion for CollectionActivityHourly {
+    fn version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
+impl GetTimeStamp for CollectionActivityHourly {
+    fn get_timestamp(&self) -> chrono::NaiveDateTime {
+        self.hour
+    }
+}
+
+impl CollectionActivityHourly {
+    /// Rolls up the transfers and burns of a batch, ordered by hour and then collection. The
+    /// collection comes from the activity, which has it for v1 tokens, v2 burns and v2 tokens whose
+    /// token resource is in the transaction. Otherwise it's looked up in the token datas of the batch,
+    /// and activities of tokens found in neither are skipped.
+    pub fn from_activities(
+        token_activities: &[RawTokenActivityV2],
+        current_token_datas: &[CurrentTokenDataV2],
+    ) -> Vec<Self> {
+        let token_collections: AHashMap<&str, &str> = current_token_datas
+            .iter()
+            .map(|token_data| {
+                (
+                    token_data.token_data_id.as_str(),
+                    token_data.collection_id.as_str(),
+                )
+            })
+            .collect();
+        let mut rollups: AHashMap<(&str, chrono::NaiveDateTime), Self> = AHashMap::new();
+        for activity in token_activities {
+            let Some(activity_type) = CollectionActivityType::from_activity_type(&activity.type_)
+            else {
+                continue;
+            };
+            let Some(collection_id) = activity.collection_id.as_deref().or_else(|| {
+                token_collections
+                    .get(activity.token_data_id.as_str())
+                    .copied()
+            }) else {
+                continue;
+            };
+            let hour = truncate_to_hour(activity.transaction_timestamp);
+            let rollup = rollups
+                .entry((collection_id, hour))
+                .or_insert_with(|| Self {
+                    collection_id: collection_id.to_string(),
+                    hour,
+                    first_transaction_version: activity.transaction_version,
+                    last_transaction_version: activity.transaction_version,
+                    ..Default::default()
+                });
+            match activity_type {
+                CollectionActivityType::Transfer => rollup.num_transfers += 1,
+                CollectionActivityType::Burn => rollup.num_burns += 1,
+            }
+            rollup.first_transaction_version = rollup
+                .first_transaction_version
+                .min(activity.transaction_version);
+            rollup.last_transaction_version = rollup
+                .last_transaction_version
+                .max(activity.transaction_version);
+        }
+        let mut rollups: Vec<Self> = rollups.into_values().collect();
+        rollups.sort_by(|a, b| (a.hour, &a.collection_id).cmp(&(b.hour, &b.collection_id)));
+        rollups
+    }
+}
+
+fn truncate_to_hour(timestamp: chrono::NaiveDateTime) -> chrono::NaiveDateTime {
+    timestamp
+        .date()
+        .and_hms_opt(timestamp.hour(), 0, 0)
+        .expect("Hour of a valid timestamp is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigdecimal::{BigDecimal, One, Zero};
+
+    fn activity(
+        transaction_version: i64,
+        token_data_id: &str,
+        type_: &str,
+        collection_id: Option<&str>,
+    ) -> RawTokenActivityV2 {
+        RawTokenActivityV2 {
+            transaction_version,
+            event_index: 0,
+            event_account_address: token_data_id.to_string(),
+            token_data_id: token_data_id.to_string(),
+            property_version_v1: BigDecimal::zero(),
+            type_: type_.to_string(),
+            from_address: None,
+            to_address: None,
+            token_amount: BigDecimal::one(),
+            before_value: None,
+            after_value: None,
+            entry_function_id_str: None,
+            token_standard: "v2".to_string(),
+            is_fungible_v2: None,
+            transaction_timestamp: chrono::NaiveDateTime::parse_from_str(
+                "2025-03-21 10:30:00",
+                "%Y-%m-%d %H:%M:%S",
+            )
+            .unwrap(),
+            collection_id: collection_id.map(|collection_id| collection_id.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_from_activities() {
+        let token_datas = vec![CurrentTokenDataV2 {
+            token_data_id: "0xa".to_string(),
+            collection_id: "0xc".to_string(),
+            ..Default::default()
+        }];
+        let rollups = CollectionActivityHourly::from_activities(
+            &[
+                // Found in the token datas of the batch
+                activity(1, "0xa", "0x1::object::TransferEvent", None),
+                activity(2, "0xa", "0x4::collection::MutationEvent", None),
+                // The collection of the activity is used when it has one
+                activity(3, "0xb", "0x4::collection::Burn", Some("0xc")),
+                activity(4, "0xd", "0x3::token::WithdrawEvent", Some("0xe")),
+                // Neither in the activity nor in the token datas of the batch
+                activity(5, "0xf", "0x1::object::TransferEvent", None),
+            ],
+            &token_datas,
+        );
+        assert_eq!(rollups.len(), 2);
+        assert_eq!(rollups[0].collection_id, "0xc");
+        assert_eq!(rollups[0].hour.to_string(), "2025-03-21 10:00:00");
+        assert_eq!(rollups[0].num_transfers, 1);
+        assert_eq!(rollups[0].num_burns, 1);
+        assert_eq!(rollups[0].first_transaction_version, 1);
+        assert_eq!(rollups[0].last_transaction_version, 3);
+        // v1 transfers are counted without their token data
+        assert_eq!(rollups[1].collection_id, "0xe");
+        assert_eq!(rollups[1].num_transfers, 1);
+        assert_eq!(rollups[1].num_burns, 0);
+        assert_eq!(rollups[1].first_transaction_version, 4);
+    }
+}
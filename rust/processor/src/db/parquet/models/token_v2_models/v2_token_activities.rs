@@ -10,6 +10,7 @@ use crate::{
     db::common::models::token_v2_models::raw_v2_token_activities::{
         RawTokenActivityV2, TokenActivityV2Convertible,
     },
+    utils::address_labels::Labeled,
 };
 use allocative_derive::Allocative;
 use bigdecimal::ToPrimitive;
@@ -37,6 +38,7 @@ pub struct TokenActivityV2 {
     pub is_fungible_v2: Option<bool>,
     #[allocative(skip)]
     pub block_timestamp: chrono::NaiveDateTime,
+    pub label: Option<String>,
 }
 
 impl NamedTable for TokenActivityV2 {
@@ -74,6 +76,18 @@ impl TokenActivityV2Convertible for TokenActivityV2 {
             token_standard: raw_item.token_standard,
             is_fungible_v2: raw_item.is_fungible_v2,
             block_timestamp: raw_item.transaction_timestamp,
+            label: None,
         }
     }
 }
+
+/// The recipient of the token, or its sender when there is none, e.g. for a burn.
+impl Labeled for TokenActivityV2 {
+    fn labeled_address(&self) -> Option<&str> {
+        self.to_address.as_deref().or(self.from_address.as_deref())
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+}
@@ -1,4 +1,6 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod parquet_entry_function_gas_hourly;
+pub mod parquet_transaction_gas_breakdown;
 pub mod parquet_write_set_size_info;
@@ -0,0 +1,157 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::extra_unused_lifetimes)]
+
+use super::parquet_transaction_gas_breakdown::TransactionGasBreakdown;
+use crate::bq_analytics::generic_parquet_processor::{GetTimeStamp, HasVersion, NamedTable};
+use ahash::AHashMap;
+use allocative_derive::Allocative;
+use chrono::Timelike;
+use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
+use serde::{Deserialize, Serialize};
+
+/// Gas charged per entry function per hour, aggregated over a single batch of transactions. An hour
+/// can span several batches and so several rows; consumers sum the rows of an (entry function, hour)
+/// to get its totals.
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct EntryFunctionGasHourly {
+    pub entry_function_id_str: String,
+    #[allocative(skip)]
+    pub hour: chrono::NaiveDateTime,
+    pub num_transactions: u64,
+    pub num_failed_transactions: u64,
+    pub total_gas_used: u64,
+    pub total_execution_gas_units: u64,
+    pub total_io_gas_units: u64,
+    pub total_storage_fee_octas: u64,
+    pub total_storage_fee_refund_octas: u64,
+    pub total_gas_fee_octas: u64,
+    pub first_transaction_version: i64,
+    pub last_transaction_version: i64,
+}
+
+impl NamedTable for EntryFunctionGasHourly {
+    const TABLE_NAME: &'static str = "entry_function_gas_hourly";
+}
+
+impl HasVersion for EntryFunctionGasHourly {
+    fn version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
+impl GetTimeStamp for EntryFunctionGasHourly {
+    fn get_timestamp(&self) -> chrono::NaiveDateTime {
+        self.hour
+    }
+}
+
+impl EntryFunctionGasHourly {
+    /// Rolls up the gas breakdowns of a batch, ordered by hour and then entry function.
+    pub fn from_gas_breakdowns(gas_breakdowns: &[TransactionGasBreakdown]) -> Vec<Self> {
+        let mut rollups: AHashMap<(&str, chrono::NaiveDateTime), Self> = AHashMap::new();
+        for gas_breakdown in gas_breakdowns {
+            let hour = truncate_to_hour(gas_breakdown.block_timestamp);
+            let rollup = rollups
+                .entry((gas_breakdown.entry_function_id_str.as_str(), hour))
+                .or_insert_with(|| Self {
+                    entry_function_id_str: gas_breakdown.entry_function_id_str.clone(),
+                    hour,
+                    first_transaction_version: gas_breakdown.txn_version,
+                    last_transaction_version: gas_breakdown.txn_version,
+                    ..Default::default()
+                });
+            rollup.num_transactions += 1;
+            if !gas_breakdown.is_transaction_success {
+                rollup.num_failed_transactions += 1;
+            }
+            rollup.total_gas_used += gas_breakdown.gas_used;
+            rollup.total_execution_gas_units += gas_breakdown.execution_gas_units;
+            rollup.total_io_gas_units += gas_breakdown.io_gas_units;
+            rollup.total_storage_fee_octas += gas_breakdown.storage_fee_octas;
+            rollup.total_storage_fee_refund_octas += gas_breakdown.storage_fee_refund_octas;
+            rollup.total_gas_fee_octas += gas_breakdown.gas_used * gas_breakdown.gas_unit_price;
+            rollup.first_transaction_version = rollup
+                .first_transaction_version
+                .min(gas_breakdown.txn_version);
+            rollup.last_transaction_version = rollup
+                .last_transaction_version
+                .max(gas_breakdown.txn_version);
+        }
+        let mut rollups: Vec<Self> = rollups.into_values().collect();
+        rollups.sort_by(|a, b| {
+            (a.hour, &a.entry_function_id_str).cmp(&(b.hour, &b.entry_function_id_str))
+        });
+        rollups
+    }
+}
+
+fn truncate_to_hour(timestamp: chrono::NaiveDateTime) -> chrono::NaiveDateTime {
+    timestamp
+        .date()
+        .and_hms_opt(timestamp.hour(), 0, 0)
+        .expect("Hour of a valid timestamp is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gas_breakdown(
+        txn_version: i64,
+        entry_function_id_str: &str,
+        timestamp: &str,
+        is_transaction_success: bool,
+    ) -> TransactionGasBreakdown {
+        TransactionGasBreakdown {
+            txn_version,
+            entry_function_id_str: entry_function_id_str.to_string(),
+            is_transaction_success,
+            gas_unit_price: 100,
+            gas_used: 10,
+            execution_gas_units: 4,
+            io_gas_units: 3,
+            storage_fee_octas: 300,
+            block_timestamp: chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+                .unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_from_gas_breakdowns() {
+        let rollups = EntryFunctionGasHourly::from_gas_breakdowns(&[
+            gas_breakdown(3, "0x1::a::f", "2025-03-14 10:59:59", false),
+            gas_breakdown(1, "0x1::a::f", "2025-03-14 10:00:00", true),
+            gas_breakdown(2, "0x1::a::g", "2025-03-14 10:30:00", true),
+            gas_breakdown(4, "0x1::a::f", "2025-03-14 11:00:00", true),
+        ]);
+        let summary: Vec<_> = rollups
+            .iter()
+            .map(|rollup| {
+                (
+                    rollup.entry_function_id_str.as_str(),
+                    rollup.hour.to_string(),
+                    rollup.num_transactions,
+                    rollup.num_failed_transactions,
+                    rollup.first_transaction_version,
+                    rollup.last_transaction_version,
+                )
+            })
+            .collect();
+        assert_eq!(summary, vec![
+            ("0x1::a::f", "2025-03-14 10:00:00".to_string(), 2, 1, 1, 3),
+            ("0x1::a::g", "2025-03-14 10:00:00".to_string(), 1, 0, 2, 2),
+            ("0x1::a::f", "2025-03-14 11:00:00".to_string(), 1, 0, 4, 4),
+        ]);
+        assert_eq!(rollups[0].total_gas_used, 20);
+        assert_eq!(rollups[0].total_execution_gas_units, 8);
+        assert_eq!(rollups[0].total_io_gas_units, 6);
+        assert_eq!(rollups[0].total_storage_fee_octas, 600);
+        assert_eq!(rollups[0].total_gas_fee_octas, 2000);
+    }
+}
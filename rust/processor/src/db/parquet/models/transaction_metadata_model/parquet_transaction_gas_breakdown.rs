@@ -0,0 +1,89 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::{
+    bq_analytics::generic_parquet_processor::{GetTimeStamp, HasVersion, NamedTable},
+    db::postgres::models::{
+        fungible_asset_models::v2_fungible_asset_utils::FeeStatement,
+        user_transactions_models::signatures::Signature,
+    },
+    utils::util::{get_entry_function_from_user_request, parse_timestamp, standardize_address},
+};
+use allocative_derive::Allocative;
+use aptos_protos::transaction::v1::{transaction::TxnData, Transaction};
+use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
+use serde::{Deserialize, Serialize};
+
+/// Gas charged to a user transaction, split by the components of its `FeeStatement` event.
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct TransactionGasBreakdown {
+    pub txn_version: i64,
+    pub sender: String,
+    pub fee_payer_address: Option<String>,
+    pub entry_function_id_str: String,
+    pub is_transaction_success: bool,
+    pub gas_unit_price: u64,
+    pub gas_used: u64,
+    pub total_charge_gas_units: u64,
+    pub execution_gas_units: u64,
+    pub io_gas_units: u64,
+    pub storage_fee_octas: u64,
+    pub storage_fee_refund_octas: u64,
+    #[allocative(skip)]
+    pub block_timestamp: chrono::NaiveDateTime,
+}
+
+impl NamedTable for TransactionGasBreakdown {
+    const TABLE_NAME: &'static str = "transaction_gas_breakdown";
+}
+
+impl HasVersion for TransactionGasBreakdown {
+    fn version(&self) -> i64 {
+        self.txn_version
+    }
+}
+
+impl GetTimeStamp for TransactionGasBreakdown {
+    fn get_timestamp(&self) -> chrono::NaiveDateTime {
+        self.block_timestamp
+    }
+}
+
+impl TransactionGasBreakdown {
+    /// None for transactions that aren't user transactions, or that predate the `FeeStatement` event.
+    pub fn from_transaction(transaction: &Transaction) -> Option<Self> {
+        let txn_version = transaction.version as i64;
+        let Some(TxnData::User(user_txn)) = transaction.txn_data.as_ref() else {
+            return None;
+        };
+        let request = user_txn.request.as_ref()?;
+        let transaction_info = transaction.info.as_ref()?;
+        let fee_statement = user_txn.events.iter().find_map(|event| {
+            FeeStatement::from_event(event.type_str.as_str(), &event.data, txn_version)
+        })?;
+        Some(Self {
+            txn_version,
+            sender: standardize_address(&request.sender),
+            fee_payer_address: request
+                .signature
+                .as_ref()
+                .and_then(|signature| Signature::get_fee_payer_address(signature, txn_version)),
+            entry_function_id_str: get_entry_function_from_user_request(request)
+                .unwrap_or_default(),
+            is_transaction_success: transaction_info.success,
+            gas_unit_price: request.gas_unit_price,
+            gas_used: transaction_info.gas_used,
+            total_charge_gas_units: fee_statement.total_charge_gas_units,
+            execution_gas_units: fee_statement.execution_gas_units,
+            io_gas_units: fee_statement.io_gas_units,
+            storage_fee_octas: fee_statement.storage_fee_octas,
+            storage_fee_refund_octas: fee_statement.storage_fee_refund_octas,
+            block_timestamp: parse_timestamp(transaction.timestamp.as_ref()?, txn_version),
+        })
+    }
+}
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS bs_alias_status_index;
+DROP TABLE IF EXISTS backfill_shards;
+ALTER TABLE backfill_processor_status ALTER COLUMN backfill_alias TYPE VARCHAR(50);
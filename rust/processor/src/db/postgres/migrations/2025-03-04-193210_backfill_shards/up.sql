@@ -0,0 +1,17 @@
+-- Your SQL goes here
+-- Shard aliases are suffixed to the parent backfill alias, so they need more room
+ALTER TABLE backfill_processor_status ALTER COLUMN backfill_alias TYPE VARCHAR(100);
+
+-- Version ranges of a sharded backfill, claimed by workers through a lease
+CREATE TABLE backfill_shards (
+  backfill_alias VARCHAR(100) NOT NULL,
+  shard_index BIGINT NOT NULL,
+  shard_start_version BIGINT NOT NULL,
+  shard_end_version BIGINT NOT NULL,
+  shard_status VARCHAR(50) NOT NULL,
+  worker_id VARCHAR(100),
+  lease_expires_at TIMESTAMP,
+  last_updated TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (backfill_alias, shard_index)
+);
+CREATE INDEX IF NOT EXISTS bs_alias_status_index ON backfill_shards (backfill_alias, shard_status);
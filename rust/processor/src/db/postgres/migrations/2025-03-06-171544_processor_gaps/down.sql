@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS pg_processor_repair_status_index;
+DROP TABLE IF EXISTS processor_gaps;
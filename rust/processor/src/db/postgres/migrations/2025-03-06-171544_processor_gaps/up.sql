@@ -0,0 +1,13 @@
+-- Your SQL goes here
+-- Missing version ranges found by the gap detectors, repaired by bounded backfills
+CREATE TABLE IF NOT EXISTS processor_gaps (
+  processor VARCHAR(100) NOT NULL,
+  gap_start_version BIGINT NOT NULL,
+  gap_end_version BIGINT NOT NULL,
+  repair_status VARCHAR(50) NOT NULL,
+  backfill_alias VARCHAR(100),
+  detected_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  last_updated TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (processor, gap_start_version)
+);
+CREATE INDEX IF NOT EXISTS pg_processor_repair_status_index ON processor_gaps (processor, repair_status);
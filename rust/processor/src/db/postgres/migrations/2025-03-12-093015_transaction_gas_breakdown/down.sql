@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS tgb_timestamp_index;
+DROP INDEX IF EXISTS tgb_sender_index;
+DROP TABLE IF EXISTS transaction_gas_breakdown;
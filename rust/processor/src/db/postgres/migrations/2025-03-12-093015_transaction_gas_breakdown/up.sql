@@ -0,0 +1,20 @@
+-- Your SQL goes here
+-- Gas charged to each user transaction, split by the components of its FeeStatement event.
+-- Gas units are multiplied by gas_unit_price to get octas, storage fees are already in octas.
+CREATE TABLE IF NOT EXISTS transaction_gas_breakdown (
+  transaction_version BIGINT PRIMARY KEY NOT NULL,
+  sender VARCHAR(66) NOT NULL,
+  entry_function_id_str VARCHAR(1000) NOT NULL,
+  is_transaction_success BOOLEAN NOT NULL,
+  gas_unit_price NUMERIC NOT NULL,
+  gas_used NUMERIC NOT NULL,
+  total_charge_gas_units NUMERIC NOT NULL,
+  execution_gas_units NUMERIC NOT NULL,
+  io_gas_units NUMERIC NOT NULL,
+  storage_fee_octas NUMERIC NOT NULL,
+  storage_fee_refund_octas NUMERIC NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS tgb_sender_index ON transaction_gas_breakdown (sender);
+CREATE INDEX IF NOT EXISTS tgb_timestamp_index ON transaction_gas_breakdown (transaction_timestamp);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS efgh_hour_index;
+DROP TABLE IF EXISTS entry_function_gas_hourly;
+DROP INDEX IF EXISTS tgb_entry_function_timestamp_index;
+ALTER TABLE transaction_gas_breakdown DROP COLUMN IF EXISTS fee_payer_address;
@@ -0,0 +1,22 @@
+-- Your SQL goes here
+ALTER TABLE transaction_gas_breakdown
+ADD COLUMN IF NOT EXISTS fee_payer_address VARCHAR(66);
+CREATE INDEX IF NOT EXISTS tgb_entry_function_timestamp_index ON transaction_gas_breakdown (entry_function_id_str, transaction_timestamp);
+-- Gas charged per entry function per hour, recomputed from transaction_gas_breakdown for every
+-- (entry function, hour) touched by a batch. Script transactions have an empty entry function.
+CREATE TABLE IF NOT EXISTS entry_function_gas_hourly (
+  entry_function_id_str VARCHAR(1000) NOT NULL,
+  hour TIMESTAMP NOT NULL,
+  num_transactions BIGINT NOT NULL,
+  num_failed_transactions BIGINT NOT NULL,
+  total_gas_used NUMERIC NOT NULL,
+  total_execution_gas_units NUMERIC NOT NULL,
+  total_io_gas_units NUMERIC NOT NULL,
+  total_storage_fee_octas NUMERIC NOT NULL,
+  total_storage_fee_refund_octas NUMERIC NOT NULL,
+  total_gas_fee_octas NUMERIC NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (entry_function_id_str, hour)
+);
+CREATE INDEX IF NOT EXISTS efgh_hour_index ON entry_function_gas_hourly (hour);
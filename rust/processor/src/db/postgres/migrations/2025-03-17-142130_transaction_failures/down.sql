@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS effh_hour_index;
+DROP TABLE IF EXISTS entry_function_failures_hourly;
+DROP INDEX IF EXISTS uto_entry_function_timestamp_index;
+DROP TABLE IF EXISTS user_transaction_outcomes;
+DROP INDEX IF EXISTS tf_timestamp_index;
+DROP INDEX IF EXISTS tf_module_abort_code_index;
+DROP INDEX IF EXISTS tf_entry_function_timestamp_index;
+DROP TABLE IF EXISTS transaction_failures;
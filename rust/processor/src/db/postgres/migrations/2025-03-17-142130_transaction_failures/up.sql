@@ -0,0 +1,47 @@
+-- Your SQL goes here
+-- Failed user transactions, with vm_status decoded into where and why they failed.
+-- error_category and error_reason follow the std::error convention (category << 16 | reason)
+-- and are null for abort codes outside of it.
+CREATE TABLE IF NOT EXISTS transaction_failures (
+  transaction_version BIGINT PRIMARY KEY NOT NULL,
+  sender VARCHAR(66) NOT NULL,
+  entry_function_id_str VARCHAR(1000) NOT NULL,
+  failure_type VARCHAR(50) NOT NULL,
+  vm_status TEXT NOT NULL,
+  module_address VARCHAR(66),
+  module_name VARCHAR(255),
+  abort_code NUMERIC,
+  error_name VARCHAR(255),
+  error_description TEXT,
+  error_category VARCHAR(50),
+  error_reason BIGINT,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS tf_entry_function_timestamp_index ON transaction_failures (entry_function_id_str, transaction_timestamp);
+CREATE INDEX IF NOT EXISTS tf_module_abort_code_index ON transaction_failures (module_address, module_name, abort_code);
+CREATE INDEX IF NOT EXISTS tf_timestamp_index ON transaction_failures (transaction_timestamp);
+-- Outcome of every user transaction, the source of the failure rate rollups.
+CREATE TABLE IF NOT EXISTS user_transaction_outcomes (
+  transaction_version BIGINT PRIMARY KEY NOT NULL,
+  entry_function_id_str VARCHAR(1000) NOT NULL,
+  is_transaction_success BOOLEAN NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS uto_entry_function_timestamp_index ON user_transaction_outcomes (entry_function_id_str, transaction_timestamp);
+-- User transactions and failures per entry function per hour. The hours touched by a batch are
+-- recomputed from user_transaction_outcomes and transaction_failures.
+CREATE TABLE IF NOT EXISTS entry_function_failures_hourly (
+  entry_function_id_str VARCHAR(1000) NOT NULL,
+  hour TIMESTAMP NOT NULL,
+  num_transactions BIGINT NOT NULL,
+  num_failed_transactions BIGINT NOT NULL,
+  num_move_aborts BIGINT NOT NULL,
+  num_execution_failures BIGINT NOT NULL,
+  num_out_of_gas BIGINT NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (entry_function_id_str, hour)
+);
+CREATE INDEX IF NOT EXISTS effh_hour_index ON entry_function_failures_hourly (hour);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS current_collection_trait_counts;
+DROP TABLE IF EXISTS current_token_properties;
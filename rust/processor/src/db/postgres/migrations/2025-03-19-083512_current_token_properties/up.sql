@@ -0,0 +1,29 @@
+-- Your SQL goes here
+-- Properties of the current token datas, one row per property. Values are the human readable
+-- values of the property map, truncated to 1000 characters. Keys and values are too long to be
+-- indexed as they are, so the indexes are on their md5 hashes.
+CREATE TABLE IF NOT EXISTS current_token_properties (
+  token_data_id VARCHAR(66) NOT NULL,
+  property_key VARCHAR(1000) NOT NULL,
+  collection_id VARCHAR(66) NOT NULL,
+  property_type VARCHAR(255) NOT NULL,
+  property_value VARCHAR(1000) NOT NULL,
+  property_key_hash VARCHAR(32) GENERATED ALWAYS AS (md5(property_key)) STORED,
+  property_value_hash VARCHAR(32) GENERATED ALWAYS AS (md5(property_value)) STORED,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (token_data_id, property_key_hash)
+);
+CREATE INDEX IF NOT EXISTS ctp_collection_key_value_index ON current_token_properties (collection_id, property_key_hash, property_value_hash);
+-- Number of tokens of a collection with each property value, for trait rarity.
+CREATE TABLE IF NOT EXISTS current_collection_trait_counts (
+  collection_id VARCHAR(66) NOT NULL,
+  property_key VARCHAR(1000) NOT NULL,
+  property_value VARCHAR(1000) NOT NULL,
+  property_key_hash VARCHAR(32) GENERATED ALWAYS AS (md5(property_key)) STORED,
+  property_value_hash VARCHAR(32) GENERATED ALWAYS AS (md5(property_value)) STORED,
+  num_tokens BIGINT NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (collection_id, property_key_hash, property_value_hash)
+);
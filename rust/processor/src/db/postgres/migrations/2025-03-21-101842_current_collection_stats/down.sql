@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP VIEW IF EXISTS collection_transfer_windows;
+DROP TABLE IF EXISTS current_collection_stats;
+DROP TABLE IF EXISTS current_collection_holders;
+DROP TABLE IF EXISTS collection_activity_hourly;
+DROP TABLE IF EXISTS collection_activities;
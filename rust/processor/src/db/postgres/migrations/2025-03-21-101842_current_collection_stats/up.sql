@@ -0,0 +1,62 @@
+-- Your SQL goes here
+-- Token transfers and burns with the collection of the token, the source of
+-- collection_activity_hourly.
+CREATE TABLE IF NOT EXISTS collection_activities (
+  transaction_version BIGINT NOT NULL,
+  event_index BIGINT NOT NULL,
+  collection_id VARCHAR(66) NOT NULL,
+  activity_type VARCHAR(50) NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (transaction_version, event_index)
+);
+CREATE INDEX IF NOT EXISTS ca_collection_timestamp_index ON collection_activities (collection_id, transaction_timestamp);
+-- Token transfers and burns per collection per hour. The hours touched by a batch are recomputed
+-- from collection_activities.
+CREATE TABLE IF NOT EXISTS collection_activity_hourly (
+  collection_id VARCHAR(66) NOT NULL,
+  hour TIMESTAMP NOT NULL,
+  num_transfers BIGINT NOT NULL,
+  num_burns BIGINT NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (collection_id, hour)
+);
+CREATE INDEX IF NOT EXISTS cah_hour_index ON collection_activity_hourly (hour);
+-- Number of tokens of a collection each owner holds, for the holder stats.
+CREATE TABLE IF NOT EXISTS current_collection_holders (
+  collection_id VARCHAR(66) NOT NULL,
+  owner_address VARCHAR(66) NOT NULL,
+  num_tokens NUMERIC NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (collection_id, owner_address)
+);
+-- Holders and burns of each collection. Holders are bucketed by the number of tokens of the
+-- collection they own.
+CREATE TABLE IF NOT EXISTS current_collection_stats (
+  collection_id VARCHAR(66) PRIMARY KEY NOT NULL,
+  num_holders BIGINT NOT NULL,
+  num_holders_1 BIGINT NOT NULL,
+  num_holders_2_to_5 BIGINT NOT NULL,
+  num_holders_6_to_20 BIGINT NOT NULL,
+  num_holders_21_plus BIGINT NOT NULL,
+  num_burned BIGINT NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS ccs_num_holders_index ON current_collection_stats (num_holders);
+-- Transfers of each collection in the hours starting within the last 24 hours and 7 days, counted
+-- when queried so the windows move with the clock rather than with the processor.
+CREATE OR REPLACE VIEW collection_transfer_windows AS
+SELECT collection_id,
+  COALESCE(
+    SUM(num_transfers) FILTER (
+      WHERE hour > (NOW() AT TIME ZONE 'UTC') - INTERVAL '24 hours'
+    ),
+    0
+  )::BIGINT AS num_transfers_24h,
+  SUM(num_transfers)::BIGINT AS num_transfers_7d
+FROM collection_activity_hourly
+WHERE hour > (NOW() AT TIME ZONE 'UTC') - INTERVAL '7 days'
+GROUP BY collection_id;
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+-- Account at the root of each object's ownership chain. depth is the number of owners between the
+-- object and its root owner, so an object owned directly by an account has depth 1.
+CREATE TABLE IF NOT EXISTS current_object_root_owners (
+  object_address VARCHAR(66) PRIMARY KEY NOT NULL,
+  root_owner_address VARCHAR(66) NOT NULL,
+  depth INT NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS coro_root_owner_index ON current_object_root_owners (root_owner_address);
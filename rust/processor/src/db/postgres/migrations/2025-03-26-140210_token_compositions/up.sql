@@ -0,0 +1,14 @@
+-- Your SQL goes here
+-- Periods in which a v2 token was owned by another token, e.g. an item equipped on a character.
+-- The decompose columns are null while the child is still owned by the parent.
+CREATE TABLE IF NOT EXISTS token_compositions (
+  child_token_data_id VARCHAR(66) NOT NULL,
+  compose_transaction_version BIGINT NOT NULL,
+  parent_token_data_id VARCHAR(66) NOT NULL,
+  compose_timestamp TIMESTAMP NOT NULL,
+  decompose_transaction_version BIGINT,
+  decompose_timestamp TIMESTAMP,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (child_token_data_id, compose_transaction_version)
+);
+CREATE INDEX IF NOT EXISTS tc_parent_decompose_index ON token_compositions (parent_token_data_id, decompose_transaction_version);
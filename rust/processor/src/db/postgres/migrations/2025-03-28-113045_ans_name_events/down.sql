@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS current_ans_name_expirations;
+DROP TABLE IF EXISTS ans_name_events;
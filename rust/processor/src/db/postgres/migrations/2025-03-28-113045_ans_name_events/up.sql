@@ -0,0 +1,39 @@
+-- Your SQL goes here
+-- Lifecycle of ANS v2 names: registrations, re-registrations by a new owner, renewals, transfers of
+-- the name's token object, expiry and the end of the grace period.
+-- renewal_duration_secs is how far a renewal moved the expiration from the previous event's.
+-- expire and grace_period_end rows aren't emitted by a transaction. They have the version of the
+-- transaction they were recorded before and a negative event_index.
+CREATE TABLE IF NOT EXISTS ans_name_events (
+  transaction_version BIGINT NOT NULL,
+  event_index BIGINT NOT NULL,
+  name_object_address VARCHAR(66),
+  domain VARCHAR(64) NOT NULL,
+  subdomain VARCHAR(64) NOT NULL,
+  token_name VARCHAR(140) NOT NULL,
+  event_type VARCHAR(50) NOT NULL,
+  registration_fee_octas NUMERIC,
+  expiration_timestamp TIMESTAMP,
+  renewal_duration_secs BIGINT,
+  from_address VARCHAR(66),
+  to_address VARCHAR(66),
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (transaction_version, event_index)
+);
+CREATE INDEX IF NOT EXISTS ane_token_name_index ON ans_name_events (token_name, transaction_version);
+CREATE INDEX IF NOT EXISTS ane_name_object_index ON ans_name_events (name_object_address, transaction_version);
+-- When each name expires and its grace period ends, and which of the two is recorded next
+CREATE TABLE IF NOT EXISTS current_ans_name_expirations (
+  token_name VARCHAR(140) PRIMARY KEY NOT NULL,
+  name_object_address VARCHAR(66),
+  domain VARCHAR(64) NOT NULL,
+  subdomain VARCHAR(64) NOT NULL,
+  expiration_timestamp TIMESTAMP NOT NULL,
+  grace_period_end_timestamp TIMESTAMP NOT NULL,
+  next_event_type VARCHAR(50),
+  next_event_timestamp TIMESTAMP,
+  last_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS cane_next_event_timestamp_index ON current_ans_name_expirations (next_event_timestamp);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS account_summaries;
+DROP TABLE IF EXISTS account_counterparties;
+DROP TABLE IF EXISTS account_activities;
@@ -0,0 +1,34 @@
+-- Your SQL goes here
+-- Activity of each account, updated incrementally from the accounts touched by each transaction
+CREATE TABLE IF NOT EXISTS account_summaries (
+  account_address VARCHAR(66) NOT NULL PRIMARY KEY,
+  first_transaction_version BIGINT NOT NULL,
+  first_transaction_timestamp TIMESTAMP NOT NULL,
+  last_transaction_version BIGINT NOT NULL,
+  last_transaction_timestamp TIMESTAMP NOT NULL,
+  num_transactions BIGINT NOT NULL,
+  num_counterparties BIGINT NOT NULL,
+  last_entry_function_id_str VARCHAR(1000),
+  last_entry_function_version BIGINT,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS as_last_transaction_version_index ON account_summaries (last_transaction_version);
+-- Distinct counterparties of each account, so that num_counterparties can be kept up to date
+-- without rescanning history
+CREATE TABLE IF NOT EXISTS account_counterparties (
+  account_address VARCHAR(66) NOT NULL,
+  counterparty_address VARCHAR(66) NOT NULL,
+  first_transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (account_address, counterparty_address)
+);
+-- Accounts touched by each transaction with the details the summaries need. Only activity that
+-- isn't here yet is added to the summaries, so replayed and out of order batches are counted once.
+CREATE TABLE IF NOT EXISTS account_activities (
+  account_address VARCHAR(66) NOT NULL,
+  transaction_version BIGINT NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  entry_function_id_str VARCHAR(1000),
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (account_address, transaction_version)
+);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS address_labels;
+ALTER TABLE account_transactions DROP COLUMN IF EXISTS label;
+ALTER TABLE fungible_asset_activities DROP COLUMN IF EXISTS label;
+ALTER TABLE token_activities_v2 DROP COLUMN IF EXISTS label;
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+-- Labels of known accounts, loaded from the address label file in the processor config
+CREATE TABLE IF NOT EXISTS address_labels (
+  address VARCHAR(66) NOT NULL PRIMARY KEY,
+  label VARCHAR(200) NOT NULL,
+  category VARCHAR(50),
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS al_label_index ON address_labels (label);
+-- Denormalized labels of the account of each row
+ALTER TABLE account_transactions
+ADD COLUMN IF NOT EXISTS label VARCHAR(200);
+ALTER TABLE fungible_asset_activities
+ADD COLUMN IF NOT EXISTS label VARCHAR(200);
+ALTER TABLE token_activities_v2
+ADD COLUMN IF NOT EXISTS label VARCHAR(200);
@@ -4,7 +4,7 @@
 // This is required because a diesel macro makes clippy sad
 #![allow(clippy::extra_unused_lifetimes)]
 #![allow(clippy::unused_unit)]
-use crate::schema::account_transactions;
+use crate::{schema::account_transactions, utils::address_labels::Labeled};
 use field_count::FieldCount;
 use serde::{Deserialize, Serialize};
 
@@ -16,4 +16,15 @@ pub type AccountTransactionPK = (String, i64);
 pub struct AccountTransaction {
     pub transaction_version: i64,
     pub account_address: String,
+    pub label: Option<String>,
+}
+
+impl Labeled for AccountTransaction {
+    fn labeled_address(&self) -> Option<&str> {
+        Some(&self.account_address)
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
 }
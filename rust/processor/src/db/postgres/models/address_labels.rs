@@ -0,0 +1,23 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::schema::address_labels;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+
+/// A known account, e.g. an exchange, a bridge or a protocol contract, as listed in the address
+/// label file.
+#[derive(
+    Clone, Debug, Deserialize, Eq, FieldCount, Identifiable, Insertable, PartialEq, Serialize,
+)]
+#[diesel(primary_key(address))]
+#[diesel(table_name = address_labels)]
+pub struct AddressLabel {
+    pub address: String,
+    pub label: String,
+    #[serde(default)]
+    pub category: Option<String>,
+}
@@ -31,6 +31,7 @@ pub struct AnsLookupV2 {
     pub token_name: String,
     pub is_deleted: bool,
     pub subdomain_expiration_policy: Option<i64>,
+    pub namespace: String,
 }
 
 impl AnsLookupV2Convertible for AnsLookupV2 {
@@ -46,6 +47,7 @@ impl AnsLookupV2Convertible for AnsLookupV2 {
             token_name: raw_item.token_name,
             is_deleted: raw_item.is_deleted,
             subdomain_expiration_policy: raw_item.subdomain_expiration_policy,
+            namespace: raw_item.namespace,
         }
     }
 }
@@ -62,7 +64,7 @@ impl AnsLookupV2Convertible for AnsLookupV2 {
     PartialEq,
     Eq,
 )]
-#[diesel(primary_key(domain, subdomain, token_standard))]
+#[diesel(primary_key(domain, subdomain, token_standard, namespace))]
 #[diesel(table_name = current_ans_lookup_v2)]
 #[diesel(treat_none_as_null = true)]
 pub struct CurrentAnsLookupV2 {
@@ -75,6 +77,7 @@ pub struct CurrentAnsLookupV2 {
     pub token_name: String,
     pub is_deleted: bool,
     pub subdomain_expiration_policy: Option<i64>,
+    pub namespace: String,
 }
 
 impl CurrentAnsLookupV2Convertible for CurrentAnsLookupV2 {
@@ -89,6 +92,7 @@ impl CurrentAnsLookupV2Convertible for CurrentAnsLookupV2 {
             token_name: raw_item.token_name,
             is_deleted: raw_item.is_deleted,
             subdomain_expiration_policy: raw_item.subdomain_expiration_policy,
+            namespace: raw_item.namespace,
         }
     }
 }
@@ -0,0 +1,658 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+
+use super::ans_utils::{
+    get_token_name, AnsWriteResource, NamingServiceConfig, RegisterNameEvent, RenewNameEvent,
+    V2AnsEvent,
+};
+use crate::{
+    db::{
+        common::models::{
+            object_models::v2_object_utils::ObjectCore,
+            token_v2_models::v2_token_utils::{Mint, MintEvent, TransferEvent},
+        },
+        postgres::models::resources::FromWriteResource,
+    },
+    schema::{ans_name_events, current_ans_name_expirations},
+    utils::util::{parse_timestamp, standardize_address},
+};
+use ahash::{AHashMap, AHashSet};
+use aptos_protos::transaction::v1::{
+    transaction::TxnData, write_set_change::Change as WriteSetChange, Transaction,
+};
+use bigdecimal::BigDecimal;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+
+pub const REGISTER_EVENT_TYPE: &str = "register";
+pub const REREGISTER_EVENT_TYPE: &str = "reregister";
+pub const RENEW_EVENT_TYPE: &str = "renew";
+pub const TRANSFER_EVENT_TYPE: &str = "transfer";
+pub const EXPIRE_EVENT_TYPE: &str = "expire";
+pub const GRACE_PERIOD_END_EVENT_TYPE: &str = "grace_period_end";
+
+/// A step in the lifecycle of an ANS v2 name: its registration, a re-registration by a new owner
+/// after it expired, a renewal, a transfer of the name's token object, its expiry or the end of its
+/// grace period. Expiry and the end of the grace period happen without a transaction, so their rows
+/// are written by the processor from `current_ans_name_expirations`.
+#[derive(Clone, Debug, Default, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(transaction_version, event_index))]
+#[diesel(table_name = ans_name_events)]
+pub struct AnsNameEvent {
+    pub transaction_version: i64,
+    pub event_index: i64,
+    pub name_object_address: Option<String>,
+    pub domain: String,
+    pub subdomain: String,
+    pub token_name: String,
+    pub event_type: String,
+    pub registration_fee_octas: Option<BigDecimal>,
+    pub expiration_timestamp: Option<chrono::NaiveDateTime>,
+    pub from_address: Option<String>,
+    pub to_address: Option<String>,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+    pub namespace: String,
+}
+
+/// When a name expires and when its grace period ends, as of its latest registration or renewal.
+/// `next_event_type` is the next of the two still to be recorded in `ans_name_events`, if any.
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, PartialEq, Serialize)]
+#[diesel(primary_key(token_name, namespace))]
+#[diesel(table_name = current_ans_name_expirations)]
+#[diesel(treat_none_as_null = true)]
+pub struct CurrentAnsNameExpiration {
+    pub token_name: String,
+    pub namespace: String,
+    pub name_object_address: Option<String>,
+    pub domain: String,
+    pub subdomain: String,
+    pub expiration_timestamp: chrono::NaiveDateTime,
+    pub grace_period_end_timestamp: chrono::NaiveDateTime,
+    pub next_event_type: Option<String>,
+    pub next_event_timestamp: Option<chrono::NaiveDateTime>,
+    pub last_transaction_version: i64,
+}
+
+impl AnsNameEvent {
+    /// Registrations, re-registrations, renewals and transfers of v2 names.
+    pub fn from_transactions(
+        transactions: &[Transaction],
+        naming_services: &[NamingServiceConfig],
+    ) -> Vec<Self> {
+        let mut name_events = vec![];
+
+        for transaction in transactions {
+            let txn_version = transaction.version as i64;
+            let user_txn = match transaction.txn_data.as_ref() {
+                Some(TxnData::User(user_txn)) => user_txn,
+                _ => continue,
+            };
+            let transaction_info = transaction
+                .info
+                .as_ref()
+                .expect("Transaction info doesn't exist!");
+            let txn_timestamp = parse_timestamp(
+                transaction
+                    .timestamp
+                    .as_ref()
+                    .expect("Transaction timestamp doesn't exist!"),
+                txn_version,
+            );
+
+            // Name records are stored at the address of the name's token object. Subdomains also
+            // have a SubdomainExt there. They're in the object's resource group, so they're written
+            // whenever its ObjectCore is, including on every transfer.
+            let mut name_records = AHashMap::new();
+            let mut subdomains = AHashMap::new();
+            let mut object_owners = AHashMap::new();
+            for wsc in transaction_info.changes.iter() {
+                if let Some(WriteSetChange::WriteResource(write_resource)) = wsc.change.as_ref() {
+                    let address = standardize_address(write_resource.address.as_str());
+                    if let Some(object_core) =
+                        ObjectCore::from_write_resource(write_resource).unwrap()
+                    {
+                        object_owners.insert(address.clone(), object_core.get_owner_address());
+                    }
+                    for naming_service in naming_services {
+                        match AnsWriteResource::from_write_resource(
+                            write_resource,
+                            naming_service,
+                            txn_version,
+                        )
+                        .unwrap()
+                        {
+                            Some(AnsWriteResource::NameRecordV2(name_record)) => {
+                                name_records.insert(
+                                    address.clone(),
+                                    (&naming_service.namespace, name_record.get_domain_trunc()),
+                                );
+                            },
+                            Some(AnsWriteResource::SubdomainExtV2(subdomain_ext)) => {
+                                subdomains
+                                    .insert(address.clone(), subdomain_ext.get_subdomain_trunc());
+                            },
+                            None => {},
+                        }
+                    }
+                }
+            }
+            let name_objects: AHashMap<(&String, String, String), String> = name_records
+                .iter()
+                .map(|(address, (namespace, domain))| {
+                    let subdomain = subdomains.get(address).cloned().unwrap_or_default();
+                    ((*namespace, domain.clone(), subdomain), address.clone())
+                })
+                .collect();
+            let sender = user_txn
+                .request
+                .as_ref()
+                .map(|request| standardize_address(&request.sender));
+
+            let mut txn_name_events = vec![];
+            let mut transfers = vec![];
+            let mut minted_objects = AHashSet::new();
+            for (event_index, event) in user_txn.events.iter().enumerate() {
+                let event_index = event_index as i64;
+                if let Some(transfer) = TransferEvent::from_event(event, txn_version).unwrap() {
+                    transfers.push((event_index, transfer));
+                    continue;
+                }
+                if let Some(mint) = MintEvent::from_event(event, txn_version).unwrap() {
+                    minted_objects.insert(mint.get_token_address());
+                    continue;
+                }
+                if let Some(mint) = Mint::from_event(event, txn_version).unwrap() {
+                    minted_objects.insert(mint.get_token_address());
+                    continue;
+                }
+                for naming_service in naming_services {
+                    let name_event =
+                        match V2AnsEvent::from_event(event, naming_service, txn_version).unwrap() {
+                            Some(V2AnsEvent::RegisterNameEvent(register)) => {
+                                Self::from_register_event(&register)
+                            },
+                            Some(V2AnsEvent::RenewNameEvent(renew)) => {
+                                Self::from_renew_event(&renew)
+                            },
+                            _ => continue,
+                        };
+                    let name_object_address = name_objects
+                        .get(&(
+                            &naming_service.namespace,
+                            name_event.domain.clone(),
+                            name_event.subdomain.clone(),
+                        ))
+                        .cloned();
+                    txn_name_events.push(Self {
+                        transaction_version: txn_version,
+                        event_index,
+                        name_object_address,
+                        transaction_timestamp: txn_timestamp,
+                        namespace: naming_service.namespace.clone(),
+                        ..name_event
+                    });
+                }
+            }
+
+            // The registrant is whoever the name object ends up with. A name object that wasn't
+            // minted in the transaction existed before, and if it changes hands the name was
+            // registered again by a new owner after it expired.
+            let mut registered_objects = AHashSet::new();
+            for name_event in txn_name_events
+                .iter_mut()
+                .filter(|name_event| name_event.event_type == REGISTER_EVENT_TYPE)
+            {
+                let name_object_address = match name_event.name_object_address.as_ref() {
+                    Some(name_object_address) => name_object_address,
+                    None => {
+                        name_event.to_address = sender.clone();
+                        continue;
+                    },
+                };
+                registered_objects.insert(name_object_address.clone());
+                let owner_address = object_owners
+                    .get(name_object_address)
+                    .cloned()
+                    .or_else(|| sender.clone());
+                let previous_owner_address = if minted_objects.contains(name_object_address) {
+                    None
+                } else {
+                    transfers
+                        .iter()
+                        .find(|(_, transfer)| &transfer.get_object_address() == name_object_address)
+                        .map(|(_, transfer)| transfer.get_from_address())
+                };
+                if previous_owner_address.is_some() && previous_owner_address != owner_address {
+                    name_event.event_type = REREGISTER_EVENT_TYPE.to_string();
+                    name_event.from_address = previous_owner_address;
+                }
+                name_event.to_address = owner_address;
+            }
+
+            // The transfers of a name registered in the transaction are its mint or the move to its
+            // new owner, which its register row already records
+            for (event_index, transfer) in transfers {
+                let object_address = transfer.get_object_address();
+                if registered_objects.contains(&object_address) {
+                    continue;
+                }
+                let (namespace, domain) = match name_records.get(&object_address) {
+                    Some(name_record) => name_record,
+                    None => continue,
+                };
+                let subdomain = subdomains.get(&object_address).cloned().unwrap_or_default();
+                txn_name_events.push(Self {
+                    transaction_version: txn_version,
+                    event_index,
+                    name_object_address: Some(object_address),
+                    token_name: get_token_name(domain, &subdomain),
+                    domain: domain.clone(),
+                    subdomain,
+                    event_type: TRANSFER_EVENT_TYPE.to_string(),
+                    from_address: Some(transfer.get_from_address()),
+                    to_address: Some(transfer.get_to_address()),
+                    transaction_timestamp: txn_timestamp,
+                    namespace: namespace.to_string(),
+                    ..Self::default()
+                });
+            }
+
+            txn_name_events.sort_by_key(|name_event| name_event.event_index);
+            name_events.extend(txn_name_events);
+        }
+        name_events
+    }
+
+    fn from_register_event(register: &RegisterNameEvent) -> Self {
+        let domain = register.get_domain_trunc();
+        let subdomain = register.get_subdomain_trunc();
+        Self {
+            token_name: get_token_name(&domain, &subdomain),
+            domain,
+            subdomain,
+            event_type: REGISTER_EVENT_TYPE.to_string(),
+            registration_fee_octas: Some(register.registration_fee_octas.clone()),
+            expiration_timestamp: Some(register.get_expiration_time()),
+            ..Self::default()
+        }
+    }
+
+    fn from_renew_event(renew: &RenewNameEvent) -> Self {
+        let domain = renew.get_domain_trunc();
+        let subdomain = renew.get_subdomain_trunc();
+        Self {
+            token_name: get_token_name(&domain, &subdomain),
+            domain,
+            subdomain,
+            event_type: RENEW_EVENT_TYPE.to_string(),
+            expiration_timestamp: Some(renew.get_expiration_time()),
+            ..Self::default()
+        }
+    }
+}
+
+impl CurrentAnsNameExpiration {
+    /// The expiration of every name registered or renewed in the batch, from its latest event.
+    pub fn from_name_events(
+        name_events: &[AnsNameEvent],
+        naming_services: &[NamingServiceConfig],
+    ) -> Vec<Self> {
+        let grace_periods: AHashMap<&String, i64> = naming_services
+            .iter()
+            .map(|naming_service| {
+                (
+                    &naming_service.namespace,
+                    naming_service.reregistration_grace_secs,
+                )
+            })
+            .collect();
+        let mut expirations: AHashMap<(String, String), Self> = AHashMap::new();
+        for name_event in name_events {
+            let expiration_timestamp = match name_event.expiration_timestamp {
+                Some(expiration_timestamp) => expiration_timestamp,
+                None => continue,
+            };
+            let grace_period_secs = grace_periods
+                .get(&name_event.namespace)
+                .copied()
+                .unwrap_or_default();
+            expirations.insert(
+                (name_event.namespace.clone(), name_event.token_name.clone()),
+                Self {
+                    token_name: name_event.token_name.clone(),
+                    namespace: name_event.namespace.clone(),
+                    name_object_address: name_event.name_object_address.clone(),
+                    domain: name_event.domain.clone(),
+                    subdomain: name_event.subdomain.clone(),
+                    expiration_timestamp,
+                    grace_period_end_timestamp: expiration_timestamp
+                        + chrono::Duration::seconds(grace_period_secs),
+                    next_event_type: Some(EXPIRE_EVENT_TYPE.to_string()),
+                    next_event_timestamp: Some(expiration_timestamp),
+                    last_transaction_version: name_event.transaction_version,
+                },
+            );
+        }
+        let mut expirations: Vec<Self> = expirations.into_values().collect();
+        // Sort by PK
+        expirations
+            .sort_by(|a, b| (&a.token_name, &a.namespace).cmp(&(&b.token_name, &b.namespace)));
+        expirations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_protos::{
+        transaction::v1::{
+            Event, MoveStructTag, TransactionInfo, UserTransaction, UserTransactionRequest,
+            WriteResource, WriteSetChange as WriteSetChangePb,
+        },
+        util::timestamp::Timestamp,
+    };
+    use serde_json::json;
+
+    const ANS_ADDRESS: &str = "0x867ed1f6bf916171b1de3ee92849b8978b7d1b9e0a8cc982a3d19d535dfd9c0c";
+    const NAME_OBJECT: &str = "0xa1";
+    const EXPIRATION_SECS: i64 = 1_800_000_000;
+
+    fn naming_services() -> Vec<NamingServiceConfig> {
+        vec![NamingServiceConfig::aptos_names(ANS_ADDRESS)]
+    }
+
+    fn write_resource(
+        address: &str,
+        struct_address: &str,
+        module: &str,
+        name: &str,
+        data: serde_json::Value,
+    ) -> WriteSetChangePb {
+        WriteSetChangePb {
+            change: Some(WriteSetChange::WriteResource(WriteResource {
+                address: address.to_string(),
+                r#type: Some(MoveStructTag {
+                    address: struct_address.to_string(),
+                    module: module.to_string(),
+                    name: name.to_string(),
+                    generic_type_params: vec![],
+                }),
+                type_str: format!("{}::{}::{}", struct_address, module, name),
+                data: data.to_string(),
+                ..WriteResource::default()
+            })),
+            ..WriteSetChangePb::default()
+        }
+    }
+
+    /// The resource group of a name object: its name record and its ObjectCore.
+    fn name_object(owner_address: &str, expiration_secs: i64) -> Vec<WriteSetChangePb> {
+        vec![
+            write_resource(
+                NAME_OBJECT,
+                ANS_ADDRESS,
+                "v2_1_domains",
+                "NameRecord",
+                json!({
+                    "domain_name": "alice",
+                    "expiration_time_sec": expiration_secs.to_string(),
+                    "target_address": { "vec": [] },
+                }),
+            ),
+            write_resource(
+                NAME_OBJECT,
+                "0x1",
+                "object",
+                "ObjectCore",
+                json!({
+                    "allow_ungated_transfer": true,
+                    "guid_creation_num": "1125899906842625",
+                    "owner": owner_address,
+                }),
+            ),
+        ]
+    }
+
+    fn event(type_str: &str, data: serde_json::Value) -> Event {
+        Event {
+            type_str: type_str.to_string(),
+            data: data.to_string(),
+            ..Event::default()
+        }
+    }
+
+    fn register_event(expiration_secs: i64) -> Event {
+        event(
+            &format!("{}::v2_1_domains::RegisterNameEvent", ANS_ADDRESS),
+            json!({
+                "domain_name": "alice",
+                "expiration_time_secs": expiration_secs.to_string(),
+                "registration_fee_octas": "100000000",
+                "subdomain_name": { "vec": [] },
+            }),
+        )
+    }
+
+    fn renew_event(expiration_secs: i64) -> Event {
+        event(
+            &format!("{}::v2_1_domains::RenewNameEvent", ANS_ADDRESS),
+            json!({
+                "domain_name": "alice",
+                "expiration_time_secs": expiration_secs.to_string(),
+                "is_primary_name": false,
+                "subdomain_name": { "vec": [] },
+                "target_address": { "vec": [] },
+            }),
+        )
+    }
+
+    fn transfer_event(object_address: &str, from_address: &str, to_address: &str) -> Event {
+        event(
+            "0x1::object::TransferEvent",
+            json!({ "object": object_address, "from": from_address, "to": to_address }),
+        )
+    }
+
+    fn mint_event(token_address: &str) -> Event {
+        event(
+            "0x4::collection::MintEvent",
+            json!({ "index": "1", "token": token_address }),
+        )
+    }
+
+    fn transaction(
+        version: u64,
+        changes: Vec<WriteSetChangePb>,
+        events: Vec<Event>,
+    ) -> Transaction {
+        Transaction {
+            version,
+            timestamp: Some(Timestamp {
+                seconds: 1_700_000_000 + version as i64,
+                nanos: 0,
+            }),
+            info: Some(TransactionInfo {
+                changes,
+                ..TransactionInfo::default()
+            }),
+            txn_data: Some(TxnData::User(UserTransaction {
+                request: Some(UserTransactionRequest {
+                    sender: "0xb0b".to_string(),
+                    ..UserTransactionRequest::default()
+                }),
+                events,
+            })),
+            ..Transaction::default()
+        }
+    }
+
+    fn summary(name_event: &AnsNameEvent) -> (i64, &str, Option<String>, Option<String>) {
+        (
+            name_event.event_index,
+            name_event.event_type.as_str(),
+            name_event.from_address.clone(),
+            name_event.to_address.clone(),
+        )
+    }
+
+    fn address(address: &str) -> Option<String> {
+        Some(standardize_address(address))
+    }
+
+    #[test]
+    fn test_register_without_mint_transfer() {
+        // The name object is minted by the contract and transferred to the registrant
+        let transactions = vec![transaction(
+            10,
+            name_object("0xb0b", EXPIRATION_SECS),
+            vec![
+                mint_event(NAME_OBJECT),
+                transfer_event(NAME_OBJECT, ANS_ADDRESS, "0xb0b"),
+                register_event(EXPIRATION_SECS),
+            ],
+        )];
+        let name_events = AnsNameEvent::from_transactions(&transactions, &naming_services());
+
+        assert_eq!(name_events.len(), 1);
+        let register = &name_events[0];
+        assert_eq!(summary(register), (2, "register", None, address("0xb0b")));
+        assert_eq!(register.transaction_version, 10);
+        assert_eq!(register.name_object_address, address(NAME_OBJECT));
+        assert_eq!(register.token_name, "alice.apt");
+        assert_eq!(register.namespace, "ans");
+        assert_eq!(
+            register.registration_fee_octas,
+            Some(BigDecimal::from(100_000_000))
+        );
+        assert_eq!(
+            register.expiration_timestamp,
+            chrono::DateTime::from_timestamp(EXPIRATION_SECS, 0).map(|t| t.naive_utc())
+        );
+    }
+
+    #[test]
+    fn test_renew() {
+        let renewed_secs = EXPIRATION_SECS + 365 * 24 * 60 * 60;
+        let transactions = vec![transaction(
+            20,
+            name_object("0xb0b", renewed_secs),
+            vec![renew_event(renewed_secs)],
+        )];
+        let name_events = AnsNameEvent::from_transactions(&transactions, &naming_services());
+
+        assert_eq!(name_events.len(), 1);
+        assert_eq!(summary(&name_events[0]), (0, "renew", None, None));
+        assert_eq!(name_events[0].registration_fee_octas, None);
+        assert_eq!(
+            name_events[0].expiration_timestamp,
+            chrono::DateTime::from_timestamp(renewed_secs, 0).map(|t| t.naive_utc())
+        );
+    }
+
+    #[test]
+    fn test_transfer_of_name_objects_only() {
+        // Another object changes hands in the same transaction, without a name record
+        let transactions = vec![transaction(
+            30,
+            name_object("0xc0c", EXPIRATION_SECS),
+            vec![
+                transfer_event("0xf1", "0xb0b", "0xc0c"),
+                transfer_event(NAME_OBJECT, "0xb0b", "0xc0c"),
+            ],
+        )];
+        let name_events = AnsNameEvent::from_transactions(&transactions, &naming_services());
+
+        assert_eq!(name_events.len(), 1);
+        let transfer = &name_events[0];
+        assert_eq!(
+            summary(transfer),
+            (1, "transfer", address("0xb0b"), address("0xc0c"))
+        );
+        assert_eq!(transfer.name_object_address, address(NAME_OBJECT));
+        assert_eq!(transfer.token_name, "alice.apt");
+        assert_eq!(transfer.expiration_timestamp, None);
+    }
+
+    #[test]
+    fn test_reregister_by_new_owner() {
+        // The expired name keeps its object, which the contract moves to the new registrant
+        let transactions = vec![transaction(
+            40,
+            name_object("0xd0d", EXPIRATION_SECS),
+            vec![
+                transfer_event(NAME_OBJECT, "0xc0c", "0xd0d"),
+                register_event(EXPIRATION_SECS),
+            ],
+        )];
+        let name_events = AnsNameEvent::from_transactions(&transactions, &naming_services());
+
+        assert_eq!(name_events.len(), 1);
+        assert_eq!(
+            summary(&name_events[0]),
+            (1, "reregister", address("0xc0c"), address("0xd0d"))
+        );
+    }
+
+    #[test]
+    fn test_reregister_by_same_owner() {
+        // Registering an expired name again without it changing hands is a plain registration
+        let transactions = vec![transaction(
+            40,
+            name_object("0xc0c", EXPIRATION_SECS),
+            vec![register_event(EXPIRATION_SECS)],
+        )];
+        let name_events = AnsNameEvent::from_transactions(&transactions, &naming_services());
+
+        assert_eq!(name_events.len(), 1);
+        assert_eq!(
+            summary(&name_events[0]),
+            (0, "register", None, address("0xc0c"))
+        );
+    }
+
+    #[test]
+    fn test_expirations_from_latest_event() {
+        let renewed_secs = EXPIRATION_SECS + 365 * 24 * 60 * 60;
+        let transactions = vec![
+            transaction(
+                10,
+                name_object("0xb0b", EXPIRATION_SECS),
+                vec![
+                    mint_event(NAME_OBJECT),
+                    transfer_event(NAME_OBJECT, ANS_ADDRESS, "0xb0b"),
+                    register_event(EXPIRATION_SECS),
+                ],
+            ),
+            transaction(
+                20,
+                name_object("0xc0c", renewed_secs),
+                vec![
+                    transfer_event(NAME_OBJECT, "0xb0b", "0xc0c"),
+                    renew_event(renewed_secs),
+                ],
+            ),
+        ];
+        let name_events = AnsNameEvent::from_transactions(&transactions, &naming_services());
+        let expirations =
+            CurrentAnsNameExpiration::from_name_events(&name_events, &naming_services());
+
+        let renewed_at = chrono::DateTime::from_timestamp(renewed_secs, 0)
+            .unwrap()
+            .naive_utc();
+        assert_eq!(expirations, vec![CurrentAnsNameExpiration {
+            token_name: "alice.apt".to_string(),
+            namespace: "ans".to_string(),
+            name_object_address: address(NAME_OBJECT),
+            domain: "alice".to_string(),
+            subdomain: "".to_string(),
+            expiration_timestamp: renewed_at,
+            grace_period_end_timestamp: renewed_at + chrono::Duration::days(30),
+            next_event_type: Some("expire".to_string()),
+            next_event_timestamp: Some(renewed_at),
+            last_transaction_version: 20,
+        }]);
+    }
+}
@@ -28,6 +28,7 @@ pub struct AnsPrimaryNameV2 {
     pub subdomain: Option<String>,
     pub token_name: Option<String>,
     pub is_deleted: bool,
+    pub namespace: String,
 }
 
 impl AnsPrimaryNameV2Convertible for AnsPrimaryNameV2 {
@@ -41,6 +42,7 @@ impl AnsPrimaryNameV2Convertible for AnsPrimaryNameV2 {
             subdomain: raw_item.subdomain,
             token_name: raw_item.token_name,
             is_deleted: raw_item.is_deleted,
+            namespace: raw_item.namespace,
         }
     }
 }
@@ -57,7 +59,7 @@ impl AnsPrimaryNameV2Convertible for AnsPrimaryNameV2 {
     PartialEq,
     Eq,
 )]
-#[diesel(primary_key(registered_address, token_standard))]
+#[diesel(primary_key(registered_address, token_standard, namespace))]
 #[diesel(table_name = current_ans_primary_name_v2)]
 #[diesel(treat_none_as_null = true)]
 pub struct CurrentAnsPrimaryNameV2 {
@@ -68,6 +70,7 @@ pub struct CurrentAnsPrimaryNameV2 {
     pub token_name: Option<String>,
     pub is_deleted: bool,
     pub last_transaction_version: i64,
+    pub namespace: String,
 }
 
 impl CurrentAnsPrimaryNameV2Convertible for CurrentAnsPrimaryNameV2 {
@@ -80,6 +83,7 @@ impl CurrentAnsPrimaryNameV2Convertible for CurrentAnsPrimaryNameV2 {
             token_name: raw_item.token_name,
             is_deleted: raw_item.is_deleted,
             last_transaction_version: raw_item.last_transaction_version,
+            namespace: raw_item.namespace,
         }
     }
 }
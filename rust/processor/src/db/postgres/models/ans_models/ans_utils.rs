@@ -17,6 +17,108 @@ use bigdecimal::BigDecimal;
 use serde::{Deserialize, Serialize};
 
 pub const DOMAIN_LENGTH: usize = 64;
+/// Namespace of the Aptos Names contract at `ans_v2_contract_address`. v1 names are in it too.
+pub const DEFAULT_NAMESPACE: &str = "ans";
+
+/// A naming service with the structure of the Aptos Names v2 contract: a name record resource at
+/// each name's token object, a subdomain resource next to it for subdomains, and events for
+/// registrations, renewals and primary names. The rows of each service are tagged with its
+/// namespace.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NamingServiceConfig {
+    pub namespace: String,
+    pub contract_address: String,
+    #[serde(default = "NamingServiceConfig::default_module_name")]
+    pub module_name: String,
+    #[serde(default = "NamingServiceConfig::default_name_record_struct")]
+    pub name_record_struct: String,
+    #[serde(default = "NamingServiceConfig::default_subdomain_ext_struct")]
+    pub subdomain_ext_struct: String,
+    #[serde(default = "NamingServiceConfig::default_register_name_event_struct")]
+    pub register_name_event_struct: String,
+    #[serde(default = "NamingServiceConfig::default_renew_name_event_struct")]
+    pub renew_name_event_struct: String,
+    #[serde(default = "NamingServiceConfig::default_set_reverse_lookup_event_struct")]
+    pub set_reverse_lookup_event_struct: String,
+    /// How long after a name expires only its owner can renew it. Anyone can register it after.
+    #[serde(default = "NamingServiceConfig::default_reregistration_grace_secs")]
+    pub reregistration_grace_secs: i64,
+}
+
+impl NamingServiceConfig {
+    /// The Aptos Names contract, in the default namespace.
+    pub fn aptos_names(contract_address: &str) -> Self {
+        Self {
+            namespace: DEFAULT_NAMESPACE.to_string(),
+            contract_address: contract_address.to_string(),
+            module_name: Self::default_module_name(),
+            name_record_struct: Self::default_name_record_struct(),
+            subdomain_ext_struct: Self::default_subdomain_ext_struct(),
+            register_name_event_struct: Self::default_register_name_event_struct(),
+            renew_name_event_struct: Self::default_renew_name_event_struct(),
+            set_reverse_lookup_event_struct: Self::default_set_reverse_lookup_event_struct(),
+            reregistration_grace_secs: Self::default_reregistration_grace_secs(),
+        }
+    }
+
+    fn default_module_name() -> String {
+        "v2_1_domains".to_string()
+    }
+
+    fn default_name_record_struct() -> String {
+        "NameRecord".to_string()
+    }
+
+    fn default_subdomain_ext_struct() -> String {
+        "SubdomainExt".to_string()
+    }
+
+    fn default_register_name_event_struct() -> String {
+        "RegisterNameEvent".to_string()
+    }
+
+    fn default_renew_name_event_struct() -> String {
+        "RenewNameEvent".to_string()
+    }
+
+    fn default_set_reverse_lookup_event_struct() -> String {
+        "SetReverseLookupEvent".to_string()
+    }
+
+    fn default_reregistration_grace_secs() -> i64 {
+        30 * 24 * 60 * 60
+    }
+
+    /// The Aptos Names contract followed by the other naming services. Namespaces must be unique.
+    pub fn with_aptos_names(
+        ans_v2_contract_address: &str,
+        additional_naming_services: &[Self],
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut naming_services = vec![Self::aptos_names(ans_v2_contract_address)];
+        for naming_service in additional_naming_services {
+            if naming_services
+                .iter()
+                .any(|existing| existing.namespace == naming_service.namespace)
+            {
+                anyhow::bail!(
+                    "Duplicate ANS naming service namespace {}",
+                    naming_service.namespace
+                );
+            }
+            naming_services.push(naming_service.clone());
+        }
+        Ok(naming_services)
+    }
+
+    /// Fully qualified type of a struct in the service's module.
+    pub fn type_str(&self, struct_name: &str) -> String {
+        format!(
+            "{}::{}::{}",
+            self.contract_address, self.module_name, struct_name
+        )
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct OptionalString {
@@ -154,14 +256,12 @@ impl NameRecordV2 {
 
     pub fn from_write_resource(
         write_resource: &WriteResource,
-        ans_v2_contract_address: &str,
+        naming_service: &NamingServiceConfig,
         txn_version: i64,
     ) -> anyhow::Result<Option<Self>> {
-        if let Some(AnsWriteResource::NameRecordV2(inner)) = AnsWriteResource::from_write_resource(
-            write_resource,
-            ans_v2_contract_address,
-            txn_version,
-        )? {
+        if let Some(AnsWriteResource::NameRecordV2(inner)) =
+            AnsWriteResource::from_write_resource(write_resource, naming_service, txn_version)?
+        {
             Ok(Some(inner))
         } else {
             Ok(None)
@@ -182,15 +282,11 @@ impl SubdomainExtV2 {
 
     pub fn from_write_resource(
         write_resource: &WriteResource,
-        ans_v2_contract_address: &str,
+        naming_service: &NamingServiceConfig,
         txn_version: i64,
     ) -> anyhow::Result<Option<Self>> {
         if let Some(AnsWriteResource::SubdomainExtV2(inner)) =
-            AnsWriteResource::from_write_resource(
-                write_resource,
-                ans_v2_contract_address,
-                txn_version,
-            )?
+            AnsWriteResource::from_write_resource(write_resource, naming_service, txn_version)?
         {
             Ok(Some(inner))
         } else {
@@ -207,17 +303,17 @@ pub enum AnsWriteResource {
 impl AnsWriteResource {
     pub fn from_write_resource(
         write_resource: &WriteResource,
-        ans_v2_contract_address: &str,
+        naming_service: &NamingServiceConfig,
         txn_version: i64,
     ) -> anyhow::Result<Option<Self>> {
         let type_str = MoveResource::get_outer_type_from_write_resource(write_resource);
         let data = write_resource.data.as_str();
 
         match type_str.clone() {
-            x if x == format!("{}::v2_1_domains::NameRecord", ans_v2_contract_address) => {
+            x if x == naming_service.type_str(&naming_service.name_record_struct) => {
                 serde_json::from_str(data).map(|inner| Some(Self::NameRecordV2(inner)))
             },
-            x if x == format!("{}::v2_1_domains::SubdomainExt", ans_v2_contract_address) => {
+            x if x == naming_service.type_str(&naming_service.subdomain_ext_struct) => {
                 serde_json::from_str(data).map(|inner| Some(Self::SubdomainExtV2(inner)))
             },
             _ => Ok(None),
@@ -231,6 +327,36 @@ impl AnsWriteResource {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegisterNameEvent {
+    domain_name: String,
+    #[serde(deserialize_with = "deserialize_from_string")]
+    expiration_time_secs: BigDecimal,
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub registration_fee_octas: BigDecimal,
+    subdomain_name: OptionalString,
+}
+
+impl RegisterNameEvent {
+    pub fn get_domain_trunc(&self) -> String {
+        truncate_str(self.domain_name.as_str(), DOMAIN_LENGTH)
+    }
+
+    pub fn get_subdomain_trunc(&self) -> String {
+        truncate_str(
+            self.subdomain_name
+                .get_string()
+                .unwrap_or_default()
+                .as_str(),
+            DOMAIN_LENGTH,
+        )
+    }
+
+    pub fn get_expiration_time(&self) -> chrono::NaiveDateTime {
+        parse_timestamp_secs(bigdecimal_to_u64(&self.expiration_time_secs), 0)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RenewNameEvent {
     domain_name: String,
@@ -242,13 +368,31 @@ pub struct RenewNameEvent {
 }
 
 impl RenewNameEvent {
+    pub fn get_domain_trunc(&self) -> String {
+        truncate_str(self.domain_name.as_str(), DOMAIN_LENGTH)
+    }
+
+    pub fn get_subdomain_trunc(&self) -> String {
+        truncate_str(
+            self.subdomain_name
+                .get_string()
+                .unwrap_or_default()
+                .as_str(),
+            DOMAIN_LENGTH,
+        )
+    }
+
+    pub fn get_expiration_time(&self) -> chrono::NaiveDateTime {
+        parse_timestamp_secs(bigdecimal_to_u64(&self.expiration_time_secs), 0)
+    }
+
     pub fn from_event(
         event: &Event,
-        ans_v2_contract_address: &str,
+        naming_service: &NamingServiceConfig,
         txn_version: i64,
     ) -> anyhow::Result<Option<Self>> {
         if let Some(V2AnsEvent::RenewNameEvent(inner)) =
-            V2AnsEvent::from_event(event, ans_v2_contract_address, txn_version).unwrap()
+            V2AnsEvent::from_event(event, naming_service, txn_version).unwrap()
         {
             Ok(Some(inner))
         } else {
@@ -301,11 +445,11 @@ impl SetReverseLookupEvent {
 
     pub fn from_event(
         event: &Event,
-        ans_v2_contract_address: &str,
+        naming_service: &NamingServiceConfig,
         txn_version: i64,
     ) -> anyhow::Result<Option<Self>> {
         if let Some(V2AnsEvent::SetReverseLookupEvent(inner)) =
-            V2AnsEvent::from_event(event, ans_v2_contract_address, txn_version).unwrap()
+            V2AnsEvent::from_event(event, naming_service, txn_version).unwrap()
         {
             Ok(Some(inner))
         } else {
@@ -318,44 +462,41 @@ impl SetReverseLookupEvent {
 pub enum V2AnsEvent {
     SetReverseLookupEvent(SetReverseLookupEvent),
     RenewNameEvent(RenewNameEvent),
+    RegisterNameEvent(RegisterNameEvent),
 }
 
 impl V2AnsEvent {
-    pub fn is_event_supported(event_type: &str, ans_v2_contract_address: &str) -> bool {
+    pub fn is_event_supported(event_type: &str, naming_service: &NamingServiceConfig) -> bool {
         [
-            format!(
-                "{}::v2_1_domains::SetReverseLookupEvent",
-                ans_v2_contract_address
-            ),
-            format!("{}::v2_1_domains::RenewNameEvent", ans_v2_contract_address),
+            naming_service.type_str(&naming_service.set_reverse_lookup_event_struct),
+            naming_service.type_str(&naming_service.renew_name_event_struct),
+            naming_service.type_str(&naming_service.register_name_event_struct),
         ]
         .contains(&event_type.to_string())
     }
 
     pub fn from_event(
         event: &Event,
-        ans_v2_contract_address: &str,
+        naming_service: &NamingServiceConfig,
         txn_version: i64,
     ) -> anyhow::Result<Option<Self>> {
         let type_str: String = event.type_str.clone();
         let data = event.data.as_str();
 
-        if !Self::is_event_supported(type_str.as_str(), ans_v2_contract_address) {
+        if !Self::is_event_supported(type_str.as_str(), naming_service) {
             return Ok(None);
         }
 
         match type_str.clone() {
-            x if x
-                == format!(
-                    "{}::v2_1_domains::SetReverseLookupEvent",
-                    ans_v2_contract_address
-                ) =>
-            {
+            x if x == naming_service.type_str(&naming_service.set_reverse_lookup_event_struct) => {
                 serde_json::from_str(data).map(|inner| Some(Self::SetReverseLookupEvent(inner)))
             },
-            x if x == format!("{}::v2_1_domains::RenewNameEvent", ans_v2_contract_address) => {
+            x if x == naming_service.type_str(&naming_service.renew_name_event_struct) => {
                 serde_json::from_str(data).map(|inner| Some(Self::RenewNameEvent(inner)))
             },
+            x if x == naming_service.type_str(&naming_service.register_name_event_struct) => {
+                serde_json::from_str(data).map(|inner| Some(Self::RegisterNameEvent(inner)))
+            },
             _ => Ok(None),
         }
         .context(format!(
@@ -3,6 +3,7 @@
 
 pub mod ans_lookup;
 pub mod ans_lookup_v2;
+pub mod ans_name_events;
 pub mod ans_primary_name_v2;
 pub mod ans_utils;
 
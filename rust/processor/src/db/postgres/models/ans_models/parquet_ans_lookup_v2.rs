@@ -11,7 +11,7 @@ use crate::{
         common::models::token_v2_models::v2_token_utils::TokenStandard,
         postgres::models::ans_models::{
             ans_lookup::{AnsPrimaryName, CurrentAnsPrimaryName},
-            ans_utils::SetReverseLookupEvent,
+            ans_utils::{NamingServiceConfig, SetReverseLookupEvent},
         },
     },
 };
@@ -111,11 +111,11 @@ impl CurrentAnsPrimaryNameV2 {
         event: &Event,
         txn_version: i64,
         event_index: i64,
-        ans_v2_contract_address: &str,
+        naming_service: &NamingServiceConfig,
         block_timestamp: chrono::NaiveDateTime,
     ) -> anyhow::Result<Option<(Self, AnsPrimaryNameV2)>> {
         if let Some(set_reverse_lookup_event) =
-            SetReverseLookupEvent::from_event(event, ans_v2_contract_address, txn_version).unwrap()
+            SetReverseLookupEvent::from_event(event, naming_service, txn_version).unwrap()
         {
             if set_reverse_lookup_event.get_curr_domain_trunc().is_empty() {
                 // Handle case where the address's primary name is unset
@@ -13,6 +13,7 @@ use crate::{
         postgres::models::coin_models::coin_utils::EventGuidResource,
     },
     schema::fungible_asset_activities,
+    utils::address_labels::Labeled,
 };
 use ahash::AHashMap;
 use bigdecimal::BigDecimal;
@@ -50,6 +51,7 @@ pub struct FungibleAssetActivity {
     pub token_standard: String,
     pub transaction_timestamp: chrono::NaiveDateTime,
     pub storage_refund_amount: BigDecimal,
+    pub label: Option<String>,
 }
 
 impl FungibleAssetActivityConvertible for FungibleAssetActivity {
@@ -71,6 +73,17 @@ impl FungibleAssetActivityConvertible for FungibleAssetActivity {
             token_standard: raw_item.token_standard,
             transaction_timestamp: raw_item.transaction_timestamp,
             storage_refund_amount: raw_item.storage_refund_amount,
+            label: None,
         }
     }
 }
+
+impl Labeled for FungibleAssetActivity {
+    fn labeled_address(&self) -> Option<&str> {
+        self.owner_address.as_deref()
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+}
@@ -22,6 +22,14 @@ const FUNGIBLE_ASSET_SYMBOL: usize = 32;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FeeStatement {
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub total_charge_gas_units: u64,
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub execution_gas_units: u64,
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub io_gas_units: u64,
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub storage_fee_octas: u64,
     #[serde(deserialize_with = "deserialize_from_string")]
     pub storage_fee_refund_octas: u64,
 }
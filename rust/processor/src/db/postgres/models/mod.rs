@@ -3,6 +3,7 @@
 
 pub mod account_restoration_models;
 pub mod account_transaction_models;
+pub mod address_labels;
 pub mod ans_models;
 pub mod coin_models;
 pub mod default_models;
@@ -10,9 +11,11 @@ pub mod events_models;
 pub mod fungible_asset_models;
 pub mod ledger_info;
 pub mod object_models;
+pub mod processor_gaps;
 pub mod processor_status;
 pub mod property_map;
 pub mod resources;
+pub mod spam_models;
 pub mod stake_models;
 pub mod token_models;
 pub mod token_v2_models;
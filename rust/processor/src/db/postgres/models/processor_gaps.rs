@@ -0,0 +1,151 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::{
+    schema::processor_gaps,
+    utils::database::{execute_with_better_error, ArcDbPool, DbPoolConnection},
+};
+use diesel::{
+    deserialize,
+    deserialize::{FromSql, FromSqlRow},
+    expression::AsExpression,
+    pg::{Pg, PgValue},
+    serialize,
+    serialize::{IsNull, Output, ToSql},
+    sql_types::Text,
+    ExpressionMethods, QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use std::io::Write;
+
+const DETECTED: &[u8] = b"detected";
+const REPAIRING: &[u8] = b"repairing";
+const REPAIRED: &[u8] = b"repaired";
+const FAILED: &[u8] = b"failed";
+
+#[derive(Clone, Copy, Debug, PartialEq, FromSqlRow, AsExpression, Eq)]
+#[diesel(sql_type = Text)]
+pub enum GapRepairStatus {
+    Detected,
+    Repairing,
+    Repaired,
+    // The repair failed too many times and is no longer retried
+    Failed,
+}
+
+impl ToSql<Text, Pg> for GapRepairStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            GapRepairStatus::Detected => out.write_all(DETECTED)?,
+            GapRepairStatus::Repairing => out.write_all(REPAIRING)?,
+            GapRepairStatus::Repaired => out.write_all(REPAIRED)?,
+            GapRepairStatus::Failed => out.write_all(FAILED)?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for GapRepairStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"detected" => Ok(GapRepairStatus::Detected),
+            b"repairing" => Ok(GapRepairStatus::Repairing),
+            b"repaired" => Ok(GapRepairStatus::Repaired),
+            b"failed" => Ok(GapRepairStatus::Failed),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = processor_gaps)]
+/// A range of versions a processor skipped over, to be filled in by a backfill
+pub struct ProcessorGap {
+    pub processor: String,
+    pub gap_start_version: i64,
+    pub gap_end_version: i64,
+    pub repair_status: GapRepairStatus,
+    pub backfill_alias: Option<String>,
+}
+
+impl ProcessorGap {
+    /// Records a gap. Recording the same gap again is a no-op, so a gap that is still being repaired
+    /// keeps its status.
+    pub async fn record(
+        pool: ArcDbPool,
+        processor_name: &str,
+        gap_start_version: u64,
+        gap_end_version: u64,
+    ) -> anyhow::Result<()> {
+        let gap = ProcessorGap {
+            processor: processor_name.to_string(),
+            gap_start_version: gap_start_version as i64,
+            gap_end_version: gap_end_version as i64,
+            repair_status: GapRepairStatus::Detected,
+            backfill_alias: None,
+        };
+        execute_with_better_error(
+            pool,
+            diesel::insert_into(processor_gaps::table)
+                .values(gap)
+                .on_conflict((processor_gaps::processor, processor_gaps::gap_start_version))
+                .do_nothing(),
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Queryable)]
+#[diesel(table_name = processor_gaps)]
+pub struct ProcessorGapQuery {
+    pub processor: String,
+    pub gap_start_version: i64,
+    pub gap_end_version: i64,
+    pub repair_status: GapRepairStatus,
+    pub backfill_alias: Option<String>,
+    pub detected_at: chrono::NaiveDateTime,
+    pub last_updated: chrono::NaiveDateTime,
+}
+
+impl ProcessorGapQuery {
+    /// Gaps of the processor that are still to be repaired, oldest version first
+    pub async fn get_unrepaired_by_processor(
+        processor_name: &str,
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<Vec<Self>> {
+        processor_gaps::table
+            .filter(processor_gaps::processor.eq(processor_name))
+            .filter(
+                processor_gaps::repair_status
+                    .eq_any([GapRepairStatus::Detected, GapRepairStatus::Repairing]),
+            )
+            .order(processor_gaps::gap_start_version.asc())
+            .load::<Self>(conn)
+            .await
+    }
+
+    pub async fn update_repair_status(
+        processor_name: &str,
+        gap_start_version: i64,
+        repair_status: GapRepairStatus,
+        backfill_alias: &str,
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<usize> {
+        diesel::update(
+            processor_gaps::table
+                .filter(processor_gaps::processor.eq(processor_name))
+                .filter(processor_gaps::gap_start_version.eq(gap_start_version)),
+        )
+        .set((
+            processor_gaps::repair_status.eq(repair_status),
+            processor_gaps::backfill_alias.eq(backfill_alias),
+            processor_gaps::last_updated.eq(diesel::dsl::now),
+        ))
+        .execute(conn)
+        .await
+    }
+}
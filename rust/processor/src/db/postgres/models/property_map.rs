@@ -7,6 +7,14 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_json::{Result, Value};
 
+/// A property with its decoded value and Move type, e.g. `u64` or `0x1::string::String`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TypedProperty {
+    pub key: String,
+    pub typ: String,
+    pub value: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PropertyValue {
     value: String,
@@ -42,6 +50,24 @@ impl PropertyMap {
         Some(Self::to_flat_json(pm))
     }
 
+    /// Deserializes PropertyValue from bcs encoded json, keeping the type of each value
+    pub fn typed_from_bcs_encode_str(val: &Value) -> Option<Vec<TypedProperty>> {
+        let records: &Vec<Value> = val.get("map")?.get("data")?.as_array()?;
+        let mut properties = vec![];
+        for entry in records {
+            let key = entry.get("key")?.as_str()?;
+            let val = entry.get("value")?.get("value")?.as_str()?;
+            let typ = entry.get("value")?.get("type")?.as_str()?;
+            let pv = create_property_value(typ.to_string(), val.to_string()).ok()?;
+            properties.push(TypedProperty {
+                key: key.to_string(),
+                typ: pv.typ,
+                value: pv.value,
+            });
+        }
+        Some(properties)
+    }
+
     /// Flattens PropertyMap which can't be easily consumable by downstream.
     /// For example: Object {"data": Object {"creation_time_sec": Object {"value": String("1666125588")}}}
     /// becomes Object {"creation_time_sec": "1666125588"}
@@ -92,6 +118,24 @@ impl TokenObjectPropertyMap {
         Some(Self::to_flat_json_new(pm))
     }
 
+    /// Deserializes PropertyValue from bcs encoded json, keeping the type of each value
+    pub fn typed_from_bcs_encode_str(val: &Value) -> Option<Vec<TypedProperty>> {
+        let records: &Vec<Value> = val.get("data")?.as_array()?;
+        let mut properties = vec![];
+        for entry in records {
+            let key = entry.get("key")?.as_str()?;
+            let val = entry.get("value")?.get("value")?.as_str()?;
+            let typ = entry.get("value")?.get("type")?.as_u64()?;
+            let pv = create_token_object_property_value(typ as u8, val.to_string()).ok()?;
+            properties.push(TypedProperty {
+                key: key.to_string(),
+                typ: token_object_property_type_name(pv.typ),
+                value: pv.value,
+            });
+        }
+        Some(properties)
+    }
+
     /// Flattens PropertyMap which can't be easily consumable by downstream.
     /// For example: Object {"data": Object {"creation_time_sec": Object {"value": String("1666125588")}}}
     /// becomes Object {"creation_time_sec": "1666125588"}
@@ -103,3 +147,112 @@ impl TokenObjectPropertyMap {
         serde_json::to_value(map).unwrap()
     }
 }
+
+/// Move type of a 0x4::property_map value, which stores the type as a number
+fn token_object_property_type_name(typ: u8) -> String {
+    match typ {
+        0 => "bool",
+        1 => "u8",
+        2 => "u16",
+        3 => "u32",
+        4 => "u64",
+        5 => "u128",
+        6 => "u256",
+        7 => "address",
+        8 => "vector<u8>",
+        9 => "0x1::string::String",
+        _ => return typ.to_string(),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_typed_from_bcs_encode_str_v1() {
+        let default_properties = json!({
+            "map": {
+                "data": [
+                    {"key": "Background", "value": {"type": "0x1::string::String", "value": "0x04426c7565"}},
+                    {"key": "Level", "value": {"type": "u64", "value": "0x0500000000000000"}},
+                    {"key": "Legendary", "value": {"type": "bool", "value": "0x01"}},
+                    // Types that can't be decoded keep their raw value
+                    {"key": "Raw", "value": {"type": "vector<u8>", "value": "0x0102"}},
+                ]
+            }
+        });
+        let properties = PropertyMap::typed_from_bcs_encode_str(&default_properties).unwrap();
+        assert_eq!(properties, vec![
+            TypedProperty {
+                key: "Background".to_string(),
+                typ: "0x1::string::String".to_string(),
+                value: "Blue".to_string(),
+            },
+            TypedProperty {
+                key: "Level".to_string(),
+                typ: "u64".to_string(),
+                value: "5".to_string(),
+            },
+            TypedProperty {
+                key: "Legendary".to_string(),
+                typ: "bool".to_string(),
+                value: "true".to_string(),
+            },
+            TypedProperty {
+                key: "Raw".to_string(),
+                typ: "vector<u8>".to_string(),
+                value: "0x0102".to_string(),
+            },
+        ]);
+
+        assert_eq!(
+            PropertyMap::typed_from_bcs_encode_str(&json!({"map": {"data": []}})),
+            Some(vec![])
+        );
+        assert_eq!(
+            PropertyMap::typed_from_bcs_encode_str(&json!({"data": []})),
+            None
+        );
+    }
+
+    #[test]
+    fn test_typed_from_bcs_encode_str_v2() {
+        let inner = json!({
+            "data": [
+                {"key": "Background", "value": {"type": 9, "value": "0x04426c7565"}},
+                {"key": "Level", "value": {"type": 4, "value": "0x0500000000000000"}},
+                {"key": "Hash", "value": {"type": 8, "value": "0x02abcd"}},
+            ]
+        });
+        let properties = TokenObjectPropertyMap::typed_from_bcs_encode_str(&inner).unwrap();
+        assert_eq!(properties, vec![
+            TypedProperty {
+                key: "Background".to_string(),
+                typ: "0x1::string::String".to_string(),
+                value: "Blue".to_string(),
+            },
+            TypedProperty {
+                key: "Level".to_string(),
+                typ: "u64".to_string(),
+                value: "5".to_string(),
+            },
+            TypedProperty {
+                key: "Hash".to_string(),
+                typ: "vector<u8>".to_string(),
+                value: "0xabcd".to_string(),
+            },
+        ]);
+
+        // The type is a number in 0x4::property_map
+        let inner = json!({
+            "data": [{"key": "Background", "value": {"type": "0x1::string::String", "value": "0x04426c7565"}}]
+        });
+        assert_eq!(
+            TokenObjectPropertyMap::typed_from_bcs_encode_str(&inner),
+            None
+        );
+    }
+}
@@ -0,0 +1,16 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::schema::spam_assets;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+
+/// Whether an asset is spam. `asset` is a fungible asset metadata address, a coin type, or a
+/// collection id. `last_updated` is set by the database.
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(asset))]
+#[diesel(table_name = spam_assets)]
+pub struct SpamAsset {
+    pub asset: String,
+    pub is_spam: bool,
+}
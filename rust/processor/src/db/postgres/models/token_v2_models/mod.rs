@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod v1_token_royalty;
+pub mod v2_collection_stats;
 pub mod v2_collections;
 pub mod v2_token_activities;
+pub mod v2_token_compositions;
 pub mod v2_token_datas;
 pub mod v2_token_metadata;
 pub mod v2_token_ownerships;
+pub mod v2_token_properties;
@@ -0,0 +1,382 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+
+use super::v2_token_activities::TokenActivityV2;
+use crate::{
+    db::common::models::token_v2_models::v2_token_utils::CollectionActivityType,
+    schema::{
+        collection_activities, collection_activity_hourly, current_collection_holders,
+        current_collection_stats,
+    },
+};
+use ahash::AHashMap;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::Timelike;
+use diesel::prelude::*;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A token transfer or burn with the collection of its token.
+#[derive(Clone, Debug, Deserialize, FieldCount, Insertable, PartialEq, Queryable, Serialize)]
+#[diesel(table_name = collection_activities)]
+pub struct CollectionActivity {
+    pub transaction_version: i64,
+    pub event_index: i64,
+    pub collection_id: String,
+    pub activity_type: String,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+}
+
+impl CollectionActivity {
+    /// Transfers and burns of the batch. `collections` maps token data ids to their collection, and
+    /// activities of tokens without one are skipped.
+    pub fn from_activities(
+        token_activities: &[TokenActivityV2],
+        collections: &AHashMap<String, String>,
+    ) -> Vec<Self> {
+        token_activities
+            .iter()
+            .filter_map(|activity| {
+                let activity_type = CollectionActivityType::from_activity_type(&activity.type_)?;
+                let collection_id = collections.get(&activity.token_data_id)?;
+                Some(Self {
+                    transaction_version: activity.transaction_version,
+                    event_index: activity.event_index,
+                    collection_id: collection_id.clone(),
+                    activity_type: activity_type.to_string(),
+                    transaction_timestamp: activity.transaction_timestamp,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Transfers and burns of a collection in an hour. As a change to `collection_activity_hourly`, the
+/// counts are added to the stored ones.
+#[derive(Clone, Debug, Deserialize, FieldCount, Insertable, PartialEq, Serialize)]
+#[diesel(table_name = collection_activity_hourly)]
+pub struct CollectionActivityHourly {
+    pub collection_id: String,
+    pub hour: chrono::NaiveDateTime,
+    pub num_transfers: i64,
+    pub num_burns: i64,
+    pub last_transaction_version: i64,
+}
+
+impl CollectionActivityHourly {
+    /// Rolls up activities by collection and hour, ordered by collection and then hour. Only the
+    /// activities recorded for the first time are rolled up, so a replayed batch adds nothing.
+    pub fn from_collection_activities(activities: &[CollectionActivity]) -> Vec<Self> {
+        let mut rollups: BTreeMap<(&str, chrono::NaiveDateTime), Self> = BTreeMap::new();
+        for activity in activities {
+            let hour = truncate_to_hour(activity.transaction_timestamp);
+            let rollup = rollups
+                .entry((activity.collection_id.as_str(), hour))
+                .or_insert_with(|| Self {
+                    collection_id: activity.collection_id.clone(),
+                    hour,
+                    num_transfers: 0,
+                    num_burns: 0,
+                    last_transaction_version: activity.transaction_version,
+                });
+            if activity.activity_type == CollectionActivityType::Burn.to_string() {
+                rollup.num_burns += 1;
+            } else {
+                rollup.num_transfers += 1;
+            }
+            rollup.last_transaction_version = rollup
+                .last_transaction_version
+                .max(activity.transaction_version);
+        }
+        rollups.into_values().collect()
+    }
+}
+
+/// The number of tokens of a collection an owner holds.
+#[derive(
+    Clone, Debug, Deserialize, FieldCount, Insertable, PartialEq, QueryableByName, Serialize,
+)]
+#[diesel(table_name = current_collection_holders)]
+pub struct CurrentCollectionHolder {
+    pub collection_id: String,
+    pub owner_address: String,
+    pub num_tokens: BigDecimal,
+    pub last_transaction_version: i64,
+}
+
+/// Holders and burns of a collection. As a change to `current_collection_stats`, the counts are the
+/// numbers gained or lost.
+#[derive(Clone, Debug, Default, Deserialize, FieldCount, Insertable, PartialEq, Serialize)]
+#[diesel(table_name = current_collection_stats)]
+pub struct CurrentCollectionStats {
+    pub collection_id: String,
+    pub num_holders: i64,
+    pub num_holders_1: i64,
+    pub num_holders_2_to_5: i64,
+    pub num_holders_6_to_20: i64,
+    pub num_holders_21_plus: i64,
+    pub num_burned: i64,
+    pub last_transaction_version: i64,
+}
+
+/// What a batch changes in `current_collection_holders` and `current_collection_stats`.
+#[derive(Debug, Default, PartialEq)]
+pub struct CollectionStatsChanges {
+    /// Holders that have tokens of the collection
+    pub holders: Vec<CurrentCollectionHolder>,
+    /// Stored holders that have no tokens of the collection anymore
+    pub removed_holders: Vec<CurrentCollectionHolder>,
+    /// Collections whose stats changed, ordered by collection
+    pub stats: Vec<CurrentCollectionStats>,
+}
+
+impl CurrentCollectionStats {
+    /// `counted_holders` are the recounted tokens of each (collection, owner) whose ownership
+    /// changed in the batch, with 0 for owners that have none left, and `stored_holders` the stored
+    /// counts of the same owners. Each stat changes by the difference between the two, so only the
+    /// holders the batch touched are read and replaying a batch leaves the stats as they are. Burns
+    /// are counted from the activities recorded for the first time.
+    pub fn get_changes(
+        stored_holders: Vec<CurrentCollectionHolder>,
+        counted_holders: Vec<CurrentCollectionHolder>,
+        new_activities: &[CollectionActivity],
+    ) -> CollectionStatsChanges {
+        let mut stored_holders: AHashMap<(String, String), CurrentCollectionHolder> =
+            stored_holders
+                .into_iter()
+                .map(|holder| {
+                    (
+                        (holder.collection_id.clone(), holder.owner_address.clone()),
+                        holder,
+                    )
+                })
+                .collect();
+        let mut changes = CollectionStatsChanges::default();
+        let mut stats: BTreeMap<String, Self> = BTreeMap::new();
+        for holder in counted_holders {
+            let stored = stored_holders
+                .remove(&(holder.collection_id.clone(), holder.owner_address.clone()));
+            let collection_stats = stats
+                .entry(holder.collection_id.clone())
+                .or_insert_with(|| Self {
+                    collection_id: holder.collection_id.clone(),
+                    ..Default::default()
+                });
+            collection_stats.last_transaction_version = collection_stats
+                .last_transaction_version
+                .max(holder.last_transaction_version);
+            if let Some(stored) = &stored {
+                collection_stats.add_holder(&stored.num_tokens, -1);
+            }
+            if holder.num_tokens > BigDecimal::zero() {
+                collection_stats.add_holder(&holder.num_tokens, 1);
+                changes.holders.push(holder);
+            } else if let Some(stored) = stored {
+                changes.removed_holders.push(stored);
+            }
+        }
+        for activity in new_activities {
+            if activity.activity_type != CollectionActivityType::Burn.to_string() {
+                continue;
+            }
+            let collection_stats =
+                stats
+                    .entry(activity.collection_id.clone())
+                    .or_insert_with(|| Self {
+                        collection_id: activity.collection_id.clone(),
+                        ..Default::default()
+                    });
+            collection_stats.num_burned += 1;
+            collection_stats.last_transaction_version = collection_stats
+                .last_transaction_version
+                .max(activity.transaction_version);
+        }
+        changes.stats = stats
+            .into_values()
+            .filter(|stats| !stats.is_unchanged())
+            .collect();
+        changes
+    }
+
+    /// Adds `delta` holders with `num_tokens` tokens of the collection to their bucket.
+    fn add_holder(&mut self, num_tokens: &BigDecimal, delta: i64) {
+        self.num_holders += delta;
+        if *num_tokens <= BigDecimal::from(1) {
+            self.num_holders_1 += delta;
+        } else if *num_tokens <= BigDecimal::from(5) {
+            self.num_holders_2_to_5 += delta;
+        } else if *num_tokens <= BigDecimal::from(20) {
+            self.num_holders_6_to_20 += delta;
+        } else {
+            self.num_holders_21_plus += delta;
+        }
+    }
+
+    fn is_unchanged(&self) -> bool {
+        self.num_holders == 0
+            && self.num_holders_1 == 0
+            && self.num_holders_2_to_5 == 0
+            && self.num_holders_6_to_20 == 0
+            && self.num_holders_21_plus == 0
+            && self.num_burned == 0
+    }
+}
+
+fn truncate_to_hour(timestamp: chrono::NaiveDateTime) -> chrono::NaiveDateTime {
+    timestamp
+        .date()
+        .and_hms_opt(timestamp.hour(), 0, 0)
+        .expect("Hour of a valid timestamp is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp(timestamp: &str) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn collection_activity(
+        transaction_version: i64,
+        activity_type: CollectionActivityType,
+        transaction_timestamp: &str,
+    ) -> CollectionActivity {
+        CollectionActivity {
+            transaction_version,
+            event_index: 0,
+            collection_id: "0xc".to_string(),
+            activity_type: activity_type.to_string(),
+            transaction_timestamp: timestamp(transaction_timestamp),
+        }
+    }
+
+    fn holder(owner_address: &str, num_tokens: i64, version: i64) -> CurrentCollectionHolder {
+        CurrentCollectionHolder {
+            collection_id: "0xc".to_string(),
+            owner_address: owner_address.to_string(),
+            num_tokens: BigDecimal::from(num_tokens),
+            last_transaction_version: version,
+        }
+    }
+
+    #[test]
+    fn test_collection_activities_from_activities() {
+        let activity =
+            |transaction_version: i64, token_data_id: &str, type_: &str| TokenActivityV2 {
+                transaction_version,
+                event_index: 0,
+                event_account_address: token_data_id.to_string(),
+                token_data_id: token_data_id.to_string(),
+                property_version_v1: BigDecimal::zero(),
+                type_: type_.to_string(),
+                from_address: None,
+                to_address: None,
+                token_amount: BigDecimal::from(1),
+                before_value: None,
+                after_value: None,
+                entry_function_id_str: None,
+                token_standard: "v2".to_string(),
+                is_fungible_v2: None,
+                transaction_timestamp: timestamp("2025-03-21 10:30:00"),
+                label: None,
+            };
+        let collections = AHashMap::from_iter([("0xa".to_string(), "0xc".to_string())]);
+        let activities = CollectionActivity::from_activities(
+            &[
+                activity(1, "0xa", "0x1::object::TransferEvent"),
+                activity(2, "0xa", "0x4::collection::MutationEvent"),
+                activity(3, "0xa", "0x4::collection::Burn"),
+                // Without a collection
+                activity(4, "0xb", "0x1::object::TransferEvent"),
+            ],
+            &collections,
+        );
+        assert_eq!(activities, vec![
+            collection_activity(1, CollectionActivityType::Transfer, "2025-03-21 10:30:00"),
+            collection_activity(3, CollectionActivityType::Burn, "2025-03-21 10:30:00"),
+        ]);
+    }
+
+    #[test]
+    fn test_collection_activity_hourly_from_collection_activities() {
+        let rollups = CollectionActivityHourly::from_collection_activities(&[
+            collection_activity(1, CollectionActivityType::Transfer, "2025-03-21 10:00:00"),
+            collection_activity(3, CollectionActivityType::Transfer, "2025-03-21 10:59:59"),
+            collection_activity(2, CollectionActivityType::Burn, "2025-03-21 10:30:00"),
+            collection_activity(4, CollectionActivityType::Transfer, "2025-03-21 11:00:00"),
+        ]);
+        assert_eq!(rollups, vec![
+            CollectionActivityHourly {
+                collection_id: "0xc".to_string(),
+                hour: timestamp("2025-03-21 10:00:00"),
+                num_transfers: 2,
+                num_burns: 1,
+                last_transaction_version: 3,
+            },
+            CollectionActivityHourly {
+                collection_id: "0xc".to_string(),
+                hour: timestamp("2025-03-21 11:00:00"),
+                num_transfers: 1,
+                num_burns: 0,
+                last_transaction_version: 4,
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_get_changes() {
+        let changes = CurrentCollectionStats::get_changes(
+            vec![
+                holder("0x1", 1, 10),
+                holder("0x2", 3, 10),
+                holder("0x3", 5, 10),
+            ],
+            vec![
+                // Receives a second token
+                holder("0x1", 2, 20),
+                // Sends its tokens away
+                holder("0x2", 0, 20),
+                // Unchanged, e.g. a replayed batch
+                holder("0x3", 5, 20),
+                // A new holder
+                holder("0x4", 21, 20),
+            ],
+            &[
+                collection_activity(15, CollectionActivityType::Burn, "2025-03-21 10:00:00"),
+                collection_activity(16, CollectionActivityType::Transfer, "2025-03-21 10:00:00"),
+            ],
+        );
+        assert_eq!(changes.holders, vec![
+            holder("0x1", 2, 20),
+            holder("0x3", 5, 20),
+            holder("0x4", 21, 20),
+        ]);
+        assert_eq!(changes.removed_holders, vec![holder("0x2", 3, 10)]);
+        assert_eq!(changes.stats, vec![CurrentCollectionStats {
+            collection_id: "0xc".to_string(),
+            // 0x4 is new and 0x2 is gone
+            num_holders: 0,
+            // 0x1 moves up a bucket
+            num_holders_1: -1,
+            num_holders_2_to_5: 0,
+            num_holders_6_to_20: 0,
+            num_holders_21_plus: 1,
+            num_burned: 1,
+            last_transaction_version: 20,
+        }]);
+    }
+
+    #[test]
+    fn test_get_changes_replayed() {
+        let holders = vec![holder("0x1", 1, 10)];
+        let changes = CurrentCollectionStats::get_changes(holders.clone(), holders.clone(), &[]);
+        assert_eq!(changes.holders, holders);
+        assert!(changes.removed_holders.is_empty());
+        assert!(changes.stats.is_empty());
+    }
+}
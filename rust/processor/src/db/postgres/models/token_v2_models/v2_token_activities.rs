@@ -10,6 +10,7 @@ use crate::{
         RawTokenActivityV2, TokenActivityV2Convertible,
     },
     schema::token_activities_v2,
+    utils::address_labels::Labeled,
 };
 use bigdecimal::BigDecimal;
 use field_count::FieldCount;
@@ -34,6 +35,7 @@ pub struct TokenActivityV2 {
     pub token_standard: String,
     pub is_fungible_v2: Option<bool>,
     pub transaction_timestamp: chrono::NaiveDateTime,
+    pub label: Option<String>,
 }
 
 impl TokenActivityV2Convertible for TokenActivityV2 {
@@ -54,6 +56,18 @@ impl TokenActivityV2Convertible for TokenActivityV2 {
             token_standard: raw_item.token_standard,
             is_fungible_v2: raw_item.is_fungible_v2,
             transaction_timestamp: raw_item.transaction_timestamp,
+            label: None,
         }
     }
 }
+
+/// The recipient of the token, or its sender when there is none, e.g. for a burn.
+impl Labeled for TokenActivityV2 {
+    fn labeled_address(&self) -> Option<&str> {
+        self.to_address.as_deref().or(self.from_address.as_deref())
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+}
@@ -0,0 +1,456 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+
+use super::{v2_token_activities::TokenActivityV2, v2_token_ownerships::TokenOwnershipV2};
+use crate::{
+    db::common::models::token_v2_models::v2_token_utils::{CollectionActivityType, TokenStandard},
+    schema::token_compositions,
+};
+use ahash::{AHashMap, AHashSet};
+use bigdecimal::{BigDecimal, One};
+use diesel::prelude::*;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A period in which a token was owned by another token. The composition is open until the child
+/// moves elsewhere or is burned.
+#[derive(
+    Clone, Debug, Deserialize, FieldCount, Insertable, PartialEq, QueryableByName, Serialize,
+)]
+#[diesel(table_name = token_compositions)]
+pub struct TokenComposition {
+    pub child_token_data_id: String,
+    pub compose_transaction_version: i64,
+    pub parent_token_data_id: String,
+    pub compose_timestamp: chrono::NaiveDateTime,
+    pub decompose_transaction_version: Option<i64>,
+    pub decompose_timestamp: Option<chrono::NaiveDateTime>,
+}
+
+/// Changes of a batch to `token_compositions`.
+#[derive(Debug, Default, PartialEq)]
+pub struct TokenCompositionChanges {
+    /// Compositions that start in the batch. A stored one is kept as it is, since a later batch may
+    /// have ended it.
+    pub compositions: Vec<TokenComposition>,
+    /// Stored compositions the batch ends, with their decompose version set.
+    pub decomposed: Vec<TokenComposition>,
+}
+
+/// An owner of a token in the order the owners changed, either from the batch or the stored
+/// composition the token was in when the batch started.
+struct Owner<'a> {
+    transaction_version: i64,
+    owner_address: Option<&'a str>,
+    transaction_timestamp: chrono::NaiveDateTime,
+    composition: Option<&'a TokenComposition>,
+}
+
+/// Owner of a v2 token as of a transaction. The owner is None once the token is burned.
+/// Compositions are derived from these: a token is composed into its owner while the owner is
+/// another token.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TokenOwnerChange {
+    pub token_data_id: String,
+    pub transaction_version: i64,
+    pub owner_address: Option<String>,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+}
+
+impl TokenOwnerChange {
+    /// Owner changes of the v2 non fungible tokens of a batch, ordered by token and version. A token
+    /// written more than once in a transaction ends up with the owner of its last write, and every
+    /// write of a token records its owner, so consecutive writes with the same owner are merged.
+    pub fn from_ownerships_and_activities(
+        token_ownerships: &[TokenOwnershipV2],
+        token_activities: &[TokenActivityV2],
+    ) -> Vec<Self> {
+        let v2_standard = TokenStandard::V2.to_string();
+        let owners = token_ownerships
+            .iter()
+            .filter(|ownership| {
+                ownership.token_standard == v2_standard
+                    && ownership.is_fungible_v2 != Some(true)
+                    && ownership.amount == BigDecimal::one()
+            })
+            .map(|ownership| {
+                (
+                    ownership.write_set_change_index,
+                    Self {
+                        token_data_id: ownership.token_data_id.clone(),
+                        transaction_version: ownership.transaction_version,
+                        owner_address: ownership.owner_address.clone(),
+                        transaction_timestamp: ownership.transaction_timestamp,
+                    },
+                )
+            });
+        let burns = token_activities
+            .iter()
+            .filter(|activity| {
+                activity.token_standard == v2_standard
+                    && CollectionActivityType::from_activity_type(&activity.type_)
+                        == Some(CollectionActivityType::Burn)
+            })
+            .map(|activity| {
+                (
+                    activity.event_index,
+                    Self {
+                        token_data_id: activity.token_data_id.clone(),
+                        transaction_version: activity.transaction_version,
+                        owner_address: None,
+                        transaction_timestamp: activity.transaction_timestamp,
+                    },
+                )
+            });
+
+        // Within a transaction, a burn comes after the ownerships, and the last write of a token
+        // comes first so that it's the one kept
+        let mut changes: Vec<(i64, Self)> = owners.chain(burns).collect();
+        changes.sort_by(|(a_index, a), (b_index, b)| {
+            a.token_data_id
+                .cmp(&b.token_data_id)
+                .then(a.transaction_version.cmp(&b.transaction_version))
+                .then(a.owner_address.is_none().cmp(&b.owner_address.is_none()))
+                .then(b_index.cmp(a_index))
+        });
+        changes.dedup_by(|(_, change), (_, previous)| {
+            change.token_data_id == previous.token_data_id
+                && change.transaction_version == previous.transaction_version
+                && change.owner_address.is_some() == previous.owner_address.is_some()
+        });
+        let mut changes: Vec<Self> = changes.into_iter().map(|(_, change)| change).collect();
+        changes.dedup_by(|change, previous| {
+            change.token_data_id == previous.token_data_id
+                && change.owner_address == previous.owner_address
+        });
+        changes
+    }
+}
+
+impl TokenComposition {
+    /// Compositions started and ended by the owner changes of a batch. `latest_compositions` holds,
+    /// for each token, the last stored composition that started before its first change in the
+    /// batch, and `tokens` the owners that are tokens. The composition a token was in when the batch
+    /// started ends once its owner changes, and one starts for every later owner that is a token.
+    pub fn get_changes(
+        owner_changes: &[TokenOwnerChange],
+        latest_compositions: Vec<Self>,
+        tokens: &AHashSet<String>,
+    ) -> TokenCompositionChanges {
+        let latest_compositions: AHashMap<String, Self> = latest_compositions
+            .into_iter()
+            .map(|composition| (composition.child_token_data_id.clone(), composition))
+            .collect();
+        let mut changes_by_token: BTreeMap<&str, Vec<&TokenOwnerChange>> = BTreeMap::new();
+        for change in owner_changes {
+            changes_by_token
+                .entry(change.token_data_id.as_str())
+                .or_default()
+                .push(change);
+        }
+
+        let mut changes = TokenCompositionChanges::default();
+        for (token_data_id, mut token_changes) in changes_by_token {
+            // A burn comes after the owner written in the same transaction
+            token_changes
+                .sort_by_key(|change| (change.transaction_version, change.owner_address.is_none()));
+            let first_version = token_changes[0].transaction_version;
+            let mut owners: Vec<Owner> = latest_compositions
+                .get(token_data_id)
+                .filter(|composition| {
+                    composition
+                        .decompose_transaction_version
+                        .map_or(true, |version| version >= first_version)
+                })
+                .map(|composition| Owner {
+                    transaction_version: composition.compose_transaction_version,
+                    owner_address: Some(composition.parent_token_data_id.as_str()),
+                    transaction_timestamp: composition.compose_timestamp,
+                    composition: Some(composition),
+                })
+                .into_iter()
+                .chain(token_changes.into_iter().map(|change| Owner {
+                    transaction_version: change.transaction_version,
+                    owner_address: change.owner_address.as_deref(),
+                    transaction_timestamp: change.transaction_timestamp,
+                    composition: None,
+                }))
+                .collect();
+            // Consecutive writes with the same owner don't change it
+            owners.dedup_by(|owner, previous| owner.owner_address == previous.owner_address);
+
+            for (index, owner) in owners.iter().enumerate() {
+                let next = owners.get(index + 1);
+                let decompose_transaction_version = next.map(|next| next.transaction_version);
+                let decompose_timestamp = next.map(|next| next.transaction_timestamp);
+                match (owner.composition, owner.owner_address) {
+                    (Some(composition), _) => {
+                        if next.is_some() {
+                            changes.decomposed.push(Self {
+                                decompose_transaction_version,
+                                decompose_timestamp,
+                                ..composition.clone()
+                            });
+                        }
+                    },
+                    (None, Some(owner_address)) if tokens.contains(owner_address) => {
+                        changes.compositions.push(Self {
+                            child_token_data_id: token_data_id.to_string(),
+                            compose_transaction_version: owner.transaction_version,
+                            parent_token_data_id: owner_address.to_string(),
+                            compose_timestamp: owner.transaction_timestamp,
+                            decompose_transaction_version,
+                            decompose_timestamp,
+                        });
+                    },
+                    _ => {},
+                }
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigdecimal::Zero;
+
+    fn timestamp(version: i64) -> chrono::NaiveDateTime {
+        chrono::DateTime::from_timestamp(version, 0)
+            .unwrap()
+            .naive_utc()
+    }
+
+    fn ownership(
+        token_data_id: &str,
+        version: i64,
+        write_set_change_index: i64,
+        owner_address: &str,
+        amount: BigDecimal,
+    ) -> TokenOwnershipV2 {
+        TokenOwnershipV2 {
+            transaction_version: version,
+            write_set_change_index,
+            token_data_id: token_data_id.to_string(),
+            property_version_v1: BigDecimal::zero(),
+            owner_address: Some(owner_address.to_string()),
+            storage_id: token_data_id.to_string(),
+            amount,
+            table_type_v1: None,
+            token_properties_mutated_v1: None,
+            is_soulbound_v2: Some(false),
+            token_standard: TokenStandard::V2.to_string(),
+            is_fungible_v2: Some(false),
+            transaction_timestamp: timestamp(version),
+            non_transferrable_by_owner: Some(false),
+        }
+    }
+
+    fn burn(token_data_id: &str, version: i64) -> TokenActivityV2 {
+        TokenActivityV2 {
+            transaction_version: version,
+            event_index: 0,
+            event_account_address: "0xc0".to_string(),
+            token_data_id: token_data_id.to_string(),
+            property_version_v1: BigDecimal::zero(),
+            type_: "0x4::collection::BurnEvent".to_string(),
+            from_address: None,
+            to_address: None,
+            token_amount: BigDecimal::one(),
+            before_value: None,
+            after_value: None,
+            entry_function_id_str: None,
+            token_standard: TokenStandard::V2.to_string(),
+            is_fungible_v2: Some(false),
+            transaction_timestamp: timestamp(version),
+            label: None,
+        }
+    }
+
+    fn change(token_data_id: &str, version: i64, owner_address: Option<&str>) -> TokenOwnerChange {
+        TokenOwnerChange {
+            token_data_id: token_data_id.to_string(),
+            transaction_version: version,
+            owner_address: owner_address.map(str::to_string),
+            transaction_timestamp: timestamp(version),
+        }
+    }
+
+    fn composition(
+        child_token_data_id: &str,
+        compose_version: i64,
+        parent_token_data_id: &str,
+        decompose_version: Option<i64>,
+    ) -> TokenComposition {
+        TokenComposition {
+            child_token_data_id: child_token_data_id.to_string(),
+            compose_transaction_version: compose_version,
+            parent_token_data_id: parent_token_data_id.to_string(),
+            compose_timestamp: timestamp(compose_version),
+            decompose_transaction_version: decompose_version,
+            decompose_timestamp: decompose_version.map(timestamp),
+        }
+    }
+
+    fn tokens(token_data_ids: &[&str]) -> AHashSet<String> {
+        token_data_ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_owner_changes_of_nested_tokens() {
+        // 0xb is equipped on 0xa, then 0xa moves to another account, which doesn't change 0xb's
+        // owner
+        let ownerships = vec![
+            ownership("0xa", 10, 0, "0x1", BigDecimal::one()),
+            ownership("0xb", 10, 1, "0xa", BigDecimal::one()),
+            ownership("0xa", 20, 0, "0x2", BigDecimal::one()),
+            // The previous owner of a transfer has an amount of 0
+            ownership("0xa", 20, 1, "0x1", BigDecimal::zero()),
+        ];
+        assert_eq!(
+            TokenOwnerChange::from_ownerships_and_activities(&ownerships, &[]),
+            vec![
+                change("0xa", 10, Some("0x1")),
+                change("0xa", 20, Some("0x2")),
+                change("0xb", 10, Some("0xa")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_owner_changes_merge_writes_with_the_same_owner() {
+        let ownerships = vec![
+            ownership("0xb", 10, 0, "0xa", BigDecimal::one()),
+            ownership("0xb", 20, 0, "0xa", BigDecimal::one()),
+            ownership("0xb", 30, 0, "0x1", BigDecimal::one()),
+        ];
+        assert_eq!(
+            TokenOwnerChange::from_ownerships_and_activities(&ownerships, &[]),
+            vec![
+                change("0xb", 10, Some("0xa")),
+                change("0xb", 30, Some("0x1"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_owner_changes_keep_the_last_write_of_a_transaction() {
+        // 0xb is moved twice in the same transaction, into 0xa and then out to 0x2
+        let ownerships = vec![
+            ownership("0xb", 10, 0, "0x1", BigDecimal::one()),
+            ownership("0xb", 20, 3, "0x2", BigDecimal::one()),
+            ownership("0xb", 20, 1, "0xa", BigDecimal::one()),
+        ];
+        assert_eq!(
+            TokenOwnerChange::from_ownerships_and_activities(&ownerships, &[]),
+            vec![
+                change("0xb", 10, Some("0x1")),
+                change("0xb", 20, Some("0x2"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_owner_changes_unnest_and_burn() {
+        // 0xb is taken off 0xa, then burned in the same transaction as its last transfer
+        let ownerships = vec![
+            ownership("0xb", 10, 0, "0xa", BigDecimal::one()),
+            ownership("0xb", 20, 0, "0x1", BigDecimal::one()),
+            ownership("0xb", 30, 0, "0x2", BigDecimal::one()),
+        ];
+        assert_eq!(
+            TokenOwnerChange::from_ownerships_and_activities(&ownerships, &[burn("0xb", 30)]),
+            vec![
+                change("0xb", 10, Some("0xa")),
+                change("0xb", 20, Some("0x1")),
+                change("0xb", 30, Some("0x2")),
+                change("0xb", 30, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_owner_changes_skip_v1_and_fungible_tokens() {
+        let mut v1 = ownership("0xa", 10, 0, "0x1", BigDecimal::one());
+        v1.token_standard = "v1".to_string();
+        let mut fungible = ownership("0xb", 10, 1, "0x1", BigDecimal::one());
+        fungible.is_fungible_v2 = Some(true);
+        let mut v1_burn = burn("0xa", 20);
+        v1_burn.token_standard = "v1".to_string();
+        assert!(
+            TokenOwnerChange::from_ownerships_and_activities(&[v1, fungible], &[v1_burn])
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_get_changes_composes_into_tokens() {
+        // 0xb is equipped on 0xa, taken off to an account and equipped again
+        let owner_changes = vec![
+            change("0xb", 10, Some("0xa")),
+            change("0xb", 20, Some("0x1")),
+            change("0xb", 30, Some("0xa")),
+        ];
+        assert_eq!(
+            TokenComposition::get_changes(&owner_changes, vec![], &tokens(&["0xa"])),
+            TokenCompositionChanges {
+                compositions: vec![
+                    composition("0xb", 10, "0xa", Some(20)),
+                    composition("0xb", 30, "0xa", None),
+                ],
+                decomposed: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_changes_ends_the_stored_composition() {
+        // 0xb was equipped on 0xa before the batch, which writes it again and then burns it
+        let owner_changes = vec![change("0xb", 30, None), change("0xb", 10, Some("0xa"))];
+        assert_eq!(
+            TokenComposition::get_changes(
+                &owner_changes,
+                vec![composition("0xb", 5, "0xa", None)],
+                &tokens(&["0xa"])
+            ),
+            TokenCompositionChanges {
+                compositions: vec![],
+                decomposed: vec![composition("0xb", 5, "0xa", Some(30))],
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_changes_replayed() {
+        let owner_changes = vec![change("0xb", 10, Some("0xc"))];
+        // A composition that ended before the batch is left as it is
+        assert_eq!(
+            TokenComposition::get_changes(
+                &owner_changes,
+                vec![composition("0xb", 5, "0xa", Some(8))],
+                &tokens(&["0xa", "0xc"])
+            ),
+            TokenCompositionChanges {
+                compositions: vec![composition("0xb", 10, "0xc", None)],
+                decomposed: vec![],
+            }
+        );
+        // A composition ended by the batch is ended at the same version again
+        assert_eq!(
+            TokenComposition::get_changes(
+                &owner_changes,
+                vec![composition("0xb", 5, "0xa", Some(10))],
+                &tokens(&["0xa", "0xc"])
+            ),
+            TokenCompositionChanges {
+                compositions: vec![composition("0xb", 10, "0xc", None)],
+                decomposed: vec![composition("0xb", 5, "0xa", Some(10))],
+            }
+        );
+    }
+}
@@ -0,0 +1,538 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::{
+    db::postgres::models::{
+        default_models::move_resources::MoveResource,
+        property_map::{PropertyMap, TokenObjectPropertyMap, TypedProperty},
+        resources::TYPE_PROPERTY_MAP,
+        token_models::token_utils::TokenWriteSet,
+    },
+    schema::{current_collection_trait_counts, current_token_properties},
+    utils::util::standardize_address,
+};
+use ahash::AHashMap;
+use aptos_protos::transaction::v1::{write_set_change::Change, Transaction, WriteTableItem};
+use diesel::prelude::*;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::warn;
+
+/// Properties with longer keys are skipped, and longer types and values are truncated. The full
+/// property map is still in `current_token_datas_v2.token_properties`.
+pub const MAX_PROPERTY_KEY_LENGTH: usize = 1000;
+pub const MAX_PROPERTY_VALUE_LENGTH: usize = 1000;
+pub const MAX_PROPERTY_TYPE_LENGTH: usize = 255;
+
+/// A row of `current_token_properties`. The collection is filled in from `current_token_datas_v2`
+/// when the row is written.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CurrentTokenProperty {
+    pub token_data_id: String,
+    pub property_key: String,
+    pub property_type: String,
+    pub property_value: String,
+    pub last_transaction_version: i64,
+}
+
+/// All properties of a token as of a transaction. Properties that aren't in the list are removed,
+/// so an empty list removes all of them.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TokenProperties {
+    pub token_data_id: String,
+    pub last_transaction_version: i64,
+    pub properties: Vec<CurrentTokenProperty>,
+}
+
+/// A row of `current_token_properties` as it's stored, with the collection of its token.
+#[derive(
+    Clone, Debug, Deserialize, FieldCount, Insertable, PartialEq, QueryableByName, Serialize,
+)]
+#[diesel(table_name = current_token_properties)]
+pub struct StoredTokenProperty {
+    pub token_data_id: String,
+    pub property_key: String,
+    pub collection_id: String,
+    pub property_type: String,
+    pub property_value: String,
+    pub last_transaction_version: i64,
+}
+
+/// The number of tokens of a collection with a property value. As a change to
+/// `current_collection_trait_counts`, `num_tokens` is the number of tokens gained or lost.
+#[derive(Clone, Debug, Deserialize, FieldCount, Insertable, PartialEq, Serialize)]
+#[diesel(table_name = current_collection_trait_counts)]
+pub struct CollectionTraitCount {
+    pub collection_id: String,
+    pub property_key: String,
+    pub property_value: String,
+    pub num_tokens: i64,
+    pub last_transaction_version: i64,
+}
+
+/// What a batch changes in `current_token_properties` and `current_collection_trait_counts`.
+#[derive(Debug, Default, PartialEq)]
+pub struct TokenPropertyChanges {
+    /// Tokens whose stored properties are replaced by `properties`
+    pub token_data_ids: Vec<String>,
+    pub properties: Vec<StoredTokenProperty>,
+    /// Traits whose number of tokens changed, ordered by collection, key and value
+    pub trait_count_changes: Vec<CollectionTraitCount>,
+}
+
+impl TokenProperties {
+    /// Replaces the properties of each token with the ones from the batch, unless they were already
+    /// written by a later transaction. `collections` maps token data ids to their collection, and
+    /// tokens without one are skipped. `stored_properties` are the current properties of the tokens.
+    ///
+    /// The trait counts change by the difference between the replaced and the new properties, so only
+    /// the traits the batch changed are touched, and replaying a batch leaves the counts as they are.
+    pub fn get_changes(
+        token_properties: Vec<Self>,
+        collections: &AHashMap<String, String>,
+        stored_properties: Vec<StoredTokenProperty>,
+    ) -> TokenPropertyChanges {
+        let mut stored_by_token: AHashMap<String, Vec<StoredTokenProperty>> = AHashMap::new();
+        for property in stored_properties {
+            stored_by_token
+                .entry(property.token_data_id.clone())
+                .or_default()
+                .push(property);
+        }
+
+        let mut changes = TokenPropertyChanges::default();
+        let mut trait_counts: BTreeMap<(String, String, String), (i64, i64)> = BTreeMap::new();
+        for token in token_properties {
+            let Some(collection_id) = collections.get(&token.token_data_id) else {
+                continue;
+            };
+            let stored = stored_by_token
+                .remove(&token.token_data_id)
+                .unwrap_or_default();
+            if stored
+                .iter()
+                .any(|property| property.last_transaction_version > token.last_transaction_version)
+            {
+                continue;
+            }
+            let version = token.last_transaction_version;
+            for property in stored {
+                let trait_count = trait_counts
+                    .entry((
+                        property.collection_id,
+                        property.property_key,
+                        property.property_value,
+                    ))
+                    .or_default();
+                trait_count.0 -= 1;
+                trait_count.1 = trait_count.1.max(version);
+            }
+            // A key can only be stored once per token, so the last value of a key wins
+            let mut properties: BTreeMap<String, CurrentTokenProperty> = BTreeMap::new();
+            for property in token.properties {
+                properties.insert(property.property_key.clone(), property);
+            }
+            for property in properties.into_values() {
+                let trait_count = trait_counts
+                    .entry((
+                        collection_id.clone(),
+                        property.property_key.clone(),
+                        property.property_value.clone(),
+                    ))
+                    .or_default();
+                trait_count.0 += 1;
+                trait_count.1 = trait_count.1.max(version);
+                changes.properties.push(StoredTokenProperty {
+                    token_data_id: token.token_data_id.clone(),
+                    property_key: property.property_key,
+                    collection_id: collection_id.clone(),
+                    property_type: property.property_type,
+                    property_value: property.property_value,
+                    last_transaction_version: version,
+                });
+            }
+            changes.token_data_ids.push(token.token_data_id);
+        }
+        changes.trait_count_changes = trait_counts
+            .into_iter()
+            .filter(|(_, (num_tokens, _))| *num_tokens != 0)
+            .map(
+                |((collection_id, property_key, property_value), (num_tokens, version))| {
+                    CollectionTraitCount {
+                        collection_id,
+                        property_key,
+                        property_value,
+                        num_tokens,
+                        last_transaction_version: version,
+                    }
+                },
+            )
+            .collect();
+        changes
+    }
+
+    /// Latest properties of each token whose property map was written or deleted in the batch,
+    /// sorted by token data id. These come from the `0x4::property_map::PropertyMap` resource of v2
+    /// tokens and the default properties of v1 token datas.
+    pub fn from_transactions(transactions: &[Transaction]) -> Vec<Self> {
+        let mut latest: AHashMap<String, Self> = AHashMap::new();
+        for transaction in transactions {
+            let txn_version = transaction.version as i64;
+            let Some(transaction_info) = transaction.info.as_ref() else {
+                continue;
+            };
+            for wsc in transaction_info.changes.iter() {
+                let token_properties = match wsc.change.as_ref() {
+                    Some(Change::WriteResource(resource))
+                        if MoveResource::get_outer_type_from_write_resource(resource)
+                            == TYPE_PROPERTY_MAP =>
+                    {
+                        let properties = serde_json::from_str::<serde_json::Value>(&resource.data)
+                            .ok()
+                            .and_then(|data| {
+                                TokenObjectPropertyMap::typed_from_bcs_encode_str(
+                                    data.get("inner")?,
+                                )
+                            });
+                        Self::new(
+                            standardize_address(&resource.address),
+                            txn_version,
+                            properties,
+                        )
+                    },
+                    Some(Change::DeleteResource(resource))
+                        if MoveResource::get_outer_type_from_delete_resource(resource)
+                            == TYPE_PROPERTY_MAP =>
+                    {
+                        Self::new(
+                            standardize_address(&resource.address),
+                            txn_version,
+                            Some(vec![]),
+                        )
+                    },
+                    Some(Change::WriteTableItem(table_item)) => {
+                        Self::from_v1_token_data(table_item, txn_version)
+                    },
+                    _ => None,
+                };
+                if let Some(token_properties) = token_properties {
+                    latest.insert(token_properties.token_data_id.clone(), token_properties);
+                }
+            }
+        }
+        let mut token_properties: Vec<Self> = latest.into_values().collect();
+        token_properties.sort_by(|a, b| a.token_data_id.cmp(&b.token_data_id));
+        token_properties
+    }
+
+    fn from_v1_token_data(table_item: &WriteTableItem, txn_version: i64) -> Option<Self> {
+        let table_item_data = table_item.data.as_ref()?;
+        if table_item_data.value_type != "0x3::token::TokenData" {
+            return None;
+        }
+        let token_data_id = match TokenWriteSet::from_table_item_type(
+            table_item_data.key_type.as_str(),
+            &table_item_data.key,
+            txn_version,
+        ) {
+            Ok(Some(TokenWriteSet::TokenDataId(inner))) => inner.to_id(),
+            _ => return None,
+        };
+        let properties = serde_json::from_str::<serde_json::Value>(&table_item_data.value)
+            .ok()
+            .and_then(|data| {
+                PropertyMap::typed_from_bcs_encode_str(data.get("default_properties")?)
+            });
+        Self::new(token_data_id, txn_version, properties)
+    }
+
+    fn new(
+        token_data_id: String,
+        txn_version: i64,
+        properties: Option<Vec<TypedProperty>>,
+    ) -> Option<Self> {
+        let Some(properties) = properties else {
+            warn!(
+                transaction_version = txn_version,
+                token_data_id = token_data_id.as_str(),
+                "Failed to parse token property map"
+            );
+            return None;
+        };
+        let properties = properties
+            .into_iter()
+            .filter(|property| property.key.chars().count() <= MAX_PROPERTY_KEY_LENGTH)
+            .map(|property| CurrentTokenProperty {
+                token_data_id: token_data_id.clone(),
+                property_key: property.key,
+                property_type: property
+                    .typ
+                    .chars()
+                    .take(MAX_PROPERTY_TYPE_LENGTH)
+                    .collect(),
+                property_value: property
+                    .value
+                    .chars()
+                    .take(MAX_PROPERTY_VALUE_LENGTH)
+                    .collect(),
+                last_transaction_version: txn_version,
+            })
+            .collect();
+        Some(Self {
+            token_data_id,
+            last_transaction_version: txn_version,
+            properties,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::postgres::models::token_models::token_utils::TokenDataIdType;
+    use aptos_protos::transaction::v1::{
+        DeleteResource, MoveStructTag, TransactionInfo, WriteResource, WriteSetChange,
+        WriteTableData,
+    };
+    use serde_json::json;
+
+    fn property_map_tag() -> Option<MoveStructTag> {
+        Some(MoveStructTag {
+            address: "0x4".to_string(),
+            module: "property_map".to_string(),
+            name: "PropertyMap".to_string(),
+            generic_type_params: vec![],
+        })
+    }
+
+    fn write_property_map(address: &str, data: serde_json::Value) -> WriteSetChange {
+        WriteSetChange {
+            change: Some(Change::WriteResource(WriteResource {
+                address: address.to_string(),
+                r#type: property_map_tag(),
+                type_str: "0x4::property_map::PropertyMap".to_string(),
+                data: json!({ "inner": data }).to_string(),
+                ..WriteResource::default()
+            })),
+            ..WriteSetChange::default()
+        }
+    }
+
+    fn transaction(version: u64, changes: Vec<WriteSetChange>) -> Transaction {
+        Transaction {
+            version,
+            info: Some(TransactionInfo {
+                changes,
+                ..TransactionInfo::default()
+            }),
+            ..Transaction::default()
+        }
+    }
+
+    fn property(
+        token_data_id: &str,
+        key: &str,
+        typ: &str,
+        value: &str,
+        version: i64,
+    ) -> CurrentTokenProperty {
+        CurrentTokenProperty {
+            token_data_id: token_data_id.to_string(),
+            property_key: key.to_string(),
+            property_type: typ.to_string(),
+            property_value: value.to_string(),
+            last_transaction_version: version,
+        }
+    }
+
+    fn token(token_data_id: &str, version: i64, properties: &[(&str, &str)]) -> TokenProperties {
+        TokenProperties {
+            token_data_id: token_data_id.to_string(),
+            last_transaction_version: version,
+            properties: properties
+                .iter()
+                .map(|(key, value)| property(token_data_id, key, "u64", value, version))
+                .collect(),
+        }
+    }
+
+    fn stored(token_data_id: &str, key: &str, value: &str, version: i64) -> StoredTokenProperty {
+        StoredTokenProperty {
+            token_data_id: token_data_id.to_string(),
+            property_key: key.to_string(),
+            collection_id: "0xc".to_string(),
+            property_type: "u64".to_string(),
+            property_value: value.to_string(),
+            last_transaction_version: version,
+        }
+    }
+
+    fn trait_count(key: &str, value: &str, num_tokens: i64, version: i64) -> CollectionTraitCount {
+        CollectionTraitCount {
+            collection_id: "0xc".to_string(),
+            property_key: key.to_string(),
+            property_value: value.to_string(),
+            num_tokens,
+            last_transaction_version: version,
+        }
+    }
+
+    #[test]
+    fn test_get_changes() {
+        let collections: AHashMap<String, String> = ["0xa", "0xb", "0xd"]
+            .into_iter()
+            .map(|token_data_id| (token_data_id.to_string(), "0xc".to_string()))
+            .collect();
+        let changes = TokenProperties::get_changes(
+            vec![
+                // Changes its level and loses its rank, and sets its level twice
+                token("0xa", 20, &[("Level", "1"), ("Level", "2")]),
+                // Stored by a later transaction
+                token("0xb", 20, &[("Level", "9")]),
+                // Its property map is deleted
+                token("0xd", 20, &[]),
+                // Not in a collection
+                token("0xe", 20, &[("Level", "2")]),
+            ],
+            &collections,
+            vec![
+                stored("0xa", "Level", "1", 10),
+                stored("0xa", "Rank", "3", 10),
+                stored("0xb", "Level", "1", 30),
+                stored("0xd", "Level", "2", 10),
+            ],
+        );
+        assert_eq!(changes.token_data_ids, vec!["0xa", "0xd"]);
+        assert_eq!(changes.properties, vec![stored("0xa", "Level", "2", 20)]);
+        // 0xa moves from level 1 to 2 while 0xd leaves level 2
+        assert_eq!(changes.trait_count_changes, vec![
+            trait_count("Level", "1", -1, 20),
+            trait_count("Rank", "3", -1, 20)
+        ]);
+    }
+
+    #[test]
+    fn test_get_changes_replayed() {
+        let collections: AHashMap<String, String> =
+            AHashMap::from_iter([("0xa".to_string(), "0xc".to_string())]);
+        let changes = TokenProperties::get_changes(
+            vec![token("0xa", 10, &[("Level", "1")])],
+            &collections,
+            vec![stored("0xa", "Level", "1", 10)],
+        );
+        assert_eq!(changes.properties, vec![stored("0xa", "Level", "1", 10)]);
+        assert!(changes.trait_count_changes.is_empty());
+    }
+
+    #[test]
+    fn test_from_transactions_keeps_latest_property_map() {
+        let token_a = standardize_address("0xa");
+        let token_b = standardize_address("0xb");
+        let transactions = vec![
+            transaction(1, vec![
+                write_property_map(
+                    "0xa",
+                    json!({"data": [{"key": "Background", "value": {"type": 9, "value": "0x04426c7565"}}]}),
+                ),
+                write_property_map(
+                    "0xb",
+                    json!({"data": [{"key": "Level", "value": {"type": 4, "value": "0x0500000000000000"}}]}),
+                ),
+            ]),
+            transaction(2, vec![
+                write_property_map(
+                    "0xa",
+                    json!({"data": [{"key": "Background", "value": {"type": 9, "value": "0x03526564"}}]}),
+                ),
+                WriteSetChange {
+                    change: Some(Change::DeleteResource(DeleteResource {
+                        address: "0xb".to_string(),
+                        r#type: property_map_tag(),
+                        type_str: "0x4::property_map::PropertyMap".to_string(),
+                        ..DeleteResource::default()
+                    })),
+                    ..WriteSetChange::default()
+                },
+            ]),
+        ];
+
+        let token_properties = TokenProperties::from_transactions(&transactions);
+        assert_eq!(token_properties, vec![
+            TokenProperties {
+                token_data_id: token_a.clone(),
+                last_transaction_version: 2,
+                properties: vec![property(
+                    &token_a,
+                    "Background",
+                    "0x1::string::String",
+                    "Red",
+                    2
+                )],
+            },
+            // Deleting the property map removes all the properties of the token
+            TokenProperties {
+                token_data_id: token_b,
+                last_transaction_version: 2,
+                properties: vec![],
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_from_transactions_v1_default_properties() {
+        let key = json!({"creator": "0xcafe", "collection": "Cats", "name": "Cat #1"}).to_string();
+        let token_data_id = serde_json::from_str::<TokenDataIdType>(&key)
+            .unwrap()
+            .to_id();
+        let long_key = "k".repeat(MAX_PROPERTY_KEY_LENGTH + 1);
+        let value = json!({
+            "default_properties": {
+                "map": {
+                    "data": [
+                        {"key": "Background", "value": {"type": "0x1::string::String", "value": "0x04426c7565"}},
+                        {"key": long_key, "value": {"type": "u64", "value": "0x0500000000000000"}},
+                    ]
+                }
+            }
+        });
+        let transactions = vec![transaction(7, vec![WriteSetChange {
+            change: Some(Change::WriteTableItem(WriteTableItem {
+                data: Some(WriteTableData {
+                    key,
+                    key_type: "0x3::token::TokenDataId".to_string(),
+                    value: value.to_string(),
+                    value_type: "0x3::token::TokenData".to_string(),
+                }),
+                ..WriteTableItem::default()
+            })),
+            ..WriteSetChange::default()
+        }])];
+
+        // Properties with keys too long to be stored are skipped
+        let token_properties = TokenProperties::from_transactions(&transactions);
+        assert_eq!(token_properties, vec![TokenProperties {
+            token_data_id: token_data_id.clone(),
+            last_transaction_version: 7,
+            properties: vec![property(
+                &token_data_id,
+                "Background",
+                "0x1::string::String",
+                "Blue",
+                7
+            )],
+        }]);
+    }
+
+    #[test]
+    fn test_from_transactions_skips_unparsable_property_map() {
+        let transactions = vec![transaction(1, vec![write_property_map(
+            "0xa",
+            json!({"data": "not a property map"}),
+        )])];
+        assert!(TokenProperties::from_transactions(&transactions).is_empty());
+    }
+}
@@ -2,5 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod event_size_info;
+pub mod transaction_gas_breakdown;
 pub mod transaction_size_info;
 pub mod write_set_size_info;
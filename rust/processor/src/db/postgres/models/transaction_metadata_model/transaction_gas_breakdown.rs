@@ -0,0 +1,155 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::{
+    db::postgres::models::{
+        fungible_asset_models::v2_fungible_asset_utils::FeeStatement,
+        user_transactions_models::signatures::Signature,
+    },
+    schema::transaction_gas_breakdown,
+    utils::util::{
+        get_entry_function_from_user_request, parse_timestamp, standardize_address,
+        u64_to_bigdecimal,
+    },
+};
+use aptos_protos::transaction::v1::{transaction::TxnData, Transaction};
+use bigdecimal::BigDecimal;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+
+/// Gas charged to a user transaction, split by the components of its `FeeStatement` event.
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(transaction_version))]
+#[diesel(table_name = transaction_gas_breakdown)]
+pub struct TransactionGasBreakdown {
+    pub transaction_version: i64,
+    pub sender: String,
+    pub entry_function_id_str: String,
+    pub is_transaction_success: bool,
+    pub gas_unit_price: BigDecimal,
+    pub gas_used: BigDecimal,
+    pub total_charge_gas_units: BigDecimal,
+    pub execution_gas_units: BigDecimal,
+    pub io_gas_units: BigDecimal,
+    pub storage_fee_octas: BigDecimal,
+    pub storage_fee_refund_octas: BigDecimal,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+    /// Set when the gas was paid by a fee payer rather than the sender
+    pub fee_payer_address: Option<String>,
+}
+
+impl TransactionGasBreakdown {
+    /// None for transactions that aren't user transactions, or that predate the `FeeStatement` event.
+    pub fn from_transaction(transaction: &Transaction) -> Option<Self> {
+        let txn_version = transaction.version as i64;
+        let Some(TxnData::User(user_txn)) = transaction.txn_data.as_ref() else {
+            return None;
+        };
+        let request = user_txn.request.as_ref()?;
+        let transaction_info = transaction.info.as_ref()?;
+        let fee_statement = user_txn.events.iter().find_map(|event| {
+            FeeStatement::from_event(event.type_str.as_str(), &event.data, txn_version)
+        })?;
+        Some(Self {
+            transaction_version: txn_version,
+            sender: standardize_address(&request.sender),
+            entry_function_id_str: get_entry_function_from_user_request(request)
+                .unwrap_or_default(),
+            is_transaction_success: transaction_info.success,
+            gas_unit_price: u64_to_bigdecimal(request.gas_unit_price),
+            gas_used: u64_to_bigdecimal(transaction_info.gas_used),
+            total_charge_gas_units: u64_to_bigdecimal(fee_statement.total_charge_gas_units),
+            execution_gas_units: u64_to_bigdecimal(fee_statement.execution_gas_units),
+            io_gas_units: u64_to_bigdecimal(fee_statement.io_gas_units),
+            storage_fee_octas: u64_to_bigdecimal(fee_statement.storage_fee_octas),
+            storage_fee_refund_octas: u64_to_bigdecimal(fee_statement.storage_fee_refund_octas),
+            transaction_timestamp: parse_timestamp(transaction.timestamp.as_ref()?, txn_version),
+            fee_payer_address: request
+                .signature
+                .as_ref()
+                .and_then(|signature| Signature::get_fee_payer_address(signature, txn_version)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_protos::{
+        transaction::v1::{
+            transaction_payload::Payload as PayloadType, EntryFunctionPayload, Event,
+            TransactionInfo, TransactionPayload, UserTransaction, UserTransactionRequest,
+        },
+        util::timestamp::Timestamp,
+    };
+
+    /// A coin transfer with the `FeeStatement` event emitted by the framework.
+    fn user_transaction(events: Vec<Event>) -> Transaction {
+        Transaction {
+            version: 1_000_000,
+            timestamp: Some(Timestamp {
+                seconds: 1_700_000_000,
+                nanos: 0,
+            }),
+            info: Some(TransactionInfo {
+                success: true,
+                gas_used: 509,
+                ..TransactionInfo::default()
+            }),
+            txn_data: Some(TxnData::User(UserTransaction {
+                request: Some(UserTransactionRequest {
+                    sender: "0xb0b".to_string(),
+                    gas_unit_price: 100,
+                    payload: Some(TransactionPayload {
+                        payload: Some(PayloadType::EntryFunctionPayload(EntryFunctionPayload {
+                            entry_function_id_str: "0x1::aptos_account::transfer".to_string(),
+                            ..EntryFunctionPayload::default()
+                        })),
+                        ..TransactionPayload::default()
+                    }),
+                    ..UserTransactionRequest::default()
+                }),
+                events,
+            })),
+            ..Transaction::default()
+        }
+    }
+
+    #[test]
+    fn test_from_transaction_with_fee_statement() {
+        let fee_statement = Event {
+            type_str: "0x1::transaction_fee::FeeStatement".to_string(),
+            data: r#"{"execution_gas_units":"4","io_gas_units":"5","storage_fee_octas":"50000","storage_fee_refund_octas":"1000","total_charge_gas_units":"509"}"#.to_string(),
+            ..Event::default()
+        };
+        let breakdown =
+            TransactionGasBreakdown::from_transaction(&user_transaction(vec![fee_statement]))
+                .unwrap();
+        assert_eq!(breakdown.transaction_version, 1_000_000);
+        assert_eq!(breakdown.sender, standardize_address("0xb0b"));
+        assert_eq!(
+            breakdown.entry_function_id_str,
+            "0x1::aptos_account::transfer"
+        );
+        assert!(breakdown.is_transaction_success);
+        assert_eq!(breakdown.gas_unit_price, BigDecimal::from(100));
+        assert_eq!(breakdown.gas_used, BigDecimal::from(509));
+        assert_eq!(breakdown.total_charge_gas_units, BigDecimal::from(509));
+        assert_eq!(breakdown.execution_gas_units, BigDecimal::from(4));
+        assert_eq!(breakdown.io_gas_units, BigDecimal::from(5));
+        assert_eq!(breakdown.storage_fee_octas, BigDecimal::from(50000));
+        assert_eq!(breakdown.storage_fee_refund_octas, BigDecimal::from(1000));
+        assert_eq!(
+            breakdown.transaction_timestamp.to_string(),
+            "2023-11-14 22:13:20"
+        );
+        assert_eq!(breakdown.fee_payer_address, None);
+    }
+
+    #[test]
+    fn test_from_transaction_without_fee_statement() {
+        assert!(TransactionGasBreakdown::from_transaction(&user_transaction(vec![])).is_none());
+    }
+}
@@ -2,4 +2,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod signatures;
+pub mod transaction_failures;
 pub mod user_transactions;
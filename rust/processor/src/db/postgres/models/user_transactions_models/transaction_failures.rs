@@ -0,0 +1,276 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::{
+    schema::{transaction_failures, user_transaction_outcomes},
+    utils::util::{
+        get_entry_function_from_user_request, parse_timestamp, standardize_address,
+        u64_to_bigdecimal,
+    },
+};
+use aptos_protos::transaction::v1::{transaction::TxnData, Transaction};
+use bigdecimal::BigDecimal;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+
+pub const MOVE_ABORT: &str = "move_abort";
+pub const EXECUTION_FAILURE: &str = "execution_failure";
+pub const OUT_OF_GAS: &str = "out_of_gas";
+pub const OTHER_FAILURE: &str = "other";
+
+/// Categories of the `std::error` abort code convention, indexed by category - 1.
+const ERROR_CATEGORIES: [&str; 13] = [
+    "INVALID_ARGUMENT",
+    "OUT_OF_RANGE",
+    "INVALID_STATE",
+    "UNAUTHENTICATED",
+    "PERMISSION_DENIED",
+    "NOT_FOUND",
+    "ABORTED",
+    "ALREADY_EXISTS",
+    "RESOURCE_EXHAUSTED",
+    "CANCELLED",
+    "INTERNAL",
+    "NOT_IMPLEMENTED",
+    "UNAVAILABLE",
+];
+
+/// A failed user transaction, with its `vm_status` decoded into where and why it failed.
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(transaction_version))]
+#[diesel(table_name = transaction_failures)]
+pub struct TransactionFailure {
+    pub transaction_version: i64,
+    pub sender: String,
+    pub entry_function_id_str: String,
+    pub failure_type: String,
+    pub vm_status: String,
+    pub module_address: Option<String>,
+    pub module_name: Option<String>,
+    pub abort_code: Option<BigDecimal>,
+    pub error_name: Option<String>,
+    pub error_description: Option<String>,
+    pub error_category: Option<String>,
+    pub error_reason: Option<i64>,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+}
+
+/// Outcome of a user transaction, successful or not, used for the failure rate rollups.
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(transaction_version))]
+#[diesel(table_name = user_transaction_outcomes)]
+pub struct UserTransactionOutcome {
+    pub transaction_version: i64,
+    pub entry_function_id_str: String,
+    pub is_transaction_success: bool,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+}
+
+/// `vm_status` of a failed transaction, decoded.
+#[derive(Debug, Default, PartialEq)]
+pub struct ParsedVmStatus {
+    pub failure_type: &'static str,
+    pub module_address: Option<String>,
+    pub module_name: Option<String>,
+    pub abort_code: Option<u64>,
+    pub error_name: Option<String>,
+    pub error_description: Option<String>,
+}
+
+impl ParsedVmStatus {
+    /// Parses statuses such as
+    /// - `Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): Not enough coins`
+    /// - `Move abort in 0x1::coin: 0x10006`
+    /// - `Execution failed in 0x1::coin::transfer at code offset 12`
+    /// - `Out of gas`
+    pub fn parse(vm_status: &str) -> Self {
+        if let Some(rest) = vm_status.strip_prefix("Move abort in ") {
+            let (location, detail) = rest.split_once(": ").unwrap_or((rest, ""));
+            let (module_address, module_name) = parse_module(location);
+            let (error_name, abort_code, error_description) = parse_abort_detail(detail);
+            return Self {
+                failure_type: MOVE_ABORT,
+                module_address,
+                module_name,
+                abort_code,
+                error_name,
+                error_description,
+            };
+        }
+        if let Some(rest) = vm_status.strip_prefix("Execution failed in ") {
+            let location = rest.split_whitespace().next().unwrap_or_default();
+            let (module_address, module_name) = parse_module(location);
+            return Self {
+                failure_type: EXECUTION_FAILURE,
+                module_address,
+                module_name,
+                ..Default::default()
+            };
+        }
+        let failure_type = if vm_status.to_lowercase().contains("out of gas") {
+            OUT_OF_GAS
+        } else {
+            OTHER_FAILURE
+        };
+        Self {
+            failure_type,
+            ..Default::default()
+        }
+    }
+
+    /// Category of the abort code under the `std::error` convention, where the code is the
+    /// category shifted left by 16 bits plus a module specific reason.
+    pub fn error_category(&self) -> Option<&'static str> {
+        let abort_code = self.abort_code?;
+        if abort_code >> 24 != 0 {
+            return None;
+        }
+        let category = (abort_code >> 16) as usize;
+        category
+            .checked_sub(1)
+            .and_then(|index| ERROR_CATEGORIES.get(index))
+            .copied()
+    }
+
+    pub fn error_reason(&self) -> Option<i64> {
+        self.error_category()?;
+        self.abort_code
+            .map(|abort_code| (abort_code & 0xffff) as i64)
+    }
+}
+
+/// Splits `0x1::coin` or `0x1::coin::transfer` into the module address and name.
+fn parse_module(location: &str) -> (Option<String>, Option<String>) {
+    let mut parts = location.split("::");
+    match (parts.next(), parts.next()) {
+        (Some(address), Some(module_name)) if address.starts_with("0x") => (
+            Some(standardize_address(address)),
+            Some(module_name.to_string()),
+        ),
+        _ => (None, None),
+    }
+}
+
+/// Splits `EINSUFFICIENT_BALANCE(0x10006): Not enough coins` or `0x10006` into the error name,
+/// abort code and description.
+fn parse_abort_detail(detail: &str) -> (Option<String>, Option<u64>, Option<String>) {
+    if let Some((error_name, rest)) = detail.split_once('(') {
+        if let Some((code, description)) = rest.split_once(')') {
+            let description = description.trim_start_matches(':').trim();
+            return (
+                Some(error_name.trim().to_string()),
+                parse_abort_code(code),
+                (!description.is_empty()).then(|| description.to_string()),
+            );
+        }
+    }
+    (None, parse_abort_code(detail), None)
+}
+
+fn parse_abort_code(code: &str) -> Option<u64> {
+    let code = code.trim();
+    match code.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => code.parse().ok(),
+    }
+}
+
+impl TransactionFailure {
+    /// Returns the outcome of every user transaction, and the failure if it failed.
+    pub fn from_transaction(
+        transaction: &Transaction,
+    ) -> Option<(UserTransactionOutcome, Option<Self>)> {
+        let txn_version = transaction.version as i64;
+        let Some(TxnData::User(user_txn)) = transaction.txn_data.as_ref() else {
+            return None;
+        };
+        let request = user_txn.request.as_ref()?;
+        let transaction_info = transaction.info.as_ref()?;
+        let transaction_timestamp = parse_timestamp(transaction.timestamp.as_ref()?, txn_version);
+        let entry_function_id_str =
+            get_entry_function_from_user_request(request).unwrap_or_default();
+
+        let failure = (!transaction_info.success).then(|| {
+            let parsed = ParsedVmStatus::parse(&transaction_info.vm_status);
+            Self {
+                transaction_version: txn_version,
+                sender: standardize_address(&request.sender),
+                entry_function_id_str: entry_function_id_str.clone(),
+                failure_type: parsed.failure_type.to_string(),
+                vm_status: transaction_info.vm_status.clone(),
+                error_category: parsed.error_category().map(String::from),
+                error_reason: parsed.error_reason(),
+                module_address: parsed.module_address,
+                module_name: parsed.module_name,
+                abort_code: parsed.abort_code.map(u64_to_bigdecimal),
+                error_name: parsed.error_name,
+                error_description: parsed.error_description,
+                transaction_timestamp,
+            }
+        });
+        let outcome = UserTransactionOutcome {
+            transaction_version: txn_version,
+            entry_function_id_str,
+            is_transaction_success: transaction_info.success,
+            transaction_timestamp,
+        };
+        Some((outcome, failure))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_move_abort_with_error_map() {
+        let parsed = ParsedVmStatus::parse(
+            "Move abort in 0x1::coin: EINSUFFICIENT_BALANCE(0x10006): Not enough coins to complete transaction",
+        );
+        assert_eq!(parsed, ParsedVmStatus {
+            failure_type: MOVE_ABORT,
+            module_address: Some(standardize_address("0x1")),
+            module_name: Some("coin".to_string()),
+            abort_code: Some(0x10006),
+            error_name: Some("EINSUFFICIENT_BALANCE".to_string()),
+            error_description: Some("Not enough coins to complete transaction".to_string()),
+        });
+        assert_eq!(parsed.error_category(), Some("INVALID_ARGUMENT"));
+        assert_eq!(parsed.error_reason(), Some(6));
+    }
+
+    #[test]
+    fn test_parse_move_abort_without_error_map() {
+        let parsed = ParsedVmStatus::parse("Move abort in 0xcafe::market: 0x50003");
+        assert_eq!(parsed.module_address, Some(standardize_address("0xcafe")));
+        assert_eq!(parsed.module_name, Some("market".to_string()));
+        assert_eq!(parsed.abort_code, Some(0x50003));
+        assert_eq!(parsed.error_name, None);
+        assert_eq!(parsed.error_category(), Some("PERMISSION_DENIED"));
+        assert_eq!(parsed.error_reason(), Some(3));
+
+        // Codes outside of the std::error convention have no category
+        let parsed = ParsedVmStatus::parse("Move abort in 0xcafe::market: 0xffffffff");
+        assert_eq!(parsed.abort_code, Some(0xffffffff));
+        assert_eq!(parsed.error_category(), None);
+        assert_eq!(parsed.error_reason(), None);
+    }
+
+    #[test]
+    fn test_parse_other_failures() {
+        let parsed =
+            ParsedVmStatus::parse("Execution failed in 0x1::math64::mul_div at code offset 12");
+        assert_eq!(parsed.failure_type, EXECUTION_FAILURE);
+        assert_eq!(parsed.module_address, Some(standardize_address("0x1")));
+        assert_eq!(parsed.module_name, Some("math64".to_string()));
+        assert_eq!(parsed.abort_code, None);
+
+        assert_eq!(ParsedVmStatus::parse("Out of gas").failure_type, OUT_OF_GAS);
+        assert_eq!(
+            ParsedVmStatus::parse("MISCELLANEOUS_ERROR").failure_type,
+            OTHER_FAILURE
+        );
+    }
+}
@@ -1,11 +1,65 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    account_activities (account_address, transaction_version) {
+        #[max_length = 66]
+        account_address -> Varchar,
+        transaction_version -> Int8,
+        transaction_timestamp -> Timestamp,
+        #[max_length = 1000]
+        entry_function_id_str -> Nullable<Varchar>,
+        inserted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    account_counterparties (account_address, counterparty_address) {
+        #[max_length = 66]
+        account_address -> Varchar,
+        #[max_length = 66]
+        counterparty_address -> Varchar,
+        first_transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    account_summaries (account_address) {
+        #[max_length = 66]
+        account_address -> Varchar,
+        first_transaction_version -> Int8,
+        first_transaction_timestamp -> Timestamp,
+        last_transaction_version -> Int8,
+        last_transaction_timestamp -> Timestamp,
+        num_transactions -> Int8,
+        num_counterparties -> Int8,
+        #[max_length = 1000]
+        last_entry_function_id_str -> Nullable<Varchar>,
+        last_entry_function_version -> Nullable<Int8>,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     account_transactions (account_address, transaction_version) {
         transaction_version -> Int8,
         #[max_length = 66]
         account_address -> Varchar,
         inserted_at -> Timestamp,
+        #[max_length = 200]
+        label -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    address_labels (address) {
+        #[max_length = 66]
+        address -> Varchar,
+        #[max_length = 200]
+        label -> Varchar,
+        #[max_length = 50]
+        category -> Nullable<Varchar>,
+        inserted_at -> Timestamp,
     }
 }
 
@@ -45,6 +99,36 @@ diesel::table! {
         is_deleted -> Bool,
         inserted_at -> Timestamp,
         subdomain_expiration_policy -> Nullable<Int8>,
+        #[max_length = 50]
+        namespace -> Varchar,
+    }
+}
+
+diesel::table! {
+    ans_name_events (transaction_version, event_index) {
+        transaction_version -> Int8,
+        event_index -> Int8,
+        #[max_length = 66]
+        name_object_address -> Nullable<Varchar>,
+        #[max_length = 64]
+        domain -> Varchar,
+        #[max_length = 64]
+        subdomain -> Varchar,
+        #[max_length = 140]
+        token_name -> Varchar,
+        #[max_length = 50]
+        event_type -> Varchar,
+        registration_fee_octas -> Nullable<Numeric>,
+        expiration_timestamp -> Nullable<Timestamp>,
+        renewal_duration_secs -> Nullable<Int8>,
+        #[max_length = 66]
+        from_address -> Nullable<Varchar>,
+        #[max_length = 66]
+        to_address -> Nullable<Varchar>,
+        transaction_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+        #[max_length = 50]
+        namespace -> Varchar,
     }
 }
 
@@ -81,6 +165,8 @@ diesel::table! {
         token_name -> Nullable<Varchar>,
         is_deleted -> Bool,
         inserted_at -> Timestamp,
+        #[max_length = 50]
+        namespace -> Varchar,
     }
 }
 
@@ -109,7 +195,7 @@ diesel::table! {
 
 diesel::table! {
     backfill_processor_status (backfill_alias) {
-        #[max_length = 50]
+        #[max_length = 100]
         backfill_alias -> Varchar,
         #[max_length = 50]
         backfill_status -> Varchar,
@@ -121,6 +207,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    backfill_shards (backfill_alias, shard_index) {
+        #[max_length = 100]
+        backfill_alias -> Varchar,
+        shard_index -> Int8,
+        shard_start_version -> Int8,
+        shard_end_version -> Int8,
+        #[max_length = 50]
+        shard_status -> Varchar,
+        #[max_length = 100]
+        worker_id -> Nullable<Varchar>,
+        lease_expires_at -> Nullable<Timestamp>,
+        last_updated -> Timestamp,
+    }
+}
+
 diesel::table! {
     block_metadata_transactions (version) {
         version -> Int8,
@@ -217,6 +319,31 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    collection_activities (transaction_version, event_index) {
+        transaction_version -> Int8,
+        event_index -> Int8,
+        #[max_length = 66]
+        collection_id -> Varchar,
+        #[max_length = 50]
+        activity_type -> Varchar,
+        transaction_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    collection_activity_hourly (collection_id, hour) {
+        #[max_length = 66]
+        collection_id -> Varchar,
+        hour -> Timestamp,
+        num_transfers -> Int8,
+        num_burns -> Int8,
+        last_transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     collection_datas (collection_data_id_hash, transaction_version) {
         #[max_length = 64]
@@ -287,7 +414,7 @@ diesel::table! {
 }
 
 diesel::table! {
-    current_ans_lookup_v2 (domain, subdomain, token_standard) {
+    current_ans_lookup_v2 (domain, subdomain, token_standard, namespace) {
         #[max_length = 64]
         domain -> Varchar,
         #[max_length = 64]
@@ -303,6 +430,30 @@ diesel::table! {
         is_deleted -> Bool,
         inserted_at -> Timestamp,
         subdomain_expiration_policy -> Nullable<Int8>,
+        #[max_length = 50]
+        namespace -> Varchar,
+    }
+}
+
+diesel::table! {
+    current_ans_name_expirations (token_name, namespace) {
+        #[max_length = 140]
+        token_name -> Varchar,
+        #[max_length = 66]
+        name_object_address -> Nullable<Varchar>,
+        #[max_length = 64]
+        domain -> Varchar,
+        #[max_length = 64]
+        subdomain -> Varchar,
+        expiration_timestamp -> Timestamp,
+        grace_period_end_timestamp -> Timestamp,
+        #[max_length = 50]
+        next_event_type -> Nullable<Varchar>,
+        next_event_timestamp -> Nullable<Timestamp>,
+        last_transaction_version -> Int8,
+        inserted_at -> Timestamp,
+        #[max_length = 50]
+        namespace -> Varchar,
     }
 }
 
@@ -323,7 +474,7 @@ diesel::table! {
 }
 
 diesel::table! {
-    current_ans_primary_name_v2 (registered_address, token_standard) {
+    current_ans_primary_name_v2 (registered_address, token_standard, namespace) {
         #[max_length = 66]
         registered_address -> Varchar,
         #[max_length = 10]
@@ -337,6 +488,8 @@ diesel::table! {
         is_deleted -> Bool,
         last_transaction_version -> Int8,
         inserted_at -> Timestamp,
+        #[max_length = 50]
+        namespace -> Varchar,
     }
 }
 
@@ -379,6 +532,51 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    current_collection_holders (collection_id, owner_address) {
+        #[max_length = 66]
+        collection_id -> Varchar,
+        #[max_length = 66]
+        owner_address -> Varchar,
+        num_tokens -> Numeric,
+        last_transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    current_collection_stats (collection_id) {
+        #[max_length = 66]
+        collection_id -> Varchar,
+        num_holders -> Int8,
+        num_holders_1 -> Int8,
+        num_holders_2_to_5 -> Int8,
+        num_holders_6_to_20 -> Int8,
+        num_holders_21_plus -> Int8,
+        num_burned -> Int8,
+        last_transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    current_collection_trait_counts (collection_id, property_key_hash, property_value_hash) {
+        #[max_length = 66]
+        collection_id -> Varchar,
+        #[max_length = 1000]
+        property_key -> Varchar,
+        #[max_length = 1000]
+        property_value -> Varchar,
+        #[max_length = 32]
+        property_key_hash -> Varchar,
+        #[max_length = 32]
+        property_value_hash -> Varchar,
+        num_tokens -> Int8,
+        last_transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     current_collections_v2 (collection_id) {
         #[max_length = 66]
@@ -506,6 +704,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    current_object_root_owners (object_address) {
+        #[max_length = 66]
+        object_address -> Varchar,
+        #[max_length = 66]
+        root_owner_address -> Varchar,
+        depth -> Int4,
+        last_transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     current_objects (object_address) {
         #[max_length = 66]
@@ -689,6 +899,27 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    current_token_properties (token_data_id, property_key_hash) {
+        #[max_length = 66]
+        token_data_id -> Varchar,
+        #[max_length = 1000]
+        property_key -> Varchar,
+        #[max_length = 66]
+        collection_id -> Varchar,
+        #[max_length = 255]
+        property_type -> Varchar,
+        #[max_length = 1000]
+        property_value -> Varchar,
+        #[max_length = 32]
+        property_key_hash -> Varchar,
+        #[max_length = 32]
+        property_value_hash -> Varchar,
+        last_transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     current_token_royalty_v1 (token_data_id) {
         #[max_length = 66]
@@ -775,6 +1006,39 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    entry_function_failures_hourly (entry_function_id_str, hour) {
+        #[max_length = 1000]
+        entry_function_id_str -> Varchar,
+        hour -> Timestamp,
+        num_transactions -> Int8,
+        num_failed_transactions -> Int8,
+        num_move_aborts -> Int8,
+        num_execution_failures -> Int8,
+        num_out_of_gas -> Int8,
+        last_transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    entry_function_gas_hourly (entry_function_id_str, hour) {
+        #[max_length = 1000]
+        entry_function_id_str -> Varchar,
+        hour -> Timestamp,
+        num_transactions -> Int8,
+        num_failed_transactions -> Int8,
+        total_gas_used -> Numeric,
+        total_execution_gas_units -> Numeric,
+        total_io_gas_units -> Numeric,
+        total_storage_fee_octas -> Numeric,
+        total_storage_fee_refund_octas -> Numeric,
+        total_gas_fee_octas -> Numeric,
+        last_transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     event_size_info (transaction_version, index) {
         transaction_version -> Int8,
@@ -829,6 +1093,8 @@ diesel::table! {
         transaction_timestamp -> Timestamp,
         inserted_at -> Timestamp,
         storage_refund_amount -> Numeric,
+        #[max_length = 200]
+        label -> Nullable<Varchar>,
     }
 }
 
@@ -974,6 +1240,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    processor_gaps (processor, gap_start_version) {
+        #[max_length = 100]
+        processor -> Varchar,
+        gap_start_version -> Int8,
+        gap_end_version -> Int8,
+        #[max_length = 50]
+        repair_status -> Varchar,
+        #[max_length = 100]
+        backfill_alias -> Nullable<Varchar>,
+        detected_at -> Timestamp,
+        last_updated -> Timestamp,
+    }
+}
+
 diesel::table! {
     processor_status (processor) {
         #[max_length = 100]
@@ -1125,6 +1406,22 @@ diesel::table! {
         is_fungible_v2 -> Nullable<Bool>,
         transaction_timestamp -> Timestamp,
         inserted_at -> Timestamp,
+        #[max_length = 200]
+        label -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    token_compositions (child_token_data_id, compose_transaction_version) {
+        #[max_length = 66]
+        child_token_data_id -> Varchar,
+        compose_transaction_version -> Int8,
+        #[max_length = 66]
+        parent_token_data_id -> Varchar,
+        compose_timestamp -> Timestamp,
+        decompose_transaction_version -> Nullable<Int8>,
+        decompose_timestamp -> Nullable<Timestamp>,
+        inserted_at -> Timestamp,
     }
 }
 
@@ -1259,6 +1556,54 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    transaction_failures (transaction_version) {
+        transaction_version -> Int8,
+        #[max_length = 66]
+        sender -> Varchar,
+        #[max_length = 1000]
+        entry_function_id_str -> Varchar,
+        #[max_length = 50]
+        failure_type -> Varchar,
+        vm_status -> Text,
+        #[max_length = 66]
+        module_address -> Nullable<Varchar>,
+        #[max_length = 255]
+        module_name -> Nullable<Varchar>,
+        abort_code -> Nullable<Numeric>,
+        #[max_length = 255]
+        error_name -> Nullable<Varchar>,
+        error_description -> Nullable<Text>,
+        #[max_length = 50]
+        error_category -> Nullable<Varchar>,
+        error_reason -> Nullable<Int8>,
+        transaction_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    transaction_gas_breakdown (transaction_version) {
+        transaction_version -> Int8,
+        #[max_length = 66]
+        sender -> Varchar,
+        #[max_length = 1000]
+        entry_function_id_str -> Varchar,
+        is_transaction_success -> Bool,
+        gas_unit_price -> Numeric,
+        gas_used -> Numeric,
+        total_charge_gas_units -> Numeric,
+        execution_gas_units -> Numeric,
+        io_gas_units -> Numeric,
+        storage_fee_octas -> Numeric,
+        storage_fee_refund_octas -> Numeric,
+        transaction_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+        #[max_length = 66]
+        fee_payer_address -> Nullable<Varchar>,
+    }
+}
+
 diesel::table! {
     transaction_size_info (transaction_version) {
         transaction_version -> Int8,
@@ -1296,6 +1641,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    user_transaction_outcomes (transaction_version) {
+        transaction_version -> Int8,
+        #[max_length = 1000]
+        entry_function_id_str -> Varchar,
+        is_transaction_success -> Bool,
+        transaction_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     user_transactions (version) {
         version -> Int8,
@@ -1348,33 +1704,46 @@ diesel::table! {
 }
 
 diesel::allow_tables_to_appear_in_same_query!(
+    account_activities,
+    account_counterparties,
+    account_summaries,
     account_transactions,
+    address_labels,
     ans_lookup,
     ans_lookup_v2,
+    ans_name_events,
     ans_primary_name,
     ans_primary_name_v2,
     auth_key_account_addresses,
     auth_key_multikey_layout,
     backfill_processor_status,
+    backfill_shards,
     block_metadata_transactions,
     coin_activities,
     coin_balances,
     coin_infos,
     coin_supply,
+    collection_activities,
+    collection_activity_hourly,
     collection_datas,
     collections_v2,
     current_ans_lookup,
     current_ans_lookup_v2,
+    current_ans_name_expirations,
     current_ans_primary_name,
     current_ans_primary_name_v2,
     current_coin_balances,
     current_collection_datas,
+    current_collection_holders,
+    current_collection_stats,
+    current_collection_trait_counts,
     current_collections_v2,
     current_delegated_staking_pool_balances,
     current_delegated_voter,
     current_delegator_balances,
     current_fungible_asset_balances,
     current_fungible_asset_balances_legacy,
+    current_object_root_owners,
     current_objects,
     current_staking_pool_voter,
     current_table_items,
@@ -1383,12 +1752,15 @@ diesel::allow_tables_to_appear_in_same_query!(
     current_token_ownerships,
     current_token_ownerships_v2,
     current_token_pending_claims,
+    current_token_properties,
     current_token_royalty_v1,
     current_token_v2_metadata,
     delegated_staking_activities,
     delegated_staking_pool_balances,
     delegated_staking_pools,
     delegator_balances,
+    entry_function_failures_hourly,
+    entry_function_gas_hourly,
     event_size_info,
     events,
     fungible_asset_activities,
@@ -1401,6 +1773,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     move_resources,
     nft_points,
     objects,
+    processor_gaps,
     processor_status,
     proposal_votes,
     public_key_auth_keys,
@@ -1410,13 +1783,17 @@ diesel::allow_tables_to_appear_in_same_query!(
     table_metadatas,
     token_activities,
     token_activities_v2,
+    token_compositions,
     token_datas,
     token_datas_v2,
     token_ownerships,
     token_ownerships_v2,
     tokens,
+    transaction_failures,
+    transaction_gas_breakdown,
     transaction_size_info,
     transactions,
+    user_transaction_outcomes,
     user_transactions,
     write_set_changes,
     write_set_size_info,
@@ -26,7 +26,10 @@ impl GapDetectorTrait for DefaultGapDetector {
         match result {
             ProcessingResult::DefaultProcessingResult(result) => {
                 // Check for gaps
-                if self.next_version_to_process != result.start_version {
+                if result.end_version < self.next_version_to_process {
+                    // A batch of a gap we already skipped arrived late. The gap's repair backfill covers it.
+                    tracing::debug!("Late batch of a skipped gap");
+                } else if self.next_version_to_process != result.start_version {
                     self.seen_versions.insert(result.start_version, result);
                     tracing::debug!("Gap detected");
                 } else {
@@ -48,6 +51,20 @@ impl GapDetectorTrait for DefaultGapDetector {
             },
         }
     }
+
+    fn oldest_gap(&self) -> Option<(u64, u64)> {
+        self.seen_versions
+            .keys()
+            .min()
+            .map(|next_seen_version| (self.next_version_to_process, next_seen_version - 1))
+    }
+
+    fn skip_gap(&mut self) {
+        if let Some(next_seen_version) = self.seen_versions.keys().min().copied() {
+            let batch = self.seen_versions.remove(&next_seen_version).unwrap();
+            self.update_prev_batch(batch);
+        }
+    }
 }
 
 impl DefaultGapDetector {
@@ -140,4 +157,45 @@ mod test {
             199 + (DEFAULT_GAP_DETECTION_BATCH_SIZE - 1) * 100
         );
     }
+
+    #[tokio::test]
+    async fn skip_gap_test() {
+        let mut default_gap_detector = DefaultGapDetector::new(0);
+        for start_version in [0, 200, 300] {
+            default_gap_detector
+                .process_versions(ProcessingResult::DefaultProcessingResult(
+                    DefaultProcessingResult {
+                        start_version,
+                        end_version: start_version + 99,
+                        last_transaction_timestamp: None,
+                        processing_duration_in_secs: 0.0,
+                        db_insertion_duration_in_secs: 0.0,
+                    },
+                ))
+                .unwrap();
+        }
+        assert_eq!(default_gap_detector.oldest_gap(), Some((100, 199)));
+
+        default_gap_detector.skip_gap();
+        assert_eq!(default_gap_detector.oldest_gap(), None);
+
+        // The skipped batch arriving late doesn't count as a new gap
+        let default_gap_detector_result = default_gap_detector
+            .process_versions(ProcessingResult::DefaultProcessingResult(
+                DefaultProcessingResult {
+                    start_version: 100,
+                    end_version: 199,
+                    last_transaction_timestamp: None,
+                    processing_duration_in_secs: 0.0,
+                    db_insertion_duration_in_secs: 0.0,
+                },
+            ))
+            .unwrap();
+        let default_gap_detector_result = match default_gap_detector_result {
+            GapDetectorResult::DefaultGapDetectorResult(res) => res,
+            _ => panic!("Invalid result type"),
+        };
+        assert_eq!(default_gap_detector_result.num_gaps, 0);
+        assert_eq!(default_gap_detector_result.next_version_to_process, 400);
+    }
 }
@@ -1,5 +1,6 @@
 use crate::{
     bq_analytics::ParquetProcessingResult,
+    db::postgres::models::processor_gaps::ProcessorGap,
     gap_detectors::{
         gap_detector::{DefaultGapDetector, DefaultGapDetectorResult},
         parquet_gap_detector::{ParquetFileGapDetectorInner, ParquetFileGapDetectorResult},
@@ -48,6 +49,12 @@ pub enum GapDetectorResult {
 #[enum_dispatch]
 pub trait GapDetectorTrait: Send {
     fn process_versions(&mut self, result: ProcessingResult) -> Result<GapDetectorResult>;
+
+    /// The oldest range of versions that hasn't been received, if any.
+    fn oldest_gap(&self) -> Option<(u64, u64)>;
+
+    /// Stops waiting for the oldest gap so that the processor can make progress past it.
+    fn skip_gap(&mut self);
 }
 
 #[derive(Debug, Clone)]
@@ -61,6 +68,7 @@ pub async fn create_gap_detector_status_tracker_loop(
     gap_detector_receiver: AsyncReceiver<ProcessingResult>,
     processor: Processor,
     gap_detection_batch_size: u64,
+    skip_gaps: bool,
 ) {
     let processor_name = processor.name();
     tracing::info!(
@@ -89,6 +97,9 @@ pub async fn create_gap_detector_status_tracker_loop(
                                     num_gaps = res.num_gaps,
                                     "[Parser] Processed {gap_detection_batch_size} batches with a gap",
                                 );
+                                    if skip_gaps {
+                                        record_and_skip_gap(&mut gap_detector, &processor).await;
+                                    }
                                     // We don't panic as everything downstream will panic if it doesn't work/receive
                                 }
                                 if let Some(res_last_success_batch) = res.last_success_batch {
@@ -146,6 +157,9 @@ pub async fn create_gap_detector_status_tracker_loop(
                                         num_gaps = res.num_gaps,
                                         "[Parser] Processed batches with a gap",
                                     );
+                                    if skip_gaps {
+                                        record_and_skip_gap(&mut gap_detector, &processor).await;
+                                    }
                                     // We don't panic as everything downstream will panic if it doesn't work/receive
                                 }
 
@@ -197,3 +211,42 @@ pub async fn create_gap_detector_status_tracker_loop(
         };
     }
 }
+
+/// Records the oldest gap in `processor_gaps` and skips past it, so the processor keeps making
+/// progress while the gap is filled by a bounded backfill. If the gap can't be recorded, it is
+/// not skipped and we try again on the next batch.
+async fn record_and_skip_gap(gap_detector: &mut GapDetector, processor: &Processor) {
+    let processor_name = processor.name();
+    let Some((gap_start_version, gap_end_version)) = gap_detector.oldest_gap() else {
+        return;
+    };
+    match ProcessorGap::record(
+        processor.get_pool(),
+        processor_name,
+        gap_start_version,
+        gap_end_version,
+    )
+    .await
+    {
+        Ok(()) => {
+            tracing::warn!(
+                processor_name,
+                service_type = PROCESSOR_SERVICE_TYPE,
+                gap_start_version,
+                gap_end_version,
+                "[Parser] Recorded gap for repair and skipped it",
+            );
+            gap_detector.skip_gap();
+        },
+        Err(e) => {
+            tracing::error!(
+                processor_name,
+                service_type = PROCESSOR_SERVICE_TYPE,
+                gap_start_version,
+                gap_end_version,
+                error = ?e,
+                "[Parser] Failed to record gap for repair",
+            );
+        },
+    }
+}
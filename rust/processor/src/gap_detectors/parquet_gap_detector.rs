@@ -15,6 +15,14 @@ impl GapDetectorTrait for Arc<Mutex<ParquetFileGapDetectorInner>> {
         let mut detector = self.lock().unwrap();
         detector.process_versions(result)
     }
+
+    fn oldest_gap(&self) -> Option<(u64, u64)> {
+        self.lock().unwrap().oldest_gap()
+    }
+
+    fn skip_gap(&mut self) {
+        self.lock().unwrap().skip_gap()
+    }
 }
 
 #[derive(Clone)]
@@ -170,4 +178,27 @@ impl GapDetectorTrait for ParquetFileGapDetectorInner {
             },
         ))
     }
+
+    /// Versions that still have structs waiting to be uploaded aren't a gap, only versions that
+    /// were never seen are.
+    fn oldest_gap(&self) -> Option<(u64, u64)> {
+        let gap_start_version = self.next_version_to_process;
+        if self.version_counters.contains_key(&gap_start_version)
+            || self.seen_versions.contains(&gap_start_version)
+        {
+            return None;
+        }
+        self.version_counters
+            .keys()
+            .filter(|version| **version > gap_start_version)
+            .min()
+            .map(|next_seen_version| (gap_start_version as u64, *next_seen_version as u64 - 1))
+    }
+
+    fn skip_gap(&mut self) {
+        if let Some((_, gap_end_version)) = self.oldest_gap() {
+            self.next_version_to_process = gap_end_version as i64 + 1;
+            self.update_next_version_to_process(self.max_version, "skipped_gap");
+        }
+    }
 }
@@ -115,6 +115,7 @@ impl ProcessorTrait for AccountTransactionsProcessor {
                     .map(|account_address| AccountTransaction {
                         transaction_version,
                         account_address,
+                        label: None,
                     })
                     .collect()
             })
@@ -18,7 +18,7 @@ use crate::{
             ans_lookup::{AnsLookup, AnsPrimaryName, CurrentAnsLookup, CurrentAnsPrimaryName},
             ans_lookup_v2::{AnsLookupV2, CurrentAnsLookupV2},
             ans_primary_name_v2::{AnsPrimaryNameV2, CurrentAnsPrimaryNameV2},
-            ans_utils::{RenewNameEvent, SubdomainExtV2},
+            ans_utils::{NamingServiceConfig, RenewNameEvent, SubdomainExtV2},
         },
     },
     gap_detectors::ProcessingResult,
@@ -51,11 +51,24 @@ pub struct AnsProcessorConfig {
     pub ans_v1_primary_names_table_handle: String,
     pub ans_v1_name_records_table_handle: String,
     pub ans_v2_contract_address: String,
+    /// Other naming services with the structure of the Aptos Names contract
+    #[serde(default)]
+    pub additional_naming_services: Vec<NamingServiceConfig>,
+}
+
+impl AnsProcessorConfig {
+    pub fn naming_services(&self) -> anyhow::Result<Vec<NamingServiceConfig>> {
+        NamingServiceConfig::with_aptos_names(
+            &self.ans_v2_contract_address,
+            &self.additional_naming_services,
+        )
+    }
 }
 
 pub struct AnsProcessor {
     connection_pool: ArcDbPool,
     config: AnsProcessorConfig,
+    naming_services: Vec<NamingServiceConfig>,
     per_table_chunk_sizes: AHashMap<String, usize>,
     deprecated_tables: TableFlags,
 }
@@ -73,9 +86,13 @@ impl AnsProcessor {
             ans_v2_contract_address = config.ans_v2_contract_address,
             "init AnsProcessor"
         );
+        let naming_services = config
+            .naming_services()
+            .expect("Invalid ANS naming services config");
         Self {
             connection_pool,
             config,
+            naming_services,
             per_table_chunk_sizes,
             deprecated_tables,
         }
@@ -292,7 +309,7 @@ pub fn insert_current_ans_lookups_v2_query(
     (
         diesel::insert_into(schema::current_ans_lookup_v2::table)
             .values(item_to_insert)
-            .on_conflict((domain, subdomain, token_standard))
+            .on_conflict((domain, subdomain, token_standard, namespace))
             .do_update()
             .set((
                 registered_address.eq(excluded(registered_address)),
@@ -339,7 +356,7 @@ pub fn insert_current_ans_primary_names_v2_query(
     (
         diesel::insert_into(schema::current_ans_primary_name_v2::table)
             .values(item_to_insert)
-            .on_conflict((registered_address, token_standard))
+            .on_conflict((registered_address, token_standard, namespace))
             .do_update()
             .set((
                 domain.eq(excluded(domain)),
@@ -399,7 +416,7 @@ impl ProcessorTrait for AnsProcessor {
             &transactions,
             self.config.ans_v1_primary_names_table_handle.clone(),
             self.config.ans_v1_name_records_table_handle.clone(),
-            self.config.ans_v2_contract_address.clone(),
+            &self.naming_services,
         );
 
         let postgres_current_ans_lookup_v2: Vec<CurrentAnsLookupV2> = all_current_ans_lookups_v2
@@ -505,7 +522,7 @@ pub fn parse_ans(
     transactions: &[Transaction],
     ans_v1_primary_names_table_handle: String,
     ans_v1_name_records_table_handle: String,
-    ans_v2_contract_address: String,
+    naming_services: &[NamingServiceConfig],
 ) -> (
     Vec<CurrentAnsLookup>,
     Vec<AnsLookup>,
@@ -563,25 +580,26 @@ pub fn parse_ans(
             // 1. RenewNameEvents: helps to fill in metadata for name records with updated expiration time
             // 2. SetReverseLookupEvents: parse to get current_ans_primary_names
             for (event_index, event) in user_txn.events.iter().enumerate() {
-                if let Some(renew_name_event) =
-                    RenewNameEvent::from_event(event, &ans_v2_contract_address, txn_version)
+                for naming_service in naming_services {
+                    if let Some(renew_name_event) =
+                        RenewNameEvent::from_event(event, naming_service, txn_version).unwrap()
+                    {
+                        v2_renew_name_events.push(renew_name_event);
+                    }
+                    if let Some((current_ans_lookup_v2, ans_lookup_v2)) =
+                        RawCurrentAnsPrimaryNameV2::parse_v2_primary_name_record_from_event(
+                            event,
+                            txn_version,
+                            event_index as i64,
+                            naming_service,
+                            block_timestamp,
+                        )
                         .unwrap()
-                {
-                    v2_renew_name_events.push(renew_name_event);
-                }
-                if let Some((current_ans_lookup_v2, ans_lookup_v2)) =
-                    RawCurrentAnsPrimaryNameV2::parse_v2_primary_name_record_from_event(
-                        event,
-                        txn_version,
-                        event_index as i64,
-                        &ans_v2_contract_address,
-                        block_timestamp,
-                    )
-                    .unwrap()
-                {
-                    all_current_ans_primary_names_v2
-                        .insert(current_ans_lookup_v2.pk(), current_ans_lookup_v2);
-                    all_ans_primary_names_v2.push(ans_lookup_v2);
+                    {
+                        all_current_ans_primary_names_v2
+                            .insert(current_ans_lookup_v2.pk(), current_ans_lookup_v2);
+                        all_ans_primary_names_v2.push(ans_lookup_v2);
+                    }
                 }
             }
 
@@ -589,18 +607,20 @@ pub fn parse_ans(
             for wsc in transaction_info.changes.iter() {
                 match wsc.change.as_ref().unwrap() {
                     WriteSetChange::WriteResource(write_resource) => {
-                        if let Some(subdomain_ext) = SubdomainExtV2::from_write_resource(
-                            write_resource,
-                            &ans_v2_contract_address,
-                            txn_version,
-                        )
-                        .unwrap()
-                        {
-                            // Track resource account -> SubdomainExt to create the full subdomain ANS later
-                            v2_address_to_subdomain_ext.insert(
-                                standardize_address(write_resource.address.as_str()),
-                                subdomain_ext,
-                            );
+                        for naming_service in naming_services {
+                            if let Some(subdomain_ext) = SubdomainExtV2::from_write_resource(
+                                write_resource,
+                                naming_service,
+                                txn_version,
+                            )
+                            .unwrap()
+                            {
+                                // Track resource account -> SubdomainExt to create the full subdomain ANS later
+                                v2_address_to_subdomain_ext.insert(
+                                    standardize_address(write_resource.address.as_str()),
+                                    subdomain_ext,
+                                );
+                            }
                         }
                     },
                     _ => continue,
@@ -728,25 +748,27 @@ pub fn parse_ans(
                         }
                     },
                     WriteSetChange::WriteResource(write_resource) => {
-                        if let Some((current_ans_lookup_v2, ans_lookup_v2)) =
-                            RawCurrentAnsLookupV2::parse_name_record_from_write_resource_v2(
-                                write_resource,
-                                &ans_v2_contract_address,
-                                txn_version,
-                                wsc_index as i64,
-                                &v2_address_to_subdomain_ext,
-                            )
-                            .unwrap_or_else(|e| {
-                                error!(
-                                    error = ?e,
-                                    "Error parsing ANS v2 name record from write resource"
-                                );
-                                panic!();
-                            })
-                        {
-                            all_current_ans_lookups_v2
-                                .insert(current_ans_lookup_v2.pk(), current_ans_lookup_v2);
-                            all_ans_lookups_v2.push(ans_lookup_v2);
+                        for naming_service in naming_services {
+                            if let Some((current_ans_lookup_v2, ans_lookup_v2)) =
+                                RawCurrentAnsLookupV2::parse_name_record_from_write_resource_v2(
+                                    write_resource,
+                                    naming_service,
+                                    txn_version,
+                                    wsc_index as i64,
+                                    &v2_address_to_subdomain_ext,
+                                )
+                                .unwrap_or_else(|e| {
+                                    error!(
+                                        error = ?e,
+                                        "Error parsing ANS v2 name record from write resource"
+                                    );
+                                    panic!();
+                                })
+                            {
+                                all_current_ans_lookups_v2
+                                    .insert(current_ans_lookup_v2.pk(), current_ans_lookup_v2);
+                                all_ans_lookups_v2.push(ans_lookup_v2);
+                            }
                         }
                     },
                     // For ANS V2, there are no delete resource changes
@@ -200,7 +200,7 @@ impl ProcessorTrait for NftMetadataProcessor {
     }
 }
 
-fn clean_token_pubsub_message(ctd: RawCurrentTokenDataV2, db_chain_id: u64) -> String {
+pub fn clean_token_pubsub_message(ctd: RawCurrentTokenDataV2, db_chain_id: u64) -> String {
     remove_null_bytes(&format!(
         "{},{},{},{},{},false",
         ctd.token_data_id,
@@ -211,7 +211,7 @@ fn clean_token_pubsub_message(ctd: RawCurrentTokenDataV2, db_chain_id: u64) -> S
     ))
 }
 
-fn clean_collection_pubsub_message(cc: CurrentCollectionV2, db_chain_id: u64) -> String {
+pub fn clean_collection_pubsub_message(cc: CurrentCollectionV2, db_chain_id: u64) -> String {
     remove_null_bytes(&format!(
         "{},{},{},{},{},false",
         cc.collection_id,
@@ -223,7 +223,7 @@ fn clean_collection_pubsub_message(cc: CurrentCollectionV2, db_chain_id: u64) ->
 }
 
 /// Copied from token_processor;
-async fn parse_v2_token(
+pub async fn parse_v2_token(
     transactions: &[Transaction],
     table_handle_to_owner: &TableHandleToOwner,
     conn: &mut DbPoolConnection<'_>,
@@ -9,6 +9,7 @@ use crate::{
     },
     db::postgres::models::ans_models::{
         ans_lookup::CurrentAnsPrimaryName,
+        ans_utils::NamingServiceConfig,
         parquet_ans_lookup_v2::{AnsPrimaryNameV2, CurrentAnsPrimaryNameV2},
     },
     gap_detectors::ProcessingResult,
@@ -144,6 +145,7 @@ fn parse_ans(
     ans_v2_contract_address: String,
 ) -> Vec<AnsPrimaryNameV2> {
     let mut all_ans_primary_names_v2 = vec![];
+    let naming_service = NamingServiceConfig::aptos_names(&ans_v2_contract_address);
 
     for transaction in transactions {
         let txn_version = transaction.version as i64;
@@ -182,7 +184,7 @@ fn parse_ans(
                         event,
                         txn_version,
                         event_index as i64,
-                        &ans_v2_contract_address,
+                        &naming_service,
                         block_timestamp,
                     )
                     .unwrap()
@@ -89,7 +89,7 @@ async fn insert_to_db(
     Ok(())
 }
 
-fn insert_transaction_sizes_query(
+pub fn insert_transaction_sizes_query(
     items_to_insert: Vec<TransactionSize>,
 ) -> (
     impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
@@ -105,7 +105,7 @@ fn insert_transaction_sizes_query(
     )
 }
 
-fn insert_event_sizes_query(
+pub fn insert_event_sizes_query(
     items_to_insert: Vec<EventSize>,
 ) -> (
     impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
@@ -121,7 +121,7 @@ fn insert_event_sizes_query(
     )
 }
 
-fn insert_write_set_sizes_query(
+pub fn insert_write_set_sizes_query(
     items_to_insert: Vec<WriteSetSize>,
 ) -> (
     impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
@@ -151,37 +151,8 @@ impl ProcessorTrait for TransactionMetadataProcessor {
         _: Option<u64>,
     ) -> anyhow::Result<ProcessingResult> {
         let processing_start = std::time::Instant::now();
-        let mut transaction_sizes = vec![];
-        let mut event_sizes = vec![];
-        let mut write_set_sizes = vec![];
-        for txn in &transactions {
-            let txn_version = txn.version as i64;
-            let size_info = match txn.size_info.as_ref() {
-                Some(size_info) => size_info,
-                None => {
-                    warn!(version = txn.version, "Transaction size info not found");
-                    continue;
-                },
-            };
-            transaction_sizes.push(TransactionSize::from_transaction_info(
-                size_info,
-                txn_version,
-            ));
-            for (index, event_size_info) in size_info.event_size_info.iter().enumerate() {
-                event_sizes.push(EventSize::from_event_size_info(
-                    event_size_info,
-                    txn_version,
-                    index as i64,
-                ));
-            }
-            for (index, write_set_size_info) in size_info.write_op_size_info.iter().enumerate() {
-                write_set_sizes.push(WriteSetSize::from_transaction_info(
-                    write_set_size_info,
-                    txn_version,
-                    index as i64,
-                ));
-            }
-        }
+        let (transaction_sizes, event_sizes, write_set_sizes) =
+            parse_transaction_metadata(&transactions);
 
         let processing_duration_in_secs = processing_start.elapsed().as_secs_f64();
         let db_insertion_start = std::time::Instant::now();
@@ -225,3 +196,41 @@ impl ProcessorTrait for TransactionMetadataProcessor {
         &self.connection_pool
     }
 }
+
+/// Parses the size of each transaction, and of its events and write set changes.
+pub fn parse_transaction_metadata(
+    transactions: &[Transaction],
+) -> (Vec<TransactionSize>, Vec<EventSize>, Vec<WriteSetSize>) {
+    let mut transaction_sizes = vec![];
+    let mut event_sizes = vec![];
+    let mut write_set_sizes = vec![];
+    for txn in transactions {
+        let txn_version = txn.version as i64;
+        let size_info = match txn.size_info.as_ref() {
+            Some(size_info) => size_info,
+            None => {
+                warn!(version = txn.version, "Transaction size info not found");
+                continue;
+            },
+        };
+        transaction_sizes.push(TransactionSize::from_transaction_info(
+            size_info,
+            txn_version,
+        ));
+        for (index, event_size_info) in size_info.event_size_info.iter().enumerate() {
+            event_sizes.push(EventSize::from_event_size_info(
+                event_size_info,
+                txn_version,
+                index as i64,
+            ));
+        }
+        for (index, write_set_size_info) in size_info.write_op_size_info.iter().enumerate() {
+            write_set_sizes.push(WriteSetSize::from_transaction_info(
+                write_set_size_info,
+                txn_version,
+                index as i64,
+            ));
+        }
+    }
+    (transaction_sizes, event_sizes, write_set_sizes)
+}
@@ -0,0 +1,296 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{db::postgres::models::address_labels::AddressLabel, utils::util::standardize_address};
+use ahash::AHashMap;
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+/// Length of the `label` columns.
+pub const MAX_LABEL_LENGTH: usize = 200;
+/// Length of the `category` column of `address_labels`.
+pub const MAX_CATEGORY_LENGTH: usize = 50;
+
+/// Labels of known accounts, keyed by the `standardize_address` form of the address so they match
+/// the addresses of indexed rows whatever format the label file uses.
+#[derive(Clone, Debug, Default)]
+pub struct AddressLabelRegistry {
+    labels: AHashMap<String, AddressLabel>,
+}
+
+/// A row that is denormalized with the label of one of its addresses.
+pub trait Labeled {
+    fn labeled_address(&self) -> Option<&str>;
+
+    fn set_label(&mut self, label: Option<String>);
+}
+
+impl AddressLabelRegistry {
+    /// Loads a YAML file (`.yaml` or `.yml`) holding a list of `address`, `label` and optional
+    /// `category` entries, or a CSV file (`.csv`) with those columns in its header.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read address label file {}", path))?;
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase());
+        match extension.as_deref() {
+            Some("yaml") | Some("yml") => Self::from_yaml_str(&contents),
+            Some("csv") => Self::from_csv_str(&contents),
+            _ => bail!(
+                "Address label file {} must be a .yaml, .yml or .csv file",
+                path
+            ),
+        }
+        .with_context(|| format!("Failed to parse address label file {}", path))
+    }
+
+    pub fn from_yaml_str(contents: &str) -> Result<Self> {
+        let labels: Vec<AddressLabel> = serde_yaml::from_str(contents)?;
+        Self::from_labels(labels)
+    }
+
+    /// Fields may be quoted to hold commas, with `""` for a quote. Quoted fields can't span lines.
+    pub fn from_csv_str(contents: &str) -> Result<Self> {
+        let mut lines = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let header = match lines.next() {
+            Some((_, header)) => parse_csv_line(header)?,
+            None => return Ok(Self::default()),
+        };
+        let column = |name: &str| header.iter().position(|column| column.trim() == name);
+        let address_column = column("address").context("Missing address column")?;
+        let label_column = column("label").context("Missing label column")?;
+        let category_column = column("category");
+
+        let mut labels = vec![];
+        for (line_index, line) in lines {
+            let fields = parse_csv_line(line)?;
+            let field = |index: usize| {
+                fields
+                    .get(index)
+                    .map(|field| field.trim().to_string())
+                    .with_context(|| format!("Line {} has too few columns", line_index + 1))
+            };
+            labels.push(AddressLabel {
+                address: field(address_column)?,
+                label: field(label_column)?,
+                category: match category_column {
+                    Some(index) => Some(field(index)?).filter(|category| !category.is_empty()),
+                    None => None,
+                },
+            });
+        }
+        Self::from_labels(labels)
+    }
+
+    /// Fails if an address is listed twice, even in different formats, if an address isn't an
+    /// account address, or if a label or category doesn't fit its column.
+    pub fn from_labels(labels: Vec<AddressLabel>) -> Result<Self> {
+        let mut registry = Self::default();
+        for label in labels {
+            let address = parse_address(&label.address)?;
+            let name = label.label.trim().to_string();
+            if name.is_empty() {
+                bail!("Address {} has an empty label", label.address);
+            }
+            if name.chars().count() > MAX_LABEL_LENGTH {
+                bail!(
+                    "Label of address {} is longer than {} characters",
+                    label.address,
+                    MAX_LABEL_LENGTH
+                );
+            }
+            let category = label
+                .category
+                .map(|category| category.trim().to_string())
+                .filter(|category| !category.is_empty());
+            if let Some(category) = &category {
+                if category.chars().count() > MAX_CATEGORY_LENGTH {
+                    bail!(
+                        "Category of address {} is longer than {} characters",
+                        label.address,
+                        MAX_CATEGORY_LENGTH
+                    );
+                }
+            }
+            if registry.labels.contains_key(&address) {
+                bail!("Address {} is labeled more than once", label.address);
+            }
+            registry.labels.insert(
+                address.clone(),
+                AddressLabel {
+                    address,
+                    label: name,
+                    category,
+                },
+            );
+        }
+        Ok(registry)
+    }
+
+    /// Label of a standardized address.
+    pub fn get_label(&self, address: &str) -> Option<&str> {
+        self.labels.get(address).map(|label| label.label.as_str())
+    }
+
+    /// Entries with their standardized address.
+    pub fn labels(&self) -> impl Iterator<Item = &AddressLabel> {
+        self.labels.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn apply_labels<T: Labeled>(&self, rows: &mut [T]) {
+        for row in rows {
+            let label = row
+                .labeled_address()
+                .and_then(|address| self.get_label(address))
+                .map(|label| label.to_string());
+            row.set_label(label);
+        }
+    }
+}
+
+/// Standardizes an account address, which is at most 64 hex digits with an optional `0x` prefix.
+fn parse_address(address: &str) -> Result<String> {
+    let lowercase = address.trim().to_lowercase();
+    let hex = lowercase.strip_prefix("0x").unwrap_or(&lowercase);
+    if hex.is_empty() || hex.len() > 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("{:?} is not an account address", address);
+    }
+    Ok(standardize_address(hex))
+}
+
+fn parse_csv_line(line: &str) -> Result<Vec<String>> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            },
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        bail!("Unterminated quote in line: {}", line);
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BINANCE: &str = "0xd91c64b777e51395c6ea9dec562ed79a4afa0cd6dad5a87b187c37198a1f855a";
+
+    #[test]
+    fn test_from_yaml_str() {
+        let registry = AddressLabelRegistry::from_yaml_str(
+            "
+- address: \"0x1\"
+  label: Aptos Framework
+  category: framework
+- address: \"0xD91C64B777E51395C6EA9DEC562ED79A4AFA0CD6DAD5A87B187C37198A1F855A\"
+  label: Binance
+",
+        )
+        .unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.get_label(&standardize_address("0x1")),
+            Some("Aptos Framework")
+        );
+        assert_eq!(registry.get_label(BINANCE), Some("Binance"));
+        assert_eq!(registry.get_label("0x1"), None);
+    }
+
+    #[test]
+    fn test_from_csv_str() {
+        let registry = AddressLabelRegistry::from_csv_str(
+            "category,address,label
+exchange,0xd91c64b777e51395c6ea9dec562ed79a4afa0cd6dad5a87b187c37198a1f855a,Binance
+,0x2,\"Bridge, \"\"Wormhole\"\"\"
+",
+        )
+        .unwrap();
+
+        let mut labels: Vec<&AddressLabel> = registry.labels().collect();
+        labels.sort_by(|a, b| a.address.cmp(&b.address));
+        assert_eq!(labels, vec![
+            &AddressLabel {
+                address: standardize_address("0x2"),
+                label: "Bridge, \"Wormhole\"".to_string(),
+                category: None,
+            },
+            &AddressLabel {
+                address: BINANCE.to_string(),
+                label: "Binance".to_string(),
+                category: Some("exchange".to_string()),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_duplicate_address() {
+        let registry = AddressLabelRegistry::from_csv_str(
+            "address,label
+0x1,Aptos Framework
+0x0000000000000000000000000000000000000000000000000000000000000001,Framework
+",
+        );
+
+        assert!(registry.is_err());
+    }
+
+    #[test]
+    fn test_invalid_labels() {
+        let too_long = "a".repeat(MAX_LABEL_LENGTH + 1);
+        for labels in [
+            "address,label\n0xg1,Not hex\n".to_string(),
+            format!("address,label\n0x{},Too long address\n", "1".repeat(65)),
+            "address,label\n0x1,  \n".to_string(),
+            format!("address,label\n0x1,{}\n", too_long),
+        ] {
+            assert!(
+                AddressLabelRegistry::from_csv_str(&labels).is_err(),
+                "{}",
+                labels
+            );
+        }
+
+        // Labels and categories are trimmed, and labels as long as the column are kept
+        let longest = "🙂".repeat(MAX_LABEL_LENGTH);
+        let registry = AddressLabelRegistry::from_csv_str(&format!(
+            "address,label,category\n0X1, {} , \n",
+            longest
+        ))
+        .unwrap();
+        let labels: Vec<&AddressLabel> = registry.labels().collect();
+        assert_eq!(
+            labels,
+            vec![&AddressLabel {
+                address: standardize_address("0x1"),
+                label: longest,
+                category: None,
+            }]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod address_labels;
 pub mod counters;
 pub mod database;
 pub mod table_flags;
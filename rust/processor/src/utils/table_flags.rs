@@ -60,12 +60,15 @@ bitflags! {
 
         // Account Transaction Processor: 71-80
         const ACCOUNT_TRANSACTIONS = 1 << 71;
+        const ACCOUNT_SUMMARIES = 1 << 72;
 
         // Events 81-90
         const EVENTS = 1 << 81;
 
         // transaction metadata 91-100
         const WRITE_SET_SIZE = 1 << 91;
+        const TRANSACTION_GAS_BREAKDOWN = 1 << 92;
+        const ENTRY_FUNCTION_GAS_HOURLY = 1 << 93;
 
         // Deprecated Tables 101-110
         const COIN_SUPPLY = 1 << 101;
@@ -74,6 +77,12 @@ bitflags! {
         const ANS_PRIMARY_NAME_V2 = 1 << 104;
         const ANS_LOOKUP = 1 << 105;
         const ANS_PRIMARY_NAME = 1 << 106;
+
+        // Token V2 Processor (continued): 111-120
+        const COLLECTION_ACTIVITY_HOURLY = 1 << 111;
+        const CURRENT_TOKEN_PROPERTIES = 1 << 112;
+        const CURRENT_COLLECTION_STATS = 1 << 113;
+        const TOKEN_COMPOSITIONS = 1 << 114;
     }
 }
 
@@ -84,6 +84,7 @@ pub struct Worker {
     pub number_concurrent_processing_tasks: usize,
     pub gap_detection_batch_size: u64,
     pub parquet_gap_detection_batch_size: u64,
+    pub skip_gaps: bool,
     pub grpc_chain_id: Option<u64>,
     pub pb_channel_txn_chunk_size: usize,
     pub per_table_chunk_sizes: AHashMap<String, usize>,
@@ -107,6 +108,7 @@ impl Worker {
         db_pool_size: Option<u32>,
         gap_detection_batch_size: u64,
         parquet_gap_detection_batch_size: u64,
+        skip_gaps: bool,
         // The number of transactions per protobuf batch
         pb_channel_txn_chunk_size: usize,
         per_table_chunk_sizes: AHashMap<String, usize>,
@@ -152,6 +154,7 @@ impl Worker {
             number_concurrent_processing_tasks,
             gap_detection_batch_size,
             parquet_gap_detection_batch_size,
+            skip_gaps,
             grpc_chain_id: None,
             pb_channel_txn_chunk_size,
             per_table_chunk_sizes,
@@ -301,6 +304,7 @@ impl Worker {
             GapDetector::DefaultGapDetector(DefaultGapDetector::new(starting_version))
         };
         let gap_detector_clone = gap_detector.clone();
+        let skip_gaps = self.skip_gaps;
 
         tokio::spawn(async move {
             create_gap_detector_status_tracker_loop(
@@ -308,6 +312,7 @@ impl Worker {
                 gap_detector_receiver,
                 processor,
                 gap_detection_batch_size,
+                skip_gaps,
             )
             .await;
         });
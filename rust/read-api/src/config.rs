@@ -0,0 +1,50 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::routes::routes;
+use anyhow::{Context, Result};
+use processor::utils::database::new_db_pool;
+use serde::{Deserialize, Serialize};
+use server_framework::RunnableConfig;
+use tracing::info;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReadApiConfig {
+    pub postgres_connection_string: String,
+    // Size of the pool for reads from the DB. Limits maximum number of queries in flight
+    #[serde(default = "ReadApiConfig::default_db_pool_size")]
+    pub db_pool_size: u32,
+    #[serde(default = "ReadApiConfig::default_api_port")]
+    pub api_port: u16,
+}
+
+impl ReadApiConfig {
+    pub const fn default_db_pool_size() -> u32 {
+        20
+    }
+
+    pub const fn default_api_port() -> u16 {
+        8090
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableConfig for ReadApiConfig {
+    /// Serves the API until the process is stopped. The schema is expected to be migrated by the
+    /// processors writing to the database, so no migrations are run here.
+    async fn run(&self) -> Result<()> {
+        let pool = new_db_pool(&self.postgres_connection_string, Some(self.db_pool_size))
+            .await
+            .context("Failed to create connection pool")?;
+        info!(api_port = self.api_port, "Starting read API");
+        warp::serve(routes(pool))
+            .run(([0, 0, 0, 0], self.api_port))
+            .await;
+        Ok(())
+    }
+
+    fn get_server_name(&self) -> String {
+        "read_api".to_string()
+    }
+}
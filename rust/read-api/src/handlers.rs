@@ -0,0 +1,347 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::ApiError,
+    models::{
+        AccountTransaction, AnsLookup, AnsPrimaryName, DelegatorBalance, FungibleAssetBalance,
+        TokenData, TokenOwnership,
+    },
+    pagination::{Cursor, Page, PageParams},
+};
+use bigdecimal::BigDecimal;
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, NullableExpressionMethods, OptionalExtension,
+    PgExpressionMethods, QueryDsl, SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+use processor::{
+    db::postgres::models::ans_models::ans_utils::DEFAULT_NAMESPACE,
+    schema::{
+        account_transactions, current_ans_lookup_v2, current_ans_primary_name_v2,
+        current_delegator_balances, current_fungible_asset_balances, current_token_datas_v2,
+        current_token_ownerships_v2,
+    },
+    utils::{
+        database::{ArcDbPool, DbPoolConnection},
+        util::standardize_address,
+    },
+};
+use serde::Deserialize;
+use warp::{reply::Json, Rejection};
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnsParams {
+    /// Naming service to resolve in, defaults to ANS.
+    pub namespace: Option<String>,
+}
+
+impl AnsParams {
+    fn namespace(&self) -> &str {
+        self.namespace.as_deref().unwrap_or(DEFAULT_NAMESPACE)
+    }
+}
+
+async fn get_conn(pool: &ArcDbPool) -> Result<DbPoolConnection<'_>, ApiError> {
+    pool.get()
+        .await
+        .map_err(|err| ApiError::Internal(err.into()))
+}
+
+/// Accepts addresses with or without `0x` and leading zeros, and returns them standardized.
+pub fn parse_address(address: &str) -> Result<String, ApiError> {
+    let hex = address.strip_prefix("0x").unwrap_or(address);
+    if hex.is_empty() || hex.len() > 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ApiError::BadRequest(format!("Invalid address {}", address)));
+    }
+    Ok(standardize_address(&hex.to_lowercase()))
+}
+
+/// Splits `name.apt` or `subdomain.name.apt`, with an optional `.apt`, into `(domain, subdomain)`.
+pub fn parse_ans_name(name: &str) -> Result<(String, String), ApiError> {
+    let lowercase_name = name.trim().to_lowercase();
+    let full_name = lowercase_name
+        .strip_suffix(".apt")
+        .unwrap_or(&lowercase_name);
+    match full_name.split('.').collect::<Vec<_>>().as_slice() {
+        [domain] if !domain.is_empty() => Ok((domain.to_string(), String::new())),
+        [subdomain, domain] if !subdomain.is_empty() && !domain.is_empty() => {
+            Ok((domain.to_string(), subdomain.to_string()))
+        },
+        _ => Err(ApiError::BadRequest(format!("Invalid name {}", name))),
+    }
+}
+
+/// Fungible asset and coin balances of an account, skipping empty stores.
+pub async fn get_account_balances(
+    address: String,
+    params: PageParams,
+    pool: ArcDbPool,
+) -> Result<Json, Rejection> {
+    use current_fungible_asset_balances::dsl::*;
+
+    let owner = parse_address(&address)?;
+    let limit = params.limit()?;
+    // The version is only null for rows that were never written by the processors
+    let version = last_transaction_version.assume_not_null();
+    let mut query = current_fungible_asset_balances
+        .filter(owner_address.eq(owner))
+        .filter(amount.gt(BigDecimal::from(0)))
+        .filter(last_transaction_version.is_not_null())
+        .select(FungibleAssetBalance::as_select())
+        .order((version.desc(), storage_id.desc()))
+        .limit(limit + 1)
+        .into_boxed();
+    if let Some(cursor) = params.cursor()? {
+        query = query.filter(
+            version.lt(cursor.transaction_version).or(version
+                .eq(cursor.transaction_version)
+                .and(storage_id.lt(cursor.key))),
+        );
+    }
+    let rows = query
+        .load::<FungibleAssetBalance>(&mut get_conn(&pool).await?)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(warp::reply::json(&Page::from_rows(rows, limit, |row| {
+        Cursor::new(
+            row.last_transaction_version.unwrap_or_default(),
+            row.storage_id.clone(),
+        )
+    })))
+}
+
+/// Tokens currently owned by an account. The cursor key is `<token_data_id>:<property_version_v1>`
+/// since v1 tokens can have several property versions.
+pub async fn get_account_tokens(
+    address: String,
+    params: PageParams,
+    pool: ArcDbPool,
+) -> Result<Json, Rejection> {
+    use current_token_ownerships_v2::dsl::*;
+
+    let owner = parse_address(&address)?;
+    let limit = params.limit()?;
+    let mut query = current_token_ownerships_v2
+        .filter(owner_address.eq(owner))
+        .filter(amount.gt(BigDecimal::from(0)))
+        .select(TokenOwnership::as_select())
+        .order((
+            last_transaction_version.desc(),
+            token_data_id.desc(),
+            property_version_v1.desc(),
+        ))
+        .limit(limit + 1)
+        .into_boxed();
+    if let Some(cursor) = params.cursor()? {
+        let (cursor_token_data_id, cursor_property_version) = cursor
+            .key
+            .split_once(':')
+            .and_then(|(id, property_version)| {
+                Some((id.to_string(), property_version.parse::<BigDecimal>().ok()?))
+            })
+            .ok_or_else(|| ApiError::BadRequest(format!("Invalid cursor {}", cursor.encode())))?;
+        query = query.filter(
+            last_transaction_version
+                .lt(cursor.transaction_version)
+                .or(last_transaction_version.eq(cursor.transaction_version).and(
+                    token_data_id
+                        .lt(cursor_token_data_id.clone())
+                        .or(token_data_id
+                            .eq(cursor_token_data_id)
+                            .and(property_version_v1.lt(cursor_property_version))),
+                )),
+        );
+    }
+    let rows = query
+        .load::<TokenOwnership>(&mut get_conn(&pool).await?)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(warp::reply::json(&Page::from_rows(rows, limit, |row| {
+        Cursor::new(
+            row.last_transaction_version,
+            format!("{}:{}", row.token_data_id, row.property_version_v1),
+        )
+    })))
+}
+
+/// Tokens of a collection, skipping burned ones.
+pub async fn get_collection_tokens(
+    collection: String,
+    params: PageParams,
+    pool: ArcDbPool,
+) -> Result<Json, Rejection> {
+    use current_token_datas_v2::dsl::*;
+
+    let collection = parse_address(&collection)?;
+    let limit = params.limit()?;
+    let mut query = current_token_datas_v2
+        .filter(collection_id.eq(collection))
+        .filter(is_deleted_v2.is_distinct_from(true))
+        .select(TokenData::as_select())
+        .order((last_transaction_version.desc(), token_data_id.desc()))
+        .limit(limit + 1)
+        .into_boxed();
+    if let Some(cursor) = params.cursor()? {
+        query = query.filter(
+            last_transaction_version
+                .lt(cursor.transaction_version)
+                .or(last_transaction_version
+                    .eq(cursor.transaction_version)
+                    .and(token_data_id.lt(cursor.key))),
+        );
+    }
+    let rows = query
+        .load::<TokenData>(&mut get_conn(&pool).await?)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(warp::reply::json(&Page::from_rows(rows, limit, |row| {
+        Cursor::new(row.last_transaction_version, row.token_data_id.clone())
+    })))
+}
+
+/// Transactions that touched an account. Versions are unique per account, so the cursor is only
+/// the version.
+pub async fn get_account_transactions(
+    address: String,
+    params: PageParams,
+    pool: ArcDbPool,
+) -> Result<Json, Rejection> {
+    use account_transactions::dsl::*;
+
+    let account = parse_address(&address)?;
+    let limit = params.limit()?;
+    let mut query = account_transactions
+        .filter(account_address.eq(account))
+        .select(AccountTransaction::as_select())
+        .order(transaction_version.desc())
+        .limit(limit + 1)
+        .into_boxed();
+    if let Some(cursor) = params.cursor()? {
+        query = query.filter(transaction_version.lt(cursor.transaction_version));
+    }
+    let rows = query
+        .load::<AccountTransaction>(&mut get_conn(&pool).await?)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(warp::reply::json(&Page::from_rows(rows, limit, |row| {
+        Cursor::new(row.transaction_version, "")
+    })))
+}
+
+/// Delegation pool shares of an account, active and pending inactive.
+pub async fn get_account_delegations(
+    address: String,
+    params: PageParams,
+    pool: ArcDbPool,
+) -> Result<Json, Rejection> {
+    use current_delegator_balances::dsl::*;
+
+    let delegator = parse_address(&address)?;
+    let limit = params.limit()?;
+    let mut query = current_delegator_balances
+        .filter(delegator_address.eq(delegator))
+        .filter(shares.gt(BigDecimal::from(0)))
+        .select(DelegatorBalance::as_select())
+        .order((last_transaction_version.desc(), table_handle.desc()))
+        .limit(limit + 1)
+        .into_boxed();
+    if let Some(cursor) = params.cursor()? {
+        query = query.filter(
+            last_transaction_version
+                .lt(cursor.transaction_version)
+                .or(last_transaction_version
+                    .eq(cursor.transaction_version)
+                    .and(table_handle.lt(cursor.key))),
+        );
+    }
+    let rows = query
+        .load::<DelegatorBalance>(&mut get_conn(&pool).await?)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(warp::reply::json(&Page::from_rows(rows, limit, |row| {
+        Cursor::new(row.last_transaction_version, row.table_handle.clone())
+    })))
+}
+
+/// Resolves a name to the address it points to.
+pub async fn get_ans_name(
+    name: String,
+    params: AnsParams,
+    pool: ArcDbPool,
+) -> Result<Json, Rejection> {
+    use current_ans_lookup_v2::dsl::*;
+
+    let (name_domain, name_subdomain) = parse_ans_name(&name)?;
+    let lookup = current_ans_lookup_v2
+        .filter(domain.eq(name_domain))
+        .filter(subdomain.eq(name_subdomain))
+        .filter(namespace.eq(params.namespace()))
+        .filter(is_deleted.eq(false))
+        .select(AnsLookup::as_select())
+        .order(last_transaction_version.desc())
+        .first::<AnsLookup>(&mut get_conn(&pool).await?)
+        .await
+        .optional()
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Name {} not found", name)))?;
+    Ok(warp::reply::json(&lookup))
+}
+
+/// Primary name of an address.
+pub async fn get_ans_primary_name(
+    address: String,
+    params: AnsParams,
+    pool: ArcDbPool,
+) -> Result<Json, Rejection> {
+    use current_ans_primary_name_v2::dsl::*;
+
+    let account = parse_address(&address)?;
+    let primary_name = current_ans_primary_name_v2
+        .filter(registered_address.eq(account))
+        .filter(namespace.eq(params.namespace()))
+        .filter(is_deleted.eq(false))
+        .select(AnsPrimaryName::as_select())
+        .order(last_transaction_version.desc())
+        .first::<AnsPrimaryName>(&mut get_conn(&pool).await?)
+        .await
+        .optional()
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::NotFound(format!("Address {} has no primary name", address)))?;
+    Ok(warp::reply::json(&primary_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(
+            parse_address("0x1").unwrap(),
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert_eq!(
+            parse_address("ABC").unwrap(),
+            "0x0000000000000000000000000000000000000000000000000000000000000abc"
+        );
+        assert!(parse_address("0x").is_err());
+        assert!(parse_address("0xzz").is_err());
+        assert!(parse_address(&format!("0x{}", "1".repeat(65))).is_err());
+    }
+
+    #[test]
+    fn test_parse_ans_name() {
+        assert_eq!(
+            parse_ans_name("Petra.apt").unwrap(),
+            ("petra".to_string(), String::new())
+        );
+        assert_eq!(
+            parse_ans_name("wallet.petra").unwrap(),
+            ("petra".to_string(), "wallet".to_string())
+        );
+        assert!(parse_ans_name(".apt").is_err());
+        assert!(parse_ans_name("a.b.c.apt").is_err());
+    }
+}
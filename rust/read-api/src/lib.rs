@@ -0,0 +1,9 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod config;
+pub mod error;
+pub mod handlers;
+pub mod models;
+pub mod pagination;
+pub mod routes;
@@ -0,0 +1,15 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use clap::Parser;
+use read_api::config::ReadApiConfig;
+use server_framework::ServerArgs;
+
+#[allow(clippy::needless_return)]
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = ServerArgs::parse();
+    args.run::<ReadApiConfig>(tokio::runtime::Handle::current())
+        .await
+}
@@ -0,0 +1,107 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Response rows, selected from the tables written by the processors. They only hold the columns
+//! that are useful to API callers, so bookkeeping columns like `inserted_at` are left out.
+
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel::{Queryable, Selectable};
+use processor::schema::{
+    account_transactions, current_ans_lookup_v2, current_ans_primary_name_v2,
+    current_delegator_balances, current_fungible_asset_balances, current_token_datas_v2,
+    current_token_ownerships_v2,
+};
+use serde::Serialize;
+
+#[derive(Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = current_fungible_asset_balances)]
+pub struct FungibleAssetBalance {
+    pub storage_id: String,
+    pub owner_address: String,
+    pub asset_type: String,
+    pub token_standard: String,
+    pub is_primary: bool,
+    pub is_frozen: bool,
+    pub amount: BigDecimal,
+    pub last_transaction_version: Option<i64>,
+    pub last_transaction_timestamp: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = current_token_ownerships_v2)]
+pub struct TokenOwnership {
+    pub token_data_id: String,
+    pub property_version_v1: BigDecimal,
+    pub owner_address: String,
+    pub storage_id: String,
+    pub amount: BigDecimal,
+    pub token_standard: String,
+    pub is_soulbound_v2: Option<bool>,
+    pub is_fungible_v2: Option<bool>,
+    pub non_transferrable_by_owner: Option<bool>,
+    pub last_transaction_version: i64,
+    pub last_transaction_timestamp: NaiveDateTime,
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = current_token_datas_v2)]
+pub struct TokenData {
+    pub token_data_id: String,
+    pub collection_id: String,
+    pub token_name: String,
+    pub maximum: Option<BigDecimal>,
+    pub supply: Option<BigDecimal>,
+    pub token_uri: String,
+    pub description: String,
+    pub token_properties: serde_json::Value,
+    pub token_standard: String,
+    pub is_fungible_v2: Option<bool>,
+    pub decimals: Option<i64>,
+    pub last_transaction_version: i64,
+    pub last_transaction_timestamp: NaiveDateTime,
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = current_ans_lookup_v2)]
+pub struct AnsLookup {
+    pub domain: String,
+    pub subdomain: String,
+    pub namespace: String,
+    pub token_standard: String,
+    pub token_name: Option<String>,
+    pub registered_address: Option<String>,
+    pub expiration_timestamp: NaiveDateTime,
+    pub last_transaction_version: i64,
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = current_ans_primary_name_v2)]
+pub struct AnsPrimaryName {
+    pub registered_address: String,
+    pub namespace: String,
+    pub token_standard: String,
+    pub domain: Option<String>,
+    pub subdomain: Option<String>,
+    pub token_name: Option<String>,
+    pub last_transaction_version: i64,
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = account_transactions)]
+pub struct AccountTransaction {
+    pub transaction_version: i64,
+    pub account_address: String,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = current_delegator_balances)]
+pub struct DelegatorBalance {
+    pub delegator_address: String,
+    pub pool_address: String,
+    pub pool_type: String,
+    pub table_handle: String,
+    pub shares: BigDecimal,
+    pub last_transaction_version: i64,
+}
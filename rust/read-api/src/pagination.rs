@@ -0,0 +1,141 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keyset pagination on transaction version. Pages are sorted newest first by
+//! `(last_transaction_version, key)`, where the key breaks ties between rows touched by the same
+//! transaction. The cursor is the position of the last row of a page, so pages stay stable while
+//! the processors keep writing.
+
+use crate::error::ApiError;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 25;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PageParams {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+impl PageParams {
+    pub fn limit(&self) -> Result<i64, ApiError> {
+        match self.limit {
+            None => Ok(DEFAULT_PAGE_SIZE),
+            Some(limit) if (1..=MAX_PAGE_SIZE).contains(&limit) => Ok(limit),
+            Some(limit) => Err(ApiError::BadRequest(format!(
+                "limit must be between 1 and {}, got {}",
+                MAX_PAGE_SIZE, limit
+            ))),
+        }
+    }
+
+    pub fn cursor(&self) -> Result<Option<Cursor>, ApiError> {
+        self.cursor.as_deref().map(Cursor::decode).transpose()
+    }
+}
+
+/// Encoded as `<transaction_version>:<key>`, or only the version for endpoints where it is unique.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Cursor {
+    pub transaction_version: i64,
+    pub key: String,
+}
+
+impl Cursor {
+    pub fn new(transaction_version: i64, key: impl Into<String>) -> Self {
+        Self {
+            transaction_version,
+            key: key.into(),
+        }
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, ApiError> {
+        let (version, key) = cursor.split_once(':').unwrap_or((cursor, ""));
+        let transaction_version = version
+            .parse::<i64>()
+            .ok()
+            .filter(|version| *version >= 0)
+            .ok_or_else(|| ApiError::BadRequest(format!("Invalid cursor {}", cursor)))?;
+        Ok(Self::new(transaction_version, key))
+    }
+
+    pub fn encode(&self) -> String {
+        if self.key.is_empty() {
+            self.transaction_version.to_string()
+        } else {
+            format!("{}:{}", self.transaction_version, self.key)
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    /// Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// `rows` should be queried with `limit + 1` so that we know whether there is a next page.
+    pub fn from_rows(mut rows: Vec<T>, limit: i64, cursor_of: impl Fn(&T) -> Cursor) -> Self {
+        let next_cursor = if rows.len() as i64 > limit {
+            rows.truncate(limit as usize);
+            rows.last().map(|row| cursor_of(row).encode())
+        } else {
+            None
+        };
+        Self {
+            data: rows,
+            next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor::new(
+            123,
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        );
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+
+        let cursor = Cursor::new(456, "");
+        assert_eq!(cursor.encode(), "456");
+        assert_eq!(Cursor::decode("456").unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_invalid_cursor() {
+        assert!(Cursor::decode("").is_err());
+        assert!(Cursor::decode("abc:0x1").is_err());
+        assert!(Cursor::decode("-1:0x1").is_err());
+    }
+
+    #[test]
+    fn test_limit() {
+        let params = |limit| PageParams {
+            limit,
+            cursor: None,
+        };
+        assert_eq!(params(None).limit().unwrap(), DEFAULT_PAGE_SIZE);
+        assert_eq!(params(Some(MAX_PAGE_SIZE)).limit().unwrap(), MAX_PAGE_SIZE);
+        assert!(params(Some(0)).limit().is_err());
+        assert!(params(Some(MAX_PAGE_SIZE + 1)).limit().is_err());
+    }
+
+    #[test]
+    fn test_page_from_rows() {
+        let page = Page::from_rows(vec![5, 4, 3], 2, |version| Cursor::new(*version, ""));
+        assert_eq!(page.data, vec![5, 4]);
+        assert_eq!(page.next_cursor, Some("4".to_string()));
+
+        let page = Page::from_rows(vec![5, 4], 2, |version| Cursor::new(*version, ""));
+        assert_eq!(page.next_cursor, None);
+    }
+}
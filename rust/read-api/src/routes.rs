@@ -0,0 +1,76 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::handle_rejection,
+    handlers::{
+        get_account_balances, get_account_delegations, get_account_tokens,
+        get_account_transactions, get_ans_name, get_ans_primary_name, get_collection_tokens,
+        AnsParams,
+    },
+    pagination::PageParams,
+};
+use processor::utils::database::ArcDbPool;
+use std::convert::Infallible;
+use warp::{Filter, Rejection, Reply};
+
+fn with_pool(pool: ArcDbPool) -> impl Filter<Extract = (ArcDbPool,), Error = Infallible> + Clone {
+    warp::any().map(move || pool.clone())
+}
+
+pub fn routes(pool: ArcDbPool) -> impl Filter<Extract = (impl Reply,), Error = Infallible> + Clone {
+    account_routes(pool.clone())
+        .or(collection_routes(pool.clone()))
+        .or(ans_routes(pool))
+        .recover(handle_rejection)
+}
+
+fn account_routes(
+    pool: ArcDbPool,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let balances = warp::path!("v1" / "accounts" / String / "balances")
+        .and(warp::get())
+        .and(warp::query::<PageParams>())
+        .and(with_pool(pool.clone()))
+        .and_then(get_account_balances);
+    let tokens = warp::path!("v1" / "accounts" / String / "tokens")
+        .and(warp::get())
+        .and(warp::query::<PageParams>())
+        .and(with_pool(pool.clone()))
+        .and_then(get_account_tokens);
+    let transactions = warp::path!("v1" / "accounts" / String / "transactions")
+        .and(warp::get())
+        .and(warp::query::<PageParams>())
+        .and(with_pool(pool.clone()))
+        .and_then(get_account_transactions);
+    let delegations = warp::path!("v1" / "accounts" / String / "delegations")
+        .and(warp::get())
+        .and(warp::query::<PageParams>())
+        .and(with_pool(pool))
+        .and_then(get_account_delegations);
+    balances.or(tokens).or(transactions).or(delegations)
+}
+
+fn collection_routes(
+    pool: ArcDbPool,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    warp::path!("v1" / "collections" / String / "tokens")
+        .and(warp::get())
+        .and(warp::query::<PageParams>())
+        .and(with_pool(pool))
+        .and_then(get_collection_tokens)
+}
+
+fn ans_routes(pool: ArcDbPool) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let name = warp::path!("v1" / "ans" / "names" / String)
+        .and(warp::get())
+        .and(warp::query::<AnsParams>())
+        .and(with_pool(pool.clone()))
+        .and_then(get_ans_name);
+    let primary_name = warp::path!("v1" / "ans" / "addresses" / String)
+        .and(warp::get())
+        .and(warp::query::<AnsParams>())
+        .and(with_pool(pool))
+        .and_then(get_ans_primary_name);
+    name.or(primary_name)
+}
@@ -0,0 +1,11 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use clap::Parser;
+use sdk_processor::fullnode_verifier::{run, FullnodeVerifierArgs};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    run(FullnodeVerifierArgs::parse()).await
+}
@@ -0,0 +1,10 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use clap::Parser;
+use sdk_processor::hasura_metadata::{run, HasuraMetadataArgs};
+
+fn main() -> Result<()> {
+    run(HasuraMetadataArgs::parse())
+}
@@ -20,18 +20,31 @@ use crate::{
         account_transactions_processor::AccountTransactionsProcessor, ans_processor::AnsProcessor,
         default_processor::DefaultProcessor, events_processor::EventsProcessor,
         fungible_asset_processor::FungibleAssetProcessor,
-        monitoring_processor::MonitoringProcessor, objects_processor::ObjectsProcessor,
-        stake_processor::StakeProcessor, token_v2_processor::TokenV2Processor,
+        monitoring_processor::MonitoringProcessor, multi_processor::MultiProcessor,
+        nft_metadata_processor::NftMetadataProcessor, objects_processor::ObjectsProcessor,
+        spam_classifier_processor::SpamClassifierProcessor, stake_processor::StakeProcessor,
+        token_v2_processor::TokenV2Processor,
+        transaction_failure_processor::TransactionFailureProcessor,
+        transaction_metadata_processor::TransactionMetadataProcessor,
         user_transaction_processor::UserTransactionProcessor,
     },
+    utils::{
+        address_labels::store_address_labels,
+        admin::{register_processor_status_source, wait_for_shutdown},
+        backfill_shards::run_sharded_backfill,
+        gap_repair::run_gap_repair,
+    },
 };
 use anyhow::Result;
 use aptos_indexer_processor_sdk::{
     aptos_indexer_transaction_stream::TransactionStreamConfig,
     traits::processor_trait::ProcessorTrait,
 };
-use aptos_indexer_processor_sdk_server_framework::RunnableConfig;
+use processor::utils::address_labels::AddressLabelRegistry;
 use serde::{Deserialize, Serialize};
+use server_framework::RunnableConfig;
+use std::sync::Arc;
+use tracing::error;
 
 pub const QUERY_DEFAULT_RETRIES: u32 = 5;
 pub const QUERY_DEFAULT_RETRY_DELAY_MS: u64 = 500;
@@ -57,6 +70,8 @@ pub struct IndexerProcessorConfig {
     pub backfill_config: Option<BackfillConfig>,
     pub bootstrap_config: Option<BootStrapConfig>,
     pub testing_config: Option<TestingConfig>,
+    pub gap_repair_config: Option<GapRepairConfig>,
+    pub address_labels_config: Option<AddressLabelsConfig>,
     #[serde(default)]
     pub mode: ProcessorMode,
 }
@@ -69,15 +84,31 @@ impl IndexerProcessorConfig {
                     return Err("testing_config must be present when mode is 'testing'".to_string());
                 }
             },
-            ProcessorMode::Backfill => {
-                if self.backfill_config.is_none() {
+            ProcessorMode::Backfill => match &self.backfill_config {
+                None => {
                     return Err(
                         "backfill_config must be present when mode is 'backfill'".to_string()
                     );
-                }
+                },
+                Some(backfill_config) => {
+                    if let Some(shard_config) = &backfill_config.shard_config {
+                        if shard_config.shard_size == 0 {
+                            return Err("shard_size must be greater than 0".to_string());
+                        }
+                        if backfill_config.overwrite_checkpoint {
+                            return Err(
+                                "overwrite_checkpoint is not supported for sharded backfills"
+                                    .to_string(),
+                            );
+                        }
+                    }
+                },
             },
             ProcessorMode::Default => {},
         }
+        if self.gap_repair_config.is_some() && !matches!(self.mode, ProcessorMode::Default) {
+            return Err("gap_repair_config is only supported when mode is 'default'".to_string());
+        }
         Ok(())
     }
 }
@@ -96,6 +127,8 @@ impl<'de> Deserialize<'de> for IndexerProcessorConfig {
             backfill_config: Option<BackfillConfig>,
             bootstrap_config: Option<BootStrapConfig>,
             testing_config: Option<TestingConfig>,
+            gap_repair_config: Option<GapRepairConfig>,
+            address_labels_config: Option<AddressLabelsConfig>,
             #[serde(default)]
             mode: ProcessorMode,
         }
@@ -108,6 +141,8 @@ impl<'de> Deserialize<'de> for IndexerProcessorConfig {
             backfill_config: inner.backfill_config,
             bootstrap_config: inner.bootstrap_config,
             testing_config: inner.testing_config,
+            gap_repair_config: inner.gap_repair_config,
+            address_labels_config: inner.address_labels_config,
             mode: inner.mode,
         };
 
@@ -116,9 +151,46 @@ impl<'de> Deserialize<'de> for IndexerProcessorConfig {
     }
 }
 
-#[async_trait::async_trait]
-impl RunnableConfig for IndexerProcessorConfig {
-    async fn run(&self) -> Result<()> {
+impl IndexerProcessorConfig {
+    /// Labels to denormalize onto the rows of the processor, if an address label file is configured.
+    pub fn address_label_registry(&self) -> Result<Option<Arc<AddressLabelRegistry>>> {
+        self.address_labels_config
+            .as_ref()
+            .map(|config| AddressLabelRegistry::from_file(&config.path).map(Arc::new))
+            .transpose()
+    }
+
+    /// The configs of the processors that keep their own processor status: the processors of a multi
+    /// processor, or else just this processor.
+    pub fn processor_configs(&self) -> Vec<IndexerProcessorConfig> {
+        match &self.processor_config {
+            ProcessorConfig::MultiProcessor(multi_processor_config) => multi_processor_config
+                .processor_configs
+                .iter()
+                .map(|processor_config| IndexerProcessorConfig {
+                    processor_config: processor_config.clone(),
+                    ..self.clone()
+                })
+                .collect(),
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// Name the pipeline of this config reports to the admin API under. A backfill reports under its
+    /// alias, so that a gap repair running next to the processor isn't taken for it.
+    pub fn pipeline_name(&self) -> String {
+        match (&self.mode, &self.backfill_config) {
+            (ProcessorMode::Backfill, Some(backfill_config)) => format!(
+                "{}_{}",
+                self.processor_config.name(),
+                backfill_config.backfill_id
+            ),
+            _ => self.processor_config.name().to_string(),
+        }
+    }
+
+    /// Runs the configured processor from its starting version until its ending version, if any.
+    pub async fn run_processor(&self) -> Result<()> {
         match self.processor_config {
             ProcessorConfig::AccountRestorationProcessor(_) => {
                 let acc_rest_processor = AccountRestorationProcessor::new(self.clone()).await?;
@@ -148,6 +220,16 @@ impl RunnableConfig for IndexerProcessorConfig {
                 let user_txns_processor = UserTransactionProcessor::new(self.clone()).await?;
                 user_txns_processor.run_processor().await
             },
+            ProcessorConfig::TransactionMetadataProcessor(_) => {
+                let transaction_metadata_processor =
+                    TransactionMetadataProcessor::new(self.clone()).await?;
+                transaction_metadata_processor.run_processor().await
+            },
+            ProcessorConfig::TransactionFailureProcessor(_) => {
+                let transaction_failure_processor =
+                    TransactionFailureProcessor::new(self.clone()).await?;
+                transaction_failure_processor.run_processor().await
+            },
             ProcessorConfig::StakeProcessor(_) => {
                 let stake_processor = StakeProcessor::new(self.clone()).await?;
                 stake_processor.run_processor().await
@@ -164,6 +246,18 @@ impl RunnableConfig for IndexerProcessorConfig {
                 let objects_processor = ObjectsProcessor::new(self.clone()).await?;
                 objects_processor.run_processor().await
             },
+            ProcessorConfig::NftMetadataProcessor(_) => {
+                let nft_metadata_processor = NftMetadataProcessor::new(self.clone()).await?;
+                nft_metadata_processor.run_processor().await
+            },
+            ProcessorConfig::SpamClassifierProcessor(_) => {
+                let spam_classifier_processor = SpamClassifierProcessor::new(self.clone()).await?;
+                spam_classifier_processor.run_processor().await
+            },
+            ProcessorConfig::MultiProcessor(_) => {
+                let multi_processor = MultiProcessor::new(self.clone()).await?;
+                multi_processor.run_processor().await
+            },
             ProcessorConfig::ParquetDefaultProcessor(_) => {
                 let parquet_default_processor = ParquetDefaultProcessor::new(self.clone()).await?;
                 parquet_default_processor.run_processor().await
@@ -211,6 +305,52 @@ impl RunnableConfig for IndexerProcessorConfig {
         }
     }
 
+    /// Runs the processor, or the shards of a sharded backfill, until they are done.
+    async fn run_until_done(&self) -> Result<()> {
+        match (&self.mode, &self.backfill_config) {
+            (
+                ProcessorMode::Backfill,
+                Some(BackfillConfig {
+                    shard_config: Some(_),
+                    ..
+                }),
+            ) => run_sharded_backfill(self).await,
+            _ => {
+                if self.gap_repair_config.is_some() {
+                    // Repairs run next to the live processor, a failing repair doesn't stop it. Gaps
+                    // are recorded per processor, so each processor of a multi processor is repaired
+                    // on its own.
+                    for config in self.processor_configs() {
+                        tokio::spawn(async move {
+                            if let Err(e) = run_gap_repair(&config).await {
+                                error!(
+                                    processor_name = config.processor_config.name(),
+                                    error = ?e,
+                                    "Gap repair stopped"
+                                );
+                            }
+                        });
+                    }
+                }
+                self.run_processor().await
+            },
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RunnableConfig for IndexerProcessorConfig {
+    async fn run(&self) -> Result<()> {
+        if self.address_labels_config.is_some() {
+            store_address_labels(self).await?;
+        }
+        register_processor_status_source(self).await?;
+        tokio::select! {
+            res = self.run_until_done() => res,
+            res = wait_for_shutdown() => res,
+        }
+    }
+
     fn get_server_name(&self) -> String {
         // Get the part before the first _ and trim to 12 characters.
         let before_underscore = self
@@ -221,6 +361,10 @@ impl RunnableConfig for IndexerProcessorConfig {
             .unwrap_or("unknown");
         before_underscore[..before_underscore.len().min(12)].to_string()
     }
+
+    fn handles_shutdown(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -230,6 +374,66 @@ pub struct BackfillConfig {
     pub initial_starting_version: u64,
     pub ending_version: u64,
     pub overwrite_checkpoint: bool,
+    // Splits the backfill into shards that several workers can claim. Read more in `backfill_shards.rs`
+    #[serde(default)]
+    pub shard_config: Option<BackfillShardConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BackfillShardConfig {
+    // Number of versions per shard
+    pub shard_size: u64,
+    // How long a worker owns a shard without renewing its lease
+    #[serde(default = "BackfillShardConfig::default_lease_duration_secs")]
+    pub lease_duration_secs: u64,
+    // Stable id of this worker, so it resumes its own shards after a restart. Defaults to the hostname.
+    #[serde(default)]
+    pub worker_id: Option<String>,
+}
+
+impl BackfillShardConfig {
+    pub const fn default_lease_duration_secs() -> u64 {
+        300
+    }
+
+    pub fn get_worker_id(&self) -> String {
+        self.worker_id.clone().unwrap_or_else(|| {
+            std::env::var("HOSTNAME").unwrap_or_else(|_| format!("worker_{}", std::process::id()))
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Repairs gaps recorded in `processor_gaps` with a bounded backfill per gap while the processor
+/// keeps running. Read more in `gap_repair.rs`
+pub struct GapRepairConfig {
+    // How often to check for new gaps
+    #[serde(default = "GapRepairConfig::default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    // How many times a gap is repaired before it is marked as failed and no longer retried
+    #[serde(default = "GapRepairConfig::default_max_repair_attempts")]
+    pub max_repair_attempts: u32,
+}
+
+impl GapRepairConfig {
+    pub const fn default_poll_interval_secs() -> u64 {
+        60
+    }
+
+    pub const fn default_max_repair_attempts() -> u32 {
+        3
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+/// Labels of known accounts, written to `address_labels` on startup and denormalized onto the
+/// activity rows of the processor. Read more in `address_labels.rs`
+pub struct AddressLabelsConfig {
+    // Path of a .yaml, .yml or .csv file with `address`, `label` and optional `category` entries
+    pub path: String,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
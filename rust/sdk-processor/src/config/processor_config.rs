@@ -1,16 +1,23 @@
 use crate::{
     parquet_processors::parquet_ans_processor::ParquetAnsProcessorConfig,
     processors::{
-        ans_processor::AnsProcessorConfig, objects_processor::ObjectsProcessorConfig,
+        ans_processor::AnsProcessorConfig, multi_processor::MultiProcessorConfig,
+        nft_metadata_processor::NftMetadataProcessorConfig,
+        objects_processor::ObjectsProcessorConfig,
+        spam_classifier_processor::SpamClassifierProcessorConfig,
         stake_processor::StakeProcessorConfig, token_v2_processor::TokenV2ProcessorConfig,
     },
+    sinks::SinkConfig,
     utils::parquet_processor_table_mapping::{format_table_name, VALID_TABLE_NAMES},
 };
 use ahash::AHashMap;
 use processor::{
     bq_analytics::generic_parquet_processor::NamedTable,
     db::parquet::models::{
-        account_transaction_models::parquet_account_transactions::AccountTransaction,
+        account_transaction_models::{
+            parquet_account_summaries::AccountSummary,
+            parquet_account_transactions::AccountTransaction,
+        },
         ans_models::{
             ans_lookup_v2::{AnsLookupV2, CurrentAnsLookupV2},
             ans_primary_name_v2::{AnsPrimaryNameV2, CurrentAnsPrimaryNameV2},
@@ -41,12 +48,17 @@ use processor::{
         token_v2_models::{
             token_claims::CurrentTokenPendingClaim,
             v1_token_royalty::CurrentTokenRoyaltyV1,
+            v2_collection_activity_hourly::CollectionActivityHourly,
             v2_token_activities::TokenActivityV2,
             v2_token_datas::{CurrentTokenDataV2, TokenDataV2},
             v2_token_metadata::CurrentTokenV2Metadata,
             v2_token_ownerships::{CurrentTokenOwnershipV2, TokenOwnershipV2},
         },
-        transaction_metadata_model::parquet_write_set_size_info::WriteSetSize,
+        transaction_metadata_model::{
+            parquet_entry_function_gas_hourly::EntryFunctionGasHourly,
+            parquet_transaction_gas_breakdown::TransactionGasBreakdown,
+            parquet_write_set_size_info::WriteSetSize,
+        },
         user_transaction_models::parquet_user_transactions::UserTransaction,
     },
 };
@@ -94,10 +106,16 @@ pub enum ProcessorConfig {
     EventsProcessor(DefaultProcessorConfig),
     FungibleAssetProcessor(DefaultProcessorConfig),
     UserTransactionProcessor(DefaultProcessorConfig),
+    TransactionMetadataProcessor(DefaultProcessorConfig),
+    TransactionFailureProcessor(DefaultProcessorConfig),
     StakeProcessor(StakeProcessorConfig),
     TokenV2Processor(TokenV2ProcessorConfig),
     ObjectsProcessor(ObjectsProcessorConfig),
     MonitoringProcessor(DefaultProcessorConfig),
+    NftMetadataProcessor(NftMetadataProcessorConfig),
+    SpamClassifierProcessor(SpamClassifierProcessorConfig),
+    // Runs several of the processors above off a single transaction stream
+    MultiProcessor(MultiProcessorConfig),
     // ParquetProcessor
     ParquetDefaultProcessor(ParquetDefaultProcessorConfig),
     ParquetEventsProcessor(ParquetDefaultProcessorConfig),
@@ -118,6 +136,23 @@ impl ProcessorConfig {
         self.into()
     }
 
+    /// Whether the processor writes parquet files, checkpointed per table as they are uploaded.
+    pub fn is_parquet(&self) -> bool {
+        matches!(
+            self,
+            ProcessorConfig::ParquetDefaultProcessor(_)
+                | ProcessorConfig::ParquetEventsProcessor(_)
+                | ProcessorConfig::ParquetAnsProcessor(_)
+                | ProcessorConfig::ParquetUserTransactionsProcessor(_)
+                | ProcessorConfig::ParquetFungibleAssetProcessor(_)
+                | ProcessorConfig::ParquetTransactionMetadataProcessor(_)
+                | ProcessorConfig::ParquetAccountTransactionsProcessor(_)
+                | ProcessorConfig::ParquetTokenV2Processor(_)
+                | ProcessorConfig::ParquetStakeProcessor(_)
+                | ProcessorConfig::ParquetObjectsProcessor(_)
+        )
+    }
+
     /// Get the Vec of table names for parquet processors only.
     ///
     /// This is a convenience method to map the table names to include the processor name as a prefix, which
@@ -193,12 +228,15 @@ impl ProcessorConfig {
                 CurrentUnifiedFungibleAssetBalance::TABLE_NAME.to_string(),
                 FungibleAssetMetadataModel::TABLE_NAME.to_string(),
             ]),
-            ProcessorName::ParquetTransactionMetadataProcessor => {
-                HashSet::from([WriteSetSize::TABLE_NAME.to_string()])
-            },
-            ProcessorName::ParquetAccountTransactionsProcessor => {
-                HashSet::from([AccountTransaction::TABLE_NAME.to_string()])
-            },
+            ProcessorName::ParquetTransactionMetadataProcessor => HashSet::from([
+                WriteSetSize::TABLE_NAME.to_string(),
+                TransactionGasBreakdown::TABLE_NAME.to_string(),
+                EntryFunctionGasHourly::TABLE_NAME.to_string(),
+            ]),
+            ProcessorName::ParquetAccountTransactionsProcessor => HashSet::from([
+                AccountTransaction::TABLE_NAME.to_string(),
+                AccountSummary::TABLE_NAME.to_string(),
+            ]),
             ProcessorName::ParquetTokenV2Processor => HashSet::from([
                 CurrentTokenPendingClaim::TABLE_NAME.to_string(),
                 CurrentTokenRoyaltyV1::TABLE_NAME.to_string(),
@@ -208,6 +246,7 @@ impl ProcessorConfig {
                 CurrentTokenDataV2::TABLE_NAME.to_string(),
                 TokenOwnershipV2::TABLE_NAME.to_string(),
                 CurrentTokenOwnershipV2::TABLE_NAME.to_string(),
+                CollectionActivityHourly::TABLE_NAME.to_string(),
             ]),
             ProcessorName::ParquetObjectsProcessor => HashSet::from([
                 Object::TABLE_NAME.to_string(),
@@ -256,6 +295,9 @@ pub struct DefaultProcessorConfig {
     // String vector for deprecated tables to skip db writes
     #[serde(default)]
     pub deprecated_tables: HashSet<String>,
+    // Sink for change events of current table upserts, for processors that support it
+    #[serde(default)]
+    pub cdc_sink: Option<SinkConfig>,
 }
 
 impl DefaultProcessorConfig {
@@ -270,6 +312,7 @@ impl Default for DefaultProcessorConfig {
             per_table_chunk_sizes: AHashMap::new(),
             channel_size: Self::default_channel_size(),
             deprecated_tables: HashSet::new(),
+            cdc_sink: None,
         }
     }
 }
@@ -376,6 +419,34 @@ mod tests {
         assert_eq!(table_names, expected_names);
     }
 
+    #[test]
+    fn test_multi_processor_config() {
+        let config: ProcessorConfig = serde_json::from_value(serde_json::json!({
+            "type": "multi_processor",
+            "per_processor_buffer_size": 5,
+            "processor_configs": [
+                { "type": "events_processor", "channel_size": 20 },
+                { "type": "token_v2_processor" },
+            ],
+        }))
+        .unwrap();
+
+        let ProcessorConfig::MultiProcessor(multi_config) = config else {
+            panic!("Expected multi processor config");
+        };
+        assert_eq!(multi_config.per_processor_buffer_size, 5);
+        assert_eq!(
+            multi_config.channel_size,
+            MultiProcessorConfig::default_channel_size()
+        );
+        let names: Vec<&str> = multi_config
+            .processor_configs
+            .iter()
+            .map(|c| c.name())
+            .collect();
+        assert_eq!(names, vec!["events_processor", "token_v2_processor"]);
+    }
+
     #[test]
     fn test_duplicate_table_names_in_backfill_names() {
         let config = ProcessorConfig::ParquetDefaultProcessor(ParquetDefaultProcessorConfig {
@@ -0,0 +1,71 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::CurrentTableCheck;
+use anyhow::Result;
+
+fn parse_storage_id(key_values: &[String]) -> Result<String> {
+    match key_values {
+        [storage_id] => Ok(storage_id.clone()),
+        _ => anyhow::bail!(
+            "Invalid key for current_fungible_asset_balances: {:?}",
+            key_values
+        ),
+    }
+}
+
+/// A store of a migrated coin has both a v1 and a v2 balance in the same current row, written from
+/// the history rows of each token standard. The v1 and v2 columns are checked separately, and the
+/// generated columns (`amount`, `asset_type`, `token_standard`, ...) are left out since they can't be
+/// written.
+pub struct CurrentFungibleAssetBalancesV1Check;
+
+impl CurrentTableCheck for CurrentFungibleAssetBalancesV1Check {
+    type PK = String;
+
+    const CURRENT_TABLE: &'static str = "current_fungible_asset_balances";
+    const HISTORY_TABLE: &'static str = "fungible_asset_balances";
+    const KEY_COLUMNS: &'static [&'static str] = &["storage_id"];
+    const COLUMNS: &'static [(&'static str, &'static str)] = &[
+        ("storage_id", "storage_id"),
+        ("owner_address", "owner_address"),
+        ("asset_type_v1", "asset_type"),
+        ("is_primary", "is_primary"),
+        ("is_frozen", "is_frozen"),
+        ("amount_v1", "amount"),
+        ("last_transaction_version_v1", "transaction_version"),
+        ("last_transaction_timestamp_v1", "transaction_timestamp"),
+    ];
+    const HISTORY_FILTER: &'static str = "token_standard = 'v1'";
+    const CURRENT_FILTER: &'static str = "last_transaction_version_v1 IS NOT NULL";
+
+    fn parse_pk(key_values: &[String]) -> Result<Self::PK> {
+        parse_storage_id(key_values)
+    }
+}
+
+pub struct CurrentFungibleAssetBalancesV2Check;
+
+impl CurrentTableCheck for CurrentFungibleAssetBalancesV2Check {
+    type PK = String;
+
+    const CURRENT_TABLE: &'static str = "current_fungible_asset_balances";
+    const HISTORY_TABLE: &'static str = "fungible_asset_balances";
+    const KEY_COLUMNS: &'static [&'static str] = &["storage_id"];
+    const COLUMNS: &'static [(&'static str, &'static str)] = &[
+        ("storage_id", "storage_id"),
+        ("owner_address", "owner_address"),
+        ("asset_type_v2", "asset_type"),
+        ("is_primary", "is_primary"),
+        ("is_frozen", "is_frozen"),
+        ("amount_v2", "amount"),
+        ("last_transaction_version_v2", "transaction_version"),
+        ("last_transaction_timestamp_v2", "transaction_timestamp"),
+    ];
+    const HISTORY_FILTER: &'static str = "token_standard = 'v2'";
+    const CURRENT_FILTER: &'static str = "last_transaction_version_v2 IS NOT NULL";
+
+    fn parse_pk(key_values: &[String]) -> Result<Self::PK> {
+        parse_storage_id(key_values)
+    }
+}
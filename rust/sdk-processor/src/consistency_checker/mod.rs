@@ -0,0 +1,449 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verifies that the current tables of a processor match the state recomputed from its history
+//! tables, e.g. `current_token_ownerships_v2` against `token_ownerships_v2`.
+//!
+//! For every primary key, the latest history row (by transaction version and write set change index)
+//! is compared against the current row. Keys that only exist on one side are reported as well. The
+//! comparison can run on a random sample of current rows, or as a full scan in pages of keys.
+//! Mismatches can be written out as a SQL fix-up script that rebuilds the affected current rows
+//! from history.
+
+pub mod fungible_asset_checks;
+pub mod stake_checks;
+pub mod token_v2_checks;
+
+use crate::{
+    config::processor_config::ProcessorName,
+    utils::database::{new_db_pool, ArcDbPool, DbPoolConnection},
+};
+use anyhow::{Context, Result};
+use clap::Parser;
+use diesel::{
+    sql_query,
+    sql_types::{Array, BigInt, Nullable, Text},
+    OptionalExtension, QueryableByName,
+};
+use diesel_async::RunQueryDsl;
+use fungible_asset_checks::{
+    CurrentFungibleAssetBalancesV1Check, CurrentFungibleAssetBalancesV2Check,
+};
+use stake_checks::CurrentDelegatorBalancesCheck;
+use std::{
+    fmt::{Debug, Write as _},
+    path::PathBuf,
+};
+use token_v2_checks::{
+    CurrentCollectionsV2Check, CurrentTokenDatasV2Check, CurrentTokenOwnershipsV2Check,
+};
+
+/// A current table and the history table it is derived from.
+pub trait CurrentTableCheck {
+    /// Primary key of the current table, parsed from the key columns.
+    type PK: Debug + Ord;
+
+    const CURRENT_TABLE: &'static str;
+    const HISTORY_TABLE: &'static str;
+    /// Primary key columns of the current table. The history table uses the same column names.
+    const KEY_COLUMNS: &'static [&'static str];
+    /// Each column of the current table, including the key columns, and the history expression it is
+    /// derived from.
+    const COLUMNS: &'static [(&'static str, &'static str)];
+    /// History rows that don't match this filter aren't reflected in the current table.
+    const HISTORY_FILTER: &'static str = "TRUE";
+    /// Current rows that don't match this filter aren't derived from the history rows of the check.
+    const CURRENT_FILTER: &'static str = "TRUE";
+
+    fn parse_pk(key_values: &[String]) -> Result<Self::PK>;
+}
+
+#[derive(Clone, Debug, Parser)]
+#[clap(author, version, about, long_about = None)]
+pub struct ConsistencyCheckerArgs {
+    /// Postgres connection string of the processor database
+    #[clap(long)]
+    pub connection_string: String,
+    /// Processor whose tables to check
+    #[clap(long, value_enum)]
+    pub processor: ProcessorName,
+    /// Check this many random current rows instead of scanning the full tables
+    #[clap(long)]
+    pub sample_size: Option<i64>,
+    /// Number of distinct leading key values per page when scanning the full tables
+    #[clap(long, default_value_t = 10_000)]
+    pub page_size: i64,
+    /// Write a SQL script that rebuilds the mismatched current rows from history
+    #[clap(long)]
+    pub fixup_script: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ScanMode {
+    Sample(i64),
+    Full { page_size: i64 },
+}
+
+/// A primary key whose current row doesn't match the latest history row.
+#[derive(Debug)]
+pub struct Mismatch {
+    pub pk: String,
+    pub key_values: Vec<String>,
+    /// None if there is no history row for the key
+    pub history_values: Option<Vec<Option<String>>>,
+    /// None if there is no current row for the key
+    pub current_values: Option<Vec<Option<String>>>,
+}
+
+impl Mismatch {
+    /// Names of the columns that differ between the history and the current row.
+    pub fn mismatched_columns(&self, columns: &[(&str, &str)]) -> Vec<String> {
+        match (&self.history_values, &self.current_values) {
+            (Some(history_values), Some(current_values)) => columns
+                .iter()
+                .zip(history_values.iter().zip(current_values.iter()))
+                .filter(|(_, (history_value, current_value))| history_value != current_value)
+                .map(|((column, _), _)| column.to_string())
+                .collect(),
+            (None, _) => vec!["<missing history>".to_string()],
+            (_, None) => vec!["<missing current row>".to_string()],
+        }
+    }
+}
+
+pub struct CheckReport {
+    pub current_table: &'static str,
+    pub history_table: &'static str,
+    pub columns: &'static [(&'static str, &'static str)],
+    pub mismatches: Vec<Mismatch>,
+    /// SQL statements that rebuild the mismatched rows from history
+    pub fixup_statements: Vec<String>,
+}
+
+#[derive(QueryableByName)]
+struct MismatchRow {
+    #[diesel(sql_type = Array<Text>)]
+    key_values: Vec<String>,
+    #[diesel(sql_type = Nullable<Array<Nullable<Text>>>)]
+    history_values: Option<Vec<Option<String>>>,
+    #[diesel(sql_type = Nullable<Array<Nullable<Text>>>)]
+    current_values: Option<Vec<Option<String>>>,
+}
+
+pub async fn run(args: ConsistencyCheckerArgs) -> Result<()> {
+    let pool = new_db_pool(&args.connection_string, Some(2))
+        .await
+        .context("Failed to create connection pool")?;
+    let scan_mode = match args.sample_size {
+        Some(sample_size) => ScanMode::Sample(sample_size),
+        None => ScanMode::Full {
+            page_size: args.page_size,
+        },
+    };
+
+    let reports = match args.processor {
+        ProcessorName::TokenV2Processor => vec![
+            check_table::<CurrentCollectionsV2Check>(pool.clone(), scan_mode).await?,
+            check_table::<CurrentTokenDatasV2Check>(pool.clone(), scan_mode).await?,
+            check_table::<CurrentTokenOwnershipsV2Check>(pool.clone(), scan_mode).await?,
+        ],
+        ProcessorName::FungibleAssetProcessor => vec![
+            check_table::<CurrentFungibleAssetBalancesV1Check>(pool.clone(), scan_mode).await?,
+            check_table::<CurrentFungibleAssetBalancesV2Check>(pool.clone(), scan_mode).await?,
+        ],
+        ProcessorName::StakeProcessor => {
+            vec![check_table::<CurrentDelegatorBalancesCheck>(pool.clone(), scan_mode).await?]
+        },
+        processor => anyhow::bail!("No consistency checks for {}", processor),
+    };
+
+    let mut fixup_script = String::new();
+    for report in &reports {
+        println!(
+            "{} against {}: {} mismatched primary keys",
+            report.current_table,
+            report.history_table,
+            report.mismatches.len()
+        );
+        for mismatch in &report.mismatches {
+            println!(
+                "  {} [{}]",
+                mismatch.pk,
+                mismatch.mismatched_columns(report.columns).join(", ")
+            );
+        }
+        for statement in &report.fixup_statements {
+            writeln!(fixup_script, "{}", statement)?;
+        }
+    }
+
+    if let Some(path) = args.fixup_script {
+        std::fs::write(&path, fixup_script)
+            .with_context(|| format!("Failed to write fix-up script to {:?}", path))?;
+        println!("Wrote fix-up script to {:?}", path);
+    }
+    Ok(())
+}
+
+/// Compares the current table of `C` against its history table.
+pub async fn check_table<C: CurrentTableCheck>(
+    pool: ArcDbPool,
+    scan_mode: ScanMode,
+) -> Result<CheckReport> {
+    let mut conn = pool.get().await?;
+    let rows = match scan_mode {
+        ScanMode::Sample(sample_size) => {
+            let keys = C::KEY_COLUMNS.join(", ");
+            // Materialized so that both sides are compared on the same random sample
+            let sample = format!(
+                "sample AS MATERIALIZED (
+                    SELECT {keys} FROM {current_table} WHERE {current_filter}
+                    ORDER BY random() LIMIT $1
+                ), ",
+                current_table = C::CURRENT_TABLE,
+                current_filter = C::CURRENT_FILTER,
+            );
+            let key_filter = format!("({keys}) IN (SELECT {keys} FROM sample)");
+            sql_query(comparison_query::<C>(&sample, &key_filter))
+                .bind::<BigInt, _>(sample_size)
+                .load::<MismatchRow>(&mut conn)
+                .await
+                .with_context(|| format!("Failed to check {}", C::CURRENT_TABLE))?
+        },
+        ScanMode::Full { page_size } => {
+            let mut rows = vec![];
+            let leading_key = C::KEY_COLUMNS[0];
+            let mut lower_bound: Option<String> = None;
+            loop {
+                // Pages are ranges of the leading key column, so that keys missing from either side
+                // still fall into exactly one page.
+                let upper_bound = page_upper_bound::<C>(&mut conn, &lower_bound, page_size).await?;
+                let key_filter = format!(
+                    "($1::TEXT IS NULL OR {leading_key} > $1) AND ($2::TEXT IS NULL OR {leading_key} <= $2)"
+                );
+                let mut page = sql_query(comparison_query::<C>("", &key_filter))
+                    .bind::<Nullable<Text>, _>(lower_bound.clone())
+                    .bind::<Nullable<Text>, _>(upper_bound.clone())
+                    .load::<MismatchRow>(&mut conn)
+                    .await
+                    .with_context(|| format!("Failed to check {}", C::CURRENT_TABLE))?;
+                rows.append(&mut page);
+                match upper_bound {
+                    Some(upper_bound) => lower_bound = Some(upper_bound),
+                    None => break,
+                }
+            }
+            rows
+        },
+    };
+
+    let mut mismatches = rows
+        .into_iter()
+        .map(|row| Ok((C::parse_pk(&row.key_values)?, row)))
+        .collect::<Result<Vec<_>>>()?;
+    mismatches.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let fixup_statements = mismatches
+        .iter()
+        .map(|(_, row)| fixup_statement::<C>(&row.key_values, row.history_values.is_some()))
+        .collect();
+    let mismatches = mismatches
+        .into_iter()
+        .map(|(pk, row)| Mismatch {
+            pk: format!("{:?}", pk),
+            key_values: row.key_values,
+            history_values: row.history_values,
+            current_values: row.current_values,
+        })
+        .collect();
+
+    Ok(CheckReport {
+        current_table: C::CURRENT_TABLE,
+        history_table: C::HISTORY_TABLE,
+        columns: C::COLUMNS,
+        mismatches,
+        fixup_statements,
+    })
+}
+
+#[derive(QueryableByName)]
+struct PageBound {
+    #[diesel(sql_type = Nullable<Text>)]
+    upper_bound: Option<String>,
+}
+
+/// The last leading key value of the next page, or None if the next page is the last one.
+async fn page_upper_bound<C: CurrentTableCheck>(
+    conn: &mut DbPoolConnection<'_>,
+    lower_bound: &Option<String>,
+    page_size: i64,
+) -> Result<Option<String>> {
+    let leading_key = C::KEY_COLUMNS[0];
+    let query = format!(
+        "SELECT MAX({leading_key})::TEXT AS upper_bound FROM (
+            SELECT DISTINCT {leading_key} FROM {current_table}
+            WHERE ({current_filter}) AND ($1::TEXT IS NULL OR {leading_key} > $1)
+            ORDER BY {leading_key}
+            LIMIT $2
+        ) page
+        HAVING COUNT(*) = $2",
+        current_table = C::CURRENT_TABLE,
+        current_filter = C::CURRENT_FILTER,
+    );
+    let bound = sql_query(query)
+        .bind::<Nullable<Text>, _>(lower_bound.clone())
+        .bind::<BigInt, _>(page_size)
+        .get_result::<PageBound>(conn)
+        .await
+        .optional()?;
+    Ok(bound.and_then(|bound| bound.upper_bound))
+}
+
+/// Full outer join of the latest history row per key against the current rows, restricted to keys
+/// matching `key_filter`. Returns only the keys whose rows differ. `ctes` are prepended to the query's
+/// common table expressions.
+fn comparison_query<C: CurrentTableCheck>(ctes: &str, key_filter: &str) -> String {
+    let keys = C::KEY_COLUMNS.join(", ");
+    let history_columns = C::COLUMNS
+        .iter()
+        .map(|(column, history_expr)| format!("{} AS {}", history_expr, column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let current_columns = C::COLUMNS
+        .iter()
+        .map(|(column, _)| *column)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let join_condition = C::KEY_COLUMNS
+        .iter()
+        .map(|key| format!("h.{key} = c.{key}"))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let key_values = C::KEY_COLUMNS
+        .iter()
+        .map(|key| format!("COALESCE(h.{key}, c.{key})::TEXT"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let text_columns = |alias: &str| {
+        C::COLUMNS
+            .iter()
+            .map(|(column, _)| format!("{alias}.{column}::TEXT"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let row_columns = |alias: &str| {
+        C::COLUMNS
+            .iter()
+            .map(|(column, _)| format!("{alias}.{column}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!(
+        "WITH {ctes}history AS (
+            SELECT DISTINCT ON ({keys}) {history_columns}
+            FROM {history_table}
+            WHERE ({history_filter}) AND {key_filter}
+            ORDER BY {keys}, transaction_version DESC, write_set_change_index DESC
+        ), current_rows AS (
+            SELECT {current_columns} FROM {current_table}
+            WHERE ({current_filter}) AND {key_filter}
+        )
+        SELECT
+            ARRAY[{key_values}] AS key_values,
+            CASE WHEN h.{leading_key} IS NULL THEN NULL ELSE ARRAY[{history_text}] END AS history_values,
+            CASE WHEN c.{leading_key} IS NULL THEN NULL ELSE ARRAY[{current_text}] END AS current_values
+        FROM history h FULL OUTER JOIN current_rows c ON {join_condition}
+        WHERE ROW({history_row}) IS DISTINCT FROM ROW({current_row})",
+        history_table = C::HISTORY_TABLE,
+        history_filter = C::HISTORY_FILTER,
+        current_table = C::CURRENT_TABLE,
+        current_filter = C::CURRENT_FILTER,
+        leading_key = C::KEY_COLUMNS[0],
+        history_text = text_columns("h"),
+        current_text = text_columns("c"),
+        history_row = row_columns("h"),
+        current_row = row_columns("c"),
+    )
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// SQL that rebuilds the current row of a key from its latest history row. Current rows without any
+/// history are only listed as a commented out delete, since they need a closer look.
+fn fixup_statement<C: CurrentTableCheck>(key_values: &[String], has_history: bool) -> String {
+    let key_condition = C::KEY_COLUMNS
+        .iter()
+        .zip(key_values)
+        .map(|(key, value)| format!("{} = {}", key, quote_literal(value)))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    if !has_history {
+        return format!(
+            "-- No history for this row\n-- DELETE FROM {} WHERE {};",
+            C::CURRENT_TABLE,
+            key_condition
+        );
+    }
+    let columns = C::COLUMNS
+        .iter()
+        .map(|(column, _)| *column)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let history_exprs = C::COLUMNS
+        .iter()
+        .map(|(_, history_expr)| *history_expr)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let updates = C::COLUMNS
+        .iter()
+        .filter(|(column, _)| !C::KEY_COLUMNS.contains(column))
+        .map(|(column, _)| format!("{column} = EXCLUDED.{column}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "INSERT INTO {current_table} ({columns})
+SELECT {history_exprs} FROM {history_table}
+WHERE ({history_filter}) AND {key_condition}
+ORDER BY transaction_version DESC, write_set_change_index DESC LIMIT 1
+ON CONFLICT ({keys}) DO UPDATE SET {updates};",
+        current_table = C::CURRENT_TABLE,
+        history_table = C::HISTORY_TABLE,
+        history_filter = C::HISTORY_FILTER,
+        keys = C::KEY_COLUMNS.join(", "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixup_statement() {
+        let statement = fixup_statement::<CurrentCollectionsV2Check>(&["0xa'b".to_string()], true);
+        assert!(statement.starts_with("INSERT INTO current_collections_v2 (collection_id, "));
+        assert!(statement.contains("WHERE (TRUE) AND collection_id = '0xa''b'"));
+        assert!(statement.contains("ON CONFLICT (collection_id) DO UPDATE SET creator_address = EXCLUDED.creator_address, "));
+        assert!(!statement.contains("collection_id = EXCLUDED.collection_id"));
+
+        let statement = fixup_statement::<CurrentCollectionsV2Check>(&["0x1".to_string()], false);
+        assert_eq!(
+            statement,
+            "-- No history for this row\n-- DELETE FROM current_collections_v2 WHERE collection_id = '0x1';"
+        );
+    }
+
+    #[test]
+    fn test_parse_pk() {
+        let pk = CurrentTokenOwnershipsV2Check::parse_pk(&[
+            "0xtoken".to_string(),
+            "1".to_string(),
+            "0xowner".to_string(),
+            "0xstorage".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(pk.1, bigdecimal::BigDecimal::from(1));
+        assert!(CurrentTokenOwnershipsV2Check::parse_pk(&["0xtoken".to_string()]).is_err());
+    }
+}
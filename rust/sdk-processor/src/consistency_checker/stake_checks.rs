@@ -0,0 +1,49 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::CurrentTableCheck;
+use anyhow::Result;
+use processor::db::postgres::models::stake_models::delegator_balances::{
+    CurrentDelegatorBalancePK, TableHandle,
+};
+
+pub struct CurrentDelegatorBalancesCheck;
+
+impl CurrentTableCheck for CurrentDelegatorBalancesCheck {
+    type PK = (CurrentDelegatorBalancePK, TableHandle);
+
+    const CURRENT_TABLE: &'static str = "current_delegator_balances";
+    const HISTORY_TABLE: &'static str = "delegator_balances";
+    const KEY_COLUMNS: &'static [&'static str] = &[
+        "delegator_address",
+        "pool_address",
+        "pool_type",
+        "table_handle",
+    ];
+    const COLUMNS: &'static [(&'static str, &'static str)] = &[
+        ("delegator_address", "delegator_address"),
+        ("pool_address", "pool_address"),
+        ("pool_type", "pool_type"),
+        ("table_handle", "table_handle"),
+        ("last_transaction_version", "transaction_version"),
+        ("shares", "shares"),
+        ("parent_table_handle", "parent_table_handle"),
+    ];
+
+    fn parse_pk(key_values: &[String]) -> Result<Self::PK> {
+        match key_values {
+            [delegator_address, pool_address, pool_type, table_handle] => Ok((
+                (
+                    delegator_address.clone(),
+                    pool_address.clone(),
+                    pool_type.clone(),
+                ),
+                table_handle.clone(),
+            )),
+            _ => anyhow::bail!(
+                "Invalid key for current_delegator_balances: {:?}",
+                key_values
+            ),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::CurrentTableCheck;
+use anyhow::{Context, Result};
+use bigdecimal::BigDecimal;
+use processor::db::{
+    common::models::token_v2_models::raw_v2_token_ownerships::CurrentTokenOwnershipV2PK,
+    postgres::models::token_v2_models::{
+        v2_collections::CurrentCollectionV2PK, v2_token_datas::CurrentTokenDataV2PK,
+    },
+};
+use std::str::FromStr;
+
+pub struct CurrentCollectionsV2Check;
+
+impl CurrentTableCheck for CurrentCollectionsV2Check {
+    type PK = CurrentCollectionV2PK;
+
+    const CURRENT_TABLE: &'static str = "current_collections_v2";
+    const HISTORY_TABLE: &'static str = "collections_v2";
+    const KEY_COLUMNS: &'static [&'static str] = &["collection_id"];
+    const COLUMNS: &'static [(&'static str, &'static str)] = &[
+        ("collection_id", "collection_id"),
+        ("creator_address", "creator_address"),
+        ("collection_name", "collection_name"),
+        ("description", "description"),
+        ("uri", "uri"),
+        ("current_supply", "current_supply"),
+        ("max_supply", "max_supply"),
+        ("total_minted_v2", "total_minted_v2"),
+        ("mutable_description", "mutable_description"),
+        ("mutable_uri", "mutable_uri"),
+        ("table_handle_v1", "table_handle_v1"),
+        ("token_standard", "token_standard"),
+        ("last_transaction_version", "transaction_version"),
+        ("last_transaction_timestamp", "transaction_timestamp"),
+        ("collection_properties", "collection_properties"),
+    ];
+
+    fn parse_pk(key_values: &[String]) -> Result<Self::PK> {
+        match key_values {
+            [collection_id] => Ok(collection_id.clone()),
+            _ => anyhow::bail!("Invalid key for current_collections_v2: {:?}", key_values),
+        }
+    }
+}
+
+pub struct CurrentTokenDatasV2Check;
+
+impl CurrentTableCheck for CurrentTokenDatasV2Check {
+    type PK = CurrentTokenDataV2PK;
+
+    const CURRENT_TABLE: &'static str = "current_token_datas_v2";
+    const HISTORY_TABLE: &'static str = "token_datas_v2";
+    const KEY_COLUMNS: &'static [&'static str] = &["token_data_id"];
+    const COLUMNS: &'static [(&'static str, &'static str)] = &[
+        ("token_data_id", "token_data_id"),
+        ("collection_id", "collection_id"),
+        ("token_name", "token_name"),
+        ("maximum", "maximum"),
+        ("supply", "supply"),
+        ("largest_property_version_v1", "largest_property_version_v1"),
+        ("token_uri", "token_uri"),
+        ("description", "description"),
+        ("token_properties", "token_properties"),
+        ("token_standard", "token_standard"),
+        ("is_fungible_v2", "is_fungible_v2"),
+        ("last_transaction_version", "transaction_version"),
+        ("last_transaction_timestamp", "transaction_timestamp"),
+        ("decimals", "decimals"),
+        ("is_deleted_v2", "is_deleted_v2"),
+    ];
+
+    fn parse_pk(key_values: &[String]) -> Result<Self::PK> {
+        match key_values {
+            [token_data_id] => Ok(token_data_id.clone()),
+            _ => anyhow::bail!("Invalid key for current_token_datas_v2: {:?}", key_values),
+        }
+    }
+}
+
+pub struct CurrentTokenOwnershipsV2Check;
+
+impl CurrentTableCheck for CurrentTokenOwnershipsV2Check {
+    type PK = CurrentTokenOwnershipV2PK;
+
+    const CURRENT_TABLE: &'static str = "current_token_ownerships_v2";
+    const HISTORY_TABLE: &'static str = "token_ownerships_v2";
+    const KEY_COLUMNS: &'static [&'static str] = &[
+        "token_data_id",
+        "property_version_v1",
+        "owner_address",
+        "storage_id",
+    ];
+    const COLUMNS: &'static [(&'static str, &'static str)] = &[
+        ("token_data_id", "token_data_id"),
+        ("property_version_v1", "property_version_v1"),
+        ("owner_address", "owner_address"),
+        ("storage_id", "storage_id"),
+        ("amount", "amount"),
+        ("table_type_v1", "table_type_v1"),
+        ("token_properties_mutated_v1", "token_properties_mutated_v1"),
+        ("is_soulbound_v2", "is_soulbound_v2"),
+        ("token_standard", "token_standard"),
+        ("is_fungible_v2", "is_fungible_v2"),
+        ("last_transaction_version", "transaction_version"),
+        ("last_transaction_timestamp", "transaction_timestamp"),
+        ("non_transferrable_by_owner", "non_transferrable_by_owner"),
+    ];
+    const HISTORY_FILTER: &'static str = "owner_address IS NOT NULL";
+
+    fn parse_pk(key_values: &[String]) -> Result<Self::PK> {
+        match key_values {
+            [token_data_id, property_version_v1, owner_address, storage_id] => Ok((
+                token_data_id.clone(),
+                BigDecimal::from_str(property_version_v1).context("Invalid property_version_v1")?,
+                owner_address.clone(),
+                storage_id.clone(),
+            )),
+            _ => anyhow::bail!(
+                "Invalid key for current_token_ownerships_v2: {:?}",
+                key_values
+            ),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::extra_unused_lifetimes)]
+
+use super::backfill_processor_status::BackfillStatus;
+use crate::utils::database::DbPoolConnection;
+use diesel::{
+    sql_query,
+    sql_types::{BigInt, Text},
+    ExpressionMethods, Insertable, QueryDsl, Queryable, QueryableByName,
+};
+use diesel_async::RunQueryDsl;
+use field_count::FieldCount;
+use processor::schema::backfill_shards;
+
+#[derive(Debug, FieldCount, Insertable, PartialEq, Eq)]
+#[diesel(table_name = backfill_shards)]
+/// A version range of a sharded backfill. Progress within the shard is tracked in
+/// `backfill_processor_status` under the shard's own alias.
+pub struct BackfillShard {
+    pub backfill_alias: String,
+    pub shard_index: i64,
+    pub shard_start_version: i64,
+    pub shard_end_version: i64,
+    pub shard_status: BackfillStatus,
+    pub worker_id: Option<String>,
+    pub lease_expires_at: Option<chrono::NaiveDateTime>,
+}
+
+impl BackfillShard {
+    /// Splits `[start_version, end_version]` into unclaimed shards of at most `shard_size` versions.
+    pub fn split_range(
+        backfill_alias: &str,
+        start_version: u64,
+        end_version: u64,
+        shard_size: u64,
+    ) -> Vec<Self> {
+        let mut shards = vec![];
+        let mut shard_start_version = start_version;
+        while shard_start_version <= end_version {
+            let shard_end_version = std::cmp::min(
+                shard_start_version.saturating_add(shard_size.saturating_sub(1)),
+                end_version,
+            );
+            shards.push(Self {
+                backfill_alias: backfill_alias.to_string(),
+                shard_index: shards.len() as i64,
+                shard_start_version: shard_start_version as i64,
+                shard_end_version: shard_end_version as i64,
+                shard_status: BackfillStatus::InProgress,
+                worker_id: None,
+                lease_expires_at: None,
+            });
+            match shard_end_version.checked_add(1) {
+                Some(next_shard_start_version) => shard_start_version = next_shard_start_version,
+                None => break,
+            }
+        }
+        shards
+    }
+}
+
+#[derive(Debug, Queryable, QueryableByName)]
+#[diesel(table_name = backfill_shards)]
+pub struct BackfillShardQuery {
+    pub backfill_alias: String,
+    pub shard_index: i64,
+    pub shard_start_version: i64,
+    pub shard_end_version: i64,
+    pub shard_status: BackfillStatus,
+    pub worker_id: Option<String>,
+    pub lease_expires_at: Option<chrono::NaiveDateTime>,
+    pub last_updated: chrono::NaiveDateTime,
+}
+
+impl BackfillShardQuery {
+    /// Claims an incomplete shard for `worker_id`. Shards already leased to this worker (e.g. before
+    /// a crash) are preferred, then unclaimed shards, then shards whose lease has expired.
+    pub async fn claim(
+        backfill_alias: &str,
+        worker_id: &str,
+        lease_duration_secs: i64,
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<Option<Self>> {
+        let mut res: Vec<Self> = sql_query(
+            "UPDATE backfill_shards
+            SET worker_id = $2, lease_expires_at = NOW() + $3 * INTERVAL '1 second', last_updated = NOW()
+            WHERE (backfill_alias, shard_index) = (
+                SELECT backfill_alias, shard_index FROM backfill_shards
+                WHERE backfill_alias = $1
+                    AND shard_status = 'in_progress'
+                    AND (worker_id IS NULL OR worker_id = $2 OR lease_expires_at < NOW())
+                ORDER BY worker_id = $2 DESC NULLS LAST, worker_id IS NULL DESC, shard_index
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *",
+        )
+        .bind::<Text, _>(backfill_alias)
+        .bind::<Text, _>(worker_id)
+        .bind::<BigInt, _>(lease_duration_secs)
+        .get_results(conn)
+        .await?;
+        Ok(res.pop())
+    }
+
+    /// Extends the lease on a shard. Returns the number of rows updated, which is 0 if the
+    /// lease has been taken over by another worker.
+    pub async fn renew_lease(
+        backfill_alias: &str,
+        shard_index: i64,
+        worker_id: &str,
+        lease_duration_secs: i64,
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<usize> {
+        sql_query(
+            "UPDATE backfill_shards
+            SET lease_expires_at = NOW() + $4 * INTERVAL '1 second', last_updated = NOW()
+            WHERE backfill_alias = $1 AND shard_index = $2 AND worker_id = $3",
+        )
+        .bind::<Text, _>(backfill_alias)
+        .bind::<BigInt, _>(shard_index)
+        .bind::<Text, _>(worker_id)
+        .bind::<BigInt, _>(lease_duration_secs)
+        .execute(conn)
+        .await
+    }
+
+    /// Marks a shard leased to `worker_id` complete. Returns the number of rows updated, which is 0
+    /// if the lease has been taken over by another worker.
+    pub async fn mark_complete(
+        backfill_alias: &str,
+        shard_index: i64,
+        worker_id: &str,
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<usize> {
+        diesel::update(
+            backfill_shards::table
+                .filter(backfill_shards::backfill_alias.eq(backfill_alias))
+                .filter(backfill_shards::shard_index.eq(shard_index))
+                .filter(backfill_shards::worker_id.eq(worker_id)),
+        )
+        .set((
+            backfill_shards::shard_status.eq(BackfillStatus::Complete),
+            backfill_shards::lease_expires_at.eq(None::<chrono::NaiveDateTime>),
+            backfill_shards::last_updated.eq(diesel::dsl::now),
+        ))
+        .execute(conn)
+        .await
+    }
+
+    pub async fn get_by_backfill_alias(
+        backfill_alias: &str,
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<Vec<Self>> {
+        backfill_shards::table
+            .filter(backfill_shards::backfill_alias.eq(backfill_alias))
+            .order(backfill_shards::shard_index.asc())
+            .load::<Self>(conn)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_range() {
+        let shards = BackfillShard::split_range("alias", 10, 34, 10);
+        let ranges: Vec<(i64, i64, i64)> = shards
+            .iter()
+            .map(|s| (s.shard_index, s.shard_start_version, s.shard_end_version))
+            .collect();
+        assert_eq!(ranges, vec![(0, 10, 19), (1, 20, 29), (2, 30, 34)]);
+    }
+
+    #[test]
+    fn test_split_range_end_of_version_range() {
+        let shards = BackfillShard::split_range("alias", u64::MAX - 14, u64::MAX, 10);
+        let ranges: Vec<(u64, u64)> = shards
+            .iter()
+            .map(|s| (s.shard_start_version as u64, s.shard_end_version as u64))
+            .collect();
+        assert_eq!(ranges, vec![
+            (u64::MAX - 14, u64::MAX - 5),
+            (u64::MAX - 4, u64::MAX)
+        ]);
+
+        let shards = BackfillShard::split_range("alias", 0, 100, u64::MAX);
+        assert_eq!(shards.len(), 1);
+        assert_eq!(shards[0].shard_end_version, 100);
+    }
+
+    #[test]
+    fn test_split_range_single_version() {
+        let shards = BackfillShard::split_range("alias", 5, 5, 100);
+        assert_eq!(shards.len(), 1);
+        assert_eq!(shards[0].shard_start_version, 5);
+        assert_eq!(shards[0].shard_end_version, 5);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod backfill_processor_status;
+pub mod backfill_shard;
 pub mod processor_status;
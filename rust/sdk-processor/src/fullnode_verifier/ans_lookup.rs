@@ -0,0 +1,109 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{fullnode_client::FullnodeClient, Divergence};
+use crate::utils::database::DbPoolConnection;
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use diesel::{
+    sql_query,
+    sql_types::{BigInt, Nullable, Text, Timestamp},
+    QueryableByName,
+};
+use diesel_async::RunQueryDsl;
+use processor::utils::util::standardize_address;
+use serde_json::{json, Value};
+
+pub const TABLE_NAME: &str = "current_ans_lookup_v2";
+
+#[derive(Clone, Debug, QueryableByName)]
+pub struct IndexedAnsLookup {
+    #[diesel(sql_type = Text)]
+    pub domain: String,
+    #[diesel(sql_type = Text)]
+    pub subdomain: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub registered_address: Option<String>,
+}
+
+/// Random v2 Aptos names whose latest change is at or before `ledger_version`, and that haven't expired at
+/// `ledger_timestamp`, the time of that version. Expired names aren't checked because the router
+/// doesn't resolve them.
+pub async fn load_sample(
+    conn: &mut DbPoolConnection<'_>,
+    ledger_version: u64,
+    ledger_timestamp: NaiveDateTime,
+    sample_size: i64,
+) -> Result<Vec<IndexedAnsLookup>> {
+    Ok(sql_query(
+        "SELECT domain, subdomain, registered_address
+        FROM current_ans_lookup_v2
+        WHERE token_standard = 'v2'
+            AND namespace = 'ans'
+            AND NOT is_deleted
+            AND expiration_timestamp > $2
+            AND last_transaction_version <= $1
+        ORDER BY random()
+        LIMIT $3",
+    )
+    .bind::<BigInt, _>(ledger_version as i64)
+    .bind::<Timestamp, _>(ledger_timestamp)
+    .bind::<BigInt, _>(sample_size)
+    .load(conn)
+    .await?)
+}
+
+/// Compares the indexed target address of each name against `router::get_target_addr`.
+pub async fn verify(
+    client: &FullnodeClient,
+    ans_v2_contract_address: &str,
+    lookups: &[IndexedAnsLookup],
+    ledger_version: u64,
+) -> Result<Vec<Divergence>> {
+    let function = format!("{}::router::get_target_addr", ans_v2_contract_address);
+    let mut divergences = vec![];
+    for lookup in lookups {
+        // Domains are stored with an empty subdomain
+        let subdomain = if lookup.subdomain.is_empty() {
+            json!({ "vec": [] })
+        } else {
+            json!({ "vec": [lookup.subdomain] })
+        };
+        let result = client
+            .view(
+                &function,
+                &[],
+                &[json!(lookup.domain), subdomain],
+                ledger_version,
+            )
+            .await?;
+        let fullnode_address = parse_optional_address(&result)
+            .with_context(|| format!("Unexpected result of {}: {:?}", function, result))?;
+        let indexed_address = lookup
+            .registered_address
+            .as_deref()
+            .map(standardize_address);
+        if indexed_address != fullnode_address {
+            divergences.push(Divergence {
+                table: TABLE_NAME,
+                key: format!("{}.{}", lookup.subdomain, lookup.domain)
+                    .trim_start_matches('.')
+                    .to_string(),
+                field: "registered_address",
+                indexed: indexed_address.unwrap_or_else(|| "NULL".to_string()),
+                fullnode: fullnode_address.unwrap_or_else(|| "NULL".to_string()),
+            });
+        }
+    }
+    Ok(divergences)
+}
+
+/// Parses the `Option<address>` returned by a view function, e.g. `[{"vec": ["0x1"]}]`.
+fn parse_optional_address(result: &[Value]) -> Option<Option<String>> {
+    let vec = result.first()?.get("vec")?.as_array()?;
+    match vec.as_slice() {
+        [] => Some(None),
+        [address] => Some(Some(standardize_address(address.as_str()?))),
+        _ => None,
+    }
+}
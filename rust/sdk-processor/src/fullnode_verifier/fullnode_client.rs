@@ -0,0 +1,177 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Context, Result};
+use aptos_indexer_processor_sdk::aptos_protos::transaction::v1::WriteResource;
+use chrono::{DateTime, NaiveDateTime};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Header with the cursor of the next page of a paginated response.
+const CURSOR_HEADER: &str = "X-Aptos-Cursor";
+
+/// A resource as returned by the fullnode REST API.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RestResource {
+    #[serde(rename = "type")]
+    pub type_str: String,
+    pub data: Value,
+}
+
+impl RestResource {
+    /// Converts the resource to the shape the processors parse, so that the same resource parsers
+    /// can be used on fullnode state.
+    pub fn to_write_resource(&self, address: &str) -> WriteResource {
+        WriteResource {
+            address: address.to_string(),
+            type_str: self.type_str.clone(),
+            data: self.data.to_string(),
+            ..WriteResource::default()
+        }
+    }
+}
+
+/// The part of a block returned by the fullnode REST API that the verifier uses.
+#[derive(Debug, Deserialize)]
+struct RestBlock {
+    /// Microseconds since the epoch, as a string
+    block_timestamp: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ViewRequest<'a> {
+    function: &'a str,
+    type_arguments: &'a [String],
+    arguments: &'a [Value],
+}
+
+/// Minimal client for the fullnode REST API. All reads are made at a fixed ledger version.
+#[derive(Clone, Debug)]
+pub struct FullnodeClient {
+    client: reqwest::Client,
+    rest_api_endpoint: String,
+}
+
+impl FullnodeClient {
+    pub fn new(rest_api_endpoint: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            rest_api_endpoint: rest_api_endpoint.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// All resources of an account. An account that doesn't exist has no resources. Accounts with
+    /// more resources than fit in a response are read page by page, following the cursor the
+    /// fullnode returns in the `X-Aptos-Cursor` header.
+    pub async fn get_account_resources(
+        &self,
+        address: &str,
+        ledger_version: u64,
+    ) -> Result<Vec<RestResource>> {
+        let mut resources = vec![];
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut url = format!(
+                "{}/v1/accounts/{}/resources?ledger_version={}",
+                self.rest_api_endpoint, address, ledger_version
+            );
+            if let Some(cursor) = &cursor {
+                url.push_str(&format!("&start={}", cursor));
+            }
+            let response = self.client.get(&url).send().await?;
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(resources);
+            }
+            let response = response.error_for_status()?;
+            cursor = response
+                .headers()
+                .get(CURSOR_HEADER)
+                .map(|cursor| cursor.to_str().map(str::to_string))
+                .transpose()
+                .with_context(|| format!("Invalid cursor from {}", url))?;
+            let mut page: Vec<RestResource> = response
+                .json()
+                .await
+                .with_context(|| format!("Failed to parse resources from {}", url))?;
+            resources.append(&mut page);
+            if cursor.is_none() {
+                return Ok(resources);
+            }
+        }
+    }
+
+    /// A single resource of an account, or None if the account doesn't have it.
+    pub async fn get_account_resource(
+        &self,
+        address: &str,
+        resource_type: &str,
+        ledger_version: u64,
+    ) -> Result<Option<RestResource>> {
+        let url = format!(
+            "{}/v1/accounts/{}/resource/{}?ledger_version={}",
+            self.rest_api_endpoint, address, resource_type, ledger_version
+        );
+        let response = self.client.get(&url).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        response
+            .error_for_status()?
+            .json()
+            .await
+            .map(Some)
+            .with_context(|| format!("Failed to parse resource from {}", url))
+    }
+
+    /// Timestamp of the block that contains `ledger_version`.
+    pub async fn get_ledger_timestamp(&self, ledger_version: u64) -> Result<NaiveDateTime> {
+        let url = format!(
+            "{}/v1/blocks/by_version/{}",
+            self.rest_api_endpoint, ledger_version
+        );
+        let block: RestBlock = self
+            .client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse block from {}", url))?;
+        let block_timestamp_us: i64 = block
+            .block_timestamp
+            .parse()
+            .with_context(|| format!("Invalid block timestamp from {}", url))?;
+        DateTime::from_timestamp_micros(block_timestamp_us)
+            .map(|timestamp| timestamp.naive_utc())
+            .with_context(|| format!("Invalid block timestamp from {}", url))
+    }
+
+    /// Calls a view function and returns its return values.
+    pub async fn view(
+        &self,
+        function: &str,
+        type_arguments: &[String],
+        arguments: &[Value],
+        ledger_version: u64,
+    ) -> Result<Vec<Value>> {
+        let url = format!(
+            "{}/v1/view?ledger_version={}",
+            self.rest_api_endpoint, ledger_version
+        );
+        self.client
+            .post(&url)
+            .json(&ViewRequest {
+                function,
+                type_arguments,
+                arguments,
+            })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse view result of {}", function))
+    }
+}
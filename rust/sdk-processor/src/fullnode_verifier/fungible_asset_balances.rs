@@ -0,0 +1,220 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{fullnode_client::FullnodeClient, Divergence};
+use crate::utils::database::DbPoolConnection;
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use diesel::{
+    sql_query,
+    sql_types::{BigInt, Bool, Nullable, Numeric, Text},
+    QueryableByName,
+};
+use diesel_async::RunQueryDsl;
+use processor::db::{
+    common::models::object_models::v2_object_utils::ObjectCore,
+    postgres::models::{
+        coin_models::coin_utils::CoinStoreResource,
+        fungible_asset_models::v2_fungible_asset_utils::{
+            ConcurrentFungibleAssetBalance, FungibleAssetStore,
+        },
+    },
+};
+
+pub const TABLE_NAME: &str = "current_fungible_asset_balances";
+const FUNGIBLE_STORE_TYPE: &str = "0x1::fungible_asset::FungibleStore";
+const CONCURRENT_BALANCE_TYPE: &str = "0x1::fungible_asset::ConcurrentFungibleBalance";
+const OBJECT_CORE_TYPE: &str = "0x1::object::ObjectCore";
+
+#[derive(Clone, Debug, QueryableByName)]
+pub struct IndexedBalance {
+    #[diesel(sql_type = Text)]
+    pub storage_id: String,
+    #[diesel(sql_type = Text)]
+    pub owner_address: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub asset_type_v1: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub asset_type_v2: Option<String>,
+    #[diesel(sql_type = Bool)]
+    pub is_frozen: bool,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub amount_v1: Option<BigDecimal>,
+    #[diesel(sql_type = Nullable<Numeric>)]
+    pub amount_v2: Option<BigDecimal>,
+}
+
+/// Random balances whose latest change is at or before `ledger_version`.
+pub async fn load_sample(
+    conn: &mut DbPoolConnection<'_>,
+    ledger_version: u64,
+    sample_size: i64,
+) -> Result<Vec<IndexedBalance>> {
+    Ok(sql_query(
+        "SELECT storage_id, owner_address, asset_type_v1, asset_type_v2, is_frozen, amount_v1, amount_v2
+        FROM current_fungible_asset_balances
+        WHERE last_transaction_version <= $1
+        ORDER BY random()
+        LIMIT $2",
+    )
+    .bind::<BigInt, _>(ledger_version as i64)
+    .bind::<BigInt, _>(sample_size)
+    .load(conn)
+    .await?)
+}
+
+/// Compares indexed balances against the fullnode. The fungible asset side of a balance is checked
+/// against the resources of its store object, and the coin side against the `CoinStore` of the
+/// owner.
+pub async fn verify(
+    client: &FullnodeClient,
+    balances: &[IndexedBalance],
+    ledger_version: u64,
+) -> Result<Vec<Divergence>> {
+    let mut divergences = vec![];
+    for balance in balances {
+        if let Some(asset_type_v2) = &balance.asset_type_v2 {
+            verify_fungible_store(
+                client,
+                balance,
+                asset_type_v2,
+                ledger_version,
+                &mut divergences,
+            )
+            .await?;
+        }
+        if let Some(asset_type_v1) = &balance.asset_type_v1 {
+            verify_coin_store(
+                client,
+                balance,
+                asset_type_v1,
+                ledger_version,
+                &mut divergences,
+            )
+            .await?;
+        }
+    }
+    Ok(divergences)
+}
+
+async fn verify_fungible_store(
+    client: &FullnodeClient,
+    balance: &IndexedBalance,
+    asset_type_v2: &str,
+    ledger_version: u64,
+    divergences: &mut Vec<Divergence>,
+) -> Result<()> {
+    let divergence = |field: &'static str, indexed: String, fullnode: String| Divergence {
+        table: TABLE_NAME,
+        key: balance.storage_id.clone(),
+        field,
+        indexed,
+        fullnode,
+    };
+    let resources = client
+        .get_account_resources(&balance.storage_id, ledger_version)
+        .await?;
+
+    let mut store = None;
+    let mut concurrent_balance = None;
+    let mut object_core = None;
+    for resource in &resources {
+        let write_resource = resource.to_write_resource(&balance.storage_id);
+        match resource.type_str.as_str() {
+            FUNGIBLE_STORE_TYPE => store = Some(FungibleAssetStore::try_from(&write_resource)?),
+            CONCURRENT_BALANCE_TYPE => {
+                concurrent_balance =
+                    Some(ConcurrentFungibleAssetBalance::try_from(&write_resource)?)
+            },
+            OBJECT_CORE_TYPE => object_core = Some(ObjectCore::try_from(&write_resource)?),
+            _ => {},
+        }
+    }
+
+    let Some(store) = store else {
+        // A deleted store is indexed with a zero balance
+        let indexed_amount = balance.amount_v2.clone().unwrap_or_default();
+        if indexed_amount != BigDecimal::from(0) {
+            divergences.push(divergence(
+                "amount_v2",
+                indexed_amount.to_string(),
+                "<missing store>".to_string(),
+            ));
+        }
+        return Ok(());
+    };
+
+    // A store with a concurrent balance keeps its balance in the aggregator
+    let fullnode_amount = match concurrent_balance {
+        Some(concurrent_balance) => concurrent_balance.balance.value,
+        None => store.balance.clone(),
+    };
+    if balance.amount_v2.as_ref() != Some(&fullnode_amount) {
+        divergences.push(divergence(
+            "amount_v2",
+            format_optional(&balance.amount_v2),
+            fullnode_amount.to_string(),
+        ));
+    }
+    if balance.is_frozen != store.frozen {
+        divergences.push(divergence(
+            "is_frozen",
+            balance.is_frozen.to_string(),
+            store.frozen.to_string(),
+        ));
+    }
+    let fullnode_asset_type = store.metadata.get_reference_address();
+    if asset_type_v2 != fullnode_asset_type {
+        divergences.push(divergence(
+            "asset_type_v2",
+            asset_type_v2.to_string(),
+            fullnode_asset_type,
+        ));
+    }
+    if let Some(object_core) = object_core {
+        if balance.owner_address != object_core.get_owner_address() {
+            divergences.push(divergence(
+                "owner_address",
+                balance.owner_address.clone(),
+                object_core.get_owner_address(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn verify_coin_store(
+    client: &FullnodeClient,
+    balance: &IndexedBalance,
+    asset_type_v1: &str,
+    ledger_version: u64,
+    divergences: &mut Vec<Divergence>,
+) -> Result<()> {
+    let coin_store_type = format!("0x1::coin::CoinStore<{}>", asset_type_v1);
+    let coin_store = client
+        .get_account_resource(&balance.owner_address, &coin_store_type, ledger_version)
+        .await?
+        .map(|resource| serde_json::from_value::<CoinStoreResource>(resource.data))
+        .transpose()?;
+    // A coin store that was migrated to fungible assets no longer exists
+    let fullnode_amount = coin_store
+        .map(|coin_store| coin_store.coin.value)
+        .unwrap_or_default();
+    let indexed_amount = balance.amount_v1.clone().unwrap_or_default();
+    if indexed_amount != fullnode_amount {
+        divergences.push(Divergence {
+            table: TABLE_NAME,
+            key: balance.storage_id.clone(),
+            field: "amount_v1",
+            indexed: indexed_amount.to_string(),
+            fullnode: fullnode_amount.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn format_optional(value: &Option<BigDecimal>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "NULL".to_string(), |value| value.to_string())
+}
@@ -0,0 +1,420 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cross-checks indexed current state against the state served by a fullnode's REST API.
+//!
+//! Each check samples rows from a current table, reads the same state from the fullnode at a fixed
+//! ledger version, parses it with the same resource parsers the processors use, and reports the
+//! fields that diverge. By default the ledger version is the last version the owning processor has
+//! processed, so rows the processor hasn't caught up on yet aren't reported. The fullnode must not
+//! have pruned that version.
+
+pub mod ans_lookup;
+pub mod fullnode_client;
+pub mod fungible_asset_balances;
+pub mod objects;
+
+use crate::{
+    config::processor_config::ProcessorName,
+    db::common::models::processor_status::ProcessorStatusQuery,
+    utils::database::{new_db_pool, ArcDbPool},
+};
+use anyhow::{Context, Result};
+use clap::Parser;
+use fullnode_client::FullnodeClient;
+
+#[derive(Clone, Debug, Parser)]
+#[clap(author, version, about, long_about = None)]
+pub struct FullnodeVerifierArgs {
+    /// Postgres connection string of the processor database
+    #[clap(long)]
+    pub connection_string: String,
+    /// Fullnode REST API endpoint, e.g. https://api.mainnet.aptoslabs.com
+    #[clap(long)]
+    pub rest_api_endpoint: String,
+    /// Ledger version to compare at. Defaults to the last version processed by each processor.
+    #[clap(long)]
+    pub ledger_version: Option<u64>,
+    /// Number of random rows to check per table
+    #[clap(long, default_value_t = 100)]
+    pub sample_size: i64,
+    /// Address of the ANS v2 contract. current_ans_lookup_v2 is only checked if this is set.
+    #[clap(long)]
+    pub ans_v2_contract_address: Option<String>,
+}
+
+/// A field of an indexed row that doesn't match the fullnode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub table: &'static str,
+    pub key: String,
+    pub field: &'static str,
+    pub indexed: String,
+    pub fullnode: String,
+}
+
+pub struct TableReport {
+    pub table: &'static str,
+    pub ledger_version: u64,
+    pub rows_checked: usize,
+    pub divergences: Vec<Divergence>,
+}
+
+pub async fn run(args: FullnodeVerifierArgs) -> Result<()> {
+    let pool = new_db_pool(&args.connection_string, Some(2))
+        .await
+        .context("Failed to create connection pool")?;
+    let client = FullnodeClient::new(&args.rest_api_endpoint);
+
+    let mut reports = vec![];
+    if let Some(ledger_version) = resolve_ledger_version(
+        pool.clone(),
+        ProcessorName::FungibleAssetProcessor,
+        args.ledger_version,
+    )
+    .await?
+    {
+        let mut conn = pool.get().await?;
+        let rows =
+            fungible_asset_balances::load_sample(&mut conn, ledger_version, args.sample_size)
+                .await?;
+        reports.push(TableReport {
+            table: fungible_asset_balances::TABLE_NAME,
+            ledger_version,
+            rows_checked: rows.len(),
+            divergences: fungible_asset_balances::verify(&client, &rows, ledger_version).await?,
+        });
+    }
+    if let Some(ledger_version) = resolve_ledger_version(
+        pool.clone(),
+        ProcessorName::ObjectsProcessor,
+        args.ledger_version,
+    )
+    .await?
+    {
+        let mut conn = pool.get().await?;
+        let rows = objects::load_sample(&mut conn, ledger_version, args.sample_size).await?;
+        reports.push(TableReport {
+            table: objects::TABLE_NAME,
+            ledger_version,
+            rows_checked: rows.len(),
+            divergences: objects::verify(&client, &rows, ledger_version).await?,
+        });
+    }
+    if let Some(ans_v2_contract_address) = &args.ans_v2_contract_address {
+        if let Some(ledger_version) = resolve_ledger_version(
+            pool.clone(),
+            ProcessorName::AnsProcessor,
+            args.ledger_version,
+        )
+        .await?
+        {
+            let ledger_timestamp = client.get_ledger_timestamp(ledger_version).await?;
+            let mut conn = pool.get().await?;
+            let rows = ans_lookup::load_sample(
+                &mut conn,
+                ledger_version,
+                ledger_timestamp,
+                args.sample_size,
+            )
+            .await?;
+            reports.push(TableReport {
+                table: ans_lookup::TABLE_NAME,
+                ledger_version,
+                rows_checked: rows.len(),
+                divergences: ans_lookup::verify(
+                    &client,
+                    ans_v2_contract_address,
+                    &rows,
+                    ledger_version,
+                )
+                .await?,
+            });
+        }
+    }
+
+    for report in &reports {
+        println!(
+            "{} at version {}: {} rows checked, {} divergences",
+            report.table,
+            report.ledger_version,
+            report.rows_checked,
+            report.divergences.len()
+        );
+        for divergence in &report.divergences {
+            println!(
+                "  {} {}: indexed {}, fullnode {}",
+                divergence.key, divergence.field, divergence.indexed, divergence.fullnode
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The ledger version to compare the tables of `processor` at, or None if the processor hasn't
+/// processed anything yet.
+async fn resolve_ledger_version(
+    pool: ArcDbPool,
+    processor: ProcessorName,
+    ledger_version: Option<u64>,
+) -> Result<Option<u64>> {
+    if ledger_version.is_some() {
+        return Ok(ledger_version);
+    }
+    let processor_name: &'static str = processor.into();
+    let mut conn = pool.get().await?;
+    let status = ProcessorStatusQuery::get_by_processor(processor_name, &mut conn)
+        .await
+        .context("Failed to query processor status")?;
+    if status.is_none() {
+        println!("Skipping {}: no processor status", processor_name);
+    }
+    Ok(status.map(|status| status.last_success_version as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ans_lookup::IndexedAnsLookup;
+    use bigdecimal::BigDecimal;
+    use fungible_asset_balances::IndexedBalance;
+    use objects::IndexedObject;
+    use processor::utils::util::standardize_address;
+    use serde_json::Value;
+    use std::collections::HashMap;
+    use warp::{http::StatusCode, Filter};
+
+    const LEDGER_VERSION: u64 = 1000;
+
+    /// Serves the recorded fullnode responses in `test_responses`. Anything else is a 404, like an
+    /// account or resource that doesn't exist. The resources of 0x9a9e are served in two pages.
+    async fn start_stub_fullnode() -> FullnodeClient {
+        let paginated_resources =
+            format!("/v1/accounts/{}/resources", standardize_address("0x9a9e"));
+        let pages: HashMap<(String, Option<String>), (&'static str, Option<&'static str>)> =
+            HashMap::from([
+                (
+                    (paginated_resources.clone(), None),
+                    (
+                        r#"[{"type": "0x1::account::Account", "data": {}}]"#,
+                        Some("0x0201"),
+                    ),
+                ),
+                (
+                    (paginated_resources, Some("0x0201".to_string())),
+                    (r#"[{"type": "0x1::object::ObjectCore", "data": {}}]"#, None),
+                ),
+            ]);
+        let resources: HashMap<String, &'static str> = HashMap::from([
+            (
+                format!("/v1/blocks/by_version/{}", LEDGER_VERSION),
+                r#"{"block_height": "10", "block_timestamp": "1735689600000000"}"#,
+            ),
+            (
+                format!("/v1/accounts/{}/resources", standardize_address("0x5707e")),
+                include_str!("test_responses/fungible_store_resources.json"),
+            ),
+            (
+                format!(
+                    "/v1/accounts/{}/resource/0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+                    standardize_address("0xb0b")
+                ),
+                include_str!("test_responses/coin_store_resource.json"),
+            ),
+            (
+                format!(
+                    "/v1/accounts/{}/resource/0x1::object::ObjectCore",
+                    standardize_address("0x0b7ec7")
+                ),
+                include_str!("test_responses/object_core_resource.json"),
+            ),
+        ]);
+        let get = warp::get()
+            .and(warp::path::full())
+            .and(warp::query::<HashMap<String, String>>())
+            .map(
+                move |path: warp::path::FullPath, query: HashMap<String, String>| {
+                    let path = path.as_str().replace("%3C", "<").replace("%3E", ">");
+                    let page = pages.get(&(path.clone(), query.get("start").cloned()));
+                    let (status, body, cursor) = match (page, resources.get(&path)) {
+                        (Some((body, cursor)), _) => (StatusCode::OK, *body, *cursor),
+                        (None, Some(body)) => (StatusCode::OK, *body, None),
+                        (None, None) => (StatusCode::NOT_FOUND, "", None),
+                    };
+                    let mut response = warp::http::Response::builder().status(status);
+                    if let Some(cursor) = cursor {
+                        response = response.header("X-Aptos-Cursor", cursor);
+                    }
+                    response.body(body.to_string()).unwrap()
+                },
+            );
+        let view = warp::post()
+            .and(warp::path!("v1" / "view"))
+            .and(warp::body::json())
+            .map(|request: Value| {
+                if request["arguments"][0] == "alice" {
+                    include_str!("test_responses/ans_get_target_addr.json").to_string()
+                } else {
+                    r#"[{"vec": []}]"#.to_string()
+                }
+            });
+        let (addr, server) = warp::serve(view.or(get)).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        FullnodeClient::new(&format!("http://{}", addr))
+    }
+
+    #[tokio::test]
+    async fn test_verify_fungible_asset_balances() {
+        let client = start_stub_fullnode().await;
+        let store_balance = IndexedBalance {
+            storage_id: standardize_address("0x5707e"),
+            owner_address: standardize_address("0xb0b"),
+            asset_type_v1: None,
+            asset_type_v2: Some(standardize_address("0xa")),
+            is_frozen: false,
+            amount_v1: None,
+            amount_v2: Some(BigDecimal::from(100)),
+        };
+        let coin_balance = IndexedBalance {
+            storage_id: standardize_address("0xc012"),
+            owner_address: standardize_address("0xb0b"),
+            asset_type_v1: Some("0x1::aptos_coin::AptosCoin".to_string()),
+            asset_type_v2: None,
+            is_frozen: false,
+            amount_v1: Some(BigDecimal::from(50)),
+            amount_v2: None,
+        };
+        let divergences = fungible_asset_balances::verify(
+            &client,
+            &[store_balance.clone(), coin_balance.clone()],
+            LEDGER_VERSION,
+        )
+        .await
+        .unwrap();
+        assert!(divergences.is_empty(), "{:?}", divergences);
+
+        let stale_store_balance = IndexedBalance {
+            owner_address: standardize_address("0xc0c"),
+            amount_v2: Some(BigDecimal::from(90)),
+            ..store_balance
+        };
+        let stale_coin_balance = IndexedBalance {
+            amount_v1: Some(BigDecimal::from(40)),
+            ..coin_balance
+        };
+        let divergences = fungible_asset_balances::verify(
+            &client,
+            &[stale_store_balance, stale_coin_balance],
+            LEDGER_VERSION,
+        )
+        .await
+        .unwrap();
+        let fields: Vec<_> = divergences
+            .iter()
+            .map(|d| (d.field, d.fullnode.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("amount_v2", "100"),
+                ("owner_address", standardize_address("0xb0b").as_str()),
+                ("amount_v1", "50"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_account_resources_pages() {
+        let client = start_stub_fullnode().await;
+        let resources = client
+            .get_account_resources(&standardize_address("0x9a9e"), LEDGER_VERSION)
+            .await
+            .unwrap();
+        let types: Vec<_> = resources.iter().map(|r| r.type_str.as_str()).collect();
+        assert_eq!(
+            types,
+            vec!["0x1::account::Account", "0x1::object::ObjectCore"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_ledger_timestamp() {
+        let client = start_stub_fullnode().await;
+        let ledger_timestamp = client.get_ledger_timestamp(LEDGER_VERSION).await.unwrap();
+        assert_eq!(ledger_timestamp.to_string(), "2025-01-01 00:00:00");
+    }
+
+    #[tokio::test]
+    async fn test_verify_objects() {
+        let client = start_stub_fullnode().await;
+        let object = IndexedObject {
+            object_address: standardize_address("0x0b7ec7"),
+            owner_address: standardize_address("0xa11ce"),
+            allow_ungated_transfer: true,
+            last_guid_creation_num: BigDecimal::from(1125899906842626_i64),
+            is_deleted: false,
+        };
+        let deleted_object = IndexedObject {
+            object_address: standardize_address("0xde1e7ed"),
+            is_deleted: true,
+            ..object.clone()
+        };
+        let divergences = objects::verify(
+            &client,
+            &[object.clone(), deleted_object.clone()],
+            LEDGER_VERSION,
+        )
+        .await
+        .unwrap();
+        assert!(divergences.is_empty(), "{:?}", divergences);
+
+        let stale_object = IndexedObject {
+            owner_address: standardize_address("0xb0b"),
+            last_guid_creation_num: BigDecimal::from(1125899906842625_i64),
+            ..object
+        };
+        let undeleted_object = IndexedObject {
+            is_deleted: false,
+            ..deleted_object
+        };
+        let divergences =
+            objects::verify(&client, &[stale_object, undeleted_object], LEDGER_VERSION)
+                .await
+                .unwrap();
+        let fields: Vec<_> = divergences.iter().map(|d| d.field).collect();
+        assert_eq!(
+            fields,
+            vec!["owner_address", "last_guid_creation_num", "is_deleted"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_ans_lookup() {
+        let client = start_stub_fullnode().await;
+        let lookups = [
+            IndexedAnsLookup {
+                domain: "alice".to_string(),
+                subdomain: "".to_string(),
+                registered_address: Some("0xa11ce".to_string()),
+            },
+            IndexedAnsLookup {
+                domain: "bob".to_string(),
+                subdomain: "pay".to_string(),
+                registered_address: Some("0xb0b".to_string()),
+            },
+        ];
+        let divergences = ans_lookup::verify(&client, "0x867ed1", &lookups, LEDGER_VERSION)
+            .await
+            .unwrap();
+        assert_eq!(
+            divergences,
+            vec![Divergence {
+                table: ans_lookup::TABLE_NAME,
+                key: "pay.bob".to_string(),
+                field: "registered_address",
+                indexed: standardize_address("0xb0b"),
+                fullnode: "NULL".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{fullnode_client::FullnodeClient, Divergence};
+use crate::utils::database::DbPoolConnection;
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use diesel::{
+    sql_query,
+    sql_types::{BigInt, Bool, Numeric, Text},
+    QueryableByName,
+};
+use diesel_async::RunQueryDsl;
+use processor::db::common::models::object_models::v2_object_utils::ObjectCore;
+
+pub const TABLE_NAME: &str = "current_objects";
+const OBJECT_CORE_TYPE: &str = "0x1::object::ObjectCore";
+
+#[derive(Clone, Debug, QueryableByName)]
+pub struct IndexedObject {
+    #[diesel(sql_type = Text)]
+    pub object_address: String,
+    #[diesel(sql_type = Text)]
+    pub owner_address: String,
+    #[diesel(sql_type = Bool)]
+    pub allow_ungated_transfer: bool,
+    #[diesel(sql_type = Numeric)]
+    pub last_guid_creation_num: BigDecimal,
+    #[diesel(sql_type = Bool)]
+    pub is_deleted: bool,
+}
+
+/// Random objects whose latest change is at or before `ledger_version`.
+pub async fn load_sample(
+    conn: &mut DbPoolConnection<'_>,
+    ledger_version: u64,
+    sample_size: i64,
+) -> Result<Vec<IndexedObject>> {
+    Ok(sql_query(
+        "SELECT object_address, owner_address, allow_ungated_transfer, last_guid_creation_num, is_deleted
+        FROM current_objects
+        WHERE last_transaction_version <= $1
+        ORDER BY random()
+        LIMIT $2",
+    )
+    .bind::<BigInt, _>(ledger_version as i64)
+    .bind::<BigInt, _>(sample_size)
+    .load(conn)
+    .await?)
+}
+
+/// Compares indexed objects against the `ObjectCore` resource on the fullnode.
+pub async fn verify(
+    client: &FullnodeClient,
+    objects: &[IndexedObject],
+    ledger_version: u64,
+) -> Result<Vec<Divergence>> {
+    let mut divergences = vec![];
+    for object in objects {
+        let divergence = |field: &'static str, indexed: String, fullnode: String| Divergence {
+            table: TABLE_NAME,
+            key: object.object_address.clone(),
+            field,
+            indexed,
+            fullnode,
+        };
+        let resource = client
+            .get_account_resource(&object.object_address, OBJECT_CORE_TYPE, ledger_version)
+            .await?;
+        let object_core = match resource {
+            Some(resource) => {
+                ObjectCore::try_from(&resource.to_write_resource(&object.object_address))?
+            },
+            None => {
+                if !object.is_deleted {
+                    divergences.push(divergence(
+                        "is_deleted",
+                        "false".to_string(),
+                        "true".to_string(),
+                    ));
+                }
+                continue;
+            },
+        };
+        if object.is_deleted {
+            divergences.push(divergence(
+                "is_deleted",
+                "true".to_string(),
+                "false".to_string(),
+            ));
+            continue;
+        }
+        if object.owner_address != object_core.get_owner_address() {
+            divergences.push(divergence(
+                "owner_address",
+                object.owner_address.clone(),
+                object_core.get_owner_address(),
+            ));
+        }
+        if object.allow_ungated_transfer != object_core.allow_ungated_transfer {
+            divergences.push(divergence(
+                "allow_ungated_transfer",
+                object.allow_ungated_transfer.to_string(),
+                object_core.allow_ungated_transfer.to_string(),
+            ));
+        }
+        if object.last_guid_creation_num != object_core.guid_creation_num {
+            divergences.push(divergence(
+                "last_guid_creation_num",
+                object.last_guid_creation_num.to_string(),
+                object_core.guid_creation_num.to_string(),
+            ));
+        }
+    }
+    Ok(divergences)
+}
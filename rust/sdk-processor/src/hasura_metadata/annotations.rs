@@ -0,0 +1,112 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! What the Hasura metadata needs beyond the diesel schema: which tables are tracked, their
+//! permissions and relationships, and the source configuration. See the comment at the top of
+//! `hasura-api/metadata-json/annotations.yaml` for the fields.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+pub const PUBLIC_SCHEMA: &str = "public";
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Annotations {
+    pub resource_version: u64,
+    pub version: u64,
+    pub source: SourceAnnotation,
+    pub defaults: DefaultsAnnotation,
+    #[serde(default)]
+    pub untracked_tables: Vec<String>,
+    pub tables: Vec<TableAnnotation>,
+    /// Copied into the metadata after the sources, e.g. `query_collections` or `api_limits`.
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+}
+
+impl Annotations {
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read Hasura annotations {}", path))?;
+        serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse Hasura annotations {}", path))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceAnnotation {
+    pub name: String,
+    pub kind: String,
+    pub configuration: Value,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DefaultsAnnotation {
+    /// Role that is granted the select permissions.
+    pub role: String,
+    pub limit: u64,
+    /// Columns of schema.rs tables that are never selectable, e.g. `inserted_at`.
+    #[serde(default)]
+    pub excluded_columns: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TableAnnotation {
+    pub name: String,
+    #[serde(default = "TableAnnotation::default_schema")]
+    pub schema: String,
+    #[serde(default)]
+    pub custom_name: Option<String>,
+    #[serde(default)]
+    pub custom_root_fields: Map<String, Value>,
+    /// Required for views and other tables that aren't in schema.rs.
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+    #[serde(default)]
+    pub exclude_columns: Vec<String>,
+    #[serde(default = "TableAnnotation::default_selectable")]
+    pub selectable: bool,
+    #[serde(default)]
+    pub allow_aggregations: bool,
+    #[serde(default)]
+    pub no_limit: bool,
+    #[serde(default)]
+    pub object_relationships: Vec<RelationshipAnnotation>,
+    #[serde(default)]
+    pub array_relationships: Vec<RelationshipAnnotation>,
+}
+
+impl TableAnnotation {
+    pub fn default_schema() -> String {
+        PUBLIC_SCHEMA.to_string()
+    }
+
+    pub const fn default_selectable() -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RelationshipAnnotation {
+    pub name: String,
+    /// `schema.table`, or `table` for a table of the public schema.
+    pub remote_table: String,
+    /// Local column to remote column.
+    pub column_mapping: BTreeMap<String, String>,
+}
+
+impl RelationshipAnnotation {
+    /// `(schema, table)` of the remote table.
+    pub fn remote_schema_and_table(&self) -> (&str, &str) {
+        self.remote_table
+            .split_once('.')
+            .unwrap_or((PUBLIC_SCHEMA, self.remote_table.as_str()))
+    }
+}
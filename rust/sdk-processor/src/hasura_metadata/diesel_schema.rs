@@ -0,0 +1,201 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reads the tables and `joinable!` entries of a `schema.rs` generated by diesel CLI. Only the
+//! layout diesel CLI prints is supported, not arbitrary `table!` macros.
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Column {
+    pub name: String,
+    /// Differs from `name` when the column is renamed with `#[sql_name]`, e.g. `type_`.
+    pub sql_name: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Table {
+    pub name: String,
+    /// Rust names of the primary key columns.
+    pub primary_key: Vec<String>,
+    pub columns: Vec<Column>,
+}
+
+impl Table {
+    pub fn sql_column_names(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|column| column.sql_name.as_str())
+    }
+
+    pub fn has_sql_column(&self, sql_name: &str) -> bool {
+        self.sql_column_names().any(|column| column == sql_name)
+    }
+
+    pub fn sql_name_of(&self, name: &str) -> Result<&str> {
+        self.columns
+            .iter()
+            .find(|column| column.name == name)
+            .map(|column| column.sql_name.as_str())
+            .with_context(|| format!("Table {} has no column {}", self.name, name))
+    }
+}
+
+/// `joinable!(child -> parent (foreign_key))`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Joinable {
+    pub child: String,
+    pub parent: String,
+    pub foreign_key: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DieselSchema {
+    pub tables: BTreeMap<String, Table>,
+    pub joinables: Vec<Joinable>,
+}
+
+impl DieselSchema {
+    pub fn from_file(path: &str) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read diesel schema {}", path))?;
+        Self::parse(&source).with_context(|| format!("Failed to parse diesel schema {}", path))
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut schema = Self::default();
+        let mut lines = source.lines().map(str::trim).enumerate();
+        while let Some((line_index, line)) = lines.next() {
+            if let Some(joinable) = line.strip_prefix("diesel::joinable!(") {
+                schema
+                    .joinables
+                    .push(parse_joinable(joinable).with_context(|| {
+                        format!("Invalid joinable! on line {}", line_index + 1)
+                    })?);
+            } else if line == "diesel::table! {" {
+                let table = parse_table(&mut lines)?;
+                if schema.tables.contains_key(&table.name) {
+                    bail!("Table {} is defined twice", table.name);
+                }
+                schema.tables.insert(table.name.clone(), table);
+            }
+        }
+        Ok(schema)
+    }
+}
+
+fn parse_joinable(joinable: &str) -> Result<Joinable> {
+    let joinable = joinable
+        .strip_suffix(");")
+        .context("Missing closing parenthesis")?;
+    let (child, rest) = joinable.split_once("->").context("Missing ->")?;
+    let (parent, foreign_key) = rest.split_once('(').context("Missing foreign key")?;
+    Ok(Joinable {
+        child: child.trim().to_string(),
+        parent: parent.trim().to_string(),
+        foreign_key: foreign_key
+            .strip_suffix(')')
+            .context("Missing closing parenthesis")?
+            .trim()
+            .to_string(),
+    })
+}
+
+/// Parses the lines after `diesel::table! {` up to the closing brace of the columns.
+fn parse_table<'a>(lines: &mut impl Iterator<Item = (usize, &'a str)>) -> Result<Table> {
+    let (line_index, header) = lines.next().context("Missing table header")?;
+    let (name, primary_key) = header
+        .strip_suffix(") {")
+        .and_then(|header| header.split_once(" ("))
+        .with_context(|| format!("Invalid table header on line {}", line_index + 1))?;
+    let mut table = Table {
+        name: name.trim().to_string(),
+        primary_key: primary_key
+            .split(',')
+            .map(|column| column.trim().to_string())
+            .collect(),
+        columns: vec![],
+    };
+
+    let mut sql_name = None;
+    for (line_index, line) in lines.by_ref() {
+        if line == "}" {
+            return Ok(table);
+        }
+        if let Some(attribute) = line.strip_prefix("#[sql_name = \"") {
+            sql_name = attribute.strip_suffix("\"]").map(str::to_string);
+        } else if line.starts_with("#[") {
+            continue;
+        } else if let Some((column, _)) = line.split_once("->") {
+            let name = column.trim().to_string();
+            table.columns.push(Column {
+                sql_name: sql_name.take().unwrap_or_else(|| name.clone()),
+                name,
+            });
+        } else {
+            bail!(
+                "Unexpected line {} in table {}: {}",
+                line_index + 1,
+                table.name,
+                line
+            );
+        }
+    }
+    bail!("Table {} is not closed", table.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let schema = DieselSchema::parse(
+            "// @generated automatically by Diesel CLI.
+
+diesel::table! {
+    events (transaction_version, event_index) {
+        transaction_version -> Int8,
+        event_index -> Int8,
+        #[max_length = 66]
+        account_address -> Varchar,
+        #[sql_name = \"type\"]
+        type_ -> Text,
+    }
+}
+
+diesel::table! {
+    transactions (version) {
+        version -> Int8,
+    }
+}
+
+diesel::joinable!(events -> transactions (transaction_version));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    events,
+    transactions,
+);
+",
+        )
+        .unwrap();
+
+        let events = &schema.tables["events"];
+        assert_eq!(events.primary_key, vec![
+            "transaction_version".to_string(),
+            "event_index".to_string(),
+        ]);
+        assert_eq!(events.sql_column_names().collect::<Vec<_>>(), vec![
+            "transaction_version",
+            "event_index",
+            "account_address",
+            "type",
+        ]);
+        assert_eq!(events.sql_name_of("type_").unwrap(), "type");
+        assert_eq!(schema.tables["transactions"].columns.len(), 1);
+        assert_eq!(schema.joinables, vec![Joinable {
+            child: "events".to_string(),
+            parent: "transactions".to_string(),
+            foreign_key: "transaction_version".to_string(),
+        }]);
+    }
+}
@@ -0,0 +1,425 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates the Hasura metadata (`hasura-api/metadata-json/unified.json`) from the diesel schema
+//! and an annotations file holding what the schema can't tell, like relationships and permissions.
+//!
+//! The selectable columns of the tables of schema.rs are derived from the schema, so a migration
+//! that adds a column changes the generated metadata. The test below fails until the metadata is
+//! regenerated, and generation fails when a table of schema.rs isn't annotated or an annotation
+//! refers to a column that was dropped.
+
+pub mod annotations;
+pub mod diesel_schema;
+
+use annotations::{Annotations, RelationshipAnnotation, TableAnnotation, PUBLIC_SCHEMA};
+use anyhow::{bail, ensure, Context, Result};
+use clap::Parser;
+use diesel_schema::{DieselSchema, Table};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub const DEFAULT_SCHEMA_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../processor/src/db/postgres/schema.rs"
+);
+pub const DEFAULT_ANNOTATIONS_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../hasura-api/metadata-json/annotations.yaml"
+);
+pub const DEFAULT_OUTPUT_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../hasura-api/metadata-json/unified.json"
+);
+
+#[derive(Clone, Debug, Parser)]
+#[clap(author, version, about, long_about = None)]
+pub struct HasuraMetadataArgs {
+    /// Diesel schema to read the tables from
+    #[clap(long, default_value = DEFAULT_SCHEMA_PATH)]
+    pub schema_path: String,
+    /// Tracked tables, relationships and permissions
+    #[clap(long, default_value = DEFAULT_ANNOTATIONS_PATH)]
+    pub annotations_path: String,
+    /// Hasura metadata file to write
+    #[clap(long, default_value = DEFAULT_OUTPUT_PATH)]
+    pub output_path: String,
+    /// Fail if the metadata file is out of date instead of writing it
+    #[clap(long)]
+    pub check: bool,
+}
+
+pub fn run(args: HasuraMetadataArgs) -> Result<()> {
+    let metadata = generate_from_files(&args.schema_path, &args.annotations_path)?;
+    if args.check {
+        let existing = std::fs::read_to_string(&args.output_path)
+            .with_context(|| format!("Failed to read {}", args.output_path))?;
+        ensure!(
+            existing == metadata,
+            "{} is out of date, regenerate it with `cargo run --bin hasura_metadata`",
+            args.output_path
+        );
+    } else {
+        std::fs::write(&args.output_path, metadata)
+            .with_context(|| format!("Failed to write {}", args.output_path))?;
+    }
+    Ok(())
+}
+
+/// The metadata file contents, pretty printed.
+pub fn generate_from_files(schema_path: &str, annotations_path: &str) -> Result<String> {
+    let schema = DieselSchema::from_file(schema_path)?;
+    let annotations = Annotations::from_file(annotations_path)?;
+    let metadata = MetadataGenerator::new(&schema, &annotations)?.generate()?;
+    Ok(format!("{}\n", serde_json::to_string_pretty(&metadata)?))
+}
+
+pub struct MetadataGenerator<'a> {
+    schema: &'a DieselSchema,
+    annotations: &'a Annotations,
+    /// Keyed by `(schema, table)`, which is also the order of the tables in the metadata.
+    tracked_tables: BTreeMap<(&'a str, &'a str), &'a TableAnnotation>,
+}
+
+impl<'a> MetadataGenerator<'a> {
+    /// Checks that every table of the diesel schema is either tracked or explicitly untracked, so
+    /// new tables can't be forgotten.
+    pub fn new(schema: &'a DieselSchema, annotations: &'a Annotations) -> Result<Self> {
+        let mut tracked_tables = BTreeMap::new();
+        for table in &annotations.tables {
+            let key = (table.schema.as_str(), table.name.as_str());
+            ensure!(
+                tracked_tables.insert(key, table).is_none(),
+                "Table {}.{} is annotated twice",
+                table.schema,
+                table.name
+            );
+        }
+        let untracked_tables: BTreeSet<&str> = annotations
+            .untracked_tables
+            .iter()
+            .map(String::as_str)
+            .collect();
+        for table in &untracked_tables {
+            ensure!(
+                schema.tables.contains_key(*table),
+                "Untracked table {} is not in schema.rs",
+                table
+            );
+            ensure!(
+                !tracked_tables.contains_key(&(PUBLIC_SCHEMA, *table)),
+                "Table {} is both tracked and untracked",
+                table
+            );
+        }
+        for table in schema.tables.keys() {
+            ensure!(
+                tracked_tables.contains_key(&(PUBLIC_SCHEMA, table.as_str()))
+                    || untracked_tables.contains(table.as_str()),
+                "Table {} of schema.rs is missing from the Hasura annotations, add it to `tables` or \
+                 `untracked_tables`",
+                table
+            );
+        }
+        Ok(Self {
+            schema,
+            annotations,
+            tracked_tables,
+        })
+    }
+
+    pub fn generate(&self) -> Result<Value> {
+        let tables = self
+            .tracked_tables
+            .values()
+            .map(|table| {
+                self.generate_table(table)
+                    .with_context(|| format!("Invalid annotations of table {}", table.name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let source = &self.annotations.source;
+
+        let mut metadata = Map::new();
+        metadata.insert("version".to_string(), json!(self.annotations.version));
+        metadata.insert(
+            "sources".to_string(),
+            json!([{
+                "name": source.name,
+                "kind": source.kind,
+                "tables": tables,
+                "configuration": source.configuration,
+            }]),
+        );
+        for (key, value) in &self.annotations.metadata {
+            ensure!(
+                !metadata.contains_key(key),
+                "Metadata key {} is generated",
+                key
+            );
+            metadata.insert(key.clone(), value.clone());
+        }
+        Ok(json!({
+            "resource_version": self.annotations.resource_version,
+            "metadata": metadata,
+        }))
+    }
+
+    /// Tables of the public schema are looked up in schema.rs, other schemas only hold views.
+    fn schema_table(&self, schema: &str, name: &str) -> Option<&'a Table> {
+        if schema == PUBLIC_SCHEMA {
+            self.schema.tables.get(name)
+        } else {
+            None
+        }
+    }
+
+    /// All columns of tables of schema.rs, or the selectable columns of views. Hidden columns of
+    /// views aren't known, so relationships of views can only use selectable columns.
+    fn known_columns(&self, table: &TableAnnotation) -> Vec<&'a str> {
+        match self.schema_table(&table.schema, &table.name) {
+            Some(schema_table) => schema_table.sql_column_names().collect(),
+            None => self.tracked_tables[&(table.schema.as_str(), table.name.as_str())]
+                .columns
+                .iter()
+                .flatten()
+                .map(String::as_str)
+                .collect(),
+        }
+    }
+
+    fn generate_table(&self, table: &TableAnnotation) -> Result<Value> {
+        let schema_table = self.schema_table(&table.schema, &table.name);
+        let mut columns: Vec<&str> = match (&table.columns, schema_table) {
+            (Some(columns), _) => {
+                ensure!(
+                    table.exclude_columns.is_empty(),
+                    "Only one of columns and exclude_columns can be set"
+                );
+                if let Some(schema_table) = schema_table {
+                    for column in columns {
+                        ensure!(
+                            schema_table.has_sql_column(column),
+                            "Column {} is not in schema.rs",
+                            column
+                        );
+                    }
+                }
+                columns.iter().map(String::as_str).collect()
+            },
+            (None, Some(schema_table)) => {
+                for column in &table.exclude_columns {
+                    ensure!(
+                        schema_table.has_sql_column(column),
+                        "Excluded column {} is not in schema.rs",
+                        column
+                    );
+                }
+                let excluded_columns = &self.annotations.defaults.excluded_columns;
+                schema_table
+                    .sql_column_names()
+                    .filter(|column| {
+                        !excluded_columns.iter().any(|excluded| excluded == column)
+                            && !table.exclude_columns.iter().any(|excluded| excluded == column)
+                    })
+                    .collect()
+            },
+            (None, None) => bail!(
+                "Table {}.{} is not in schema.rs, list its columns",
+                table.schema,
+                table.name
+            ),
+        };
+        columns.sort_unstable();
+
+        let mut value = Map::new();
+        value.insert(
+            "table".to_string(),
+            json!({ "name": table.name, "schema": table.schema }),
+        );
+        if table.custom_name.is_some() || !table.custom_root_fields.is_empty() {
+            value.insert(
+                "configuration".to_string(),
+                json!({
+                    "column_config": {},
+                    "custom_column_names": {},
+                    "custom_name": table.custom_name,
+                    "custom_root_fields": table.custom_root_fields,
+                }),
+            );
+        }
+
+        let mut object_relationships = table.object_relationships.clone();
+        if let Some(schema_table) = schema_table {
+            object_relationships.extend(self.joinable_relationships(schema_table, table)?);
+        }
+        for (kind, relationships) in [
+            ("object_relationships", object_relationships),
+            ("array_relationships", table.array_relationships.clone()),
+        ] {
+            if !relationships.is_empty() {
+                value.insert(
+                    kind.to_string(),
+                    self.generate_relationships(table, relationships)?,
+                );
+            }
+        }
+
+        if table.selectable {
+            let mut permission = Map::new();
+            permission.insert("columns".to_string(), json!(columns));
+            permission.insert("filter".to_string(), json!({}));
+            if !table.no_limit {
+                permission.insert(
+                    "limit".to_string(),
+                    json!(self.annotations.defaults.limit),
+                );
+            }
+            if table.allow_aggregations {
+                permission.insert("allow_aggregations".to_string(), json!(true));
+            }
+            value.insert(
+                "select_permissions".to_string(),
+                json!([{ "role": self.annotations.defaults.role, "permission": permission }]),
+            );
+        }
+        Ok(Value::Object(value))
+    }
+
+    /// Object relationships named after the parent table for the `joinable!` entries of a table,
+    /// unless the annotations already have a relationship with that name.
+    fn joinable_relationships(
+        &self,
+        schema_table: &Table,
+        table: &TableAnnotation,
+    ) -> Result<Vec<RelationshipAnnotation>> {
+        let mut relationships = vec![];
+        for joinable in &self.schema.joinables {
+            if joinable.child != schema_table.name
+                || !self
+                    .tracked_tables
+                    .contains_key(&(PUBLIC_SCHEMA, joinable.parent.as_str()))
+                || table
+                    .object_relationships
+                    .iter()
+                    .any(|relationship| relationship.name == joinable.parent)
+            {
+                continue;
+            }
+            let parent = self
+                .schema
+                .tables
+                .get(&joinable.parent)
+                .with_context(|| format!("joinable! parent {} is not a table", joinable.parent))?;
+            let [parent_key] = parent.primary_key.as_slice() else {
+                bail!(
+                    "joinable! parent {} must have a single column primary key",
+                    parent.name
+                );
+            };
+            relationships.push(RelationshipAnnotation {
+                name: joinable.parent.clone(),
+                remote_table: joinable.parent.clone(),
+                column_mapping: BTreeMap::from([(
+                    schema_table.sql_name_of(&joinable.foreign_key)?.to_string(),
+                    parent.sql_name_of(parent_key)?.to_string(),
+                )]),
+            });
+        }
+        Ok(relationships)
+    }
+
+    fn generate_relationships(
+        &self,
+        table: &TableAnnotation,
+        mut relationships: Vec<RelationshipAnnotation>,
+    ) -> Result<Value> {
+        relationships.sort_by(|a, b| a.name.cmp(&b.name));
+        let local_columns = self.known_columns(table);
+        let mut values = vec![];
+        for relationship in relationships {
+            let (remote_schema, remote_name) = relationship.remote_schema_and_table();
+            let remote_table = self
+                .tracked_tables
+                .get(&(remote_schema, remote_name))
+                .with_context(|| {
+                    format!(
+                        "Relationship {} points to untracked table {}",
+                        relationship.name, relationship.remote_table
+                    )
+                })?;
+            let remote_columns = self.known_columns(remote_table);
+            for (local_column, remote_column) in &relationship.column_mapping {
+                ensure!(
+                    local_columns.contains(&local_column.as_str()),
+                    "Relationship {} maps unknown column {}",
+                    relationship.name,
+                    local_column
+                );
+                ensure!(
+                    remote_columns.contains(&remote_column.as_str()),
+                    "Relationship {} maps to unknown column {} of {}",
+                    relationship.name,
+                    remote_column,
+                    relationship.remote_table
+                );
+            }
+            values.push(json!({
+                "name": relationship.name,
+                "using": {
+                    "manual_configuration": {
+                        "column_mapping": relationship.column_mapping,
+                        "insertion_order": null,
+                        "remote_table": { "name": remote_name, "schema": remote_schema },
+                    },
+                },
+            }));
+        }
+        Ok(Value::Array(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_metadata_is_up_to_date() {
+        let generated = generate_from_files(DEFAULT_SCHEMA_PATH, DEFAULT_ANNOTATIONS_PATH).unwrap();
+        let checked_in = std::fs::read_to_string(DEFAULT_OUTPUT_PATH).unwrap();
+        assert!(
+            generated == checked_in,
+            "unified.json is out of date, regenerate it with `cargo run --bin hasura_metadata`"
+        );
+    }
+
+    #[test]
+    fn test_unannotated_table() {
+        let schema = DieselSchema::parse(
+            "diesel::table! {
+    events (transaction_version, event_index) {
+        transaction_version -> Int8,
+        event_index -> Int8,
+    }
+}
+",
+        )
+        .unwrap();
+        let annotations: Annotations = serde_yaml::from_str(
+            "
+resource_version: 1
+version: 3
+source:
+  name: indexer-v2
+  kind: postgres
+  configuration: {}
+defaults:
+  role: anonymous
+  limit: 100
+tables: []
+",
+        )
+        .unwrap();
+
+        assert!(MetadataGenerator::new(&schema, &annotations).is_err());
+    }
+}
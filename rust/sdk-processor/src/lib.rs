@@ -1,6 +1,10 @@
 pub mod config;
-mod db;
+pub mod consistency_checker;
+pub mod db;
+pub mod fullnode_verifier;
+pub mod hasura_metadata;
 pub mod parquet_processors;
 pub mod processors;
+pub mod sinks;
 pub mod steps;
 pub mod utils;
@@ -13,7 +13,10 @@ use google_cloud_storage::client::{Client as GCSClient, ClientConfig as GcsClien
 use parquet::schema::types::Type;
 use processor::{
     db::parquet::models::{
-        account_transaction_models::parquet_account_transactions::AccountTransaction,
+        account_transaction_models::{
+            parquet_account_summaries::AccountSummary,
+            parquet_account_transactions::AccountTransaction,
+        },
         ans_models::{
             ans_lookup_v2::{AnsLookupV2, CurrentAnsLookupV2},
             ans_primary_name_v2::{AnsPrimaryNameV2, CurrentAnsPrimaryNameV2},
@@ -45,12 +48,17 @@ use processor::{
         token_v2_models::{
             token_claims::CurrentTokenPendingClaim,
             v1_token_royalty::CurrentTokenRoyaltyV1,
+            v2_collection_activity_hourly::CollectionActivityHourly,
             v2_token_activities::TokenActivityV2,
             v2_token_datas::{CurrentTokenDataV2, TokenDataV2},
             v2_token_metadata::CurrentTokenV2Metadata,
             v2_token_ownerships::{CurrentTokenOwnershipV2, TokenOwnershipV2},
         },
-        transaction_metadata_model::parquet_write_set_size_info::WriteSetSize,
+        transaction_metadata_model::{
+            parquet_entry_function_gas_hourly::EntryFunctionGasHourly,
+            parquet_transaction_gas_breakdown::TransactionGasBreakdown,
+            parquet_write_set_size_info::WriteSetSize,
+        },
         user_transaction_models::parquet_user_transactions::UserTransaction,
     },
     utils::table_flags::TableFlags,
@@ -125,8 +133,11 @@ pub enum ParquetTypeEnum {
     CurrentFungibleAssetBalancesLegacy,
     // txn metadata,
     WriteSetSize,
+    TransactionGasBreakdown,
+    EntryFunctionGasHourly,
     // account transactions
     AccountTransactions,
+    AccountSummaries,
     // token v2
     CurrentTokenPendingClaims,
     CurrentTokenRoyaltiesV1,
@@ -136,6 +147,7 @@ pub enum ParquetTypeEnum {
     CurrentTokenDatasV2,
     TokenOwnershipsV2,
     CurrentTokenOwnershipsV2,
+    CollectionActivityHourly,
     // stake
     DelegatedStakingActivities,
     CurrentDelegatorBalances,
@@ -221,7 +233,16 @@ impl_parquet_trait!(
     ParquetTypeEnum::CurrentFungibleAssetBalancesLegacy
 );
 impl_parquet_trait!(WriteSetSize, ParquetTypeEnum::WriteSetSize);
+impl_parquet_trait!(
+    TransactionGasBreakdown,
+    ParquetTypeEnum::TransactionGasBreakdown
+);
+impl_parquet_trait!(
+    EntryFunctionGasHourly,
+    ParquetTypeEnum::EntryFunctionGasHourly
+);
 impl_parquet_trait!(AccountTransaction, ParquetTypeEnum::AccountTransactions);
+impl_parquet_trait!(AccountSummary, ParquetTypeEnum::AccountSummaries);
 impl_parquet_trait!(
     CurrentTokenPendingClaim,
     ParquetTypeEnum::CurrentTokenPendingClaims
@@ -248,6 +269,10 @@ impl_parquet_trait!(
     CurrentTokenOwnershipV2,
     ParquetTypeEnum::CurrentTokenOwnershipsV2
 );
+impl_parquet_trait!(
+    CollectionActivityHourly,
+    ParquetTypeEnum::CollectionActivityHourly
+);
 impl_parquet_trait!(
     DelegatedStakingActivity,
     ParquetTypeEnum::DelegatedStakingActivities
@@ -289,8 +314,11 @@ pub enum ParquetTypeStructs {
     CurrentUnifiedFungibleAssetBalance(Vec<CurrentUnifiedFungibleAssetBalance>),
     // Txn metadata
     WriteSetSize(Vec<WriteSetSize>),
+    TransactionGasBreakdown(Vec<TransactionGasBreakdown>),
+    EntryFunctionGasHourly(Vec<EntryFunctionGasHourly>),
     // account txn
     AccountTransaction(Vec<AccountTransaction>),
+    AccountSummary(Vec<AccountSummary>),
     // Token V2
     CurrentTokenPendingClaim(Vec<CurrentTokenPendingClaim>),
     CurrentTokenRoyaltyV1(Vec<CurrentTokenRoyaltyV1>),
@@ -300,6 +328,7 @@ pub enum ParquetTypeStructs {
     CurrentTokenDataV2(Vec<CurrentTokenDataV2>),
     TokenOwnershipV2(Vec<TokenOwnershipV2>),
     CurrentTokenOwnershipV2(Vec<CurrentTokenOwnershipV2>),
+    CollectionActivityHourly(Vec<CollectionActivityHourly>),
     // Stake
     DelegatedStakingActivity(Vec<DelegatedStakingActivity>),
     CurrentDelegatorBalance(Vec<CurrentDelegatorBalance>),
@@ -342,9 +371,16 @@ impl ParquetTypeStructs {
                 ParquetTypeStructs::CurrentUnifiedFungibleAssetBalance(Vec::new())
             },
             ParquetTypeEnum::WriteSetSize => ParquetTypeStructs::WriteSetSize(Vec::new()),
+            ParquetTypeEnum::TransactionGasBreakdown => {
+                ParquetTypeStructs::TransactionGasBreakdown(Vec::new())
+            },
+            ParquetTypeEnum::EntryFunctionGasHourly => {
+                ParquetTypeStructs::EntryFunctionGasHourly(Vec::new())
+            },
             ParquetTypeEnum::AccountTransactions => {
                 ParquetTypeStructs::AccountTransaction(Vec::new())
             },
+            ParquetTypeEnum::AccountSummaries => ParquetTypeStructs::AccountSummary(Vec::new()),
             ParquetTypeEnum::CurrentTokenPendingClaims => {
                 ParquetTypeStructs::CurrentTokenPendingClaim(Vec::new())
             },
@@ -370,6 +406,9 @@ impl ParquetTypeStructs {
             ParquetTypeEnum::CurrentTokenOwnershipsV2 => {
                 ParquetTypeStructs::CurrentTokenOwnershipV2(Vec::new())
             },
+            ParquetTypeEnum::CollectionActivityHourly => {
+                ParquetTypeStructs::CollectionActivityHourly(Vec::new())
+            },
             ParquetTypeEnum::DelegatedStakingActivities => {
                 ParquetTypeStructs::DelegatedStakingActivity(Vec::new())
             },
@@ -486,12 +525,30 @@ impl ParquetTypeStructs {
             ) => {
                 handle_append!(self_data, other_data)
             },
+            (
+                ParquetTypeStructs::TransactionGasBreakdown(self_data),
+                ParquetTypeStructs::TransactionGasBreakdown(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
+            (
+                ParquetTypeStructs::EntryFunctionGasHourly(self_data),
+                ParquetTypeStructs::EntryFunctionGasHourly(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
             (
                 ParquetTypeStructs::AccountTransaction(self_data),
                 ParquetTypeStructs::AccountTransaction(other_data),
             ) => {
                 handle_append!(self_data, other_data)
             },
+            (
+                ParquetTypeStructs::AccountSummary(self_data),
+                ParquetTypeStructs::AccountSummary(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
             (
                 ParquetTypeStructs::CurrentTokenPendingClaim(self_data),
                 ParquetTypeStructs::CurrentTokenPendingClaim(other_data),
@@ -565,6 +622,12 @@ impl ParquetTypeStructs {
             ) => {
                 handle_append!(self_data, other_data)
             },
+            (
+                ParquetTypeStructs::CollectionActivityHourly(self_data),
+                ParquetTypeStructs::CollectionActivityHourly(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
             (
                 ParquetTypeStructs::DelegatedStakingActivity(self_data),
                 ParquetTypeStructs::DelegatedStakingActivity(other_data),
@@ -649,6 +712,8 @@ async fn initialize_parquet_buffer_step(
     bucket_name: String,
     bucket_root: String,
     processor_name: String,
+    pipeline_name: String,
+    gap_repair_pool: Option<ArcDbPool>,
 ) -> anyhow::Result<ParquetBufferStep> {
     let parquet_type_to_writer = parquet_type_to_schemas
         .iter()
@@ -667,11 +732,15 @@ async fn initialize_parquet_buffer_step(
         processor_name,
     )?;
 
-    let default_size_buffer_step = ParquetBufferStep::new(
+    let mut default_size_buffer_step = ParquetBufferStep::new(
         Duration::from_secs(upload_interval),
         buffer_uploader,
         max_buffer_size,
-    );
+    )
+    .with_pipeline_name(pipeline_name);
+    if let Some(db_pool) = gap_repair_pool {
+        default_size_buffer_step = default_size_buffer_step.with_gap_repair(db_pool);
+    }
 
     Ok(default_size_buffer_step)
 }
@@ -10,6 +10,7 @@ use crate::{
     },
     steps::{
         common::{
+            admin_control_step::AdminControlStep,
             parquet_version_tracker_step::ParquetVersionTrackerStep,
             processor_status_saver::get_processor_status_saver,
         },
@@ -24,14 +25,19 @@ use crate::{
 use anyhow::Context;
 use aptos_indexer_processor_sdk::{
     aptos_indexer_transaction_stream::{TransactionStream, TransactionStreamConfig},
+    aptos_protos::transaction::v1::Transaction,
     builder::ProcessorBuilder,
     common_steps::{TransactionStreamStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS},
+    instrumented_channel::InstrumentedAsyncReceiver,
     traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    types::transaction_context::TransactionContext,
 };
 use parquet::schema::types::Type;
 use processor::{
     bq_analytics::generic_parquet_processor::HasParquetSchema,
-    db::parquet::models::account_transaction_models::parquet_account_transactions::AccountTransaction,
+    db::parquet::models::account_transaction_models::{
+        parquet_account_summaries::AccountSummary, parquet_account_transactions::AccountTransaction,
+    },
 };
 use std::{collections::HashMap, sync::Arc};
 use tracing::{debug, info};
@@ -46,6 +52,92 @@ impl ParquetAccountTransactionsProcessor {
         let db_pool = initialize_database_pool(&config.db_config).await?;
         Ok(Self { config, db_pool })
     }
+
+    /// Connects the steps of the processor to a stream of transactions. This is how the processor
+    /// runs off its own transaction stream, and off the stream of a gap repair.
+    pub async fn build_pipeline(
+        &self,
+        transaction_receiver: InstrumentedAsyncReceiver<TransactionContext<Vec<Transaction>>>,
+    ) -> anyhow::Result<InstrumentedAsyncReceiver<TransactionContext<()>>> {
+        let parquet_db_config = match self.config.db_config {
+            DbConfig::ParquetConfig(ref parquet_config) => parquet_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid db config for ParquetAccountTransactionsProcessor {:?}",
+                    self.config.db_config
+                ));
+            },
+        };
+        let parquet_processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::ParquetAccountTransactionsProcessor(parquet_processor_config) => {
+                parquet_processor_config
+            },
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor configuration for ParquetAccountTransactionsProcessor {:?}",
+                    self.config.processor_config
+                ));
+            },
+        };
+
+        let backfill_table = set_backfill_table_flag(parquet_processor_config.backfill_table);
+        let parquet_account_transactions_extractor = ParquetAccountTransactionsExtractor {
+            opt_in_tables: backfill_table,
+            address_labels: self.config.address_label_registry()?,
+        };
+
+        let gcs_client =
+            initialize_gcs_client(parquet_db_config.google_application_credentials.clone()).await;
+
+        let parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>> = [
+            (
+                ParquetTypeEnum::AccountTransactions,
+                AccountTransaction::schema(),
+            ),
+            (ParquetTypeEnum::AccountSummaries, AccountSummary::schema()),
+        ]
+        .into_iter()
+        .collect();
+
+        let default_size_buffer_step = initialize_parquet_buffer_step(
+            gcs_client.clone(),
+            parquet_type_to_schemas,
+            parquet_processor_config.upload_interval,
+            parquet_processor_config.max_buffer_size,
+            parquet_db_config.bucket_name.clone(),
+            parquet_db_config.bucket_root.clone(),
+            self.name().to_string(),
+            self.config.pipeline_name(),
+            self.config
+                .gap_repair_config
+                .as_ref()
+                .map(|_| self.db_pool.clone()),
+        )
+        .await
+        .unwrap_or_else(|e| {
+            panic!("Failed to initialize parquet buffer step: {:?}", e);
+        });
+
+        let parquet_version_tracker_step = ParquetVersionTrackerStep::new(
+            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        let channel_size = parquet_processor_config.channel_size;
+
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_runnable_input_receiver_first_step(
+            transaction_receiver,
+            parquet_account_transactions_extractor.into_runnable_step(),
+            channel_size,
+        )
+        .connect_to(default_size_buffer_step.into_runnable_step(), channel_size)
+        .connect_to(
+            parquet_version_tracker_step.into_runnable_step(),
+            channel_size,
+        )
+        .end_and_return_output_receiver(channel_size);
+        Ok(buffer_receiver)
+    }
 }
 
 #[async_trait::async_trait]
@@ -56,14 +148,13 @@ impl ProcessorTrait for ParquetAccountTransactionsProcessor {
 
     async fn run_processor(&self) -> anyhow::Result<()> {
         // Run Migrations
-        let parquet_db_config = match self.config.db_config {
+        match self.config.db_config {
             DbConfig::ParquetConfig(ref parquet_config) => {
                 run_migrations(
                     parquet_config.connection_string.clone(),
                     self.db_pool.clone(),
                 )
                 .await;
-                parquet_config
             },
             _ => {
                 return Err(anyhow::anyhow!(
@@ -126,56 +217,18 @@ impl ProcessorTrait for ParquetAccountTransactionsProcessor {
         })
         .await?;
 
-        let backfill_table = set_backfill_table_flag(parquet_processor_config.backfill_table);
-        let parquet_account_transactions_extractor = ParquetAccountTransactionsExtractor {
-            opt_in_tables: backfill_table,
-        };
-
-        let gcs_client =
-            initialize_gcs_client(parquet_db_config.google_application_credentials.clone()).await;
-
-        let parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>> = [(
-            ParquetTypeEnum::AccountTransactions,
-            AccountTransaction::schema(),
-        )]
-        .into_iter()
-        .collect();
-
-        let default_size_buffer_step = initialize_parquet_buffer_step(
-            gcs_client.clone(),
-            parquet_type_to_schemas,
-            parquet_processor_config.upload_interval,
-            parquet_processor_config.max_buffer_size,
-            parquet_db_config.bucket_name.clone(),
-            parquet_db_config.bucket_root.clone(),
-            self.name().to_string(),
-        )
-        .await
-        .unwrap_or_else(|e| {
-            panic!("Failed to initialize parquet buffer step: {:?}", e);
-        });
-
-        let parquet_version_tracker_step = ParquetVersionTrackerStep::new(
-            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
-            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
-        );
-
         let channel_size = parquet_processor_config.channel_size;
 
         // Connect processor steps together
-        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+        let (_, transaction_receiver) = ProcessorBuilder::new_with_inputless_first_step(
             transaction_stream.into_runnable_step(),
         )
         .connect_to(
-            parquet_account_transactions_extractor.into_runnable_step(),
-            channel_size,
-        )
-        .connect_to(default_size_buffer_step.into_runnable_step(), channel_size)
-        .connect_to(
-            parquet_version_tracker_step.into_runnable_step(),
+            AdminControlStep::new(&self.config, channel_size).into_runnable_step(),
             channel_size,
         )
         .end_and_return_output_receiver(channel_size);
+        let buffer_receiver = self.build_pipeline(transaction_receiver).await?;
 
         loop {
             match buffer_receiver.recv().await {
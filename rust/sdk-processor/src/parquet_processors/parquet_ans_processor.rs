@@ -10,6 +10,7 @@ use crate::{
     },
     steps::{
         common::{
+            admin_control_step::AdminControlStep,
             parquet_version_tracker_step::ParquetVersionTrackerStep,
             processor_status_saver::get_processor_status_saver,
         },
@@ -24,16 +25,22 @@ use crate::{
 use anyhow::Context;
 use aptos_indexer_processor_sdk::{
     aptos_indexer_transaction_stream::{TransactionStream, TransactionStreamConfig},
+    aptos_protos::transaction::v1::Transaction,
     builder::ProcessorBuilder,
     common_steps::{TransactionStreamStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS},
+    instrumented_channel::InstrumentedAsyncReceiver,
     traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    types::transaction_context::TransactionContext,
 };
 use parquet::schema::types::Type;
 use processor::{
     bq_analytics::generic_parquet_processor::HasParquetSchema,
-    db::parquet::models::ans_models::{
-        ans_lookup_v2::{AnsLookupV2, CurrentAnsLookupV2},
-        ans_primary_name_v2::{AnsPrimaryNameV2, CurrentAnsPrimaryNameV2},
+    db::{
+        parquet::models::ans_models::{
+            ans_lookup_v2::{AnsLookupV2, CurrentAnsLookupV2},
+            ans_primary_name_v2::{AnsPrimaryNameV2, CurrentAnsPrimaryNameV2},
+        },
+        postgres::models::ans_models::ans_utils::NamingServiceConfig,
     },
 };
 use serde::{Deserialize, Serialize};
@@ -48,6 +55,18 @@ pub struct ParquetAnsProcessorConfig {
     pub ans_v1_primary_names_table_handle: String,
     pub ans_v1_name_records_table_handle: String,
     pub ans_v2_contract_address: String,
+    /// Other naming services with the structure of the Aptos Names contract
+    #[serde(default)]
+    pub additional_naming_services: Vec<NamingServiceConfig>,
+}
+
+impl ParquetAnsProcessorConfig {
+    pub fn naming_services(&self) -> anyhow::Result<Vec<NamingServiceConfig>> {
+        NamingServiceConfig::with_aptos_names(
+            &self.ans_v2_contract_address,
+            &self.additional_naming_services,
+        )
+    }
 }
 
 pub struct ParquetAnsProcessor {
@@ -60,6 +79,100 @@ impl ParquetAnsProcessor {
         let db_pool = initialize_database_pool(&config.db_config).await?;
         Ok(Self { config, db_pool })
     }
+
+    /// Connects the steps of the processor to a stream of transactions. This is how the processor
+    /// runs off its own transaction stream, and off the stream of a gap repair.
+    pub async fn build_pipeline(
+        &self,
+        transaction_receiver: InstrumentedAsyncReceiver<TransactionContext<Vec<Transaction>>>,
+    ) -> anyhow::Result<InstrumentedAsyncReceiver<TransactionContext<()>>> {
+        let parquet_db_config = match self.config.db_config {
+            DbConfig::ParquetConfig(ref parquet_config) => parquet_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid db config for ParquetAnsProcessor {:?}",
+                    self.config.db_config
+                ));
+            },
+        };
+        let parquet_processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::ParquetAnsProcessor(ans_config) => ans_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor configuration for ParquetAnsProcessor {:?}",
+                    self.config.processor_config
+                ));
+            },
+        };
+
+        let backfill_table =
+            set_backfill_table_flag(parquet_processor_config.clone().default.backfill_table);
+        let parquet_ans_extractor = ParquetAnsExtractor {
+            naming_services: parquet_processor_config.naming_services()?,
+            ans_config: parquet_processor_config.clone(),
+            opt_in_tables: backfill_table,
+        };
+
+        let gcs_client =
+            initialize_gcs_client(parquet_db_config.google_application_credentials.clone()).await;
+
+        let parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>> = [
+            (
+                ParquetTypeEnum::AnsPrimaryNameV2,
+                AnsPrimaryNameV2::schema(),
+            ),
+            (
+                ParquetTypeEnum::CurrentAnsPrimaryNameV2,
+                CurrentAnsPrimaryNameV2::schema(),
+            ),
+            (ParquetTypeEnum::AnsLookupV2, AnsLookupV2::schema()),
+            (
+                ParquetTypeEnum::CurrentAnsLookupV2,
+                CurrentAnsLookupV2::schema(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let default_size_buffer_step = initialize_parquet_buffer_step(
+            gcs_client.clone(),
+            parquet_type_to_schemas,
+            parquet_processor_config.default.upload_interval,
+            parquet_processor_config.default.max_buffer_size,
+            parquet_db_config.bucket_name.clone(),
+            parquet_db_config.bucket_root.clone(),
+            self.name().to_string(),
+            self.config.pipeline_name(),
+            self.config
+                .gap_repair_config
+                .as_ref()
+                .map(|_| self.db_pool.clone()),
+        )
+        .await
+        .unwrap_or_else(|e| {
+            panic!("Failed to initialize parquet buffer step: {:?}", e);
+        });
+
+        let parquet_version_tracker_step = ParquetVersionTrackerStep::new(
+            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        let channel_size = parquet_processor_config.default.channel_size;
+
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_runnable_input_receiver_first_step(
+            transaction_receiver,
+            parquet_ans_extractor.into_runnable_step(),
+            channel_size,
+        )
+        .connect_to(default_size_buffer_step.into_runnable_step(), channel_size)
+        .connect_to(
+            parquet_version_tracker_step.into_runnable_step(),
+            channel_size,
+        )
+        .end_and_return_output_receiver(channel_size);
+        Ok(buffer_receiver)
+    }
 }
 
 #[async_trait::async_trait]
@@ -70,14 +183,13 @@ impl ProcessorTrait for ParquetAnsProcessor {
 
     async fn run_processor(&self) -> anyhow::Result<()> {
         // Run Migrations
-        let parquet_db_config = match self.config.db_config {
+        match self.config.db_config {
             DbConfig::ParquetConfig(ref parquet_config) => {
                 run_migrations(
                     parquet_config.connection_string.clone(),
                     self.db_pool.clone(),
                 )
                 .await;
-                parquet_config
             },
             _ => {
                 return Err(anyhow::anyhow!(
@@ -138,66 +250,18 @@ impl ProcessorTrait for ParquetAnsProcessor {
         })
         .await?;
 
-        let backfill_table =
-            set_backfill_table_flag(parquet_processor_config.clone().default.backfill_table);
-        let parquet_ans_extractor = ParquetAnsExtractor {
-            ans_config: parquet_processor_config.clone(),
-            opt_in_tables: backfill_table,
-        };
-
-        let gcs_client =
-            initialize_gcs_client(parquet_db_config.google_application_credentials.clone()).await;
-
-        let parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>> = [
-            (
-                ParquetTypeEnum::AnsPrimaryNameV2,
-                AnsPrimaryNameV2::schema(),
-            ),
-            (
-                ParquetTypeEnum::CurrentAnsPrimaryNameV2,
-                CurrentAnsPrimaryNameV2::schema(),
-            ),
-            (ParquetTypeEnum::AnsLookupV2, AnsLookupV2::schema()),
-            (
-                ParquetTypeEnum::CurrentAnsLookupV2,
-                CurrentAnsLookupV2::schema(),
-            ),
-        ]
-        .into_iter()
-        .collect();
-
-        let default_size_buffer_step = initialize_parquet_buffer_step(
-            gcs_client.clone(),
-            parquet_type_to_schemas,
-            parquet_processor_config.default.upload_interval,
-            parquet_processor_config.default.max_buffer_size,
-            parquet_db_config.bucket_name.clone(),
-            parquet_db_config.bucket_root.clone(),
-            self.name().to_string(),
-        )
-        .await
-        .unwrap_or_else(|e| {
-            panic!("Failed to initialize parquet buffer step: {:?}", e);
-        });
-
-        let parquet_version_tracker_step = ParquetVersionTrackerStep::new(
-            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
-            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
-        );
-
         let channel_size = parquet_processor_config.default.channel_size;
 
         // Connect processor steps together
-        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+        let (_, transaction_receiver) = ProcessorBuilder::new_with_inputless_first_step(
             transaction_stream.into_runnable_step(),
         )
-        .connect_to(parquet_ans_extractor.into_runnable_step(), channel_size)
-        .connect_to(default_size_buffer_step.into_runnable_step(), channel_size)
         .connect_to(
-            parquet_version_tracker_step.into_runnable_step(),
+            AdminControlStep::new(&self.config, channel_size).into_runnable_step(),
             channel_size,
         )
         .end_and_return_output_receiver(channel_size);
+        let buffer_receiver = self.build_pipeline(transaction_receiver).await?;
 
         loop {
             match buffer_receiver.recv().await {
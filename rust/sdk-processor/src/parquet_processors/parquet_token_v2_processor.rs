@@ -10,6 +10,7 @@ use crate::{
     },
     steps::{
         common::{
+            admin_control_step::AdminControlStep,
             parquet_version_tracker_step::ParquetVersionTrackerStep,
             processor_status_saver::get_processor_status_saver,
         },
@@ -24,9 +25,12 @@ use crate::{
 use anyhow::Context;
 use aptos_indexer_processor_sdk::{
     aptos_indexer_transaction_stream::{TransactionStream, TransactionStreamConfig},
+    aptos_protos::transaction::v1::Transaction,
     builder::ProcessorBuilder,
     common_steps::{TransactionStreamStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS},
+    instrumented_channel::InstrumentedAsyncReceiver,
     traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    types::transaction_context::TransactionContext,
 };
 use parquet::schema::types::Type;
 use processor::{
@@ -34,6 +38,7 @@ use processor::{
     db::parquet::models::token_v2_models::{
         token_claims::CurrentTokenPendingClaim,
         v1_token_royalty::CurrentTokenRoyaltyV1,
+        v2_collection_activity_hourly::CollectionActivityHourly,
         v2_token_activities::TokenActivityV2,
         v2_token_datas::{CurrentTokenDataV2, TokenDataV2},
         v2_token_metadata::CurrentTokenV2Metadata,
@@ -53,25 +58,15 @@ impl ParquetTokenV2Processor {
         let db_pool = initialize_database_pool(&config.db_config).await?;
         Ok(Self { config, db_pool })
     }
-}
-
-#[async_trait::async_trait]
-impl ProcessorTrait for ParquetTokenV2Processor {
-    fn name(&self) -> &'static str {
-        self.config.processor_config.name()
-    }
 
-    async fn run_processor(&self) -> anyhow::Result<()> {
-        // Run Migrations
+    /// Connects the steps of the processor to a stream of transactions. This is how the processor
+    /// runs off its own transaction stream, and off the stream of a gap repair.
+    pub async fn build_pipeline(
+        &self,
+        transaction_receiver: InstrumentedAsyncReceiver<TransactionContext<Vec<Transaction>>>,
+    ) -> anyhow::Result<InstrumentedAsyncReceiver<TransactionContext<()>>> {
         let parquet_db_config = match self.config.db_config {
-            DbConfig::ParquetConfig(ref parquet_config) => {
-                run_migrations(
-                    parquet_config.connection_string.clone(),
-                    self.db_pool.clone(),
-                )
-                .await;
-                parquet_config
-            },
+            DbConfig::ParquetConfig(ref parquet_config) => parquet_config,
             _ => {
                 return Err(anyhow::anyhow!(
                     "Invalid db config for ParquetTokenV2Processor {:?}",
@@ -79,14 +74,6 @@ impl ProcessorTrait for ParquetTokenV2Processor {
                 ));
             },
         };
-
-        // Check and update the ledger chain id to ensure we're indexing the correct chain
-        let grpc_chain_id = TransactionStream::new(self.config.transaction_stream_config.clone())
-            .await?
-            .get_chain_id()
-            .await?;
-        check_or_update_chain_id(grpc_chain_id as i64, self.db_pool.clone()).await?;
-
         let parquet_processor_config = match self.config.processor_config.clone() {
             ProcessorConfig::ParquetTokenV2Processor(parquet_processor_config) => {
                 parquet_processor_config
@@ -99,44 +86,11 @@ impl ProcessorTrait for ParquetTokenV2Processor {
             },
         };
 
-        let processor_status_table_names = self
-            .config
-            .processor_config
-            .get_processor_status_table_names()
-            .context("Failed to get table names for the processor status table")?;
-
-        let starting_version = get_min_last_success_version_parquet(
-            &self.config,
-            self.db_pool.clone(),
-            processor_status_table_names,
-        )
-        .await?;
-        println!("Starting version: {:?}", starting_version);
-
-        // Define processor transaction stream config
-        let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
-            starting_version: Some(starting_version),
-            request_ending_version: match self.config.mode {
-                ProcessorMode::Default => None,
-                ProcessorMode::Backfill => self
-                    .config
-                    .backfill_config
-                    .as_ref()
-                    .map(|c| c.ending_version),
-                ProcessorMode::Testing => self
-                    .config
-                    .testing_config
-                    .as_ref()
-                    .map(|c| c.ending_version),
-            },
-            ..self.config.transaction_stream_config.clone()
-        })
-        .await?;
-
         let backfill_table = set_backfill_table_flag(parquet_processor_config.backfill_table);
         // TODO: Update this
         let parquet_token_v2_extractor = ParquetTokenV2Extractor {
             opt_in_tables: backfill_table,
+            address_labels: self.config.address_label_registry()?,
         };
 
         let gcs_client =
@@ -173,6 +127,10 @@ impl ProcessorTrait for ParquetTokenV2Processor {
                 ParquetTypeEnum::CurrentTokenOwnershipsV2,
                 CurrentTokenOwnershipV2::schema(),
             ),
+            (
+                ParquetTypeEnum::CollectionActivityHourly,
+                CollectionActivityHourly::schema(),
+            ),
         ]
         .into_iter()
         .collect();
@@ -185,6 +143,11 @@ impl ProcessorTrait for ParquetTokenV2Processor {
             parquet_db_config.bucket_name.clone(),
             parquet_db_config.bucket_root.clone(),
             self.name().to_string(),
+            self.config.pipeline_name(),
+            self.config
+                .gap_repair_config
+                .as_ref()
+                .map(|_| self.db_pool.clone()),
         )
         .await
         .unwrap_or_else(|e| {
@@ -198,11 +161,8 @@ impl ProcessorTrait for ParquetTokenV2Processor {
 
         let channel_size = parquet_processor_config.channel_size;
 
-        // Connect processor steps together
-        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
-            transaction_stream.into_runnable_step(),
-        )
-        .connect_to(
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_runnable_input_receiver_first_step(
+            transaction_receiver,
             parquet_token_v2_extractor.into_runnable_step(),
             channel_size,
         )
@@ -212,6 +172,99 @@ impl ProcessorTrait for ParquetTokenV2Processor {
             channel_size,
         )
         .end_and_return_output_receiver(channel_size);
+        Ok(buffer_receiver)
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessorTrait for ParquetTokenV2Processor {
+    fn name(&self) -> &'static str {
+        self.config.processor_config.name()
+    }
+
+    async fn run_processor(&self) -> anyhow::Result<()> {
+        // Run Migrations
+        match self.config.db_config {
+            DbConfig::ParquetConfig(ref parquet_config) => {
+                run_migrations(
+                    parquet_config.connection_string.clone(),
+                    self.db_pool.clone(),
+                )
+                .await;
+            },
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid db config for ParquetTokenV2Processor {:?}",
+                    self.config.db_config
+                ));
+            },
+        };
+
+        // Check and update the ledger chain id to ensure we're indexing the correct chain
+        let grpc_chain_id = TransactionStream::new(self.config.transaction_stream_config.clone())
+            .await?
+            .get_chain_id()
+            .await?;
+        check_or_update_chain_id(grpc_chain_id as i64, self.db_pool.clone()).await?;
+
+        let parquet_processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::ParquetTokenV2Processor(parquet_processor_config) => {
+                parquet_processor_config
+            },
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor configuration for ParquetTokenV2Processor {:?}",
+                    self.config.processor_config
+                ));
+            },
+        };
+
+        let processor_status_table_names = self
+            .config
+            .processor_config
+            .get_processor_status_table_names()
+            .context("Failed to get table names for the processor status table")?;
+
+        let starting_version = get_min_last_success_version_parquet(
+            &self.config,
+            self.db_pool.clone(),
+            processor_status_table_names,
+        )
+        .await?;
+        println!("Starting version: {:?}", starting_version);
+
+        // Define processor transaction stream config
+        let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+            starting_version: Some(starting_version),
+            request_ending_version: match self.config.mode {
+                ProcessorMode::Default => None,
+                ProcessorMode::Backfill => self
+                    .config
+                    .backfill_config
+                    .as_ref()
+                    .map(|c| c.ending_version),
+                ProcessorMode::Testing => self
+                    .config
+                    .testing_config
+                    .as_ref()
+                    .map(|c| c.ending_version),
+            },
+            ..self.config.transaction_stream_config.clone()
+        })
+        .await?;
+
+        let channel_size = parquet_processor_config.channel_size;
+
+        // Connect processor steps together
+        let (_, transaction_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+            transaction_stream.into_runnable_step(),
+        )
+        .connect_to(
+            AdminControlStep::new(&self.config, channel_size).into_runnable_step(),
+            channel_size,
+        )
+        .end_and_return_output_receiver(channel_size);
+        let buffer_receiver = self.build_pipeline(transaction_receiver).await?;
 
         loop {
             match buffer_receiver.recv().await {
@@ -6,7 +6,7 @@ use crate::{
     },
     steps::{
         account_transactions_processor::{AccountTransactionsExtractor, AccountTransactionsStorer},
-        common::get_processor_status_saver,
+        common::{admin_control_step::AdminControlStep, get_processor_status_saver},
     },
     utils::{
         chain_id::check_or_update_chain_id,
@@ -17,11 +17,14 @@ use crate::{
 use anyhow::Result;
 use aptos_indexer_processor_sdk::{
     aptos_indexer_transaction_stream::{TransactionStream, TransactionStreamConfig},
+    aptos_protos::transaction::v1::Transaction,
     builder::ProcessorBuilder,
     common_steps::{
         TransactionStreamStep, VersionTrackerStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
     },
+    instrumented_channel::InstrumentedAsyncReceiver,
     traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    types::transaction_context::TransactionContext,
 };
 use tracing::{debug, info};
 
@@ -57,6 +60,44 @@ impl AccountTransactionsProcessor {
             )),
         }
     }
+
+    /// Connects the steps of the processor to a stream of transactions. This is how the processor
+    /// runs off its own transaction stream, and off the stream shared by the multi processor.
+    pub async fn build_pipeline(
+        &self,
+        transaction_receiver: InstrumentedAsyncReceiver<TransactionContext<Vec<Transaction>>>,
+    ) -> Result<InstrumentedAsyncReceiver<TransactionContext<()>>> {
+        let processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::AccountTransactionsProcessor(processor_config) => processor_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor config for Account Transactions Processor: {:?}",
+                    self.config.processor_config
+                ))
+            },
+        };
+        let channel_size = processor_config.channel_size;
+
+        let acc_txns_extractor = AccountTransactionsExtractor {
+            address_labels: self.config.address_label_registry()?,
+        };
+        let acc_txns_storer =
+            AccountTransactionsStorer::new(self.db_pool.clone(), processor_config);
+        let version_tracker = VersionTrackerStep::new(
+            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_runnable_input_receiver_first_step(
+            transaction_receiver,
+            acc_txns_extractor.into_runnable_step(),
+            channel_size,
+        )
+        .connect_to(acc_txns_storer.into_runnable_step(), channel_size)
+        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .end_and_return_output_receiver(channel_size);
+        Ok(buffer_receiver)
+    }
 }
 
 #[async_trait::async_trait]
@@ -115,22 +156,17 @@ impl ProcessorTrait for AccountTransactionsProcessor {
             ..self.config.transaction_stream_config.clone()
         })
         .await?;
-        let acc_txns_extractor = AccountTransactionsExtractor {};
-        let acc_txns_storer =
-            AccountTransactionsStorer::new(self.db_pool.clone(), processor_config);
-        let version_tracker = VersionTrackerStep::new(
-            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
-            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
-        );
 
         // Connect processor steps together.
-        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+        let (_, transaction_receiver) = ProcessorBuilder::new_with_inputless_first_step(
             transaction_stream.into_runnable_step(),
         )
-        .connect_to(acc_txns_extractor.into_runnable_step(), channel_size)
-        .connect_to(acc_txns_storer.into_runnable_step(), channel_size)
-        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .connect_to(
+            AdminControlStep::new(&self.config, channel_size).into_runnable_step(),
+            channel_size,
+        )
         .end_and_return_output_receiver(channel_size);
+        let buffer_receiver = self.build_pipeline(transaction_receiver).await?;
 
         loop {
             match buffer_receiver.recv().await {
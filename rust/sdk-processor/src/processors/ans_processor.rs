@@ -6,7 +6,7 @@ use crate::{
     },
     steps::{
         ans_processor::{AnsExtractor, AnsStorer},
-        common::get_processor_status_saver,
+        common::{admin_control_step::AdminControlStep, get_processor_status_saver},
     },
     utils::{
         chain_id::check_or_update_chain_id,
@@ -17,13 +17,19 @@ use crate::{
 use anyhow::Result;
 use aptos_indexer_processor_sdk::{
     aptos_indexer_transaction_stream::{TransactionStream, TransactionStreamConfig},
+    aptos_protos::transaction::v1::Transaction,
     builder::ProcessorBuilder,
     common_steps::{
         TransactionStreamStep, VersionTrackerStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
     },
+    instrumented_channel::InstrumentedAsyncReceiver,
     traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    types::transaction_context::TransactionContext,
+};
+use processor::{
+    db::postgres::models::ans_models::ans_utils::NamingServiceConfig,
+    utils::table_flags::TableFlags,
 };
-use processor::utils::table_flags::TableFlags;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
@@ -35,6 +41,18 @@ pub struct AnsProcessorConfig {
     pub ans_v1_primary_names_table_handle: String,
     pub ans_v1_name_records_table_handle: String,
     pub ans_v2_contract_address: String,
+    /// Other naming services with the structure of the Aptos Names contract
+    #[serde(default)]
+    pub additional_naming_services: Vec<NamingServiceConfig>,
+}
+
+impl AnsProcessorConfig {
+    pub fn naming_services(&self) -> anyhow::Result<Vec<NamingServiceConfig>> {
+        NamingServiceConfig::with_aptos_names(
+            &self.ans_v2_contract_address,
+            &self.additional_naming_services,
+        )
+    }
 }
 
 pub struct AnsProcessor {
@@ -69,6 +87,44 @@ impl AnsProcessor {
             )),
         }
     }
+
+    /// Connects the steps of the processor to a stream of transactions. This is how the processor
+    /// runs off its own transaction stream, and off the stream shared by the multi processor.
+    pub async fn build_pipeline(
+        &self,
+        transaction_receiver: InstrumentedAsyncReceiver<TransactionContext<Vec<Transaction>>>,
+    ) -> Result<InstrumentedAsyncReceiver<TransactionContext<()>>> {
+        let processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::AnsProcessor(processor_config) => processor_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor config for ANS Processor: {:?}",
+                    self.config.processor_config
+                ))
+            },
+        };
+        let channel_size = processor_config.default.channel_size;
+        let deprecated_table_flags =
+            TableFlags::from_set(&processor_config.default.deprecated_tables);
+
+        let acc_txns_extractor =
+            AnsExtractor::new(deprecated_table_flags, self.config.processor_config.clone());
+        let acc_txns_storer = AnsStorer::new(self.db_pool.clone(), processor_config);
+        let version_tracker = VersionTrackerStep::new(
+            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_runnable_input_receiver_first_step(
+            transaction_receiver,
+            acc_txns_extractor?.into_runnable_step(),
+            channel_size,
+        )
+        .connect_to(acc_txns_storer.into_runnable_step(), channel_size)
+        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .end_and_return_output_receiver(channel_size);
+        Ok(buffer_receiver)
+    }
 }
 
 #[async_trait::async_trait]
@@ -107,8 +163,6 @@ impl ProcessorTrait for AnsProcessor {
             },
         };
         let channel_size = processor_config.default.channel_size;
-        let deprecated_table_flags =
-            TableFlags::from_set(&processor_config.default.deprecated_tables);
 
         // Define processor steps.
         let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
@@ -129,22 +183,17 @@ impl ProcessorTrait for AnsProcessor {
             ..self.config.transaction_stream_config.clone()
         })
         .await?;
-        let acc_txns_extractor =
-            AnsExtractor::new(deprecated_table_flags, self.config.processor_config.clone());
-        let acc_txns_storer = AnsStorer::new(self.db_pool.clone(), processor_config);
-        let version_tracker = VersionTrackerStep::new(
-            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
-            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
-        );
 
         // Connect processor steps together.
-        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+        let (_, transaction_receiver) = ProcessorBuilder::new_with_inputless_first_step(
             transaction_stream.into_runnable_step(),
         )
-        .connect_to(acc_txns_extractor?.into_runnable_step(), channel_size)
-        .connect_to(acc_txns_storer.into_runnable_step(), channel_size)
-        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .connect_to(
+            AdminControlStep::new(&self.config, channel_size).into_runnable_step(),
+            channel_size,
+        )
         .end_and_return_output_receiver(channel_size);
+        let buffer_receiver = self.build_pipeline(transaction_receiver).await?;
 
         loop {
             match buffer_receiver.recv().await {
@@ -5,7 +5,7 @@ use crate::{
         processor_config::ProcessorConfig,
     },
     steps::{
-        common::get_processor_status_saver,
+        common::{admin_control_step::AdminControlStep, get_processor_status_saver},
         default_processor::{default_extractor::DefaultExtractor, default_storer::DefaultStorer},
     },
     utils::{
@@ -17,11 +17,14 @@ use crate::{
 use anyhow::Result;
 use aptos_indexer_processor_sdk::{
     aptos_indexer_transaction_stream::{TransactionStream, TransactionStreamConfig},
+    aptos_protos::transaction::v1::Transaction,
     builder::ProcessorBuilder,
     common_steps::{
         TransactionStreamStep, VersionTrackerStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
     },
+    instrumented_channel::InstrumentedAsyncReceiver,
     traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    types::transaction_context::TransactionContext,
 };
 use async_trait::async_trait;
 use processor::utils::table_flags::TableFlags;
@@ -59,6 +62,44 @@ impl DefaultProcessor {
             )),
         }
     }
+
+    /// Connects the steps of the processor to a stream of transactions. This is how the processor
+    /// runs off its own transaction stream, and off the stream shared by the multi processor.
+    pub async fn build_pipeline(
+        &self,
+        transaction_receiver: InstrumentedAsyncReceiver<TransactionContext<Vec<Transaction>>>,
+    ) -> Result<InstrumentedAsyncReceiver<TransactionContext<()>>> {
+        let processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::DefaultProcessor(processor_config) => processor_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor config for DefaultProcessor: {:?}",
+                    self.config.processor_config
+                ))
+            },
+        };
+        let channel_size = processor_config.channel_size;
+        let deprecated_table_flags = TableFlags::from_set(&processor_config.deprecated_tables);
+
+        let default_extractor = DefaultExtractor {
+            deprecated_table_flags,
+        };
+        let default_storer = DefaultStorer::new(self.db_pool.clone(), processor_config);
+        let version_tracker = VersionTrackerStep::new(
+            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_runnable_input_receiver_first_step(
+            transaction_receiver,
+            default_extractor.into_runnable_step(),
+            channel_size,
+        )
+        .connect_to(default_storer.into_runnable_step(), channel_size)
+        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .end_and_return_output_receiver(channel_size);
+        Ok(buffer_receiver)
+    }
 }
 
 #[async_trait]
@@ -97,7 +138,6 @@ impl ProcessorTrait for DefaultProcessor {
             },
         };
         let channel_size = processor_config.channel_size;
-        let deprecated_table_flags = TableFlags::from_set(&processor_config.deprecated_tables);
 
         // Define processor steps
         let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
@@ -118,23 +158,17 @@ impl ProcessorTrait for DefaultProcessor {
             ..self.config.transaction_stream_config.clone()
         })
         .await?;
-        let default_extractor = DefaultExtractor {
-            deprecated_table_flags,
-        };
-        let default_storer = DefaultStorer::new(self.db_pool.clone(), processor_config);
-        let version_tracker = VersionTrackerStep::new(
-            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
-            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
-        );
 
         // Connect processor steps together
-        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+        let (_, transaction_receiver) = ProcessorBuilder::new_with_inputless_first_step(
             transaction_stream.into_runnable_step(),
         )
-        .connect_to(default_extractor.into_runnable_step(), channel_size)
-        .connect_to(default_storer.into_runnable_step(), channel_size)
-        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .connect_to(
+            AdminControlStep::new(&self.config, channel_size).into_runnable_step(),
+            channel_size,
+        )
         .end_and_return_output_receiver(channel_size);
+        let buffer_receiver = self.build_pipeline(transaction_receiver).await?;
 
         // (Optional) Parse the results
         loop {
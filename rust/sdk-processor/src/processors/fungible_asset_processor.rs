@@ -5,7 +5,10 @@ use crate::{
         processor_config::ProcessorConfig,
     },
     steps::{
-        common::get_processor_status_saver,
+        common::{
+            admin_control_step::AdminControlStep, change_capture::ChangeCapture,
+            get_processor_status_saver,
+        },
         fungible_asset_processor::{
             fungible_asset_extractor::FungibleAssetExtractor,
             fungible_asset_storer::FungibleAssetStorer,
@@ -20,11 +23,14 @@ use crate::{
 use anyhow::Result;
 use aptos_indexer_processor_sdk::{
     aptos_indexer_transaction_stream::{TransactionStream, TransactionStreamConfig},
+    aptos_protos::transaction::v1::Transaction,
     builder::ProcessorBuilder,
     common_steps::{
         TransactionStreamStep, VersionTrackerStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
     },
+    instrumented_channel::InstrumentedAsyncReceiver,
     traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    types::transaction_context::TransactionContext,
 };
 use processor::utils::table_flags::TableFlags;
 use tracing::{debug, info};
@@ -61,6 +67,47 @@ impl FungibleAssetProcessor {
             )),
         }
     }
+
+    /// Connects the steps of the processor to a stream of transactions. This is how the processor
+    /// runs off its own transaction stream, and off the stream shared by the multi processor.
+    pub async fn build_pipeline(
+        &self,
+        transaction_receiver: InstrumentedAsyncReceiver<TransactionContext<Vec<Transaction>>>,
+    ) -> Result<InstrumentedAsyncReceiver<TransactionContext<()>>> {
+        let processor_config = match &self.config.processor_config {
+            ProcessorConfig::FungibleAssetProcessor(processor_config) => processor_config,
+            _ => return Err(anyhow::anyhow!("Processor config is wrong type")),
+        };
+        let channel_size = processor_config.channel_size;
+        let deprecated_table_flags = TableFlags::from_set(&processor_config.deprecated_tables);
+
+        let mut fa_extractor = FungibleAssetExtractor::new(self.config.address_label_registry()?);
+        fa_extractor
+            .bootstrap_fa_to_coin_mapping(self.db_pool.clone())
+            .await?;
+        let change_capture =
+            ChangeCapture::from_config(self.db_pool.clone(), &processor_config.cdc_sink).await?;
+        let fa_storer = FungibleAssetStorer::new(
+            self.db_pool.clone(),
+            processor_config.clone(),
+            deprecated_table_flags,
+            change_capture,
+        );
+        let version_tracker = VersionTrackerStep::new(
+            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_runnable_input_receiver_first_step(
+            transaction_receiver,
+            fa_extractor.into_runnable_step(),
+            channel_size,
+        )
+        .connect_to(fa_storer.into_runnable_step(), channel_size)
+        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .end_and_return_output_receiver(channel_size);
+        Ok(buffer_receiver)
+    }
 }
 
 #[async_trait::async_trait]
@@ -94,7 +141,6 @@ impl ProcessorTrait for FungibleAssetProcessor {
             _ => return Err(anyhow::anyhow!("Processor config is wrong type")),
         };
         let channel_size = processor_config.channel_size;
-        let deprecated_table_flags = TableFlags::from_set(&processor_config.deprecated_tables);
 
         // Define processor steps
         let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
@@ -116,27 +162,16 @@ impl ProcessorTrait for FungibleAssetProcessor {
         })
         .await?;
 
-        let mut fa_extractor = FungibleAssetExtractor::new();
-        fa_extractor
-            .bootstrap_fa_to_coin_mapping(self.db_pool.clone())
-            .await?;
-        let fa_storer = FungibleAssetStorer::new(
-            self.db_pool.clone(),
-            processor_config.clone(),
-            deprecated_table_flags,
-        );
-        let version_tracker = VersionTrackerStep::new(
-            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
-            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
-        );
         // Connect processor steps together
-        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+        let (_, transaction_receiver) = ProcessorBuilder::new_with_inputless_first_step(
             transaction_stream.into_runnable_step(),
         )
-        .connect_to(fa_extractor.into_runnable_step(), channel_size)
-        .connect_to(fa_storer.into_runnable_step(), channel_size)
-        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .connect_to(
+            AdminControlStep::new(&self.config, channel_size).into_runnable_step(),
+            channel_size,
+        )
         .end_and_return_output_receiver(channel_size);
+        let buffer_receiver = self.build_pipeline(transaction_receiver).await?;
 
         // (Optional) Parse the results
         loop {
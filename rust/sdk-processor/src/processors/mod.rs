@@ -5,7 +5,171 @@ pub mod default_processor;
 pub mod events_processor;
 pub mod fungible_asset_processor;
 pub mod monitoring_processor;
+pub mod multi_processor;
+pub mod nft_metadata_processor;
 pub mod objects_processor;
+pub mod spam_classifier_processor;
 pub mod stake_processor;
 pub mod token_v2_processor;
+pub mod transaction_failure_processor;
+pub mod transaction_metadata_processor;
 pub mod user_transaction_processor;
+
+use crate::{
+    config::{indexer_processor_config::IndexerProcessorConfig, processor_config::ProcessorConfig},
+    parquet_processors::{
+        parquet_account_transactions_processor::ParquetAccountTransactionsProcessor,
+        parquet_ans_processor::ParquetAnsProcessor,
+        parquet_default_processor::ParquetDefaultProcessor,
+        parquet_events_processor::ParquetEventsProcessor,
+        parquet_fungible_asset_processor::ParquetFungibleAssetProcessor,
+        parquet_objects_processor::ParquetObjectsProcessor,
+        parquet_stake_processor::ParquetStakeProcessor,
+        parquet_token_v2_processor::ParquetTokenV2Processor,
+        parquet_transaction_metadata_processor::ParquetTransactionMetadataProcessor,
+        parquet_user_transaction_processor::ParquetUserTransactionsProcessor,
+    },
+    utils::database::ArcDbPool,
+};
+use account_restoration_processor::AccountRestorationProcessor;
+use account_transactions_processor::AccountTransactionsProcessor;
+use ans_processor::AnsProcessor;
+use anyhow::Result;
+use aptos_indexer_processor_sdk::{
+    aptos_protos::transaction::v1::Transaction, instrumented_channel::InstrumentedAsyncReceiver,
+    types::transaction_context::TransactionContext,
+};
+use default_processor::DefaultProcessor;
+use events_processor::EventsProcessor;
+use fungible_asset_processor::FungibleAssetProcessor;
+use objects_processor::ObjectsProcessor;
+use stake_processor::StakeProcessor;
+use token_v2_processor::TokenV2Processor;
+use transaction_failure_processor::TransactionFailureProcessor;
+use transaction_metadata_processor::TransactionMetadataProcessor;
+use user_transaction_processor::UserTransactionProcessor;
+
+/// Connects the steps of the processor in `config` to a stream of transactions that is run by the
+/// caller, sharing its db pool.
+pub async fn build_pipeline(
+    config: IndexerProcessorConfig,
+    db_pool: ArcDbPool,
+    transaction_receiver: InstrumentedAsyncReceiver<TransactionContext<Vec<Transaction>>>,
+) -> Result<InstrumentedAsyncReceiver<TransactionContext<()>>> {
+    match config.processor_config {
+        ProcessorConfig::AccountRestorationProcessor(_) => {
+            AccountRestorationProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::AccountTransactionsProcessor(_) => {
+            AccountTransactionsProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::AnsProcessor(_) => {
+            AnsProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::DefaultProcessor(_) => {
+            DefaultProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::EventsProcessor(_) => {
+            EventsProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::FungibleAssetProcessor(_) => {
+            FungibleAssetProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::UserTransactionProcessor(_) => {
+            UserTransactionProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::TransactionMetadataProcessor(_) => {
+            TransactionMetadataProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::TransactionFailureProcessor(_) => {
+            TransactionFailureProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::StakeProcessor(_) => {
+            StakeProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::TokenV2Processor(_) => {
+            TokenV2Processor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ObjectsProcessor(_) => {
+            ObjectsProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ParquetDefaultProcessor(_) => {
+            ParquetDefaultProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ParquetEventsProcessor(_) => {
+            ParquetEventsProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ParquetUserTransactionsProcessor(_) => {
+            ParquetUserTransactionsProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ParquetFungibleAssetProcessor(_) => {
+            ParquetFungibleAssetProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ParquetTransactionMetadataProcessor(_) => {
+            ParquetTransactionMetadataProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ParquetAccountTransactionsProcessor(_) => {
+            ParquetAccountTransactionsProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ParquetTokenV2Processor(_) => {
+            ParquetTokenV2Processor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ParquetAnsProcessor(_) => {
+            ParquetAnsProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ParquetStakeProcessor(_) => {
+            ParquetStakeProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        ProcessorConfig::ParquetObjectsProcessor(_) => {
+            ParquetObjectsProcessor { config, db_pool }
+                .build_pipeline(transaction_receiver)
+                .await
+        },
+        _ => Err(anyhow::anyhow!(
+            "Processor {} can't run off a shared transaction stream",
+            config.processor_config.name()
+        )),
+    }
+}
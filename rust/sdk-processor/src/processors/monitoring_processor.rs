@@ -4,7 +4,7 @@ use crate::{
         indexer_processor_config::{IndexerProcessorConfig, ProcessorMode},
         processor_config::ProcessorConfig,
     },
-    steps::common::get_processor_status_saver,
+    steps::common::{admin_control_step::AdminControlStep, get_processor_status_saver},
     utils::{
         chain_id::check_or_update_chain_id,
         database::{new_db_pool, run_migrations, ArcDbPool},
@@ -122,6 +122,10 @@ impl ProcessorTrait for MonitoringProcessor {
         let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
             transaction_stream.into_runnable_step(),
         )
+        .connect_to(
+            AdminControlStep::new(&self.config, channel_size).into_runnable_step(),
+            channel_size,
+        )
         .connect_to(version_tracker.into_runnable_step(), channel_size)
         .end_and_return_output_receiver(channel_size);
 
@@ -0,0 +1,356 @@
+use crate::{
+    config::{
+        db_config::DbConfig,
+        indexer_processor_config::{IndexerProcessorConfig, ProcessorMode},
+        processor_config::ProcessorConfig,
+    },
+    processors::build_pipeline,
+    steps::common::admin_control_step::AdminControlStep,
+    utils::{
+        chain_id::check_or_update_chain_id,
+        database::{new_db_pool, run_migrations, ArcDbPool},
+        starting_version::get_starting_version,
+    },
+};
+use anyhow::Result;
+use aptos_indexer_processor_sdk::{
+    aptos_indexer_transaction_stream::{TransactionStream, TransactionStreamConfig},
+    aptos_protos::transaction::v1::Transaction,
+    builder::ProcessorBuilder,
+    common_steps::TransactionStreamStep,
+    instrumented_channel::{
+        instrumented_bounded_channel, InstrumentedAsyncReceiver, InstrumentedAsyncSender,
+    },
+    traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    types::transaction_context::TransactionContext,
+};
+use serde::{Deserialize, Serialize};
+use server_framework::admin::admin_state;
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+/// Config for running several postgres processors off a single transaction stream.
+///
+/// Each entry in `processor_configs` is a regular processor config (e.g. `type: events_processor`).
+/// The processors share the db pool and the gRPC stream, but keep their own `processor_status` rows.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MultiProcessorConfig {
+    pub processor_configs: Vec<ProcessorConfig>,
+    // Size of channel between the transaction stream and the fanout
+    #[serde(default = "MultiProcessorConfig::default_channel_size")]
+    pub channel_size: usize,
+    // Number of batches a processor may fall behind before it blocks the shared stream
+    #[serde(default = "MultiProcessorConfig::default_per_processor_buffer_size")]
+    pub per_processor_buffer_size: usize,
+}
+
+impl MultiProcessorConfig {
+    pub const fn default_channel_size() -> usize {
+        10
+    }
+
+    pub const fn default_per_processor_buffer_size() -> usize {
+        50
+    }
+}
+
+/// A processor in the fanout, along with the version it should start receiving batches from.
+struct ChildProcessor {
+    name: &'static str,
+    // Name the processor reports to the admin API under
+    pipeline_name: String,
+    starting_version: u64,
+    sender: InstrumentedAsyncSender<TransactionContext<Vec<Transaction>>>,
+    handle: JoinHandle<()>,
+}
+
+pub struct MultiProcessor {
+    pub config: IndexerProcessorConfig,
+    pub db_pool: ArcDbPool,
+}
+
+impl MultiProcessor {
+    pub async fn new(config: IndexerProcessorConfig) -> Result<Self> {
+        let processor_config = match &config.processor_config {
+            ProcessorConfig::MultiProcessor(processor_config) => processor_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor config for MultiProcessor: {:?}",
+                    config.processor_config
+                ))
+            },
+        };
+        if processor_config.processor_configs.is_empty() {
+            return Err(anyhow::anyhow!(
+                "MultiProcessor requires at least one processor config"
+            ));
+        }
+
+        match config.db_config {
+            DbConfig::PostgresConfig(ref postgres_config) => {
+                let conn_pool = new_db_pool(
+                    &postgres_config.connection_string,
+                    Some(postgres_config.db_pool_size),
+                )
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to create connection pool for PostgresConfig: {:?}",
+                        e
+                    )
+                })?;
+
+                Ok(Self {
+                    config,
+                    db_pool: conn_pool,
+                })
+            },
+            _ => Err(anyhow::anyhow!(
+                "Invalid db config for MultiProcessor {:?}",
+                config.db_config
+            )),
+        }
+    }
+
+    /// Connects the steps of a single processor to `input_receiver`, using the same pipeline as when
+    /// the processor runs on its own, and spawns a task that drains the end of the pipeline.
+    async fn spawn_child_pipeline(
+        &self,
+        child_config: IndexerProcessorConfig,
+        input_receiver: InstrumentedAsyncReceiver<TransactionContext<Vec<Transaction>>>,
+    ) -> Result<JoinHandle<()>> {
+        let processor_name = child_config.processor_config.name();
+        let output_receiver =
+            build_pipeline(child_config, self.db_pool.clone(), input_receiver).await?;
+        Ok(tokio::spawn(async move {
+            loop {
+                match output_receiver.recv().await {
+                    Ok(txn_context) => {
+                        debug!(
+                            processor_name = processor_name,
+                            "Finished processing versions [{:?}, {:?}]",
+                            txn_context.metadata.start_version,
+                            txn_context.metadata.end_version,
+                        );
+                    },
+                    Err(e) => {
+                        info!(
+                            processor_name = processor_name,
+                            "No more transactions in channel: {:?}", e
+                        );
+                        break;
+                    },
+                }
+            }
+        }))
+    }
+}
+
+/// The part of the batch from `starting_version` on, or None if the whole batch is before it. A batch
+/// that straddles the starting version of a processor is trimmed so that the processor doesn't
+/// reprocess versions it has already checkpointed.
+fn batch_from_version(
+    txn_context: &TransactionContext<Vec<Transaction>>,
+    starting_version: u64,
+) -> Option<TransactionContext<Vec<Transaction>>> {
+    if txn_context.metadata.end_version < starting_version {
+        return None;
+    }
+    if txn_context.metadata.start_version >= starting_version {
+        return Some(txn_context.clone());
+    }
+    let data: Vec<Transaction> = txn_context
+        .data
+        .iter()
+        .filter(|txn| txn.version >= starting_version)
+        .cloned()
+        .collect();
+    let mut metadata = txn_context.metadata.clone();
+    metadata.start_version = starting_version;
+    metadata.start_transaction_timestamp = data.first().and_then(|txn| txn.timestamp.clone());
+    Some(TransactionContext { data, metadata })
+}
+
+#[async_trait::async_trait]
+impl ProcessorTrait for MultiProcessor {
+    fn name(&self) -> &'static str {
+        self.config.processor_config.name()
+    }
+
+    async fn run_processor(&self) -> Result<()> {
+        //  Run migrations
+        if let DbConfig::PostgresConfig(ref postgres_config) = self.config.db_config {
+            run_migrations(
+                postgres_config.connection_string.clone(),
+                self.db_pool.clone(),
+            )
+            .await;
+        }
+
+        let multi_processor_config = match &self.config.processor_config {
+            ProcessorConfig::MultiProcessor(processor_config) => processor_config,
+            _ => return Err(anyhow::anyhow!("Processor config is wrong type")),
+        };
+
+        // Check and update the ledger chain id to ensure we're indexing the correct chain
+        let grpc_chain_id = TransactionStream::new(self.config.transaction_stream_config.clone())
+            .await?
+            .get_chain_id()
+            .await?;
+        check_or_update_chain_id(grpc_chain_id as i64, self.db_pool.clone()).await?;
+
+        // Every processor keeps its own checkpoint, so the shared stream has to start from the
+        // processor that is furthest behind. Processors that are ahead skip the versions they've seen.
+        let mut children = vec![];
+        for child_config in self.config.processor_configs() {
+            let processor_name = child_config.processor_config.name();
+            let pipeline_name = child_config.pipeline_name();
+            let starting_version =
+                get_starting_version(&child_config, self.db_pool.clone()).await?;
+            let (sender, receiver) = instrumented_bounded_channel(
+                &format!("multi_processor_{}", processor_name),
+                multi_processor_config.per_processor_buffer_size,
+            );
+            let handle = self.spawn_child_pipeline(child_config, receiver).await?;
+            info!(
+                processor_name = processor_name,
+                starting_version = starting_version,
+                "Started processor pipeline in MultiProcessor"
+            );
+            children.push(ChildProcessor {
+                name: processor_name,
+                pipeline_name,
+                starting_version,
+                sender,
+                handle,
+            });
+        }
+        let starting_version = children
+            .iter()
+            .map(|child| child.starting_version)
+            .min()
+            .unwrap_or_default();
+
+        let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+            starting_version: Some(starting_version),
+            request_ending_version: match self.config.mode {
+                ProcessorMode::Default => None,
+                ProcessorMode::Backfill => self
+                    .config
+                    .backfill_config
+                    .as_ref()
+                    .map(|c| c.ending_version),
+                ProcessorMode::Testing => self
+                    .config
+                    .testing_config
+                    .as_ref()
+                    .map(|c| c.ending_version),
+            },
+            ..self.config.transaction_stream_config.clone()
+        })
+        .await?;
+        let (_, transaction_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+            transaction_stream.into_runnable_step(),
+        )
+        .connect_to(
+            AdminControlStep::new(&self.config, multi_processor_config.channel_size)
+                .into_runnable_step(),
+            multi_processor_config.channel_size,
+        )
+        .end_and_return_output_receiver(multi_processor_config.channel_size);
+
+        // Fan each batch out to every processor. A processor whose buffer is full blocks the
+        // stream, so the others can run at most `per_processor_buffer_size` batches ahead of it.
+        let admin = admin_state();
+        loop {
+            match transaction_receiver.recv().await {
+                Ok(txn_context) => {
+                    for child in &children {
+                        let Some(batch) = batch_from_version(&txn_context, child.starting_version)
+                        else {
+                            continue;
+                        };
+                        let end_version = batch.metadata.end_version;
+                        child.sender.send(batch).await.map_err(|e| {
+                            anyhow::anyhow!(
+                                "Processor {} stopped receiving transactions: {:?}",
+                                child.name,
+                                e
+                            )
+                        })?;
+                        admin.record_processor_ingested(&child.pipeline_name, end_version);
+                    }
+                },
+                Err(e) => {
+                    info!("No more transactions in channel: {:?}", e);
+                    break;
+                },
+            }
+        }
+
+        // Closing the senders lets each processor drain its buffer and shut down.
+        for child in children {
+            drop(child.sender);
+            child.handle.await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_indexer_processor_sdk::{
+        aptos_protos::util::timestamp::Timestamp, types::transaction_context::TransactionMetadata,
+    };
+
+    fn batch(start_version: u64, end_version: u64) -> TransactionContext<Vec<Transaction>> {
+        let data = (start_version..=end_version)
+            .map(|version| Transaction {
+                version,
+                timestamp: Some(Timestamp {
+                    seconds: version as i64,
+                    nanos: 0,
+                }),
+                ..Transaction::default()
+            })
+            .collect();
+        TransactionContext {
+            data,
+            metadata: TransactionMetadata {
+                start_version,
+                end_version,
+                start_transaction_timestamp: Some(Timestamp {
+                    seconds: start_version as i64,
+                    nanos: 0,
+                }),
+                ..TransactionMetadata::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_batch_from_version() {
+        let txn_context = batch(100, 199);
+
+        assert!(batch_from_version(&txn_context, 200).is_none());
+
+        let whole_batch = batch_from_version(&txn_context, 100).unwrap();
+        assert_eq!(whole_batch.data.len(), 100);
+        assert_eq!(whole_batch.metadata.start_version, 100);
+
+        let trimmed_batch = batch_from_version(&txn_context, 150).unwrap();
+        assert_eq!(trimmed_batch.data.len(), 50);
+        assert_eq!(trimmed_batch.data[0].version, 150);
+        assert_eq!(trimmed_batch.metadata.start_version, 150);
+        assert_eq!(trimmed_batch.metadata.end_version, 199);
+        assert_eq!(
+            trimmed_batch.metadata.start_transaction_timestamp,
+            Some(Timestamp {
+                seconds: 150,
+                nanos: 0,
+            })
+        );
+    }
+}
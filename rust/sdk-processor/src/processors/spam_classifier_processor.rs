@@ -0,0 +1,253 @@
+use crate::{
+    config::{
+        db_config::DbConfig,
+        indexer_processor_config::{
+            IndexerProcessorConfig, ProcessorMode, QUERY_DEFAULT_RETRIES,
+            QUERY_DEFAULT_RETRY_DELAY_MS,
+        },
+        processor_config::{DefaultProcessorConfig, ProcessorConfig},
+    },
+    steps::{
+        common::{admin_control_step::AdminControlStep, get_processor_status_saver},
+        spam_classifier_processor::{
+            spam_assets_storer::{insert_spam_asset_overrides_query, SpamAssetsStorer},
+            spam_classifier_extractor::SpamClassifierExtractor,
+            spam_rules::SpamRules,
+        },
+    },
+    utils::{
+        chain_id::check_or_update_chain_id,
+        database::{
+            execute_in_chunks, get_config_table_chunk_size, new_db_pool, run_migrations, ArcDbPool,
+        },
+        starting_version::get_starting_version,
+    },
+};
+use ahash::AHashMap;
+use anyhow::Result;
+use aptos_indexer_processor_sdk::{
+    aptos_indexer_transaction_stream::{TransactionStream, TransactionStreamConfig},
+    builder::ProcessorBuilder,
+    common_steps::{
+        TransactionStreamStep, VersionTrackerStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+    },
+    traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+};
+use processor::db::postgres::models::spam_models::spam_assets::SpamAsset;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpamClassifierProcessorConfig {
+    #[serde(flatten)]
+    pub default_config: DefaultProcessorConfig,
+    #[serde(default)]
+    pub rules: SpamRulesConfig,
+    /// Assets to always mark as spam (true) or not spam (false), keyed by fungible asset metadata
+    /// address, coin type, or collection id. Written on startup and never overwritten by the rules.
+    #[serde(default)]
+    pub overrides: AHashMap<String, bool>,
+    #[serde(default = "SpamClassifierProcessorConfig::default_query_retries")]
+    pub query_retries: u32,
+    #[serde(default = "SpamClassifierProcessorConfig::default_query_retry_delay_ms")]
+    pub query_retry_delay_ms: u64,
+}
+
+impl SpamClassifierProcessorConfig {
+    pub const fn default_query_retries() -> u32 {
+        QUERY_DEFAULT_RETRIES
+    }
+
+    pub const fn default_query_retry_delay_ms() -> u64 {
+        QUERY_DEFAULT_RETRY_DELAY_MS
+    }
+}
+
+/// Heuristics for classifying fungible assets, coins, and collections as spam. Each heuristic an
+/// asset matches adds one to its score.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpamRulesConfig {
+    /// Regexes matched case-insensitively against names and symbols
+    #[serde(default)]
+    pub name_patterns: Vec<String>,
+    /// Domains, including their subdomains, of icon, project, and collection URIs
+    #[serde(default)]
+    pub uri_domains: Vec<String>,
+    #[serde(default)]
+    pub creator_blocklist: Vec<String>,
+    /// Distinct recipients of an asset in a single transaction that count as a mass airdrop. Only
+    /// tokens minted in the same batch are counted for collections. Disabled if unset.
+    #[serde(default)]
+    pub airdrop_min_recipients: Option<usize>,
+    /// Score at which an asset is classified as spam
+    #[serde(default = "SpamRulesConfig::default_min_score")]
+    pub min_score: usize,
+}
+
+impl SpamRulesConfig {
+    pub const fn default_min_score() -> usize {
+        1
+    }
+}
+
+impl Default for SpamRulesConfig {
+    fn default() -> Self {
+        Self {
+            name_patterns: vec![],
+            uri_domains: vec![],
+            creator_blocklist: vec![],
+            airdrop_min_recipients: None,
+            min_score: Self::default_min_score(),
+        }
+    }
+}
+
+/// Scores the fungible assets, coins, and collections seen on chain with the configured heuristics
+/// and writes the ones classified as spam to `spam_assets`.
+pub struct SpamClassifierProcessor {
+    pub config: IndexerProcessorConfig,
+    pub db_pool: ArcDbPool,
+}
+
+impl SpamClassifierProcessor {
+    pub async fn new(config: IndexerProcessorConfig) -> Result<Self> {
+        match config.db_config {
+            DbConfig::PostgresConfig(ref postgres_config) => {
+                let conn_pool = new_db_pool(
+                    &postgres_config.connection_string,
+                    Some(postgres_config.db_pool_size),
+                )
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to create connection pool for PostgresConfig: {:?}",
+                        e
+                    )
+                })?;
+
+                Ok(Self {
+                    config,
+                    db_pool: conn_pool,
+                })
+            },
+            _ => Err(anyhow::anyhow!(
+                "Invalid db config for SpamClassifierProcessor {:?}",
+                config.db_config
+            )),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessorTrait for SpamClassifierProcessor {
+    fn name(&self) -> &'static str {
+        self.config.processor_config.name()
+    }
+
+    async fn run_processor(&self) -> Result<()> {
+        //  Run migrations
+        if let DbConfig::PostgresConfig(ref postgres_config) = self.config.db_config {
+            run_migrations(
+                postgres_config.connection_string.clone(),
+                self.db_pool.clone(),
+            )
+            .await;
+        }
+
+        // Merge the starting version from config and the latest processed version from the DB
+        let starting_version = get_starting_version(&self.config, self.db_pool.clone()).await?;
+
+        // Check and update the ledger chain id to ensure we're indexing the correct chain
+        let grpc_chain_id = TransactionStream::new(self.config.transaction_stream_config.clone())
+            .await?
+            .get_chain_id()
+            .await?;
+        check_or_update_chain_id(grpc_chain_id as i64, self.db_pool.clone()).await?;
+
+        let processor_config = match &self.config.processor_config {
+            ProcessorConfig::SpamClassifierProcessor(processor_config) => processor_config,
+            _ => return Err(anyhow::anyhow!("Processor config is wrong type")),
+        };
+        let channel_size = processor_config.default_config.channel_size;
+        let rules = SpamRules::new(&processor_config.rules, &processor_config.overrides)?;
+
+        // Manual overrides take precedence over whatever the rules classified before
+        execute_in_chunks(
+            self.db_pool.clone(),
+            insert_spam_asset_overrides_query,
+            &rules.override_rows(),
+            get_config_table_chunk_size::<SpamAsset>(
+                "spam_assets",
+                &processor_config.default_config.per_table_chunk_sizes,
+            ),
+        )
+        .await?;
+
+        // Define processor steps
+        let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+            starting_version: Some(starting_version),
+            request_ending_version: match self.config.mode {
+                ProcessorMode::Default => None,
+                ProcessorMode::Backfill => self
+                    .config
+                    .backfill_config
+                    .as_ref()
+                    .map(|c| c.ending_version),
+                ProcessorMode::Testing => self
+                    .config
+                    .testing_config
+                    .as_ref()
+                    .map(|c| c.ending_version),
+            },
+            ..self.config.transaction_stream_config.clone()
+        })
+        .await?;
+        let spam_classifier_extractor = SpamClassifierExtractor::new(
+            processor_config.query_retries,
+            processor_config.query_retry_delay_ms,
+            self.db_pool.clone(),
+            rules,
+        );
+        let spam_assets_storer = SpamAssetsStorer::new(
+            self.db_pool.clone(),
+            processor_config
+                .default_config
+                .per_table_chunk_sizes
+                .clone(),
+        );
+        let version_tracker = VersionTrackerStep::new(
+            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        // Connect processor steps together
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+            transaction_stream.into_runnable_step(),
+        )
+        .connect_to(
+            AdminControlStep::new(&self.config, channel_size).into_runnable_step(),
+            channel_size,
+        )
+        .connect_to(spam_classifier_extractor.into_runnable_step(), channel_size)
+        .connect_to(spam_assets_storer.into_runnable_step(), channel_size)
+        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .end_and_return_output_receiver(channel_size);
+
+        loop {
+            match buffer_receiver.recv().await {
+                Ok(txn_context) => {
+                    debug!(
+                        "Finished processing versions [{:?}, {:?}]",
+                        txn_context.metadata.start_version, txn_context.metadata.end_version,
+                    );
+                },
+                Err(e) => {
+                    info!("No more transactions in channel: {:?}", e);
+                    break Ok(());
+                },
+            }
+        }
+    }
+}
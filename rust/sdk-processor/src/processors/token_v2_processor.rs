@@ -8,7 +8,10 @@ use crate::{
         processor_config::{DefaultProcessorConfig, ProcessorConfig},
     },
     steps::{
-        common::get_processor_status_saver,
+        common::{
+            admin_control_step::AdminControlStep, change_capture::ChangeCapture,
+            get_processor_status_saver,
+        },
         token_v2_processor::{
             token_v2_extractor::TokenV2Extractor, token_v2_storer::TokenV2Storer,
         },
@@ -22,12 +25,16 @@ use crate::{
 use anyhow::Result;
 use aptos_indexer_processor_sdk::{
     aptos_indexer_transaction_stream::{TransactionStream, TransactionStreamConfig},
+    aptos_protos::transaction::v1::Transaction,
     builder::ProcessorBuilder,
     common_steps::{
         TransactionStreamStep, VersionTrackerStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
     },
+    instrumented_channel::InstrumentedAsyncReceiver,
     traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    types::transaction_context::TransactionContext,
 };
+use processor::utils::table_flags::TableFlags;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
@@ -83,6 +90,51 @@ impl TokenV2Processor {
             )),
         }
     }
+
+    /// Connects the steps of the processor to a stream of transactions. This is how the processor
+    /// runs off its own transaction stream, and off the stream shared by the multi processor.
+    pub async fn build_pipeline(
+        &self,
+        transaction_receiver: InstrumentedAsyncReceiver<TransactionContext<Vec<Transaction>>>,
+    ) -> Result<InstrumentedAsyncReceiver<TransactionContext<()>>> {
+        let processor_config = match &self.config.processor_config {
+            ProcessorConfig::TokenV2Processor(processor_config) => processor_config,
+            _ => return Err(anyhow::anyhow!("Processor config is wrong type")),
+        };
+        let channel_size = processor_config.default_config.channel_size;
+
+        let token_v2_extractor = TokenV2Extractor::new(
+            processor_config.query_retries,
+            processor_config.query_retry_delay_ms,
+            self.db_pool.clone(),
+            self.config.address_label_registry()?,
+        );
+        let change_capture = ChangeCapture::from_config(
+            self.db_pool.clone(),
+            &processor_config.default_config.cdc_sink,
+        )
+        .await?;
+        let token_v2_storer = TokenV2Storer::new(
+            self.db_pool.clone(),
+            processor_config.clone(),
+            TableFlags::from_set(&processor_config.default_config.deprecated_tables),
+            change_capture,
+        );
+        let version_tracker = VersionTrackerStep::new(
+            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_runnable_input_receiver_first_step(
+            transaction_receiver,
+            token_v2_extractor.into_runnable_step(),
+            channel_size,
+        )
+        .connect_to(token_v2_storer.into_runnable_step(), channel_size)
+        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .end_and_return_output_receiver(channel_size);
+        Ok(buffer_receiver)
+    }
 }
 
 #[async_trait::async_trait]
@@ -136,24 +188,17 @@ impl ProcessorTrait for TokenV2Processor {
             ..self.config.transaction_stream_config.clone()
         })
         .await?;
-        let token_v2_extractor = TokenV2Extractor::new(
-            processor_config.query_retries,
-            processor_config.query_retry_delay_ms,
-            self.db_pool.clone(),
-        );
-        let token_v2_storer = TokenV2Storer::new(self.db_pool.clone(), processor_config.clone());
-        let version_tracker = VersionTrackerStep::new(
-            get_processor_status_saver(self.db_pool.clone(), self.config.clone()),
-            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
-        );
+
         // Connect processor steps together
-        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+        let (_, transaction_receiver) = ProcessorBuilder::new_with_inputless_first_step(
             transaction_stream.into_runnable_step(),
         )
-        .connect_to(token_v2_extractor.into_runnable_step(), channel_size)
-        .connect_to(token_v2_storer.into_runnable_step(), channel_size)
-        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .connect_to(
+            AdminControlStep::new(&self.config, channel_size).into_runnable_step(),
+            channel_size,
+        )
         .end_and_return_output_receiver(channel_size);
+        let buffer_receiver = self.build_pipeline(transaction_receiver).await?;
 
         loop {
             match buffer_receiver.recv().await {
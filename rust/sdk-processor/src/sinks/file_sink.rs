@@ -0,0 +1,85 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{FileSinkConfig, Sink, SinkMessage};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt, sync::Mutex};
+
+/// Appends messages to a local file, one JSON object per line. A batch is acknowledged once it is
+/// synced to disk. Meant for tests and local development.
+pub struct FileSink {
+    path: PathBuf,
+    // Keeps batches from interleaving
+    lock: Mutex<()>,
+}
+
+impl FileSink {
+    pub fn new(config: &FileSinkConfig) -> Self {
+        Self {
+            path: config.path.clone(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for FileSink {
+    async fn publish(&self, messages: &[SinkMessage]) -> Result<()> {
+        let mut lines = String::new();
+        for message in messages {
+            lines.push_str(&serde_json::to_string(message)?);
+            lines.push('\n');
+        }
+
+        let _guard = self.lock.lock().await;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .with_context(|| format!("Failed to open {:?}", self.path))?;
+        file.write_all(lines.as_bytes()).await?;
+        file.sync_all().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_sink_appends_in_order() {
+        let path =
+            std::env::temp_dir().join(format!("file_sink_test_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sink = FileSink::new(&FileSinkConfig { path: path.clone() });
+        let message = |key: &str, id: &str| SinkMessage {
+            key: key.to_string(),
+            id: id.to_string(),
+            payload: format!("{},payload", id),
+        };
+
+        sink.publish(&[message("0xa", "0xa_1"), message("0xb", "0xb_1")])
+            .await
+            .unwrap();
+        sink.publish(&[message("0xa", "0xa_2")]).await.unwrap();
+
+        let published: Vec<SinkMessage> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            published,
+            vec![
+                message("0xa", "0xa_1"),
+                message("0xb", "0xb_1"),
+                message("0xa", "0xa_2"),
+            ]
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
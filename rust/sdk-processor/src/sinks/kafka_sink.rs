@@ -0,0 +1,54 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{KafkaSinkConfig, Sink, SinkMessage};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::join_all;
+use rdkafka::{
+    producer::{FutureProducer, FutureRecord},
+    util::Timeout,
+    ClientConfig,
+};
+
+/// Produces messages keyed by the message key, so all messages with the same key land on the same
+/// partition. The idempotent producer keeps them in order on that partition across retries.
+pub struct KafkaSink {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaSink {
+    pub fn new(config: &KafkaSinkConfig) -> Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", &config.bootstrap_servers)
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .set("message.timeout.ms", config.message_timeout_ms.to_string())
+            .create()?;
+        Ok(Self {
+            producer,
+            topic: config.topic.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Sink for KafkaSink {
+    async fn publish(&self, messages: &[SinkMessage]) -> Result<()> {
+        // Messages are enqueued in order when the futures are first polled
+        let deliveries = join_all(messages.iter().map(|message| {
+            self.producer.send(
+                FutureRecord::to(&self.topic)
+                    .key(&message.key)
+                    .payload(&message.payload),
+                Timeout::Never,
+            )
+        }))
+        .await;
+        for delivery in deliveries {
+            delivery.map_err(|(e, _)| e)?;
+        }
+        Ok(())
+    }
+}
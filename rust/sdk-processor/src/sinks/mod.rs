@@ -0,0 +1,118 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Message sinks that processors can publish to instead of, or next to, Postgres.
+//!
+//! A sink returns from `publish` only once every message has been acknowledged, so a step that
+//! publishes before the version tracker gives at-least-once delivery: if publishing fails the
+//! processor stops, and restarts from the last saved version. Messages are published in order, and
+//! messages with the same key are delivered in that order.
+
+pub mod file_sink;
+#[cfg(feature = "kafka")]
+pub mod kafka_sink;
+#[cfg(feature = "nats")]
+pub mod nats_sink;
+pub mod webhook_sink;
+
+use ahash::AHashMap;
+use anyhow::Result;
+use async_trait::async_trait;
+use file_sink::FileSink;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use webhook_sink::WebhookSink;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct SinkMessage {
+    /// Messages with the same key are delivered in order, e.g. the token they are about
+    pub key: String,
+    /// Unique per message, so that consumers can drop messages that are delivered twice
+    pub id: String,
+    pub payload: String,
+}
+
+#[async_trait]
+pub trait Sink: Send + Sync {
+    /// Publishes the messages and waits until the sink has acknowledged all of them.
+    async fn publish(&self, messages: &[SinkMessage]) -> Result<()>;
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkConfig {
+    Kafka(KafkaSinkConfig),
+    Nats(NatsSinkConfig),
+    Webhook(WebhookSinkConfig),
+    File(FileSinkConfig),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct KafkaSinkConfig {
+    /// Comma separated list of brokers, e.g. `localhost:9092`
+    pub bootstrap_servers: String,
+    pub topic: String,
+    #[serde(default = "KafkaSinkConfig::default_message_timeout_ms")]
+    pub message_timeout_ms: u64,
+}
+
+impl KafkaSinkConfig {
+    pub const fn default_message_timeout_ms() -> u64 {
+        30_000
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NatsSinkConfig {
+    pub url: String,
+    /// Subject of a JetStream stream
+    pub subject: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSinkConfig {
+    pub url: String,
+    /// Extra headers to send with every request, e.g. for authentication
+    #[serde(default)]
+    pub headers: AHashMap<String, String>,
+    #[serde(default = "WebhookSinkConfig::default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl WebhookSinkConfig {
+    pub const fn default_timeout_secs() -> u64 {
+        30
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileSinkConfig {
+    pub path: PathBuf,
+}
+
+impl SinkConfig {
+    pub async fn build(&self) -> Result<Box<dyn Sink>> {
+        Ok(match self {
+            #[cfg(feature = "kafka")]
+            SinkConfig::Kafka(config) => Box::new(kafka_sink::KafkaSink::new(config)?),
+            #[cfg(not(feature = "kafka"))]
+            SinkConfig::Kafka(_) => {
+                anyhow::bail!(
+                    "Kafka sink requires sdk-processor to be built with the kafka feature"
+                )
+            },
+            #[cfg(feature = "nats")]
+            SinkConfig::Nats(config) => Box::new(nats_sink::NatsSink::new(config).await?),
+            #[cfg(not(feature = "nats"))]
+            SinkConfig::Nats(_) => {
+                anyhow::bail!("NATS sink requires sdk-processor to be built with the nats feature")
+            },
+            SinkConfig::Webhook(config) => Box::new(WebhookSink::new(config)?),
+            SinkConfig::File(config) => Box::new(FileSink::new(config)),
+        })
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{NatsSinkConfig, Sink, SinkMessage};
+use anyhow::Result;
+use async_nats::{jetstream, HeaderMap};
+use async_trait::async_trait;
+
+/// Publishes messages to a JetStream subject. Messages are sent in order over a single connection,
+/// and the message id is set as `Nats-Msg-Id` so that JetStream drops messages published twice.
+pub struct NatsSink {
+    jetstream: jetstream::Context,
+    subject: String,
+}
+
+impl NatsSink {
+    pub async fn new(config: &NatsSinkConfig) -> Result<Self> {
+        let client = async_nats::connect(&config.url).await?;
+        Ok(Self {
+            jetstream: jetstream::new(client),
+            subject: config.subject.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Sink for NatsSink {
+    async fn publish(&self, messages: &[SinkMessage]) -> Result<()> {
+        let mut acks = Vec::with_capacity(messages.len());
+        for message in messages {
+            let mut headers = HeaderMap::new();
+            headers.insert("Nats-Msg-Id", message.id.as_str());
+            acks.push(
+                self.jetstream
+                    .publish_with_headers(
+                        self.subject.clone(),
+                        headers,
+                        message.payload.clone().into(),
+                    )
+                    .await?,
+            );
+        }
+        for ack in acks {
+            ack.await?;
+        }
+        Ok(())
+    }
+}
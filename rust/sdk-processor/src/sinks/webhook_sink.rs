@@ -0,0 +1,48 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Sink, SinkMessage, WebhookSinkConfig};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::time::Duration;
+
+#[derive(Serialize)]
+struct WebhookRequest<'a> {
+    messages: &'a [SinkMessage],
+}
+
+/// POSTs each batch of messages as one JSON request. A batch is acknowledged by a 2xx response.
+pub struct WebhookSink {
+    client: reqwest::Client,
+    config: WebhookSinkConfig,
+}
+
+impl WebhookSink {
+    pub fn new(config: &WebhookSinkConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()?;
+        Ok(Self {
+            client,
+            config: config.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Sink for WebhookSink {
+    async fn publish(&self, messages: &[SinkMessage]) -> Result<()> {
+        let mut request = self.client.post(&self.config.url);
+        for (name, value) in &self.config.headers {
+            request = request.header(name, value);
+        }
+        request
+            .json(&WebhookRequest { messages })
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Webhook {} rejected batch", self.config.url))?;
+        Ok(())
+    }
+}
@@ -5,27 +5,60 @@ use aptos_indexer_processor_sdk::{
     utils::errors::ProcessorError,
 };
 use async_trait::async_trait;
-use processor::db::{
-    common::models::account_transaction_models::raw_account_transactions::RawAccountTransaction,
-    postgres::models::account_transaction_models::account_transactions::AccountTransaction,
+use processor::{
+    db::{
+        common::models::account_transaction_models::{
+            raw_account_summaries::{RawAccountActivity, RawAccountCounterparty},
+            raw_account_transactions::RawAccountTransaction,
+        },
+        postgres::models::account_transaction_models::account_transactions::AccountTransaction,
+    },
+    utils::address_labels::AddressLabelRegistry,
 };
 use rayon::prelude::*;
+use std::sync::Arc;
 
 pub struct AccountTransactionsExtractor
 where
-    Self: Sized + Send + 'static, {}
+    Self: Sized + Send + 'static,
+{
+    pub address_labels: Option<Arc<AddressLabelRegistry>>,
+}
 
 #[async_trait]
 impl Processable for AccountTransactionsExtractor {
     type Input = Vec<Transaction>;
-    type Output = Vec<AccountTransaction>;
+    type Output = (
+        Vec<AccountTransaction>,
+        Vec<RawAccountActivity>,
+        Vec<RawAccountCounterparty>,
+    );
     type RunType = AsyncRunType;
 
     async fn process(
         &mut self,
         input: TransactionContext<Vec<Transaction>>,
-    ) -> Result<Option<TransactionContext<Vec<AccountTransaction>>>, ProcessorError> {
-        let acc_txns: Vec<AccountTransaction> = input
+    ) -> Result<
+        Option<
+            TransactionContext<(
+                Vec<AccountTransaction>,
+                Vec<RawAccountActivity>,
+                Vec<RawAccountCounterparty>,
+            )>,
+        >,
+        ProcessorError,
+    > {
+        let (account_activities, account_counterparties): (Vec<_>, Vec<_>) = input
+            .data
+            .par_iter()
+            .map(RawAccountActivity::from_transaction)
+            .unzip();
+        let account_activities: Vec<RawAccountActivity> =
+            account_activities.into_iter().flatten().collect();
+        let account_counterparties: Vec<RawAccountCounterparty> =
+            account_counterparties.into_iter().flatten().collect();
+
+        let mut acc_txns: Vec<AccountTransaction> = input
             .data
             .into_par_iter()
             .map(|txn| {
@@ -36,6 +69,7 @@ impl Processable for AccountTransactionsExtractor {
                     .map(|account_address| AccountTransaction {
                         transaction_version,
                         account_address,
+                        label: None,
                     })
                     .collect()
             })
@@ -43,9 +77,12 @@ impl Processable for AccountTransactionsExtractor {
             .into_iter()
             .flatten()
             .collect();
+        if let Some(address_labels) = &self.address_labels {
+            address_labels.apply_labels(&mut acc_txns);
+        }
 
         Ok(Some(TransactionContext {
-            data: acc_txns,
+            data: (acc_txns, account_activities, account_counterparties),
             metadata: input.metadata,
         }))
     }
@@ -1,6 +1,8 @@
 use crate::{
     config::processor_config::DefaultProcessorConfig,
-    utils::database::{execute_in_chunks, get_config_table_chunk_size, ArcDbPool},
+    utils::database::{
+        execute_in_chunks, execute_with_better_error, get_config_table_chunk_size, ArcDbPool,
+    },
 };
 use ahash::AHashMap;
 use anyhow::Result;
@@ -10,12 +12,130 @@ use aptos_indexer_processor_sdk::{
     utils::errors::ProcessorError,
 };
 use async_trait::async_trait;
+use diesel::{
+    sql_query,
+    sql_types::{Array, BigInt, Nullable, Text, Timestamp},
+};
 use processor::{
-    db::postgres::models::account_transaction_models::account_transactions::AccountTransaction,
+    db::{
+        common::models::account_transaction_models::raw_account_summaries::{
+            RawAccountActivity, RawAccountCounterparty,
+        },
+        postgres::models::account_transaction_models::account_transactions::AccountTransaction,
+    },
     processors::account_transactions_processor::insert_account_transactions_query,
 };
 use tracing::debug;
 
+/// Lowers the first version of the counterparties the batch saw earlier than the stored one, which
+/// happens when batches are processed out of order.
+const UPDATE_COUNTERPARTY_FIRST_VERSIONS_QUERY: &str = "
+    UPDATE account_counterparties c
+    SET first_transaction_version = u.first_transaction_version
+    FROM (
+        SELECT account_address, counterparty_address, MIN(transaction_version) AS first_transaction_version
+        FROM unnest($1::TEXT[], $2::TEXT[], $3::BIGINT[])
+            AS u(account_address, counterparty_address, transaction_version)
+        GROUP BY account_address, counterparty_address
+    ) u
+    WHERE c.account_address = u.account_address
+        AND c.counterparty_address = u.counterparty_address
+        AND c.first_transaction_version > u.first_transaction_version
+";
+
+/// Records the activity and counterparties of the batch, and adds the ones that weren't recorded
+/// yet to `account_summaries`. Replaying a batch adds nothing, and a batch older than the stored
+/// summary can still lower its first activity.
+const UPDATE_ACCOUNT_SUMMARIES_QUERY: &str = "
+    WITH new_activities AS (
+        INSERT INTO account_activities (
+            account_address,
+            transaction_version,
+            transaction_timestamp,
+            entry_function_id_str
+        )
+        SELECT *
+        FROM unnest($1::TEXT[], $2::BIGINT[], $3::TIMESTAMP[], $4::TEXT[])
+        ON CONFLICT (account_address, transaction_version) DO NOTHING
+        RETURNING account_address, transaction_version, transaction_timestamp, entry_function_id_str
+    ),
+    new_counterparties AS (
+        INSERT INTO account_counterparties (
+            account_address,
+            counterparty_address,
+            first_transaction_version
+        )
+        SELECT account_address, counterparty_address, MIN(transaction_version)
+        FROM unnest($5::TEXT[], $6::TEXT[], $7::BIGINT[])
+            AS c(account_address, counterparty_address, transaction_version)
+        GROUP BY account_address, counterparty_address
+        ON CONFLICT (account_address, counterparty_address) DO NOTHING
+        RETURNING account_address
+    ),
+    counterparty_counts AS (
+        SELECT account_address, COUNT(*) AS num_counterparties
+        FROM new_counterparties
+        GROUP BY account_address
+    )
+    INSERT INTO account_summaries (
+        account_address,
+        first_transaction_version,
+        first_transaction_timestamp,
+        last_transaction_version,
+        last_transaction_timestamp,
+        num_transactions,
+        num_counterparties,
+        last_entry_function_id_str,
+        last_entry_function_version
+    )
+    SELECT
+        a.account_address,
+        MIN(a.transaction_version),
+        (ARRAY_AGG(a.transaction_timestamp ORDER BY a.transaction_version))[1],
+        MAX(a.transaction_version),
+        (ARRAY_AGG(a.transaction_timestamp ORDER BY a.transaction_version DESC))[1],
+        COUNT(*),
+        COALESCE(MAX(c.num_counterparties), 0),
+        (ARRAY_AGG(a.entry_function_id_str ORDER BY a.transaction_version DESC)
+            FILTER (WHERE a.entry_function_id_str IS NOT NULL))[1],
+        MAX(a.transaction_version) FILTER (WHERE a.entry_function_id_str IS NOT NULL)
+    FROM new_activities a
+    LEFT JOIN counterparty_counts c ON c.account_address = a.account_address
+    GROUP BY a.account_address
+    ON CONFLICT (account_address) DO UPDATE SET
+        first_transaction_version = LEAST(
+            account_summaries.first_transaction_version,
+            EXCLUDED.first_transaction_version
+        ),
+        first_transaction_timestamp = CASE
+            WHEN EXCLUDED.first_transaction_version < account_summaries.first_transaction_version
+                THEN EXCLUDED.first_transaction_timestamp
+            ELSE account_summaries.first_transaction_timestamp
+        END,
+        last_transaction_version = GREATEST(
+            account_summaries.last_transaction_version,
+            EXCLUDED.last_transaction_version
+        ),
+        last_transaction_timestamp = CASE
+            WHEN EXCLUDED.last_transaction_version > account_summaries.last_transaction_version
+                THEN EXCLUDED.last_transaction_timestamp
+            ELSE account_summaries.last_transaction_timestamp
+        END,
+        num_transactions = account_summaries.num_transactions + EXCLUDED.num_transactions,
+        num_counterparties = account_summaries.num_counterparties + EXCLUDED.num_counterparties,
+        last_entry_function_id_str = CASE
+            WHEN EXCLUDED.last_entry_function_version
+                > COALESCE(account_summaries.last_entry_function_version, -1)
+                THEN EXCLUDED.last_entry_function_id_str
+            ELSE account_summaries.last_entry_function_id_str
+        END,
+        last_entry_function_version = GREATEST(
+            account_summaries.last_entry_function_version,
+            EXCLUDED.last_entry_function_version
+        ),
+        inserted_at = NOW()
+";
+
 pub struct AccountTransactionsStorer
 where
     Self: Sized + Send + 'static,
@@ -35,21 +155,26 @@ impl AccountTransactionsStorer {
 
 #[async_trait]
 impl Processable for AccountTransactionsStorer {
-    type Input = Vec<AccountTransaction>;
+    type Input = (
+        Vec<AccountTransaction>,
+        Vec<RawAccountActivity>,
+        Vec<RawAccountCounterparty>,
+    );
     type Output = ();
     type RunType = AsyncRunType;
 
     async fn process(
         &mut self,
-        input: TransactionContext<Vec<AccountTransaction>>,
+        input: TransactionContext<Self::Input>,
     ) -> Result<Option<TransactionContext<()>>, ProcessorError> {
+        let (account_transactions, account_activities, account_counterparties) = input.data;
         let per_table_chunk_sizes: AHashMap<String, usize> =
             self.processor_config.per_table_chunk_sizes.clone();
 
         let res = execute_in_chunks(
             self.conn_pool.clone(),
             insert_account_transactions_query,
-            &input.data,
+            &account_transactions,
             get_config_table_chunk_size::<AccountTransaction>(
                 "account_transactions",
                 &per_table_chunk_sizes,
@@ -57,25 +182,30 @@ impl Processable for AccountTransactionsStorer {
         )
         .await;
 
-        match res {
-            Ok(_) => {
-                debug!(
-                    "Account transactions version [{}, {}] stored successfully",
-                    input.metadata.start_version, input.metadata.end_version
-                );
-                Ok(Some(TransactionContext {
-                    data: (),
-                    metadata: input.metadata,
-                }))
-            },
-            Err(e) => Err(ProcessorError::DBStoreError {
+        if let Err(e) = res {
+            return Err(ProcessorError::DBStoreError {
                 message: format!(
                     "Failed to store account transactions versions {} to {}: {:?}",
                     input.metadata.start_version, input.metadata.end_version, e,
                 ),
                 query: None,
-            }),
+            });
         }
+        update_account_summaries(
+            self.conn_pool.clone(),
+            account_activities,
+            account_counterparties,
+        )
+        .await?;
+
+        debug!(
+            "Account transactions version [{}, {}] stored successfully",
+            input.metadata.start_version, input.metadata.end_version
+        );
+        Ok(Some(TransactionContext {
+            data: (),
+            metadata: input.metadata,
+        }))
     }
 }
 
@@ -86,3 +216,56 @@ impl NamedStep for AccountTransactionsStorer {
         "AccountTransactionsStorer".to_string()
     }
 }
+
+/// Adds the activity of a batch to the account summaries. Activity and counterparties already
+/// recorded by an earlier run of the batch are skipped.
+pub async fn update_account_summaries(
+    conn_pool: ArcDbPool,
+    account_activities: Vec<RawAccountActivity>,
+    account_counterparties: Vec<RawAccountCounterparty>,
+) -> Result<(), ProcessorError> {
+    if account_activities.is_empty() {
+        return Ok(());
+    }
+    let mut account_addresses = Vec::with_capacity(account_activities.len());
+    let mut transaction_versions = Vec::with_capacity(account_activities.len());
+    let mut transaction_timestamps = Vec::with_capacity(account_activities.len());
+    let mut entry_function_id_strs = Vec::with_capacity(account_activities.len());
+    for activity in account_activities {
+        account_addresses.push(activity.account_address);
+        transaction_versions.push(activity.transaction_version);
+        transaction_timestamps.push(activity.transaction_timestamp);
+        entry_function_id_strs.push(activity.entry_function_id_str);
+    }
+    let mut counterparty_accounts = Vec::with_capacity(account_counterparties.len());
+    let mut counterparty_addresses = Vec::with_capacity(account_counterparties.len());
+    let mut counterparty_versions = Vec::with_capacity(account_counterparties.len());
+    for counterparty in account_counterparties {
+        counterparty_accounts.push(counterparty.account_address);
+        counterparty_addresses.push(counterparty.counterparty_address);
+        counterparty_versions.push(counterparty.transaction_version);
+    }
+    execute_with_better_error(
+        conn_pool.clone(),
+        sql_query(UPDATE_COUNTERPARTY_FIRST_VERSIONS_QUERY)
+            .bind::<Array<Text>, _>(counterparty_accounts.clone())
+            .bind::<Array<Text>, _>(counterparty_addresses.clone())
+            .bind::<Array<BigInt>, _>(counterparty_versions.clone()),
+        None,
+    )
+    .await?;
+    execute_with_better_error(
+        conn_pool,
+        sql_query(UPDATE_ACCOUNT_SUMMARIES_QUERY)
+            .bind::<Array<Text>, _>(account_addresses)
+            .bind::<Array<BigInt>, _>(transaction_versions)
+            .bind::<Array<Timestamp>, _>(transaction_timestamps)
+            .bind::<Array<Nullable<Text>>, _>(entry_function_id_strs)
+            .bind::<Array<Text>, _>(counterparty_accounts)
+            .bind::<Array<Text>, _>(counterparty_addresses)
+            .bind::<Array<BigInt>, _>(counterparty_versions),
+        None,
+    )
+    .await?;
+    Ok(())
+}
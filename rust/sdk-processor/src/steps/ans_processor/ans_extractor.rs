@@ -17,7 +17,9 @@ use processor::{
         postgres::models::ans_models::{
             ans_lookup::{AnsLookup, AnsPrimaryName, CurrentAnsLookup, CurrentAnsPrimaryName},
             ans_lookup_v2::{AnsLookupV2, CurrentAnsLookupV2},
+            ans_name_events::{AnsNameEvent, CurrentAnsNameExpiration},
             ans_primary_name_v2::CurrentAnsPrimaryNameV2,
+            ans_utils::NamingServiceConfig,
         },
     },
     processors::ans_processor::parse_ans,
@@ -30,6 +32,7 @@ where
 {
     deprecated_table_flags: TableFlags,
     config: AnsProcessorConfig,
+    naming_services: Vec<NamingServiceConfig>,
 }
 
 impl AnsExtractor {
@@ -49,6 +52,7 @@ impl AnsExtractor {
 
         Ok(Self {
             deprecated_table_flags,
+            naming_services: processor_config.naming_services()?,
             config: processor_config,
         })
     }
@@ -65,6 +69,8 @@ impl Processable for AnsExtractor {
         Vec<CurrentAnsLookupV2>,
         Vec<AnsLookupV2>,
         Vec<CurrentAnsPrimaryNameV2>,
+        Vec<AnsNameEvent>,
+        Vec<CurrentAnsNameExpiration>,
     );
     type RunType = AsyncRunType;
 
@@ -81,6 +87,8 @@ impl Processable for AnsExtractor {
                 Vec<CurrentAnsLookupV2>,
                 Vec<AnsLookupV2>,
                 Vec<CurrentAnsPrimaryNameV2>,
+                Vec<AnsNameEvent>,
+                Vec<CurrentAnsNameExpiration>,
             )>,
         >,
         ProcessorError,
@@ -98,7 +106,7 @@ impl Processable for AnsExtractor {
             &input.data,
             self.config.ans_v1_primary_names_table_handle.clone(),
             self.config.ans_v1_name_records_table_handle.clone(),
-            self.config.ans_v2_contract_address.clone(),
+            &self.naming_services,
         );
 
         if self
@@ -139,6 +147,10 @@ impl Processable for AnsExtractor {
                 .map(CurrentAnsPrimaryNameV2::from_raw)
                 .collect();
 
+        let ans_name_events = AnsNameEvent::from_transactions(&input.data, &self.naming_services);
+        let current_ans_name_expirations =
+            CurrentAnsNameExpiration::from_name_events(&ans_name_events, &self.naming_services);
+
         Ok(Some(TransactionContext {
             data: (
                 all_current_ans_lookups,
@@ -148,6 +160,8 @@ impl Processable for AnsExtractor {
                 postgres_current_ans_lookups_v2,
                 postgres_ans_lookups_v2,
                 postgres_current_ans_primary_names_v2,
+                ans_name_events,
+                current_ans_name_expirations,
             ),
             metadata: input.metadata,
         }))
@@ -1,19 +1,29 @@
 use crate::{
     processors::ans_processor::AnsProcessorConfig,
-    utils::database::{execute_in_chunks, get_config_table_chunk_size, ArcDbPool},
+    utils::database::{
+        execute_in_chunks, execute_with_better_error, get_config_table_chunk_size, ArcDbPool,
+    },
 };
 use ahash::AHashMap;
 use anyhow::Result;
 use aptos_indexer_processor_sdk::{
     traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
     types::transaction_context::TransactionContext,
-    utils::errors::ProcessorError,
+    utils::{errors::ProcessorError, time::parse_timestamp},
 };
 use async_trait::async_trait;
+use diesel::{
+    pg::{upsert::excluded, Pg},
+    query_builder::QueryFragment,
+    sql_query,
+    sql_types::{Array, BigInt, Text, Timestamp},
+    ExpressionMethods,
+};
 use processor::{
     db::postgres::models::ans_models::{
         ans_lookup::{AnsLookup, AnsPrimaryName, CurrentAnsLookup, CurrentAnsPrimaryName},
         ans_lookup_v2::{AnsLookupV2, CurrentAnsLookupV2},
+        ans_name_events::{AnsNameEvent, CurrentAnsNameExpiration},
         ans_primary_name_v2::CurrentAnsPrimaryNameV2,
     },
     processors::ans_processor::{
@@ -21,8 +31,103 @@ use processor::{
         insert_current_ans_lookups_query, insert_current_ans_lookups_v2_query,
         insert_current_ans_primary_names_query, insert_current_ans_primary_names_v2_query,
     },
+    schema,
 };
 
+/// Records the expiry and the end of the grace period of names in `ans_name_events` once they've
+/// passed. A name registered or renewed in the batch is only checked up to its first such event,
+/// and what passed by then is recorded just before that event. Other names are checked up to the
+/// end of the batch and recorded at its last version. The rows get negative event indexes, the
+/// earliest lowest, so they sort before the events of the transaction they're recorded at.
+const RECORD_NAME_EXPIRIES_QUERY: &str = "
+    WITH batch_names AS (
+        SELECT *
+        FROM unnest($1::TEXT[], $2::TEXT[], $3::BIGINT[], $4::TIMESTAMP[])
+            AS b(namespace, token_name, transaction_version, transaction_timestamp)
+    ),
+    due AS (
+        SELECT
+            e.*,
+            COALESCE(b.transaction_version, $5) AS recorded_version,
+            COALESCE(b.transaction_timestamp, $6) AS cutoff_timestamp
+        FROM current_ans_name_expirations e
+        LEFT JOIN batch_names b ON b.namespace = e.namespace AND b.token_name = e.token_name
+        WHERE e.next_event_timestamp <= $6
+            AND e.next_event_timestamp <= COALESCE(b.transaction_timestamp, $6)
+    ),
+    name_events AS (
+        SELECT *, 'expire' AS event_type, expiration_timestamp AS event_timestamp
+        FROM due
+        WHERE next_event_type = 'expire'
+        UNION ALL
+        SELECT *, 'grace_period_end', grace_period_end_timestamp
+        FROM due
+        WHERE grace_period_end_timestamp <= cutoff_timestamp
+    ),
+    inserted AS (
+        INSERT INTO ans_name_events (
+            transaction_version,
+            event_index,
+            name_object_address,
+            domain,
+            subdomain,
+            token_name,
+            event_type,
+            expiration_timestamp,
+            transaction_timestamp,
+            namespace
+        )
+        SELECT
+            recorded_version,
+            -ROW_NUMBER() OVER (
+                PARTITION BY recorded_version
+                ORDER BY event_timestamp DESC, namespace DESC, token_name DESC, event_type
+            ),
+            name_object_address,
+            domain,
+            subdomain,
+            token_name,
+            event_type,
+            expiration_timestamp,
+            event_timestamp,
+            namespace
+        FROM name_events
+        ON CONFLICT (transaction_version, event_index) DO NOTHING
+    )
+    UPDATE current_ans_name_expirations e
+    SET
+        next_event_type = CASE
+            WHEN d.grace_period_end_timestamp <= d.cutoff_timestamp THEN NULL
+            ELSE 'grace_period_end'
+        END,
+        next_event_timestamp = CASE
+            WHEN d.grace_period_end_timestamp <= d.cutoff_timestamp THEN NULL
+            ELSE d.grace_period_end_timestamp
+        END,
+        inserted_at = NOW()
+    FROM due d
+    WHERE e.namespace = d.namespace AND e.token_name = d.token_name
+";
+
+/// Fills in how far each renewal of the batch moved the name's expiration, compared with the
+/// latest earlier registration or renewal of the name.
+const UPDATE_RENEWAL_DURATIONS_QUERY: &str = "
+    UPDATE ans_name_events e
+    SET renewal_duration_secs = (
+        SELECT EXTRACT(EPOCH FROM e.expiration_timestamp - p.expiration_timestamp)::BIGINT
+        FROM ans_name_events p
+        WHERE p.namespace = e.namespace
+            AND p.token_name = e.token_name
+            AND p.expiration_timestamp IS NOT NULL
+            AND (p.transaction_version, p.event_index) < (e.transaction_version, e.event_index)
+        ORDER BY p.transaction_version DESC, p.event_index DESC
+        LIMIT 1
+    )
+    WHERE e.event_type = 'renew'
+        AND e.transaction_version BETWEEN $1 AND $2
+        AND e.renewal_duration_secs IS NULL
+";
+
 pub struct AnsStorer
 where
     Self: Sized + Send + 'static,
@@ -50,6 +155,8 @@ impl Processable for AnsStorer {
         Vec<CurrentAnsLookupV2>,
         Vec<AnsLookupV2>,
         Vec<CurrentAnsPrimaryNameV2>,
+        Vec<AnsNameEvent>,
+        Vec<CurrentAnsNameExpiration>,
     );
     type Output = ();
     type RunType = AsyncRunType;
@@ -64,6 +171,8 @@ impl Processable for AnsStorer {
             Vec<CurrentAnsLookupV2>,
             Vec<AnsLookupV2>,
             Vec<CurrentAnsPrimaryNameV2>,
+            Vec<AnsNameEvent>,
+            Vec<CurrentAnsNameExpiration>,
         )>,
     ) -> Result<Option<TransactionContext<()>>, ProcessorError> {
         let (
@@ -74,6 +183,8 @@ impl Processable for AnsStorer {
             current_ans_lookups_v2,
             ans_lookups_v2,
             current_ans_primary_names_v2,
+            ans_name_events,
+            current_ans_name_expirations,
         ) = input.data;
 
         let per_table_chunk_sizes: AHashMap<String, usize> =
@@ -137,7 +248,44 @@ impl Processable for AnsStorer {
             ),
         );
 
-        futures::try_join!(cal, al, capn, apn, cal_v2, al_v2, capn_v2)?;
+        let ane = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_ans_name_events_query,
+            &ans_name_events,
+            get_config_table_chunk_size::<AnsNameEvent>("ans_name_events", &per_table_chunk_sizes),
+        );
+
+        futures::try_join!(cal, al, capn, apn, cal_v2, al_v2, capn_v2, ane)?;
+
+        // The expiries that passed are recorded from the expirations before the batch's
+        // registrations and renewals replace them
+        if let Some(end_timestamp) = input.metadata.end_transaction_timestamp.as_ref() {
+            record_name_expiries(
+                self.conn_pool.clone(),
+                &ans_name_events,
+                input.metadata.end_version as i64,
+                parse_timestamp(end_timestamp, input.metadata.end_version as i64).naive_utc(),
+            )
+            .await?;
+        }
+        execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_current_ans_name_expirations_query,
+            &current_ans_name_expirations,
+            get_config_table_chunk_size::<CurrentAnsNameExpiration>(
+                "current_ans_name_expirations",
+                &per_table_chunk_sizes,
+            ),
+        )
+        .await?;
+        execute_with_better_error(
+            self.conn_pool.clone(),
+            sql_query(UPDATE_RENEWAL_DURATIONS_QUERY)
+                .bind::<BigInt, _>(input.metadata.start_version as i64)
+                .bind::<BigInt, _>(input.metadata.end_version as i64),
+            None,
+        )
+        .await?;
 
         Ok(Some(TransactionContext {
             data: (),
@@ -153,3 +301,88 @@ impl NamedStep for AnsStorer {
         "AnsStorer".to_string()
     }
 }
+
+fn insert_ans_name_events_query(
+    items_to_insert: Vec<AnsNameEvent>,
+) -> (
+    impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
+    Option<&'static str>,
+) {
+    use schema::ans_name_events::dsl::*;
+    (
+        diesel::insert_into(schema::ans_name_events::table)
+            .values(items_to_insert)
+            .on_conflict((transaction_version, event_index))
+            .do_nothing(),
+        None,
+    )
+}
+
+fn insert_current_ans_name_expirations_query(
+    items_to_insert: Vec<CurrentAnsNameExpiration>,
+) -> (
+    impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
+    Option<&'static str>,
+) {
+    use schema::current_ans_name_expirations::dsl::*;
+    (
+        diesel::insert_into(schema::current_ans_name_expirations::table)
+            .values(items_to_insert)
+            .on_conflict((token_name, namespace))
+            .do_update()
+            .set((
+                name_object_address.eq(excluded(name_object_address)),
+                domain.eq(excluded(domain)),
+                subdomain.eq(excluded(subdomain)),
+                expiration_timestamp.eq(excluded(expiration_timestamp)),
+                grace_period_end_timestamp.eq(excluded(grace_period_end_timestamp)),
+                next_event_type.eq(excluded(next_event_type)),
+                next_event_timestamp.eq(excluded(next_event_timestamp)),
+                last_transaction_version.eq(excluded(last_transaction_version)),
+                inserted_at.eq(excluded(inserted_at)),
+            )),
+        Some(" WHERE current_ans_name_expirations.last_transaction_version <= excluded.last_transaction_version "),
+    )
+}
+
+/// Records the expiries that passed by the end of the batch, checking each name registered or
+/// renewed in the batch only up to its first registration or renewal.
+async fn record_name_expiries(
+    conn_pool: ArcDbPool,
+    ans_name_events: &[AnsNameEvent],
+    end_version: i64,
+    end_timestamp: chrono::NaiveDateTime,
+) -> Result<(), ProcessorError> {
+    let mut first_events: AHashMap<(&String, &String), &AnsNameEvent> = AHashMap::new();
+    for name_event in ans_name_events
+        .iter()
+        .filter(|name_event| name_event.expiration_timestamp.is_some())
+    {
+        first_events
+            .entry((&name_event.namespace, &name_event.token_name))
+            .or_insert(name_event);
+    }
+    let mut namespaces = Vec::with_capacity(first_events.len());
+    let mut token_names = Vec::with_capacity(first_events.len());
+    let mut transaction_versions = Vec::with_capacity(first_events.len());
+    let mut transaction_timestamps = Vec::with_capacity(first_events.len());
+    for name_event in first_events.into_values() {
+        namespaces.push(name_event.namespace.clone());
+        token_names.push(name_event.token_name.clone());
+        transaction_versions.push(name_event.transaction_version);
+        transaction_timestamps.push(name_event.transaction_timestamp);
+    }
+    execute_with_better_error(
+        conn_pool,
+        sql_query(RECORD_NAME_EXPIRIES_QUERY)
+            .bind::<Array<Text>, _>(namespaces)
+            .bind::<Array<Text>, _>(token_names)
+            .bind::<Array<BigInt>, _>(transaction_versions)
+            .bind::<Array<Timestamp>, _>(transaction_timestamps)
+            .bind::<BigInt, _>(end_version)
+            .bind::<Timestamp, _>(end_timestamp),
+        None,
+    )
+    .await?;
+    Ok(())
+}
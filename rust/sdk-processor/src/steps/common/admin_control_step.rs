@@ -0,0 +1,74 @@
+use crate::config::{
+    indexer_processor_config::IndexerProcessorConfig, processor_config::ProcessorConfig,
+};
+use aptos_indexer_processor_sdk::{
+    aptos_protos::transaction::v1::Transaction,
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use server_framework::admin::{admin_state, AdminState};
+use std::sync::Arc;
+
+/// `AdminControlStep` lets the transactions of the stream into the pipeline, holding them while
+/// ingestion is paused through the admin API. It also records the pipeline shown by the admin API,
+/// with the processors it feeds.
+pub struct AdminControlStep
+where
+    Self: Sized + Send + 'static,
+{
+    admin: Arc<AdminState>,
+    // The processor fed by every batch, unless the batches are fanned out by a multi processor,
+    // which records what each of its processors is fed
+    pipeline_name: Option<String>,
+}
+
+impl AdminControlStep {
+    pub fn new(config: &IndexerProcessorConfig, channel_size: usize) -> Self {
+        let admin = admin_state();
+        admin.start_pipeline(config.processor_config.name(), channel_size);
+        for processor_config in config.processor_configs() {
+            admin.add_processor(
+                &processor_config.pipeline_name(),
+                processor_config.processor_config.is_parquet(),
+            );
+        }
+        let pipeline_name = match config.processor_config {
+            ProcessorConfig::MultiProcessor(_) => None,
+            _ => Some(config.pipeline_name()),
+        };
+        Self {
+            admin,
+            pipeline_name,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for AdminControlStep {
+    type Input = Vec<Transaction>;
+    type Output = Vec<Transaction>;
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        item: TransactionContext<Vec<Transaction>>,
+    ) -> Result<Option<TransactionContext<Vec<Transaction>>>, ProcessorError> {
+        self.admin.wait_while_paused().await;
+        self.admin.record_ingested(item.metadata.end_version);
+        if let Some(pipeline_name) = &self.pipeline_name {
+            self.admin
+                .record_processor_ingested(pipeline_name, item.metadata.end_version);
+        }
+        Ok(Some(item))
+    }
+}
+
+impl AsyncStep for AdminControlStep {}
+
+impl NamedStep for AdminControlStep {
+    fn name(&self) -> String {
+        "AdminControlStep".to_string()
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Change events for current table upserts, published to a sink so that services can subscribe to
+//! changes instead of polling the current tables.
+//!
+//! A storer snapshots the current rows of the keys it is about to upsert, writes the batch, and then
+//! publishes an event for every row the batch changed with its old and new value. The rows are read
+//! back as JSON after the write, so the conditional upserts decide which rows changed, and the old and
+//! new values have the same shape. The event is published before the batch reaches the version
+//! tracker, so it is delivered at least once. If the processor stops after writing a batch but before
+//! publishing it, the old values are gone when the batch is replayed. Those events are published with
+//! `replayed` set and without an old value.
+
+use crate::{
+    sinks::{Sink, SinkConfig, SinkMessage},
+    utils::database::ArcDbPool,
+};
+use ahash::AHashMap;
+use anyhow::{Context, Result};
+use diesel::{sql_query, sql_types::Text, QueryableByName};
+use diesel_async::RunQueryDsl;
+use processor::db::postgres::models::{
+    fungible_asset_models::v2_fungible_asset_balances::CurrentUnifiedFungibleAssetBalance,
+    object_models::v2_objects::CurrentObject,
+    token_v2_models::v2_token_ownerships::CurrentTokenOwnershipV2,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// A current table whose upserts can be captured.
+pub trait CapturedTable {
+    const TABLE_NAME: &'static str;
+    /// Primary key columns and their Postgres types
+    const KEY_COLUMNS: &'static [(&'static str, &'static str)];
+
+    /// Primary key of the row, formatted the way Postgres formats the key columns as text.
+    fn key_values(&self) -> Vec<String>;
+}
+
+impl CapturedTable for CurrentTokenOwnershipV2 {
+    const KEY_COLUMNS: &'static [(&'static str, &'static str)] = &[
+        ("token_data_id", "text"),
+        ("property_version_v1", "numeric"),
+        ("owner_address", "text"),
+        ("storage_id", "text"),
+    ];
+    const TABLE_NAME: &'static str = "current_token_ownerships_v2";
+
+    fn key_values(&self) -> Vec<String> {
+        vec![
+            self.token_data_id.clone(),
+            self.property_version_v1.to_string(),
+            self.owner_address.clone(),
+            self.storage_id.clone(),
+        ]
+    }
+}
+
+impl CapturedTable for CurrentUnifiedFungibleAssetBalance {
+    const KEY_COLUMNS: &'static [(&'static str, &'static str)] = &[("storage_id", "text")];
+    const TABLE_NAME: &'static str = "current_fungible_asset_balances";
+
+    fn key_values(&self) -> Vec<String> {
+        vec![self.storage_id.clone()]
+    }
+}
+
+impl CapturedTable for CurrentObject {
+    const KEY_COLUMNS: &'static [(&'static str, &'static str)] = &[("object_address", "text")];
+    const TABLE_NAME: &'static str = "current_objects";
+
+    fn key_values(&self) -> Vec<String> {
+        vec![self.object_address.clone()]
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ChangeEvent {
+    pub table: &'static str,
+    pub key: Map<String, Value>,
+    /// Version of the transaction that made the change
+    pub transaction_version: i64,
+    /// None if the row didn't exist before, or if the event is replayed
+    pub old: Option<Value>,
+    pub new: Value,
+    /// The batch was written before, so the old value is unknown
+    pub replayed: bool,
+}
+
+/// Current rows of the keys a storer is about to upsert.
+pub struct TableSnapshot {
+    table: &'static str,
+    key_columns: &'static [(&'static str, &'static str)],
+    keys: Vec<Vec<String>>,
+    rows: AHashMap<Vec<String>, Value>,
+}
+
+#[derive(QueryableByName)]
+struct JsonRow {
+    #[diesel(sql_type = Text)]
+    row: String,
+}
+
+pub struct ChangeCapture {
+    conn_pool: ArcDbPool,
+    sink: Box<dyn Sink>,
+}
+
+impl ChangeCapture {
+    /// Change capture for a storer, if the processor has a CDC sink configured.
+    pub async fn from_config(
+        conn_pool: ArcDbPool,
+        cdc_sink: &Option<SinkConfig>,
+    ) -> Result<Option<Self>> {
+        match cdc_sink {
+            Some(sink_config) => Ok(Some(Self {
+                conn_pool,
+                sink: sink_config.build().await?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn snapshot<'a, T: CapturedTable + 'a>(
+        &self,
+        rows: impl IntoIterator<Item = &'a T>,
+    ) -> Result<TableSnapshot> {
+        let mut keys: Vec<Vec<String>> = rows.into_iter().map(|row| row.key_values()).collect();
+        keys.sort();
+        keys.dedup();
+        let rows = self.load_rows(T::TABLE_NAME, T::KEY_COLUMNS, &keys).await?;
+        Ok(TableSnapshot {
+            table: T::TABLE_NAME,
+            key_columns: T::KEY_COLUMNS,
+            keys,
+            rows,
+        })
+    }
+
+    /// Publishes an event for every row of the snapshot that was changed by the batch of versions
+    /// `start_version` to `end_version`.
+    pub async fn publish_changes(
+        &self,
+        snapshot: TableSnapshot,
+        start_version: u64,
+        end_version: u64,
+    ) -> Result<()> {
+        let new_rows = self
+            .load_rows(snapshot.table, snapshot.key_columns, &snapshot.keys)
+            .await?;
+        let events = change_events(&snapshot, &new_rows, start_version, end_version);
+        let messages = events
+            .into_iter()
+            .map(|(key_values, event)| {
+                let key = format!("{}:{}", event.table, key_values.join(":"));
+                Ok(SinkMessage {
+                    id: format!("{}:{}", key, event.transaction_version),
+                    key,
+                    payload: serde_json::to_string(&event)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if messages.is_empty() {
+            return Ok(());
+        }
+        self.sink.publish(&messages).await
+    }
+
+    async fn load_rows(
+        &self,
+        table: &'static str,
+        key_columns: &'static [(&'static str, &'static str)],
+        keys: &[Vec<String>],
+    ) -> Result<AHashMap<Vec<String>, Value>> {
+        if keys.is_empty() {
+            return Ok(AHashMap::new());
+        }
+        let key_records: Vec<Map<String, Value>> = keys
+            .iter()
+            .map(|key_values| {
+                key_columns
+                    .iter()
+                    .zip(key_values)
+                    .map(|((column, _), value)| (column.to_string(), Value::from(value.clone())))
+                    .collect()
+            })
+            .collect();
+        let column_names: Vec<&str> = key_columns.iter().map(|(column, _)| *column).collect();
+        let column_definitions: Vec<String> = key_columns
+            .iter()
+            .map(|(column, column_type)| format!("{} {}", column, column_type))
+            .collect();
+        // Joining on the key columns lets Postgres use the primary key index
+        let query = format!(
+            "SELECT row_to_json(t)::text AS row
+            FROM {table} t
+            JOIN jsonb_to_recordset($1::jsonb) AS k({definitions}) USING ({columns})",
+            definitions = column_definitions.join(", "),
+            columns = column_names.join(", "),
+        );
+        let mut conn = self.conn_pool.get().await?;
+        let rows: Vec<JsonRow> = sql_query(query)
+            .bind::<Text, _>(serde_json::to_string(&key_records)?)
+            .load(&mut conn)
+            .await
+            .with_context(|| format!("Failed to load current rows of {}", table))?;
+
+        let mut rows_by_key = AHashMap::new();
+        for row in rows {
+            let mut value: Value = serde_json::from_str(&row.row)?;
+            if let Value::Object(columns) = &mut value {
+                // Not part of the row's state
+                columns.remove("inserted_at");
+            }
+            let key_values = column_names
+                .iter()
+                .map(|column| match &value[*column] {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect();
+            rows_by_key.insert(key_values, value);
+        }
+        Ok(rows_by_key)
+    }
+}
+
+fn row_version(row: &Value) -> Option<i64> {
+    row["last_transaction_version"].as_i64()
+}
+
+/// Change events for the rows of the snapshot whose latest version after the write is within the
+/// batch, in key order.
+fn change_events(
+    snapshot: &TableSnapshot,
+    new_rows: &AHashMap<Vec<String>, Value>,
+    start_version: u64,
+    end_version: u64,
+) -> Vec<(Vec<String>, ChangeEvent)> {
+    let in_batch = |version: i64| (start_version as i64..=end_version as i64).contains(&version);
+    let mut events = vec![];
+    for key_values in &snapshot.keys {
+        let Some(new) = new_rows.get(key_values) else {
+            continue;
+        };
+        let Some(transaction_version) = row_version(new).filter(|v| in_batch(*v)) else {
+            continue;
+        };
+        let old = snapshot.rows.get(key_values);
+        let replayed = old.and_then(row_version).is_some_and(in_batch);
+        if !replayed && old == Some(new) {
+            continue;
+        }
+        let key = snapshot
+            .key_columns
+            .iter()
+            .map(|(column, _)| (column.to_string(), new[*column].clone()))
+            .collect();
+        events.push((
+            key_values.clone(),
+            ChangeEvent {
+                table: snapshot.table,
+                key,
+                transaction_version,
+                old: if replayed { None } else { old.cloned() },
+                new: new.clone(),
+                replayed,
+            },
+        ));
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_change_events() {
+        let key = |address: &str| vec![address.to_string()];
+        let snapshot = TableSnapshot {
+            table: CurrentObject::TABLE_NAME,
+            key_columns: CurrentObject::KEY_COLUMNS,
+            keys: vec![key("0x1"), key("0x2"), key("0x3"), key("0x4")],
+            rows: AHashMap::from([
+                (
+                    key("0x1"),
+                    json!({"object_address": "0x1", "owner_address": "0xa", "last_transaction_version": 5}),
+                ),
+                (
+                    key("0x3"),
+                    json!({"object_address": "0x3", "owner_address": "0xa", "last_transaction_version": 12}),
+                ),
+                (
+                    key("0x4"),
+                    json!({"object_address": "0x4", "owner_address": "0xa", "last_transaction_version": 20}),
+                ),
+            ]),
+        };
+        let new_rows = AHashMap::from([
+            // Transferred in the batch
+            (
+                key("0x1"),
+                json!({"object_address": "0x1", "owner_address": "0xb", "last_transaction_version": 11}),
+            ),
+            // Created in the batch
+            (
+                key("0x2"),
+                json!({"object_address": "0x2", "owner_address": "0xb", "last_transaction_version": 12}),
+            ),
+            // Written by an earlier attempt of the batch
+            (
+                key("0x3"),
+                json!({"object_address": "0x3", "owner_address": "0xa", "last_transaction_version": 12}),
+            ),
+            // Newer than the batch, so the upsert was skipped
+            (
+                key("0x4"),
+                json!({"object_address": "0x4", "owner_address": "0xa", "last_transaction_version": 20}),
+            ),
+        ]);
+
+        let events = change_events(&snapshot, &new_rows, 10, 15);
+        let summary: Vec<_> = events
+            .iter()
+            .map(|(key_values, event)| {
+                (
+                    key_values[0].as_str(),
+                    event.transaction_version,
+                    event.old.is_some(),
+                    event.replayed,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("0x1", 11, true, false),
+                ("0x2", 12, false, false),
+                ("0x3", 12, false, true),
+            ]
+        );
+        assert_eq!(
+            events[0].1.key,
+            json!({"object_address": "0x1"})
+                .as_object()
+                .unwrap()
+                .clone()
+        );
+        assert_eq!(events[0].1.old.as_ref().unwrap()["owner_address"], "0xa");
+        assert_eq!(events[0].1.new["owner_address"], "0xb");
+    }
+}
@@ -1,3 +1,5 @@
+pub mod admin_control_step;
+pub mod change_capture;
 pub mod gcs_uploader;
 pub mod parquet_buffer_step;
 pub mod parquet_version_tracker_step;
@@ -3,6 +3,7 @@ use crate::parquet_processors::ParquetTypeTrait;
 use crate::{
     parquet_processors::{ParquetTypeEnum, ParquetTypeStructs},
     steps::common::gcs_uploader::{GCSUploader, Uploadable},
+    utils::database::ArcDbPool,
 };
 use anyhow::Result;
 use aptos_indexer_processor_sdk::{
@@ -13,8 +14,19 @@ use aptos_indexer_processor_sdk::{
     utils::errors::ProcessorError,
 };
 use async_trait::async_trait;
-use std::{collections::HashMap, time::Duration};
-use tracing::debug;
+use processor::db::postgres::models::processor_gaps::ProcessorGap;
+use server_framework::admin::{admin_state, AdminState};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::{debug, info, warn};
+
+pub const PARQUET_BUFFER_STEP_NAME: &str = "ParquetBufferStep";
+
+/// How often flushes requested through the admin API are checked for.
+const FLUSH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 
 /// `ParquetBuffer` is a struct that holds `ParquetTypeStructs` data
 /// and tracks the buffer size in bytes, along with metadata about the data in the buffer.
@@ -35,19 +47,31 @@ impl ParquetBuffer {
 
     /// Updates the metadata of the internal buffer with the latest information from `cur_batch_metadata`.
     /// This is used to track the end version and timestamp of the batch data in the buffer.
+    ///
+    /// If `allow_gaps` is set, versions missing between the buffer and the current batch are returned
+    /// instead of failing, so that they can be repaired.
     pub fn update_current_batch_metadata(
         &mut self,
         cur_batch_metadata: &TransactionMetadata,
-    ) -> Result<(), ProcessorError> {
+        allow_gaps: bool,
+    ) -> Result<Option<(u64, u64)>, ProcessorError> {
+        let mut gap = None;
         if let Some(buffer_metadata) = &mut self.current_batch_metadata {
             if buffer_metadata.end_version + 1 != cur_batch_metadata.start_version {
-                // this shouldn't happen but if it does, we want to know
-                return Err(ProcessorError::ProcessError {
-                    message: format!(
-                        "Gap founded: Buffer metadata end_version mismatch: {} != {}",
-                        buffer_metadata.end_version, cur_batch_metadata.start_version
-                    ),
-                });
+                if !allow_gaps || buffer_metadata.end_version + 1 > cur_batch_metadata.start_version
+                {
+                    // this shouldn't happen but if it does, we want to know
+                    return Err(ProcessorError::ProcessError {
+                        message: format!(
+                            "Gap founded: Buffer metadata end_version mismatch: {} != {}",
+                            buffer_metadata.end_version, cur_batch_metadata.start_version
+                        ),
+                    });
+                }
+                gap = Some((
+                    buffer_metadata.end_version + 1,
+                    cur_batch_metadata.start_version - 1,
+                ));
             }
 
             // Update metadata fields with the current batch's end information
@@ -59,7 +83,7 @@ impl ParquetBuffer {
             // Initialize the metadata with the current batch's start information
             self.current_batch_metadata = Some(cur_batch_metadata.clone());
         }
-        Ok(())
+        Ok(gap)
     }
 }
 
@@ -69,6 +93,8 @@ impl ParquetBuffer {
 /// This step is typically used to manage large data volumes efficiently by buffering and uploading
 /// only when necessary.
 ///
+/// Buffers are also uploaded every `poll_interval`, or sooner when a flush is requested through the
+/// admin API.
 ///
 /// # Type Parameters
 /// - `U`: A type that implements the `Uploadable` trait, providing the uploading functionality.
@@ -77,6 +103,12 @@ pub struct ParquetBufferStep {
     pub poll_interval: Duration,
     pub buffer_uploader: GCSUploader,
     pub buffer_max_size: usize,
+    // If set, gaps between batches are recorded in `processor_gaps` for repair instead of failing the step
+    pub gap_repair_pool: Option<ArcDbPool>,
+    // Name the processor reports to the admin API under, the processor name by default
+    pub pipeline_name: String,
+    admin: Arc<AdminState>,
+    last_upload: Instant,
 }
 
 impl ParquetBufferStep {
@@ -88,11 +120,25 @@ impl ParquetBufferStep {
         Self {
             internal_buffers: HashMap::new(),
             poll_interval,
+            pipeline_name: buffer_uploader.processor_name.clone(),
             buffer_uploader,
             buffer_max_size,
+            gap_repair_pool: None,
+            admin: admin_state(),
+            last_upload: Instant::now(),
         }
     }
 
+    pub fn with_gap_repair(mut self, db_pool: ArcDbPool) -> Self {
+        self.gap_repair_pool = Some(db_pool);
+        self
+    }
+
+    pub fn with_pipeline_name(mut self, pipeline_name: String) -> Self {
+        self.pipeline_name = pipeline_name;
+        self
+    }
+
     fn append_to_buffer(
         buffer: &mut ParquetBuffer,
         parquet_data: ParquetTypeStructs,
@@ -156,7 +202,29 @@ impl ParquetBufferStep {
 
         // Append new data to the buffer
         Self::append_to_buffer(buffer, parquet_data)?;
-        buffer.update_current_batch_metadata(cur_batch_metadata)?;
+        let gap = buffer
+            .update_current_batch_metadata(cur_batch_metadata, self.gap_repair_pool.is_some())?;
+        if let (Some((gap_start_version, gap_end_version)), Some(db_pool)) =
+            (gap, &self.gap_repair_pool)
+        {
+            warn!(
+                processor_name = self.buffer_uploader.processor_name.as_str(),
+                gap_start_version = gap_start_version,
+                gap_end_version = gap_end_version,
+                "Gap found between batches, recording it for repair",
+            );
+            ProcessorGap::record(
+                db_pool.clone(),
+                &self.buffer_uploader.processor_name,
+                gap_start_version,
+                gap_end_version,
+            )
+            .await
+            .map_err(|e| ProcessorError::DBStoreError {
+                message: format!("Failed to record gap: {:?}", e),
+                query: None,
+            })?;
+        }
 
         debug!(
             "Updated buffer size for {:?}: {} bytes",
@@ -181,6 +249,8 @@ impl Processable for ParquetBufferStep {
         item: TransactionContext<Self::Input>,
     ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
         debug!("Starting process for {} data items", item.data.len());
+        self.admin
+            .record_step(&self.pipeline_name, &self.name(), item.metadata.end_version);
 
         let mut upload_metadata_map = HashMap::new();
         for (parquet_type, parquet_data) in item.data {
@@ -244,14 +314,20 @@ impl Processable for ParquetBufferStep {
 #[async_trait]
 impl PollableAsyncStep for ParquetBufferStep {
     fn poll_interval(&self) -> Duration {
-        self.poll_interval
+        self.poll_interval.min(FLUSH_CHECK_INTERVAL)
     }
 
-    /// Polls all buffers to check if any should be uploaded based on the current size.
-    /// Uploads data and clears the buffer if necessary, and returns upload metadata.
+    /// Uploads all buffers once `poll_interval` has passed since the last upload, or when a flush
+    /// was requested, and returns upload metadata.
     async fn poll(
         &mut self,
     ) -> Result<Option<Vec<TransactionContext<Self::Output>>>, ProcessorError> {
+        let flush = self.admin.pending_flush(&self.pipeline_name);
+        if flush.is_none() && self.last_upload.elapsed() < self.poll_interval {
+            return Ok(None);
+        }
+        self.last_upload = Instant::now();
+
         let mut metadata_map = HashMap::new();
         debug!("Polling to check if any buffers need uploading.");
 
@@ -271,6 +347,14 @@ impl PollableAsyncStep for ParquetBufferStep {
                 buffer.current_batch_metadata = None;
             }
         }
+        if let Some(generation) = flush {
+            info!(
+                processor_name = self.buffer_uploader.processor_name.as_str(),
+                num_uploads = metadata_map.len(),
+                "Flushed parquet buffers",
+            );
+            self.admin.complete_flush(&self.pipeline_name, generation);
+        }
 
         if !metadata_map.is_empty() {
             return Ok(Some(vec![TransactionContext {
@@ -284,7 +368,7 @@ impl PollableAsyncStep for ParquetBufferStep {
 
 impl NamedStep for ParquetBufferStep {
     fn name(&self) -> String {
-        "ParquetBufferStep".to_string()
+        PARQUET_BUFFER_STEP_NAME.to_string()
     }
 }
 
@@ -8,7 +8,8 @@ use aptos_indexer_processor_sdk::{
     utils::errors::ProcessorError,
 };
 use async_trait::async_trait;
-use std::collections::HashMap;
+use server_framework::admin::{admin_state, AdminState};
+use std::{collections::HashMap, sync::Arc};
 use tracing::debug;
 
 /// The ParquetProcessorStatusSaver trait object is intended to save
@@ -21,6 +22,9 @@ pub trait ParquetProcessorStatusSaver {
         last_success_batch: &TransactionContext<()>,
         table_name: &str,
     ) -> Result<(), ProcessorError>;
+
+    /// Name the processor reports its checkpoints to the admin API under.
+    fn pipeline_name(&self) -> &str;
 }
 
 /// Tracks the versioned processing of sequential transactions, ensuring no gaps
@@ -37,6 +41,7 @@ where
     last_success_batch: HashMap<ParquetTypeEnum, TransactionContext<()>>,
     polling_interval_secs: u64,
     processor_status_saver: S,
+    admin: Arc<AdminState>,
 }
 
 impl<S> ParquetVersionTrackerStep<S>
@@ -49,6 +54,7 @@ where
             last_success_batch: HashMap::new(),
             processor_status_saver,
             polling_interval_secs,
+            admin: admin_state(),
         }
     }
 
@@ -59,6 +65,15 @@ where
                 .save_parquet_processor_status(last_success_batch, &table_name)
                 .await?;
         }
+        if let Some(version) = self
+            .last_success_batch
+            .values()
+            .map(|batch| batch.metadata.end_version)
+            .max()
+        {
+            self.admin
+                .record_checkpoint(self.processor_status_saver.pipeline_name(), version);
+        }
         Ok(())
     }
 }
@@ -22,6 +22,7 @@ use aptos_indexer_processor_sdk::{
 use async_trait::async_trait;
 use diesel::{upsert::excluded, ExpressionMethods};
 use processor::schema::{backfill_processor_status, processor_status};
+use server_framework::admin::admin_state;
 
 pub fn get_processor_status_saver(
     conn_pool: ArcDbPool,
@@ -85,7 +86,12 @@ impl ProcessorStatusSaver for ProcessorStatusSaverEnum {
         last_success_batch: &TransactionContext<()>,
     ) -> Result<(), ProcessorError> {
         self.save_processor_status_with_optional_table_names(last_success_batch, None)
-            .await
+            .await?;
+        admin_state().record_checkpoint(
+            self.pipeline_name(),
+            last_success_batch.metadata.end_version,
+        );
+        Ok(())
     }
 }
 
@@ -102,6 +108,14 @@ impl ParquetProcessorStatusSaver for ProcessorStatusSaverEnum {
         )
         .await
     }
+
+    fn pipeline_name(&self) -> &str {
+        match self {
+            ProcessorStatusSaverEnum::Postgres { processor_name, .. }
+            | ProcessorStatusSaverEnum::Parquet { processor_name, .. } => processor_name,
+            ProcessorStatusSaverEnum::Backfill { backfill_alias, .. } => backfill_alias,
+        }
+    }
 }
 
 impl ProcessorStatusSaverEnum {
@@ -34,7 +34,9 @@ use processor::{
         },
     },
     processors::fungible_asset_processor::{get_fa_to_coin_mapping, parse_v2_coin},
+    utils::address_labels::AddressLabelRegistry,
 };
+use std::sync::Arc;
 
 /// Extracts fungible asset events, metadata, balances, and v1 supply from transactions
 pub struct FungibleAssetExtractor
@@ -42,12 +44,14 @@ where
     Self: Sized + Send + 'static,
 {
     pub fa_to_coin_mapping: FungibleAssetToCoinMappings,
+    pub address_labels: Option<Arc<AddressLabelRegistry>>,
 }
 
 impl FungibleAssetExtractor {
-    pub fn new() -> Self {
+    pub fn new(address_labels: Option<Arc<AddressLabelRegistry>>) -> Self {
         Self {
             fa_to_coin_mapping: AHashMap::new(),
+            address_labels,
         }
     }
 
@@ -66,12 +70,6 @@ impl FungibleAssetExtractor {
     }
 }
 
-impl Default for FungibleAssetExtractor {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 #[async_trait]
 impl Processable for FungibleAssetExtractor {
     type Input = Vec<Transaction>;
@@ -119,11 +117,14 @@ impl Processable for FungibleAssetExtractor {
             fa_to_coin_mappings,
         ) = parse_v2_coin(&transactions.data, Some(&self.fa_to_coin_mapping)).await;
 
-        let postgres_fungible_asset_activities: Vec<FungibleAssetActivity> =
+        let mut postgres_fungible_asset_activities: Vec<FungibleAssetActivity> =
             raw_fungible_asset_activities
                 .into_iter()
                 .map(FungibleAssetActivity::from_raw)
                 .collect();
+        if let Some(address_labels) = &self.address_labels {
+            address_labels.apply_labels(&mut postgres_fungible_asset_activities);
+        }
 
         let postgres_fungible_asset_metadata: Vec<FungibleAssetMetadataModel> =
             raw_fungible_asset_metadata
@@ -1,5 +1,6 @@
 use crate::{
     config::processor_config::DefaultProcessorConfig,
+    steps::common::change_capture::ChangeCapture,
     utils::database::{execute_in_chunks, get_config_table_chunk_size, ArcDbPool},
 };
 use ahash::AHashMap;
@@ -38,6 +39,7 @@ where
     conn_pool: ArcDbPool,
     processor_config: DefaultProcessorConfig,
     deprecated_tables: TableFlags,
+    change_capture: Option<ChangeCapture>,
 }
 
 impl FungibleAssetStorer {
@@ -45,11 +47,13 @@ impl FungibleAssetStorer {
         conn_pool: ArcDbPool,
         processor_config: DefaultProcessorConfig,
         deprecated_tables: TableFlags,
+        change_capture: Option<ChangeCapture>,
     ) -> Self {
         Self {
             conn_pool,
             processor_config,
             deprecated_tables,
+            change_capture,
         }
     }
 }
@@ -127,6 +131,23 @@ impl Processable for FungibleAssetStorer {
             fungible_asset_metadata.clear();
         }
 
+        // Snapshot the rows about to be upserted before they are overwritten
+        let balances_snapshot = match &self.change_capture {
+            Some(change_capture) => Some(
+                change_capture
+                    .snapshot(current_unified_fab_v1.iter().chain(&current_unified_fab_v2))
+                    .await
+                    .map_err(|e| ProcessorError::DBStoreError {
+                        message: format!(
+                            "Failed to snapshot current_fungible_asset_balances: {:?}",
+                            e
+                        ),
+                        query: None,
+                    })?,
+            ),
+            None => None,
+        };
+
         let faa = execute_in_chunks(
             self.conn_pool.clone(),
             insert_fungible_asset_activities_query,
@@ -195,6 +216,22 @@ impl Processable for FungibleAssetStorer {
             }
         }
 
+        if let (Some(change_capture), Some(snapshot)) = (&self.change_capture, balances_snapshot) {
+            change_capture
+                .publish_changes(
+                    snapshot,
+                    input.metadata.start_version,
+                    input.metadata.end_version,
+                )
+                .await
+                .map_err(|e| ProcessorError::ProcessError {
+                    message: format!(
+                        "Failed to publish change events for versions {} to {}: {:?}",
+                        input.metadata.start_version, input.metadata.end_version, e,
+                    ),
+                })?;
+        }
+
         Ok(Some(TransactionContext {
             data: (),
             metadata: input.metadata,
@@ -5,9 +5,13 @@ pub mod common;
 pub mod default_processor;
 pub mod events_processor;
 pub mod fungible_asset_processor;
+pub mod nft_metadata_processor;
+pub mod spam_classifier_processor;
 pub mod objects_processor;
 pub mod stake_processor;
 pub mod token_v2_processor;
+pub mod transaction_failure_processor;
+pub mod transaction_metadata_processor;
 pub mod user_transaction_processor;
 
 // parquet
@@ -0,0 +1,2 @@
+pub mod nft_metadata_extractor;
+pub mod nft_metadata_publisher;
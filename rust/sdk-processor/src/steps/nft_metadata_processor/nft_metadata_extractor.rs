@@ -0,0 +1,121 @@
+use crate::{sinks::SinkMessage, utils::database::ArcDbPool};
+use aptos_indexer_processor_sdk::{
+    aptos_protos::transaction::v1::Transaction,
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use processor::{
+    db::postgres::models::token_models::tokens::TableMetadataForToken,
+    processors::nft_metadata_processor::{
+        clean_collection_pubsub_message, clean_token_pubsub_message, parse_v2_token,
+    },
+};
+
+/// Extracts the latest token data and collection changes of each batch as messages for the NFT
+/// metadata crawler, keyed by token data id and collection id.
+pub struct NftMetadataExtractor
+where
+    Self: Sized + Send + 'static,
+{
+    query_retries: u32,
+    query_retry_delay_ms: u64,
+    conn_pool: ArcDbPool,
+    chain_id: u64,
+}
+
+impl NftMetadataExtractor {
+    pub fn new(
+        query_retries: u32,
+        query_retry_delay_ms: u64,
+        conn_pool: ArcDbPool,
+        chain_id: u64,
+    ) -> Self {
+        Self {
+            query_retries,
+            query_retry_delay_ms,
+            conn_pool,
+            chain_id,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for NftMetadataExtractor {
+    type Input = Vec<Transaction>;
+    type Output = Vec<SinkMessage>;
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        transactions: TransactionContext<Vec<Transaction>>,
+    ) -> Result<Option<TransactionContext<Vec<SinkMessage>>>, ProcessorError> {
+        let mut conn = self
+            .conn_pool
+            .get()
+            .await
+            .map_err(|e| ProcessorError::DBStoreError {
+                message: format!("Failed to get connection from pool: {:?}", e),
+                query: None,
+            })?;
+
+        // First get all token related table metadata from the batch of transactions. This is in case
+        // an earlier transaction has metadata (in resources) that's missing from a later transaction.
+        let table_handle_to_owner =
+            TableMetadataForToken::get_table_handle_to_owner_from_transactions(&transactions.data);
+
+        let (token_datas, collections) = parse_v2_token(
+            &transactions.data,
+            &table_handle_to_owner,
+            &mut conn,
+            self.query_retries,
+            self.query_retry_delay_ms,
+        )
+        .await;
+
+        let mut messages: Vec<(i64, SinkMessage)> =
+            Vec::with_capacity(token_datas.len() + collections.len());
+        for token_data in token_datas {
+            messages.push((
+                token_data.last_transaction_version,
+                SinkMessage {
+                    id: format!(
+                        "{}_{}",
+                        token_data.token_data_id, token_data.last_transaction_version
+                    ),
+                    key: token_data.token_data_id.clone(),
+                    payload: clean_token_pubsub_message(token_data, self.chain_id),
+                },
+            ));
+        }
+        for collection in collections {
+            messages.push((
+                collection.last_transaction_version,
+                SinkMessage {
+                    id: format!(
+                        "{}_{}",
+                        collection.collection_id, collection.last_transaction_version
+                    ),
+                    key: collection.collection_id.clone(),
+                    payload: clean_collection_pubsub_message(collection, self.chain_id),
+                },
+            ));
+        }
+        // Publish in version order, so consumers see changes in the order they happened
+        messages.sort_by_key(|(version, _)| *version);
+
+        Ok(Some(TransactionContext {
+            data: messages.into_iter().map(|(_, message)| message).collect(),
+            metadata: transactions.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for NftMetadataExtractor {}
+
+impl NamedStep for NftMetadataExtractor {
+    fn name(&self) -> String {
+        "NftMetadataExtractor".to_string()
+    }
+}
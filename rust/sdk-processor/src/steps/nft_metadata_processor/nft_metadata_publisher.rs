@@ -0,0 +1,180 @@
+use crate::sinks::{Sink, SinkMessage};
+use anyhow::Result;
+use aptos_indexer_processor_sdk::{
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use std::time::Duration;
+use tracing::warn;
+
+/// Publishes each batch of messages to the sink and only passes the batch on once the sink has
+/// acknowledged it, so the processor status never moves past unpublished messages.
+pub struct NftMetadataPublisher
+where
+    Self: Sized + Send + 'static,
+{
+    sink: Box<dyn Sink>,
+    publish_retries: u32,
+    publish_retry_delay_ms: u64,
+    publish_max_retry_delay_ms: u64,
+}
+
+impl NftMetadataPublisher {
+    pub fn new(
+        sink: Box<dyn Sink>,
+        publish_retries: u32,
+        publish_retry_delay_ms: u64,
+        publish_max_retry_delay_ms: u64,
+    ) -> Self {
+        Self {
+            sink,
+            publish_retries,
+            publish_retry_delay_ms,
+            publish_max_retry_delay_ms,
+        }
+    }
+
+    /// Exponential backoff from `publish_retry_delay_ms`, capped at `publish_max_retry_delay_ms`.
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let delay_ms = 2u64
+            .checked_pow(attempt.saturating_sub(1))
+            .map_or(u64::MAX, |factor| {
+                self.publish_retry_delay_ms.saturating_mul(factor)
+            });
+        Duration::from_millis(delay_ms.min(self.publish_max_retry_delay_ms))
+    }
+}
+
+#[async_trait]
+impl Processable for NftMetadataPublisher {
+    type Input = Vec<SinkMessage>;
+    type Output = ();
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        input: TransactionContext<Vec<SinkMessage>>,
+    ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
+        let mut attempt = 0;
+        while let Err(e) = self.sink.publish(&input.data).await {
+            attempt += 1;
+            if attempt > self.publish_retries {
+                return Err(ProcessorError::ProcessError {
+                    message: format!(
+                        "Failed to publish versions {} to {}: {:?}",
+                        input.metadata.start_version, input.metadata.end_version, e,
+                    ),
+                });
+            }
+            warn!(
+                start_version = input.metadata.start_version,
+                end_version = input.metadata.end_version,
+                attempt = attempt,
+                error = ?e,
+                "Failed to publish, retrying",
+            );
+            // Republishing the whole batch keeps per-key order, duplicates are dropped by message id
+            tokio::time::sleep(self.retry_delay(attempt)).await;
+        }
+
+        Ok(Some(TransactionContext {
+            data: (),
+            metadata: input.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for NftMetadataPublisher {}
+
+impl NamedStep for NftMetadataPublisher {
+    fn name(&self) -> String {
+        "NftMetadataPublisher".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::SinkMessage;
+    use aptos_indexer_processor_sdk::types::transaction_context::TransactionMetadata;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    /// Fails the first `failures` publishes, then acknowledges everything.
+    struct FailingSink {
+        failures: u32,
+        attempts: Arc<AtomicU32>,
+    }
+
+    #[async_trait]
+    impl Sink for FailingSink {
+        async fn publish(&self, _messages: &[SinkMessage]) -> Result<()> {
+            let attempt = self.attempts.fetch_add(1, Ordering::SeqCst);
+            if attempt < self.failures {
+                anyhow::bail!("sink unavailable");
+            }
+            Ok(())
+        }
+    }
+
+    fn publisher(failures: u32, publish_retries: u32) -> (NftMetadataPublisher, Arc<AtomicU32>) {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let sink = FailingSink {
+            failures,
+            attempts: attempts.clone(),
+        };
+        (
+            NftMetadataPublisher::new(Box::new(sink), publish_retries, 1, 4),
+            attempts,
+        )
+    }
+
+    fn batch() -> TransactionContext<Vec<SinkMessage>> {
+        TransactionContext {
+            data: vec![SinkMessage {
+                key: "0xa".to_string(),
+                id: "0xa_1".to_string(),
+                payload: "{}".to_string(),
+            }],
+            metadata: TransactionMetadata {
+                start_version: 1,
+                end_version: 2,
+                start_transaction_timestamp: None,
+                end_transaction_timestamp: None,
+                total_size_in_bytes: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        let (publisher, _) = publisher(0, 5);
+        let delays: Vec<_> = (1..=5)
+            .map(|attempt| publisher.retry_delay(attempt).as_millis())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 4, 4]);
+        // Doesn't overflow with many retries
+        assert_eq!(publisher.retry_delay(100), Duration::from_millis(4));
+        assert_eq!(publisher.retry_delay(u32::MAX), Duration::from_millis(4));
+    }
+
+    #[tokio::test]
+    async fn test_publish_retries_until_acknowledged() {
+        let (mut publisher, attempts) = publisher(3, 5);
+        let output = publisher.process(batch()).await.unwrap().unwrap();
+        assert_eq!(output.metadata.end_version, 2);
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_publish_fails_after_retries() {
+        let (mut publisher, attempts) = publisher(10, 2);
+        assert!(publisher.process(batch()).await.is_err());
+        // The first attempt and 2 retries
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+}
@@ -1,4 +1,9 @@
-use crate::utils::database::{execute_in_chunks, get_config_table_chunk_size, ArcDbPool};
+use crate::{
+    steps::common::change_capture::ChangeCapture,
+    utils::database::{
+        execute_in_chunks, execute_with_better_error, get_config_table_chunk_size, ArcDbPool,
+    },
+};
 use ahash::AHashMap;
 use anyhow::Result;
 use aptos_indexer_processor_sdk::{
@@ -7,25 +12,88 @@ use aptos_indexer_processor_sdk::{
     utils::errors::ProcessorError,
 };
 use async_trait::async_trait;
+use diesel::{
+    sql_query,
+    sql_types::{Array, BigInt, Text},
+};
 use processor::{
     self,
     db::postgres::models::object_models::v2_objects::{CurrentObject, Object},
     processors::objects_processor::{insert_current_objects_query, insert_objects_query},
 };
 
+/// Maximum number of owners followed up from an object, in case the ownership chain has a cycle.
+pub const MAX_OBJECT_OWNERSHIP_DEPTH: i64 = 64;
+
+/// Recomputes the root owner of the given objects and of every object they own, directly or
+/// through other objects, from `current_objects`. Objects that were deleted are removed.
+const UPDATE_CURRENT_OBJECT_ROOT_OWNERS_QUERY: &str = "
+    WITH RECURSIVE affected AS (
+        SELECT object_address FROM unnest($1::TEXT[]) AS a(object_address)
+        UNION
+        SELECT o.object_address
+        FROM current_objects o
+        JOIN affected a ON o.owner_address = a.object_address
+        WHERE NOT o.is_deleted
+    ),
+    ancestors AS (
+        SELECT a.object_address, o.owner_address AS ancestor_address, 1 AS depth
+        FROM affected a
+        JOIN current_objects o ON o.object_address = a.object_address
+        WHERE NOT o.is_deleted
+        UNION ALL
+        SELECT an.object_address, o.owner_address, an.depth + 1
+        FROM ancestors an
+        JOIN current_objects o ON o.object_address = an.ancestor_address
+        WHERE NOT o.is_deleted AND an.depth < $3
+    ),
+    roots AS (
+        SELECT DISTINCT ON (object_address)
+            object_address,
+            ancestor_address AS root_owner_address,
+            depth
+        FROM ancestors
+        ORDER BY object_address, depth DESC
+    ),
+    deleted AS (
+        DELETE FROM current_object_root_owners r
+        USING affected a
+        WHERE r.object_address = a.object_address
+            AND NOT EXISTS (SELECT 1 FROM roots WHERE roots.object_address = r.object_address)
+    )
+    INSERT INTO current_object_root_owners
+        (object_address, root_owner_address, depth, last_transaction_version)
+    SELECT object_address, root_owner_address, depth, $2
+    FROM roots
+    ON CONFLICT (object_address) DO UPDATE SET
+        root_owner_address = EXCLUDED.root_owner_address,
+        depth = EXCLUDED.depth,
+        last_transaction_version = GREATEST(
+            current_object_root_owners.last_transaction_version,
+            EXCLUDED.last_transaction_version
+        ),
+        inserted_at = NOW()
+";
+
 pub struct ObjectsStorer
 where
     Self: Sized + Send + 'static,
 {
     conn_pool: ArcDbPool,
     per_table_chunk_sizes: AHashMap<String, usize>,
+    change_capture: Option<ChangeCapture>,
 }
 
 impl ObjectsStorer {
-    pub fn new(conn_pool: ArcDbPool, per_table_chunk_sizes: AHashMap<String, usize>) -> Self {
+    pub fn new(
+        conn_pool: ArcDbPool,
+        per_table_chunk_sizes: AHashMap<String, usize>,
+        change_capture: Option<ChangeCapture>,
+    ) -> Self {
         Self {
             conn_pool,
             per_table_chunk_sizes,
+            change_capture,
         }
     }
 }
@@ -42,6 +110,17 @@ impl Processable for ObjectsStorer {
     ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
         let (objects, current_objects) = input.data;
 
+        // Snapshot the rows about to be upserted before they are overwritten
+        let current_objects_snapshot = match &self.change_capture {
+            Some(change_capture) => Some(change_capture.snapshot(&current_objects).await.map_err(
+                |e| ProcessorError::DBStoreError {
+                    message: format!("Failed to snapshot current_objects: {:?}", e),
+                    query: None,
+                },
+            )?),
+            None => None,
+        };
+
         let io = execute_in_chunks(
             self.conn_pool.clone(),
             insert_objects_query,
@@ -73,6 +152,32 @@ impl Processable for ObjectsStorer {
             }
         }
 
+        // Runs after current_objects is committed, since the ownership chains are read from it
+        update_current_object_root_owners(
+            self.conn_pool.clone(),
+            &current_objects,
+            input.metadata.end_version as i64,
+        )
+        .await?;
+
+        if let (Some(change_capture), Some(snapshot)) =
+            (&self.change_capture, current_objects_snapshot)
+        {
+            change_capture
+                .publish_changes(
+                    snapshot,
+                    input.metadata.start_version,
+                    input.metadata.end_version,
+                )
+                .await
+                .map_err(|e| ProcessorError::ProcessError {
+                    message: format!(
+                        "Failed to publish change events for versions {} to {}: {:?}",
+                        input.metadata.start_version, input.metadata.end_version, e,
+                    ),
+                })?;
+        }
+
         Ok(Some(TransactionContext {
             data: (),
             metadata: input.metadata,
@@ -80,6 +185,32 @@ impl Processable for ObjectsStorer {
     }
 }
 
+/// Updates the root owners of the objects whose `ObjectCore` was written or deleted in the batch,
+/// which covers every transfer, and of everything they own.
+pub async fn update_current_object_root_owners(
+    conn_pool: ArcDbPool,
+    current_objects: &[CurrentObject],
+    end_version: i64,
+) -> Result<(), ProcessorError> {
+    if current_objects.is_empty() {
+        return Ok(());
+    }
+    let object_addresses: Vec<String> = current_objects
+        .iter()
+        .map(|current_object| current_object.object_address.clone())
+        .collect();
+    execute_with_better_error(
+        conn_pool,
+        sql_query(UPDATE_CURRENT_OBJECT_ROOT_OWNERS_QUERY)
+            .bind::<Array<Text>, _>(object_addresses)
+            .bind::<BigInt, _>(end_version)
+            .bind::<BigInt, _>(MAX_OBJECT_OWNERSHIP_DEPTH),
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
 impl AsyncStep for ObjectsStorer {}
 
 impl NamedStep for ObjectsStorer {
@@ -12,19 +12,28 @@ use async_trait::async_trait;
 use chrono::NaiveDateTime;
 use processor::{
     db::{
-        common::models::account_transaction_models::raw_account_transactions::RawAccountTransaction,
-        parquet::models::account_transaction_models::parquet_account_transactions::AccountTransaction,
+        common::models::account_transaction_models::{
+            raw_account_summaries::{
+                AccountSummaryConvertible, RawAccountActivity, RawAccountSummary,
+            },
+            raw_account_transactions::RawAccountTransaction,
+        },
+        parquet::models::account_transaction_models::{
+            parquet_account_summaries::AccountSummary,
+            parquet_account_transactions::AccountTransaction,
+        },
     },
-    utils::table_flags::TableFlags,
+    utils::{address_labels::AddressLabelRegistry, table_flags::TableFlags},
 };
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use tracing::debug;
 pub struct ParquetAccountTransactionsExtractor
 where
     Self: Processable + Send + Sized + 'static,
 {
     pub opt_in_tables: TableFlags,
+    pub address_labels: Option<Arc<AddressLabelRegistry>>,
 }
 
 type ParquetTypeMap = HashMap<ParquetTypeEnum, ParquetTypeStructs>;
@@ -39,7 +48,21 @@ impl Processable for ParquetAccountTransactionsExtractor {
         &mut self,
         transactions: TransactionContext<Self::Input>,
     ) -> anyhow::Result<Option<TransactionContext<ParquetTypeMap>>, ProcessorError> {
-        let acc_txns: Vec<AccountTransaction> = transactions
+        let (account_activities, account_counterparties): (Vec<_>, Vec<_>) = transactions
+            .data
+            .par_iter()
+            .map(RawAccountActivity::from_transaction)
+            .unzip();
+        let account_activities: Vec<RawAccountActivity> =
+            account_activities.into_iter().flatten().collect();
+        let account_counterparties: Vec<_> = account_counterparties.into_iter().flatten().collect();
+        let account_summaries: Vec<AccountSummary> =
+            RawAccountSummary::from_activities(&account_activities, &account_counterparties)
+                .into_iter()
+                .map(AccountSummary::from_raw)
+                .collect();
+
+        let mut acc_txns: Vec<AccountTransaction> = transactions
             .data
             .into_par_iter()
             .map(|txn| {
@@ -60,6 +83,7 @@ impl Processable for ParquetAccountTransactionsExtractor {
                         txn_version: transaction_version,
                         account_address,
                         block_timestamp,
+                        label: None,
                     })
                     .collect()
             })
@@ -67,18 +91,29 @@ impl Processable for ParquetAccountTransactionsExtractor {
             .into_iter()
             .flatten()
             .collect();
+        if let Some(address_labels) = &self.address_labels {
+            address_labels.apply_labels(&mut acc_txns);
+        }
         // Print the size of each extracted data type
         debug!("Processed data sizes:");
         debug!(" - AccountTransaction: {}", acc_txns.len());
+        debug!(" - AccountSummary: {}", account_summaries.len());
 
         let mut map: HashMap<ParquetTypeEnum, ParquetTypeStructs> = HashMap::new();
 
         // Array of tuples for each data type and its corresponding enum variant and flag
-        let data_types = [(
-            TableFlags::ACCOUNT_TRANSACTIONS,
-            ParquetTypeEnum::AccountTransactions,
-            ParquetTypeStructs::AccountTransaction(acc_txns),
-        )];
+        let data_types = [
+            (
+                TableFlags::ACCOUNT_TRANSACTIONS,
+                ParquetTypeEnum::AccountTransactions,
+                ParquetTypeStructs::AccountTransaction(acc_txns),
+            ),
+            (
+                TableFlags::ACCOUNT_SUMMARIES,
+                ParquetTypeEnum::AccountSummaries,
+                ParquetTypeStructs::AccountSummary(account_summaries),
+            ),
+        ];
 
         // Populate the map based on opt-in tables
         add_to_map_if_opted_in_for_backfill(self.opt_in_tables, &mut map, data_types.to_vec());
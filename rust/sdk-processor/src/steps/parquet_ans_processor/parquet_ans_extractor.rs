@@ -23,6 +23,7 @@ use processor::{
             ans_lookup_v2::{AnsLookupV2, CurrentAnsLookupV2},
             ans_primary_name_v2::{AnsPrimaryNameV2, CurrentAnsPrimaryNameV2},
         },
+        postgres::models::ans_models::ans_utils::NamingServiceConfig,
     },
     processors::ans_processor::parse_ans,
     utils::table_flags::TableFlags,
@@ -35,6 +36,7 @@ where
     Self: Processable + Send + Sized + 'static,
 {
     pub ans_config: ParquetAnsProcessorConfig,
+    pub naming_services: Vec<NamingServiceConfig>,
     pub opt_in_tables: TableFlags,
 }
 
@@ -63,7 +65,7 @@ impl Processable for ParquetAnsExtractor {
             &input.data,
             self.ans_config.ans_v1_primary_names_table_handle.clone(),
             self.ans_config.ans_v1_name_records_table_handle.clone(),
-            self.ans_config.ans_v2_contract_address.clone(),
+            &self.naming_services,
         );
 
         let parquet_ans_lookup_v2: Vec<AnsLookupV2> = raw_ans_lookups_v2
@@ -27,9 +27,9 @@ use processor::{
         },
     },
     processors::fungible_asset_processor::{get_fa_to_coin_mapping, parse_v2_coin},
-    utils::{database::ArcDbPool, table_flags::TableFlags},
+    utils::{address_labels::AddressLabelRegistry, database::ArcDbPool, table_flags::TableFlags},
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use tracing::debug;
 
 /// Extracts parquet data from transactions, allowing optional selection of specific tables.
@@ -39,13 +39,18 @@ where
 {
     pub opt_in_tables: TableFlags,
     pub fa_to_coin_mapping: FungibleAssetToCoinMappings,
+    pub address_labels: Option<Arc<AddressLabelRegistry>>,
 }
 
 impl ParquetFungibleAssetExtractor {
-    pub fn new(opt_in_tables: TableFlags) -> Self {
+    pub fn new(
+        opt_in_tables: TableFlags,
+        address_labels: Option<Arc<AddressLabelRegistry>>,
+    ) -> Self {
         Self {
             opt_in_tables,
             fa_to_coin_mapping: AHashMap::new(),
+            address_labels,
         }
     }
 
@@ -83,11 +88,14 @@ impl Processable for ParquetFungibleAssetExtractor {
             _raw_fa_to_coin_mappings,
         ) = parse_v2_coin(&transactions.data, Some(&self.fa_to_coin_mapping)).await;
 
-        let parquet_fungible_asset_activities: Vec<FungibleAssetActivity> =
+        let mut parquet_fungible_asset_activities: Vec<FungibleAssetActivity> =
             raw_fungible_asset_activities
                 .into_iter()
                 .map(FungibleAssetActivity::from_raw)
                 .collect();
+        if let Some(address_labels) = &self.address_labels {
+            address_labels.apply_labels(&mut parquet_fungible_asset_activities);
+        }
 
         let parquet_fungible_asset_metadata: Vec<FungibleAssetMetadataModel> =
             raw_fungible_asset_metadata
@@ -24,6 +24,7 @@ use processor::{
         parquet::models::token_v2_models::{
             token_claims::CurrentTokenPendingClaim,
             v1_token_royalty::CurrentTokenRoyaltyV1,
+            v2_collection_activity_hourly::CollectionActivityHourly,
             v2_token_activities::TokenActivityV2,
             v2_token_datas::{CurrentTokenDataV2, TokenDataV2},
             v2_token_metadata::CurrentTokenV2Metadata,
@@ -32,9 +33,9 @@ use processor::{
         postgres::models::token_models::tokens::TableMetadataForToken,
     },
     processors::token_v2_processor::parse_v2_token,
-    utils::table_flags::TableFlags,
+    utils::{address_labels::AddressLabelRegistry, table_flags::TableFlags},
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use tracing::debug;
 
 /// Extracts parquet data from transactions, allowing optional selection of specific tables.
@@ -43,6 +44,7 @@ where
     Self: Processable + Send + Sized + 'static,
 {
     pub opt_in_tables: TableFlags,
+    pub address_labels: Option<Arc<AddressLabelRegistry>>,
 }
 
 type ParquetTypeMap = HashMap<ParquetTypeEnum, ParquetTypeStructs>;
@@ -94,10 +96,14 @@ impl Processable for ParquetTokenV2Extractor {
                 .map(CurrentTokenV2Metadata::from_raw)
                 .collect();
 
-        let parquet_token_activities_v2: Vec<TokenActivityV2> = raw_token_activities_v2
-            .into_iter()
+        let mut parquet_token_activities_v2: Vec<TokenActivityV2> = raw_token_activities_v2
+            .iter()
+            .cloned()
             .map(TokenActivityV2::from_raw)
             .collect();
+        if let Some(address_labels) = &self.address_labels {
+            address_labels.apply_labels(&mut parquet_token_activities_v2);
+        }
 
         let parquet_token_datas_v2: Vec<TokenDataV2> = raw_token_datas_v2
             .into_iter()
@@ -175,6 +181,15 @@ impl Processable for ParquetTokenV2Extractor {
             .iter()
             .for_each(|x| combined_current_token_datas_v2.push(x.clone()));
 
+        let parquet_collection_activity_hourly = CollectionActivityHourly::from_activities(
+            &raw_token_activities_v2,
+            &combined_current_token_datas_v2,
+        );
+        debug!(
+            " - CollectionActivityHourly: {}",
+            parquet_collection_activity_hourly.len()
+        );
+
         let mut merged_current_token_ownerships_v2: Vec<CurrentTokenOwnershipV2> = Vec::new();
         parquet_current_token_ownerships_v2
             .iter()
@@ -227,6 +242,11 @@ impl Processable for ParquetTokenV2Extractor {
                 ParquetTypeEnum::CurrentTokenOwnershipsV2,
                 ParquetTypeStructs::CurrentTokenOwnershipV2(merged_current_token_ownerships_v2),
             ),
+            (
+                TableFlags::COLLECTION_ACTIVITY_HOURLY,
+                ParquetTypeEnum::CollectionActivityHourly,
+                ParquetTypeStructs::CollectionActivityHourly(parquet_collection_activity_hourly),
+            ),
         ];
 
         // Populate the map based on opt-in tables
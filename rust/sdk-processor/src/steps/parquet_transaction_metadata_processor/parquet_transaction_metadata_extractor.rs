@@ -11,6 +11,10 @@ use aptos_indexer_processor_sdk::{
 };
 use async_trait::async_trait;
 use processor::{
+    db::parquet::models::transaction_metadata_model::{
+        parquet_entry_function_gas_hourly::EntryFunctionGasHourly,
+        parquet_transaction_gas_breakdown::TransactionGasBreakdown,
+    },
     processors::parquet_processors::parquet_transaction_metadata_processor::process_transaction,
     utils::table_flags::TableFlags,
 };
@@ -37,20 +41,45 @@ impl Processable for ParquetTransactionMetadataExtractor {
         &mut self,
         transactions: TransactionContext<Self::Input>,
     ) -> anyhow::Result<Option<TransactionContext<ParquetTypeMap>>, ProcessorError> {
+        let gas_breakdowns: Vec<TransactionGasBreakdown> = transactions
+            .data
+            .iter()
+            .filter_map(TransactionGasBreakdown::from_transaction)
+            .collect();
+        let entry_function_gas_hourly =
+            EntryFunctionGasHourly::from_gas_breakdowns(&gas_breakdowns);
+
         let mut transaction_version_to_struct_count: AHashMap<i64, i64> = AHashMap::new();
         let write_set_size =
             process_transaction(transactions.data, &mut transaction_version_to_struct_count);
 
         debug!("Processed data sizes:");
         debug!(" - WriteSetSize: {}", write_set_size.len());
+        debug!(" - TransactionGasBreakdown: {}", gas_breakdowns.len());
+        debug!(
+            " - EntryFunctionGasHourly: {}",
+            entry_function_gas_hourly.len()
+        );
 
         let mut map: HashMap<ParquetTypeEnum, ParquetTypeStructs> = HashMap::new();
 
-        let data_types = [(
-            TableFlags::WRITE_SET_SIZE,
-            ParquetTypeEnum::WriteSetSize,
-            ParquetTypeStructs::WriteSetSize(write_set_size),
-        )];
+        let data_types = [
+            (
+                TableFlags::WRITE_SET_SIZE,
+                ParquetTypeEnum::WriteSetSize,
+                ParquetTypeStructs::WriteSetSize(write_set_size),
+            ),
+            (
+                TableFlags::TRANSACTION_GAS_BREAKDOWN,
+                ParquetTypeEnum::TransactionGasBreakdown,
+                ParquetTypeStructs::TransactionGasBreakdown(gas_breakdowns),
+            ),
+            (
+                TableFlags::ENTRY_FUNCTION_GAS_HOURLY,
+                ParquetTypeEnum::EntryFunctionGasHourly,
+                ParquetTypeStructs::EntryFunctionGasHourly(entry_function_gas_hourly),
+            ),
+        ];
 
         // Populate the map based on opt-in tables
         add_to_map_if_opted_in_for_backfill(self.opt_in_tables, &mut map, data_types.to_vec());
@@ -0,0 +1,3 @@
+pub mod spam_assets_storer;
+pub mod spam_classifier_extractor;
+pub mod spam_rules;
@@ -0,0 +1,103 @@
+use crate::utils::database::{execute_in_chunks, get_config_table_chunk_size, ArcDbPool};
+use ahash::AHashMap;
+use anyhow::Result;
+use aptos_indexer_processor_sdk::{
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use diesel::{
+    dsl::now,
+    pg::{upsert::excluded, Pg},
+    query_builder::QueryFragment,
+    ExpressionMethods,
+};
+use processor::{db::postgres::models::spam_models::spam_assets::SpamAsset, schema};
+
+pub struct SpamAssetsStorer
+where
+    Self: Sized + Send + 'static,
+{
+    conn_pool: ArcDbPool,
+    per_table_chunk_sizes: AHashMap<String, usize>,
+}
+
+impl SpamAssetsStorer {
+    pub fn new(conn_pool: ArcDbPool, per_table_chunk_sizes: AHashMap<String, usize>) -> Self {
+        Self {
+            conn_pool,
+            per_table_chunk_sizes,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for SpamAssetsStorer {
+    type Input = Vec<SpamAsset>;
+    type Output = ();
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        input: TransactionContext<Vec<SpamAsset>>,
+    ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
+        execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_spam_assets_query,
+            &input.data,
+            get_config_table_chunk_size::<SpamAsset>("spam_assets", &self.per_table_chunk_sizes),
+        )
+        .await?;
+
+        Ok(Some(TransactionContext {
+            data: (),
+            metadata: input.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for SpamAssetsStorer {}
+
+impl NamedStep for SpamAssetsStorer {
+    fn name(&self) -> String {
+        "SpamAssetsStorer".to_string()
+    }
+}
+
+/// Heuristic results never overwrite an existing row, so a manual override or an asset that was
+/// unflagged by hand stays as it is.
+pub fn insert_spam_assets_query(
+    items_to_insert: Vec<SpamAsset>,
+) -> (
+    impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
+    Option<&'static str>,
+) {
+    use schema::spam_assets::dsl::*;
+
+    (
+        diesel::insert_into(schema::spam_assets::table)
+            .values(items_to_insert)
+            .on_conflict(asset)
+            .do_nothing(),
+        None,
+    )
+}
+
+pub fn insert_spam_asset_overrides_query(
+    items_to_insert: Vec<SpamAsset>,
+) -> (
+    impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
+    Option<&'static str>,
+) {
+    use schema::spam_assets::dsl::*;
+
+    (
+        diesel::insert_into(schema::spam_assets::table)
+            .values(items_to_insert)
+            .on_conflict(asset)
+            .do_update()
+            .set((is_spam.eq(excluded(is_spam)), last_updated.eq(now))),
+        None,
+    )
+}
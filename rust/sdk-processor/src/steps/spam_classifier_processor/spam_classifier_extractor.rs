@@ -0,0 +1,207 @@
+use crate::{
+    steps::spam_classifier_processor::spam_rules::{AssetCandidate, SpamRules},
+    utils::database::ArcDbPool,
+};
+use ahash::{AHashMap, AHashSet};
+use aptos_indexer_processor_sdk::{
+    aptos_protos::transaction::v1::Transaction,
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use processor::{
+    db::{
+        common::models::{
+            fungible_asset_models::{
+                raw_v2_fungible_asset_activities::RawFungibleAssetActivity,
+                raw_v2_fungible_metadata::RawFungibleAssetMetadataModel,
+            },
+            token_v2_models::{
+                raw_v2_token_activities::RawTokenActivityV2, raw_v2_token_datas::RawTokenDataV2,
+            },
+        },
+        postgres::models::{
+            spam_models::spam_assets::SpamAsset, token_models::tokens::TableMetadataForToken,
+            token_v2_models::v2_collections::CollectionV2,
+        },
+    },
+    processors::{fungible_asset_processor::parse_v2_coin, token_v2_processor::parse_v2_token},
+    utils::database::DbContext,
+};
+
+const DEPOSIT_EVENT_TYPES: [&str; 4] = [
+    "0x1::fungible_asset::Deposit",
+    "0x1::fungible_asset::DepositEvent",
+    "0x1::coin::CoinDeposit",
+    "0x1::coin::DepositEvent",
+];
+
+/// Scores the fungible assets, coins, and collections seen in each batch and returns the ones
+/// classified as spam.
+pub struct SpamClassifierExtractor
+where
+    Self: Sized + Send + 'static,
+{
+    query_retries: u32,
+    query_retry_delay_ms: u64,
+    conn_pool: ArcDbPool,
+    rules: SpamRules,
+}
+
+impl SpamClassifierExtractor {
+    pub fn new(
+        query_retries: u32,
+        query_retry_delay_ms: u64,
+        conn_pool: ArcDbPool,
+        rules: SpamRules,
+    ) -> Self {
+        Self {
+            query_retries,
+            query_retry_delay_ms,
+            conn_pool,
+            rules,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for SpamClassifierExtractor {
+    type Input = Vec<Transaction>;
+    type Output = Vec<SpamAsset>;
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        transactions: TransactionContext<Vec<Transaction>>,
+    ) -> Result<Option<TransactionContext<Vec<SpamAsset>>>, ProcessorError> {
+        let conn = self
+            .conn_pool
+            .get()
+            .await
+            .map_err(|e| ProcessorError::DBStoreError {
+                message: format!("Failed to get connection from pool: {:?}", e),
+                query: None,
+            })?;
+
+        let (fungible_asset_activities, fungible_asset_metadata, _, _, _, _) =
+            parse_v2_coin(&transactions.data, None).await;
+
+        // First get all token related table metadata from the batch of transactions. This is in case
+        // an earlier transaction has metadata (in resources) that's missing from a later transaction.
+        let table_handle_to_owner =
+            TableMetadataForToken::get_table_handle_to_owner_from_transactions(&transactions.data);
+        let db_connection = DbContext {
+            conn,
+            query_retries: self.query_retries,
+            query_retry_delay_ms: self.query_retry_delay_ms,
+        };
+        let (collections_v2, token_datas_v2, _, _, _, _, _, _, token_activities_v2, _, _, _) =
+            parse_v2_token(
+                &transactions.data,
+                &table_handle_to_owner,
+                &mut Some(db_connection),
+            )
+            .await;
+
+        let candidates = get_candidates(
+            &fungible_asset_metadata,
+            &fungible_asset_activities,
+            &collections_v2,
+            &token_datas_v2,
+            &token_activities_v2,
+        );
+
+        Ok(Some(TransactionContext {
+            data: self.rules.classify(&candidates),
+            metadata: transactions.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for SpamClassifierExtractor {}
+
+impl NamedStep for SpamClassifierExtractor {
+    fn name(&self) -> String {
+        "SpamClassifierExtractor".to_string()
+    }
+}
+
+/// Collects what the batch says about each asset, keyed by fungible asset metadata address, coin
+/// type, or collection id.
+fn get_candidates(
+    fungible_asset_metadata: &[RawFungibleAssetMetadataModel],
+    fungible_asset_activities: &[RawFungibleAssetActivity],
+    collections_v2: &[CollectionV2],
+    token_datas_v2: &[RawTokenDataV2],
+    token_activities_v2: &[RawTokenActivityV2],
+) -> AHashMap<String, AssetCandidate> {
+    let mut candidates: AHashMap<String, AssetCandidate> = AHashMap::new();
+    for metadata in fungible_asset_metadata {
+        let candidate = candidates.entry(metadata.asset_type.clone()).or_default();
+        candidate
+            .names
+            .extend([metadata.name.clone(), metadata.symbol.clone()]);
+        candidate.uris.extend(
+            metadata
+                .icon_uri
+                .iter()
+                .chain(&metadata.project_uri)
+                .cloned(),
+        );
+        candidate.creator_address = Some(metadata.creator_address.clone());
+    }
+    for collection in collections_v2 {
+        let candidate = candidates
+            .entry(collection.collection_id.clone())
+            .or_default();
+        candidate.names.push(collection.collection_name.clone());
+        candidate.uris.push(collection.uri.clone());
+        candidate.creator_address = Some(collection.creator_address.clone());
+    }
+
+    // Distinct recipients of each asset in each transaction
+    let mut recipients: AHashMap<(i64, &str), AHashSet<&str>> = AHashMap::new();
+    for activity in fungible_asset_activities {
+        if !activity.is_transaction_success
+            || !DEPOSIT_EVENT_TYPES.contains(&activity.event_type.as_str())
+        {
+            continue;
+        }
+        if let (Some(asset_type), Some(owner_address)) =
+            (&activity.asset_type, &activity.owner_address)
+        {
+            recipients
+                .entry((activity.transaction_version, asset_type.as_str()))
+                .or_default()
+                .insert(owner_address.as_str());
+        }
+    }
+    // Token activities don't have the collection, so only tokens minted in the batch are counted
+    let collection_by_token: AHashMap<&str, &str> = token_datas_v2
+        .iter()
+        .map(|token_data| {
+            (
+                token_data.token_data_id.as_str(),
+                token_data.collection_id.as_str(),
+            )
+        })
+        .collect();
+    for activity in token_activities_v2 {
+        if let (Some(collection_id), Some(to_address)) = (
+            collection_by_token.get(activity.token_data_id.as_str()),
+            &activity.to_address,
+        ) {
+            recipients
+                .entry((activity.transaction_version, *collection_id))
+                .or_default()
+                .insert(to_address.as_str());
+        }
+    }
+    for ((_, asset), owners) in recipients {
+        let candidate = candidates.entry(asset.to_string()).or_default();
+        candidate.max_recipients_per_transaction =
+            candidate.max_recipients_per_transaction.max(owners.len());
+    }
+    candidates
+}
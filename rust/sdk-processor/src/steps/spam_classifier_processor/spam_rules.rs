@@ -0,0 +1,233 @@
+use crate::processors::spam_classifier_processor::SpamRulesConfig;
+use ahash::{AHashMap, AHashSet};
+use anyhow::{Context, Result};
+use processor::{
+    db::postgres::models::spam_models::spam_assets::SpamAsset, utils::util::standardize_address,
+};
+use regex::{RegexSet, RegexSetBuilder};
+use tracing::info;
+use url::Url;
+
+/// What a batch says about a fungible asset, coin, or collection.
+#[derive(Debug, Default)]
+pub struct AssetCandidate {
+    /// Name and symbol of a fungible asset or coin, or name of a collection
+    pub names: Vec<String>,
+    pub uris: Vec<String>,
+    pub creator_address: Option<String>,
+    /// Most distinct recipients of the asset in a single transaction
+    pub max_recipients_per_transaction: usize,
+}
+
+/// Heuristics from the processor config, compiled once at startup.
+pub struct SpamRules {
+    name_patterns: RegexSet,
+    uri_domains: Vec<String>,
+    creator_blocklist: AHashSet<String>,
+    airdrop_min_recipients: Option<usize>,
+    min_score: usize,
+    overrides: AHashMap<String, bool>,
+}
+
+impl SpamRules {
+    pub fn new(config: &SpamRulesConfig, overrides: &AHashMap<String, bool>) -> Result<Self> {
+        let name_patterns = RegexSetBuilder::new(&config.name_patterns)
+            .case_insensitive(true)
+            .build()
+            .context("Invalid spam name pattern")?;
+        Ok(Self {
+            name_patterns,
+            uri_domains: config
+                .uri_domains
+                .iter()
+                .map(|domain| domain.trim_start_matches('.').to_lowercase())
+                .collect(),
+            creator_blocklist: config
+                .creator_blocklist
+                .iter()
+                .map(|address| standardize_address(address))
+                .collect(),
+            airdrop_min_recipients: config.airdrop_min_recipients,
+            min_score: config.min_score,
+            overrides: overrides
+                .iter()
+                .map(|(asset, is_spam)| (standardize_asset(asset), *is_spam))
+                .collect(),
+        })
+    }
+
+    /// Rows for the manual overrides, which take precedence over the heuristics.
+    pub fn override_rows(&self) -> Vec<SpamAsset> {
+        let mut rows: Vec<SpamAsset> = self
+            .overrides
+            .iter()
+            .map(|(asset, is_spam)| SpamAsset {
+                asset: asset.clone(),
+                is_spam: *is_spam,
+            })
+            .collect();
+        rows.sort_by(|a, b| a.asset.cmp(&b.asset));
+        rows
+    }
+
+    /// The heuristics the candidate matches. Each one adds one to the spam score.
+    pub fn signals(&self, candidate: &AssetCandidate) -> Vec<&'static str> {
+        let mut signals = vec![];
+        if candidate
+            .names
+            .iter()
+            .any(|name| self.name_patterns.is_match(name))
+        {
+            signals.push("name_pattern");
+        }
+        if candidate
+            .uris
+            .iter()
+            .any(|uri| self.matches_uri_domain(uri))
+        {
+            signals.push("uri_domain");
+        }
+        if candidate
+            .creator_address
+            .as_ref()
+            .is_some_and(|creator| self.creator_blocklist.contains(creator))
+        {
+            signals.push("creator_blocklist");
+        }
+        if self.airdrop_min_recipients.is_some_and(|min_recipients| {
+            candidate.max_recipients_per_transaction >= min_recipients
+        }) {
+            signals.push("mass_airdrop");
+        }
+        signals
+    }
+
+    /// Spam rows for the candidates that reach the minimum score, sorted by asset. Assets with a
+    /// manual override are left alone.
+    pub fn classify(&self, candidates: &AHashMap<String, AssetCandidate>) -> Vec<SpamAsset> {
+        let mut spam_assets = vec![];
+        for (asset, candidate) in candidates {
+            if self.overrides.contains_key(asset) {
+                continue;
+            }
+            let signals = self.signals(candidate);
+            if !signals.is_empty() && signals.len() >= self.min_score {
+                info!(asset = asset.as_str(), signals = ?signals, "Classified asset as spam");
+                spam_assets.push(SpamAsset {
+                    asset: asset.clone(),
+                    is_spam: true,
+                });
+            }
+        }
+        spam_assets.sort_by(|a, b| a.asset.cmp(&b.asset));
+        spam_assets
+    }
+
+    fn matches_uri_domain(&self, uri: &str) -> bool {
+        let Some(host) = Url::parse(uri)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_lowercase()))
+        else {
+            return false;
+        };
+        self.uri_domains.iter().any(|domain| {
+            host == *domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        })
+    }
+}
+
+/// Coin types are kept as they are, addresses are standardized.
+fn standardize_asset(asset: &str) -> String {
+    if asset.contains("::") {
+        asset.to_string()
+    } else {
+        standardize_address(asset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> SpamRules {
+        let config = SpamRulesConfig {
+            name_patterns: vec!["claim.*reward".to_string(), r"\.(xyz|top)$".to_string()],
+            uri_domains: vec!["spam.example".to_string()],
+            creator_blocklist: vec!["0xbad".to_string()],
+            airdrop_min_recipients: Some(50),
+            min_score: 1,
+        };
+        let overrides = AHashMap::from([("0xa".to_string(), false), ("0xb".to_string(), true)]);
+        SpamRules::new(&config, &overrides).unwrap()
+    }
+
+    #[test]
+    fn test_signals() {
+        let rules = rules();
+        let candidate = AssetCandidate {
+            names: vec!["Claim your REWARD".to_string(), "CLAIM".to_string()],
+            uris: vec!["https://cdn.spam.example/icon.png".to_string()],
+            creator_address: Some(standardize_address("0xbad")),
+            max_recipients_per_transaction: 50,
+        };
+        assert_eq!(
+            rules.signals(&candidate),
+            vec![
+                "name_pattern",
+                "uri_domain",
+                "creator_blocklist",
+                "mass_airdrop"
+            ]
+        );
+
+        let candidate = AssetCandidate {
+            names: vec!["Aptos Coin".to_string(), "APT".to_string()],
+            uris: vec![
+                "https://notspam.example/icon.png".to_string(),
+                "not a uri".to_string(),
+            ],
+            creator_address: Some(standardize_address("0x1")),
+            max_recipients_per_transaction: 49,
+        };
+        assert!(rules.signals(&candidate).is_empty());
+    }
+
+    #[test]
+    fn test_classify_skips_overrides() {
+        let rules = rules();
+        let spam_candidate = || AssetCandidate {
+            names: vec!["rewards.xyz".to_string()],
+            ..Default::default()
+        };
+        let candidates = AHashMap::from([
+            (standardize_address("0xa"), spam_candidate()),
+            (standardize_address("0xc"), spam_candidate()),
+            (
+                "0x1::aptos_coin::AptosCoin".to_string(),
+                AssetCandidate::default(),
+            ),
+        ]);
+        let spam_assets: Vec<_> = rules
+            .classify(&candidates)
+            .into_iter()
+            .map(|row| row.asset)
+            .collect();
+        assert_eq!(spam_assets, vec![standardize_address("0xc")]);
+
+        let overrides: Vec<_> = rules
+            .override_rows()
+            .into_iter()
+            .map(|row| (row.asset, row.is_spam))
+            .collect();
+        assert_eq!(
+            overrides,
+            vec![
+                (standardize_address("0xa"), false),
+                (standardize_address("0xb"), true),
+            ]
+        );
+    }
+}
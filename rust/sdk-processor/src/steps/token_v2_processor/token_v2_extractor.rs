@@ -27,12 +27,14 @@ use processor::{
                 v2_token_datas::{CurrentTokenDataV2, TokenDataV2},
                 v2_token_metadata::CurrentTokenV2Metadata,
                 v2_token_ownerships::{CurrentTokenOwnershipV2, TokenOwnershipV2},
+                v2_token_properties::TokenProperties,
             },
         },
     },
     processors::token_v2_processor::parse_v2_token,
-    utils::database::DbContext,
+    utils::{address_labels::AddressLabelRegistry, database::DbContext},
 };
+use std::sync::Arc;
 
 /// Extracts fungible asset events, metadata, balances, and v1 supply from transactions
 pub struct TokenV2Extractor
@@ -42,14 +44,21 @@ where
     query_retries: u32,
     query_retry_delay_ms: u64,
     conn_pool: ArcDbPool,
+    address_labels: Option<Arc<AddressLabelRegistry>>,
 }
 
 impl TokenV2Extractor {
-    pub fn new(query_retries: u32, query_retry_delay_ms: u64, conn_pool: ArcDbPool) -> Self {
+    pub fn new(
+        query_retries: u32,
+        query_retry_delay_ms: u64,
+        conn_pool: ArcDbPool,
+        address_labels: Option<Arc<AddressLabelRegistry>>,
+    ) -> Self {
         Self {
             query_retries,
             query_retry_delay_ms,
             conn_pool,
+            address_labels,
         }
     }
 }
@@ -70,6 +79,7 @@ impl Processable for TokenV2Extractor {
         Vec<CurrentTokenV2Metadata>,
         Vec<CurrentTokenRoyaltyV1>,
         Vec<CurrentTokenPendingClaim>,
+        Vec<TokenProperties>,
     );
     type RunType = AsyncRunType;
 
@@ -91,6 +101,7 @@ impl Processable for TokenV2Extractor {
                 Vec<CurrentTokenV2Metadata>,
                 Vec<CurrentTokenRoyaltyV1>,
                 Vec<CurrentTokenPendingClaim>,
+                Vec<TokenProperties>,
             )>,
         >,
         ProcessorError,
@@ -114,6 +125,8 @@ impl Processable for TokenV2Extractor {
             query_retry_delay_ms: self.query_retry_delay_ms,
         };
 
+        let token_properties = TokenProperties::from_transactions(&transactions.data);
+
         let (
             collections_v2,
             raw_token_datas_v2,
@@ -151,10 +164,13 @@ impl Processable for TokenV2Extractor {
                 .map(CurrentTokenV2Metadata::from_raw)
                 .collect();
 
-        let postgres_token_activities_v2: Vec<TokenActivityV2> = raw_token_activities_v2
+        let mut postgres_token_activities_v2: Vec<TokenActivityV2> = raw_token_activities_v2
             .into_iter()
             .map(TokenActivityV2::from_raw)
             .collect();
+        if let Some(address_labels) = &self.address_labels {
+            address_labels.apply_labels(&mut postgres_token_activities_v2);
+        }
 
         let postgres_token_datas_v2: Vec<TokenDataV2> = raw_token_datas_v2
             .into_iter()
@@ -203,6 +219,7 @@ impl Processable for TokenV2Extractor {
                 postgres_current_token_v2_metadata,
                 postgres_current_token_royalties_v1,
                 postgres_current_token_claims,
+                token_properties,
             ),
             metadata: transactions.metadata,
         }))
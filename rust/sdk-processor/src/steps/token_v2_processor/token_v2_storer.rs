@@ -1,8 +1,11 @@
 use crate::{
     processors::token_v2_processor::TokenV2ProcessorConfig,
-    utils::database::{execute_in_chunks, get_config_table_chunk_size, ArcDbPool},
+    steps::common::change_capture::ChangeCapture,
+    utils::database::{
+        execute_in_chunks, get_config_table_chunk_size, ArcDbPool, MAX_DIESEL_PARAM_SIZE,
+    },
 };
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
 use anyhow::Result;
 use aptos_indexer_processor_sdk::{
     traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
@@ -10,17 +13,32 @@ use aptos_indexer_processor_sdk::{
     utils::errors::ProcessorError,
 };
 use async_trait::async_trait;
+use diesel::{
+    dsl::{now, sql},
+    sql_query,
+    sql_types::{Array, BigInt, Text},
+    upsert::excluded,
+    ExpressionMethods, QueryDsl, QueryableByName,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+use field_count::FieldCount;
 use processor::{
     self,
     db::postgres::models::{
         token_models::token_claims::CurrentTokenPendingClaim,
         token_v2_models::{
             v1_token_royalty::CurrentTokenRoyaltyV1,
+            v2_collection_stats::{
+                CollectionActivity, CollectionActivityHourly, CurrentCollectionHolder,
+                CurrentCollectionStats,
+            },
             v2_collections::{CollectionV2, CurrentCollectionV2},
             v2_token_activities::TokenActivityV2,
+            v2_token_compositions::{TokenComposition, TokenOwnerChange},
             v2_token_datas::{CurrentTokenDataV2, TokenDataV2},
             v2_token_metadata::CurrentTokenV2Metadata,
             v2_token_ownerships::{CurrentTokenOwnershipV2, TokenOwnershipV2},
+            v2_token_properties::{CollectionTraitCount, StoredTokenProperty, TokenProperties},
         },
     },
     processors::token_v2_processor::{
@@ -32,7 +50,81 @@ use processor::{
         insert_token_activities_v2_query, insert_token_datas_v2_query,
         insert_token_ownerships_v2_query,
     },
+    schema,
+    utils::table_flags::TableFlags,
 };
+use std::collections::BTreeSet;
+
+/// Collections of the given tokens.
+const SELECT_TOKEN_COLLECTIONS_QUERY: &str = "
+    SELECT token_data_id, collection_id
+    FROM current_token_datas_v2
+    WHERE token_data_id = ANY($1)
+";
+
+/// Stored properties of the given tokens, locked until the batch's changes are written.
+const SELECT_CURRENT_TOKEN_PROPERTIES_QUERY: &str = "
+    SELECT token_data_id, property_key, collection_id, property_type, property_value, last_transaction_version
+    FROM current_token_properties
+    WHERE token_data_id = ANY($1)
+    FOR UPDATE
+";
+
+/// Recounts the tokens each given owner holds in the given collection from
+/// `current_token_ownerships_v2`, with 0 for owners that have none left.
+const COUNT_COLLECTION_HOLDERS_QUERY: &str = "
+    SELECT
+        h.collection_id,
+        h.owner_address,
+        COALESCE((
+            SELECT SUM(o.amount)
+            FROM current_token_ownerships_v2 o
+            JOIN current_token_datas_v2 d ON d.token_data_id = o.token_data_id
+            WHERE o.owner_address = h.owner_address
+                AND d.collection_id = h.collection_id
+                AND o.amount > 0
+        ), 0) AS num_tokens,
+        $3::BIGINT AS last_transaction_version
+    FROM unnest($1::TEXT[], $2::TEXT[]) AS h(collection_id, owner_address)
+";
+
+/// Stored holders of the given (collection, owner) pairs, locked until the batch's changes are
+/// written.
+const SELECT_CURRENT_COLLECTION_HOLDERS_QUERY: &str = "
+    SELECT c.collection_id, c.owner_address, c.num_tokens, c.last_transaction_version
+    FROM current_collection_holders c
+    JOIN unnest($1::TEXT[], $2::TEXT[]) AS h(collection_id, owner_address)
+        ON h.collection_id = c.collection_id AND h.owner_address = c.owner_address
+    FOR UPDATE OF c
+";
+
+const DELETE_CURRENT_COLLECTION_HOLDERS_QUERY: &str = "
+    DELETE FROM current_collection_holders c
+    USING unnest($1::TEXT[], $2::TEXT[]) AS h(collection_id, owner_address)
+    WHERE c.collection_id = h.collection_id AND c.owner_address = h.owner_address
+";
+
+/// The last composition of each token that started before the token's first owner change in the
+/// batch. The compositions are locked until the batch's changes are written.
+const SELECT_LATEST_TOKEN_COMPOSITIONS_QUERY: &str = "
+    SELECT tc.*
+    FROM unnest($1::TEXT[], $2::BIGINT[]) AS f(child_token_data_id, transaction_version)
+    CROSS JOIN LATERAL (
+        SELECT
+            child_token_data_id,
+            compose_transaction_version,
+            parent_token_data_id,
+            compose_timestamp,
+            decompose_transaction_version,
+            decompose_timestamp
+        FROM token_compositions
+        WHERE child_token_data_id = f.child_token_data_id
+            AND compose_transaction_version < f.transaction_version
+        ORDER BY compose_transaction_version DESC
+        LIMIT 1
+        FOR UPDATE
+    ) tc
+";
 
 pub struct TokenV2Storer
 where
@@ -40,13 +132,22 @@ where
 {
     conn_pool: ArcDbPool,
     processor_config: TokenV2ProcessorConfig,
+    deprecated_tables: TableFlags,
+    change_capture: Option<ChangeCapture>,
 }
 
 impl TokenV2Storer {
-    pub fn new(conn_pool: ArcDbPool, processor_config: TokenV2ProcessorConfig) -> Self {
+    pub fn new(
+        conn_pool: ArcDbPool,
+        processor_config: TokenV2ProcessorConfig,
+        deprecated_tables: TableFlags,
+        change_capture: Option<ChangeCapture>,
+    ) -> Self {
         Self {
             conn_pool,
             processor_config,
+            deprecated_tables,
+            change_capture,
         }
     }
 }
@@ -66,6 +167,7 @@ impl Processable for TokenV2Storer {
         Vec<CurrentTokenV2Metadata>,
         Vec<CurrentTokenRoyaltyV1>,
         Vec<CurrentTokenPendingClaim>,
+        Vec<TokenProperties>,
     );
     type Output = ();
     type RunType = AsyncRunType;
@@ -85,6 +187,7 @@ impl Processable for TokenV2Storer {
             Vec<CurrentTokenV2Metadata>,
             Vec<CurrentTokenRoyaltyV1>,
             Vec<CurrentTokenPendingClaim>,
+            Vec<TokenProperties>,
         )>,
     ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
         let (
@@ -100,6 +203,7 @@ impl Processable for TokenV2Storer {
             current_token_v2_metadata,
             current_token_royalties_v1,
             current_token_claims,
+            token_properties,
         ) = input.data;
 
         let per_table_chunk_sizes: AHashMap<String, usize> = self
@@ -108,6 +212,24 @@ impl Processable for TokenV2Storer {
             .per_table_chunk_sizes
             .clone();
 
+        // Snapshot the rows about to be upserted before they are overwritten
+        let ownerships_snapshot = match &self.change_capture {
+            Some(change_capture) => Some(
+                change_capture
+                    .snapshot(
+                        current_token_ownerships_v2
+                            .iter()
+                            .chain(&current_deleted_token_ownerships_v2),
+                    )
+                    .await
+                    .map_err(|e| ProcessorError::DBStoreError {
+                        message: format!("Failed to snapshot current_token_ownerships_v2: {:?}", e),
+                        query: None,
+                    })?,
+            ),
+            None => None,
+        };
+
         let coll_v2 = execute_in_chunks(
             self.conn_pool.clone(),
             insert_collections_v2_query,
@@ -257,6 +379,58 @@ impl Processable for TokenV2Storer {
             }
         }
 
+        // These run after the token datas are committed, since they take the collection from them
+        if !self
+            .deprecated_tables
+            .contains(TableFlags::CURRENT_TOKEN_PROPERTIES)
+        {
+            sync_token_properties(self.conn_pool.clone(), token_properties).await?;
+        }
+        if !self
+            .deprecated_tables
+            .contains(TableFlags::TOKEN_COMPOSITIONS)
+        {
+            update_token_compositions(
+                self.conn_pool.clone(),
+                TokenOwnerChange::from_ownerships_and_activities(
+                    &token_ownerships_v2,
+                    &token_activities_v2,
+                ),
+            )
+            .await?;
+        }
+        if !self
+            .deprecated_tables
+            .contains(TableFlags::CURRENT_COLLECTION_STATS)
+        {
+            update_collection_stats(
+                self.conn_pool.clone(),
+                &token_activities_v2,
+                current_token_ownerships_v2
+                    .iter()
+                    .chain(&current_deleted_token_ownerships_v2),
+                input.metadata.end_version as i64,
+            )
+            .await?;
+        }
+
+        if let (Some(change_capture), Some(snapshot)) = (&self.change_capture, ownerships_snapshot)
+        {
+            change_capture
+                .publish_changes(
+                    snapshot,
+                    input.metadata.start_version,
+                    input.metadata.end_version,
+                )
+                .await
+                .map_err(|e| ProcessorError::ProcessError {
+                    message: format!(
+                        "Failed to publish change events for versions {} to {}: {:?}",
+                        input.metadata.start_version, input.metadata.end_version, e,
+                    ),
+                })?;
+        }
+
         Ok(Some(TransactionContext {
             data: (),
             metadata: input.metadata,
@@ -264,6 +438,372 @@ impl Processable for TokenV2Storer {
     }
 }
 
+#[derive(QueryableByName)]
+#[diesel(table_name = schema::current_token_datas_v2)]
+struct TokenCollection {
+    token_data_id: String,
+    collection_id: String,
+}
+
+/// Keeps `current_token_properties` and `current_collection_trait_counts` in sync with the latest
+/// property maps of the batch. The collection of a token comes from `current_token_datas_v2`, so
+/// this runs after the token datas are written.
+///
+/// The stored properties are read and the changes written in one transaction, so a batch that
+/// fails halfway is replayed against the properties it started from.
+pub async fn sync_token_properties(
+    conn_pool: ArcDbPool,
+    token_properties: Vec<TokenProperties>,
+) -> Result<(), ProcessorError> {
+    if token_properties.is_empty() {
+        return Ok(());
+    }
+    let token_data_ids: Vec<String> = token_properties
+        .iter()
+        .map(|token| token.token_data_id.clone())
+        .collect();
+    let mut conn = conn_pool
+        .get()
+        .await
+        .map_err(|e| ProcessorError::DBStoreError {
+            message: format!("{:#}", e),
+            query: None,
+        })?;
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            use schema::current_collection_trait_counts::dsl::*;
+
+            let collections: AHashMap<String, String> = sql_query(SELECT_TOKEN_COLLECTIONS_QUERY)
+                .bind::<Array<Text>, _>(token_data_ids.clone())
+                .load::<TokenCollection>(conn)
+                .await?
+                .into_iter()
+                .map(|token| (token.token_data_id, token.collection_id))
+                .collect();
+            let stored_properties = sql_query(SELECT_CURRENT_TOKEN_PROPERTIES_QUERY)
+                .bind::<Array<Text>, _>(token_data_ids)
+                .load::<StoredTokenProperty>(conn)
+                .await?;
+            let changes =
+                TokenProperties::get_changes(token_properties, &collections, stored_properties);
+
+            diesel::delete(schema::current_token_properties::table.filter(
+                schema::current_token_properties::token_data_id.eq_any(&changes.token_data_ids),
+            ))
+            .execute(conn)
+            .await?;
+            for chunk in changes
+                .properties
+                .chunks(MAX_DIESEL_PARAM_SIZE / StoredTokenProperty::field_count())
+            {
+                diesel::insert_into(schema::current_token_properties::table)
+                    .values(chunk)
+                    .execute(conn)
+                    .await?;
+            }
+
+            for chunk in changes
+                .trait_count_changes
+                .chunks(MAX_DIESEL_PARAM_SIZE / CollectionTraitCount::field_count())
+            {
+                diesel::insert_into(current_collection_trait_counts)
+                    .values(chunk)
+                    .on_conflict((collection_id, property_key_hash, property_value_hash))
+                    .do_update()
+                    .set((
+                        num_tokens.eq(num_tokens + excluded(num_tokens)),
+                        last_transaction_version.eq(sql::<BigInt>(
+                            "GREATEST(current_collection_trait_counts.last_transaction_version, EXCLUDED.last_transaction_version)",
+                        )),
+                        inserted_at.eq(now),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+            // Traits no token has anymore
+            let collection_ids: Vec<&String> = changes
+                .trait_count_changes
+                .iter()
+                .map(|trait_count| &trait_count.collection_id)
+                .collect();
+            diesel::delete(
+                current_collection_trait_counts
+                    .filter(num_tokens.le(0))
+                    .filter(collection_id.eq_any(collection_ids)),
+            )
+            .execute(conn)
+            .await?;
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(|e| ProcessorError::DBStoreError {
+        message: format!("Failed to sync token properties: {:#}", e),
+        query: None,
+    })
+}
+
+/// Records the periods in which tokens were owned by other tokens. Compositions are keyed by their
+/// compose version, so replaying a batch doesn't add them twice. The owners that are tokens are
+/// looked up in `current_token_datas_v2`, so this runs after the token datas are written.
+pub async fn update_token_compositions(
+    conn_pool: ArcDbPool,
+    owner_changes: Vec<TokenOwnerChange>,
+) -> Result<(), ProcessorError> {
+    if owner_changes.is_empty() {
+        return Ok(());
+    }
+    let mut first_versions: AHashMap<String, i64> = AHashMap::new();
+    for owner_change in &owner_changes {
+        first_versions
+            .entry(owner_change.token_data_id.clone())
+            .and_modify(|version| *version = (*version).min(owner_change.transaction_version))
+            .or_insert(owner_change.transaction_version);
+    }
+    let (child_token_data_ids, first_change_versions): (Vec<String>, Vec<i64>) =
+        first_versions.into_iter().unzip();
+    let owner_addresses: Vec<String> = owner_changes
+        .iter()
+        .filter_map(|owner_change| owner_change.owner_address.clone())
+        .collect::<AHashSet<_>>()
+        .into_iter()
+        .collect();
+    let mut conn = conn_pool
+        .get()
+        .await
+        .map_err(|e| ProcessorError::DBStoreError {
+            message: format!("{:#}", e),
+            query: None,
+        })?;
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let latest_compositions = sql_query(SELECT_LATEST_TOKEN_COMPOSITIONS_QUERY)
+                .bind::<Array<Text>, _>(child_token_data_ids)
+                .bind::<Array<BigInt>, _>(first_change_versions)
+                .load::<TokenComposition>(conn)
+                .await?;
+            let tokens: AHashSet<String> = sql_query(SELECT_TOKEN_COLLECTIONS_QUERY)
+                .bind::<Array<Text>, _>(owner_addresses)
+                .load::<TokenCollection>(conn)
+                .await?
+                .into_iter()
+                .map(|token| token.token_data_id)
+                .collect();
+            let changes =
+                TokenComposition::get_changes(&owner_changes, latest_compositions, &tokens);
+
+            for chunk in changes
+                .compositions
+                .chunks(MAX_DIESEL_PARAM_SIZE / TokenComposition::field_count())
+            {
+                diesel::insert_into(schema::token_compositions::table)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .await?;
+            }
+            for chunk in changes
+                .decomposed
+                .chunks(MAX_DIESEL_PARAM_SIZE / TokenComposition::field_count())
+            {
+                use schema::token_compositions::dsl::*;
+                diesel::insert_into(token_compositions)
+                    .values(chunk)
+                    .on_conflict((child_token_data_id, compose_transaction_version))
+                    .do_update()
+                    .set((
+                        decompose_transaction_version.eq(excluded(decompose_transaction_version)),
+                        decompose_timestamp.eq(excluded(decompose_timestamp)),
+                        inserted_at.eq(now),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(|e| ProcessorError::DBStoreError {
+        message: format!("Failed to update token compositions: {:#}", e),
+        query: None,
+    })
+}
+
+/// Records the transfers and burns of the batch and adds the ones recorded for the first time to
+/// the hourly rollup and the burned counts, then updates the holders of every (collection, owner)
+/// whose ownership changed. The collection of a token comes from `current_token_datas_v2`, so this
+/// runs after the token datas are written.
+///
+/// Everything is read and written in one transaction, so a batch that fails halfway is replayed
+/// against the activities and holders it started from.
+pub async fn update_collection_stats<'a>(
+    conn_pool: ArcDbPool,
+    token_activities: &[TokenActivityV2],
+    ownerships: impl Iterator<Item = &'a CurrentTokenOwnershipV2>,
+    end_version: i64,
+) -> Result<(), ProcessorError> {
+    let ownerships: Vec<&CurrentTokenOwnershipV2> = ownerships.collect();
+    if token_activities.is_empty() && ownerships.is_empty() {
+        return Ok(());
+    }
+    let token_data_ids: Vec<String> = token_activities
+        .iter()
+        .map(|activity| activity.token_data_id.clone())
+        .chain(
+            ownerships
+                .iter()
+                .map(|ownership| ownership.token_data_id.clone()),
+        )
+        .collect::<AHashSet<_>>()
+        .into_iter()
+        .collect();
+    let mut conn = conn_pool
+        .get()
+        .await
+        .map_err(|e| ProcessorError::DBStoreError {
+            message: format!("{:#}", e),
+            query: None,
+        })?;
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let collections: AHashMap<String, String> = sql_query(SELECT_TOKEN_COLLECTIONS_QUERY)
+                .bind::<Array<Text>, _>(token_data_ids)
+                .load::<TokenCollection>(conn)
+                .await?
+                .into_iter()
+                .map(|token| (token.token_data_id, token.collection_id))
+                .collect();
+
+            // Activities already recorded by an earlier run of the batch aren't returned
+            let mut new_activities = vec![];
+            for chunk in CollectionActivity::from_activities(token_activities, &collections)
+                .chunks(MAX_DIESEL_PARAM_SIZE / CollectionActivity::field_count())
+            {
+                use schema::collection_activities::dsl::*;
+                new_activities.extend(
+                    diesel::insert_into(collection_activities)
+                        .values(chunk)
+                        .on_conflict_do_nothing()
+                        .returning((
+                            transaction_version,
+                            event_index,
+                            collection_id,
+                            activity_type,
+                            transaction_timestamp,
+                        ))
+                        .get_results::<CollectionActivity>(conn)
+                        .await?,
+                );
+            }
+            for chunk in CollectionActivityHourly::from_collection_activities(&new_activities)
+                .chunks(MAX_DIESEL_PARAM_SIZE / CollectionActivityHourly::field_count())
+            {
+                use schema::collection_activity_hourly::dsl::*;
+                diesel::insert_into(collection_activity_hourly)
+                    .values(chunk)
+                    .on_conflict((collection_id, hour))
+                    .do_update()
+                    .set((
+                        num_transfers.eq(num_transfers + excluded(num_transfers)),
+                        num_burns.eq(num_burns + excluded(num_burns)),
+                        last_transaction_version.eq(sql::<BigInt>(
+                            "GREATEST(collection_activity_hourly.last_transaction_version, EXCLUDED.last_transaction_version)",
+                        )),
+                        inserted_at.eq(now),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+
+            let (holder_collection_ids, holder_owner_addresses): (Vec<String>, Vec<String>) =
+                ownerships
+                    .iter()
+                    .filter_map(|ownership| {
+                        let collection_id = collections.get(&ownership.token_data_id)?;
+                        Some((collection_id.clone(), ownership.owner_address.clone()))
+                    })
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .unzip();
+            let counted_holders = sql_query(COUNT_COLLECTION_HOLDERS_QUERY)
+                .bind::<Array<Text>, _>(holder_collection_ids.clone())
+                .bind::<Array<Text>, _>(holder_owner_addresses.clone())
+                .bind::<BigInt, _>(end_version)
+                .load::<CurrentCollectionHolder>(conn)
+                .await?;
+            let stored_holders = sql_query(SELECT_CURRENT_COLLECTION_HOLDERS_QUERY)
+                .bind::<Array<Text>, _>(holder_collection_ids)
+                .bind::<Array<Text>, _>(holder_owner_addresses)
+                .load::<CurrentCollectionHolder>(conn)
+                .await?;
+            let changes =
+                CurrentCollectionStats::get_changes(stored_holders, counted_holders, &new_activities);
+
+            for chunk in changes
+                .holders
+                .chunks(MAX_DIESEL_PARAM_SIZE / CurrentCollectionHolder::field_count())
+            {
+                use schema::current_collection_holders::dsl::*;
+                diesel::insert_into(current_collection_holders)
+                    .values(chunk)
+                    .on_conflict((collection_id, owner_address))
+                    .do_update()
+                    .set((
+                        num_tokens.eq(excluded(num_tokens)),
+                        last_transaction_version.eq(excluded(last_transaction_version)),
+                        inserted_at.eq(now),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+            let (removed_collection_ids, removed_owner_addresses): (Vec<String>, Vec<String>) =
+                changes
+                    .removed_holders
+                    .into_iter()
+                    .map(|holder| (holder.collection_id, holder.owner_address))
+                    .unzip();
+            sql_query(DELETE_CURRENT_COLLECTION_HOLDERS_QUERY)
+                .bind::<Array<Text>, _>(removed_collection_ids)
+                .bind::<Array<Text>, _>(removed_owner_addresses)
+                .execute(conn)
+                .await?;
+            for chunk in changes
+                .stats
+                .chunks(MAX_DIESEL_PARAM_SIZE / CurrentCollectionStats::field_count())
+            {
+                use schema::current_collection_stats::dsl::*;
+                diesel::insert_into(current_collection_stats)
+                    .values(chunk)
+                    .on_conflict(collection_id)
+                    .do_update()
+                    .set((
+                        num_holders.eq(num_holders + excluded(num_holders)),
+                        num_holders_1.eq(num_holders_1 + excluded(num_holders_1)),
+                        num_holders_2_to_5.eq(num_holders_2_to_5 + excluded(num_holders_2_to_5)),
+                        num_holders_6_to_20.eq(num_holders_6_to_20 + excluded(num_holders_6_to_20)),
+                        num_holders_21_plus.eq(num_holders_21_plus + excluded(num_holders_21_plus)),
+                        num_burned.eq(num_burned + excluded(num_burned)),
+                        last_transaction_version.eq(sql::<BigInt>(
+                            "GREATEST(current_collection_stats.last_transaction_version, EXCLUDED.last_transaction_version)",
+                        )),
+                        inserted_at.eq(now),
+                    ))
+                    .execute(conn)
+                    .await?;
+            }
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(|e| ProcessorError::DBStoreError {
+        message: format!("Failed to update collection stats: {:#}", e),
+        query: None,
+    })
+}
+
 impl AsyncStep for TokenV2Storer {}
 
 impl NamedStep for TokenV2Storer {
@@ -0,0 +1,5 @@
+pub mod transaction_failure_extractor;
+pub mod transaction_failure_storer;
+
+pub use transaction_failure_extractor::TransactionFailureExtractor;
+pub use transaction_failure_storer::TransactionFailureStorer;
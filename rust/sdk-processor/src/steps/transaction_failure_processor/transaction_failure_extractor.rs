@@ -0,0 +1,52 @@
+use aptos_indexer_processor_sdk::{
+    aptos_protos::transaction::v1::Transaction,
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use processor::db::postgres::models::user_transactions_models::transaction_failures::{
+    TransactionFailure, UserTransactionOutcome,
+};
+
+/// Extracts the failed user transactions, and the outcome of every user transaction for the
+/// failure rate rollups
+pub struct TransactionFailureExtractor
+where
+    Self: Sized + Send + 'static, {}
+
+#[async_trait]
+impl Processable for TransactionFailureExtractor {
+    type Input = Vec<Transaction>;
+    type Output = (Vec<TransactionFailure>, Vec<UserTransactionOutcome>);
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        item: TransactionContext<Vec<Transaction>>,
+    ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
+        let mut failures = vec![];
+        let mut outcomes = vec![];
+        for (outcome, failure) in item
+            .data
+            .iter()
+            .filter_map(TransactionFailure::from_transaction)
+        {
+            outcomes.push(outcome);
+            failures.extend(failure);
+        }
+
+        Ok(Some(TransactionContext {
+            data: (failures, outcomes),
+            metadata: item.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for TransactionFailureExtractor {}
+
+impl NamedStep for TransactionFailureExtractor {
+    fn name(&self) -> String {
+        "TransactionFailureExtractor".to_string()
+    }
+}
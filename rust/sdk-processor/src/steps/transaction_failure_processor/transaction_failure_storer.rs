@@ -0,0 +1,196 @@
+use crate::{
+    config::processor_config::DefaultProcessorConfig,
+    utils::database::{
+        execute_in_chunks, execute_with_better_error, get_config_table_chunk_size, ArcDbPool,
+    },
+};
+use ahash::AHashMap;
+use anyhow::Result;
+use aptos_indexer_processor_sdk::{
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use diesel::{
+    pg::Pg,
+    query_builder::QueryFragment,
+    sql_query,
+    sql_types::{Array, BigInt},
+};
+use processor::{
+    db::postgres::models::user_transactions_models::transaction_failures::{
+        TransactionFailure, UserTransactionOutcome,
+    },
+    schema,
+};
+
+/// Recomputes the hourly rollup of every (entry function, hour) touched by the given transaction
+/// versions from `user_transaction_outcomes` and `transaction_failures`. Recomputing rather than
+/// incrementing keeps the rollup correct when a batch is replayed or processed out of order.
+const RECOMPUTE_ENTRY_FUNCTION_FAILURES_HOURLY_QUERY: &str = "
+    WITH buckets AS (
+        SELECT DISTINCT entry_function_id_str, date_trunc('hour', transaction_timestamp) AS hour
+        FROM user_transaction_outcomes
+        WHERE transaction_version = ANY($1)
+    )
+    INSERT INTO entry_function_failures_hourly (
+        entry_function_id_str,
+        hour,
+        num_transactions,
+        num_failed_transactions,
+        num_move_aborts,
+        num_execution_failures,
+        num_out_of_gas,
+        last_transaction_version
+    )
+    SELECT
+        b.entry_function_id_str,
+        b.hour,
+        COUNT(*),
+        COUNT(*) FILTER (WHERE NOT o.is_transaction_success),
+        COUNT(*) FILTER (WHERE f.failure_type = 'move_abort'),
+        COUNT(*) FILTER (WHERE f.failure_type = 'execution_failure'),
+        COUNT(*) FILTER (WHERE f.failure_type = 'out_of_gas'),
+        MAX(o.transaction_version)
+    FROM buckets b
+    JOIN user_transaction_outcomes o
+        ON o.entry_function_id_str = b.entry_function_id_str
+        AND o.transaction_timestamp >= b.hour
+        AND o.transaction_timestamp < b.hour + INTERVAL '1 hour'
+    LEFT JOIN transaction_failures f ON f.transaction_version = o.transaction_version
+    GROUP BY b.entry_function_id_str, b.hour
+    ON CONFLICT (entry_function_id_str, hour) DO UPDATE SET
+        num_transactions = EXCLUDED.num_transactions,
+        num_failed_transactions = EXCLUDED.num_failed_transactions,
+        num_move_aborts = EXCLUDED.num_move_aborts,
+        num_execution_failures = EXCLUDED.num_execution_failures,
+        num_out_of_gas = EXCLUDED.num_out_of_gas,
+        last_transaction_version = EXCLUDED.last_transaction_version,
+        inserted_at = NOW()
+";
+
+pub struct TransactionFailureStorer
+where
+    Self: Sized + Send + 'static,
+{
+    conn_pool: ArcDbPool,
+    processor_config: DefaultProcessorConfig,
+}
+
+impl TransactionFailureStorer {
+    pub fn new(conn_pool: ArcDbPool, processor_config: DefaultProcessorConfig) -> Self {
+        Self {
+            conn_pool,
+            processor_config,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for TransactionFailureStorer {
+    type Input = (Vec<TransactionFailure>, Vec<UserTransactionOutcome>);
+    type Output = ();
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        input: TransactionContext<Self::Input>,
+    ) -> Result<Option<TransactionContext<()>>, ProcessorError> {
+        let (failures, outcomes) = input.data;
+
+        let per_table_chunk_sizes: AHashMap<String, usize> =
+            self.processor_config.per_table_chunk_sizes.clone();
+
+        let tf_res = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_transaction_failures_query,
+            &failures,
+            get_config_table_chunk_size::<TransactionFailure>(
+                "transaction_failures",
+                &per_table_chunk_sizes,
+            ),
+        );
+        let transaction_versions: Vec<i64> = outcomes
+            .iter()
+            .map(|outcome| outcome.transaction_version)
+            .collect();
+        let uto_res = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_user_transaction_outcomes_query,
+            &outcomes,
+            get_config_table_chunk_size::<UserTransactionOutcome>(
+                "user_transaction_outcomes",
+                &per_table_chunk_sizes,
+            ),
+        );
+
+        futures::try_join!(tf_res, uto_res)?;
+
+        // The rollup reads the rows written above, so it runs once they're committed
+        recompute_entry_function_failures_hourly(self.conn_pool.clone(), transaction_versions)
+            .await?;
+
+        Ok(Some(TransactionContext {
+            data: (),
+            metadata: input.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for TransactionFailureStorer {}
+
+impl NamedStep for TransactionFailureStorer {
+    fn name(&self) -> String {
+        "TransactionFailureStorer".to_string()
+    }
+}
+
+fn insert_transaction_failures_query(
+    items_to_insert: Vec<TransactionFailure>,
+) -> (
+    impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
+    Option<&'static str>,
+) {
+    use schema::transaction_failures::dsl::*;
+    (
+        diesel::insert_into(schema::transaction_failures::table)
+            .values(items_to_insert)
+            .on_conflict(transaction_version)
+            .do_nothing(),
+        None,
+    )
+}
+
+fn insert_user_transaction_outcomes_query(
+    items_to_insert: Vec<UserTransactionOutcome>,
+) -> (
+    impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
+    Option<&'static str>,
+) {
+    use schema::user_transaction_outcomes::dsl::*;
+    (
+        diesel::insert_into(schema::user_transaction_outcomes::table)
+            .values(items_to_insert)
+            .on_conflict(transaction_version)
+            .do_nothing(),
+        None,
+    )
+}
+
+async fn recompute_entry_function_failures_hourly(
+    conn_pool: ArcDbPool,
+    transaction_versions: Vec<i64>,
+) -> Result<(), ProcessorError> {
+    if transaction_versions.is_empty() {
+        return Ok(());
+    }
+    execute_with_better_error(
+        conn_pool,
+        sql_query(RECOMPUTE_ENTRY_FUNCTION_FAILURES_HOURLY_QUERY)
+            .bind::<Array<BigInt>, _>(transaction_versions),
+        None,
+    )
+    .await?;
+    Ok(())
+}
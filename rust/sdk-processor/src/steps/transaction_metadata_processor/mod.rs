@@ -0,0 +1,5 @@
+pub mod transaction_metadata_extractor;
+pub mod transaction_metadata_storer;
+
+pub use transaction_metadata_extractor::TransactionMetadataExtractor;
+pub use transaction_metadata_storer::TransactionMetadataStorer;
@@ -0,0 +1,62 @@
+use aptos_indexer_processor_sdk::{
+    aptos_protos::transaction::v1::Transaction,
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use processor::{
+    db::postgres::models::transaction_metadata_model::{
+        event_size_info::EventSize, transaction_gas_breakdown::TransactionGasBreakdown,
+        transaction_size_info::TransactionSize, write_set_size_info::WriteSetSize,
+    },
+    processors::transaction_metadata_processor::parse_transaction_metadata,
+};
+
+/// Extracts transaction, event, and write set sizes, and the gas breakdown of user transactions
+pub struct TransactionMetadataExtractor
+where
+    Self: Sized + Send + 'static, {}
+
+#[async_trait]
+impl Processable for TransactionMetadataExtractor {
+    type Input = Vec<Transaction>;
+    type Output = (
+        Vec<TransactionSize>,
+        Vec<EventSize>,
+        Vec<WriteSetSize>,
+        Vec<TransactionGasBreakdown>,
+    );
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        item: TransactionContext<Vec<Transaction>>,
+    ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
+        let (transaction_sizes, event_sizes, write_set_sizes) =
+            parse_transaction_metadata(&item.data);
+        let gas_breakdowns: Vec<TransactionGasBreakdown> = item
+            .data
+            .iter()
+            .filter_map(TransactionGasBreakdown::from_transaction)
+            .collect();
+
+        Ok(Some(TransactionContext {
+            data: (
+                transaction_sizes,
+                event_sizes,
+                write_set_sizes,
+                gas_breakdowns,
+            ),
+            metadata: item.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for TransactionMetadataExtractor {}
+
+impl NamedStep for TransactionMetadataExtractor {
+    fn name(&self) -> String {
+        "TransactionMetadataExtractor".to_string()
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    config::processor_config::DefaultProcessorConfig,
+    utils::database::{
+        execute_in_chunks, execute_with_better_error, get_config_table_chunk_size, ArcDbPool,
+    },
+};
+use ahash::AHashMap;
+use anyhow::Result;
+use aptos_indexer_processor_sdk::{
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use diesel::{
+    pg::Pg,
+    query_builder::QueryFragment,
+    sql_query,
+    sql_types::{Array, BigInt},
+};
+use processor::{
+    db::postgres::models::transaction_metadata_model::{
+        event_size_info::EventSize, transaction_gas_breakdown::TransactionGasBreakdown,
+        transaction_size_info::TransactionSize, write_set_size_info::WriteSetSize,
+    },
+    processors::transaction_metadata_processor::{
+        insert_event_sizes_query, insert_transaction_sizes_query, insert_write_set_sizes_query,
+    },
+    schema,
+};
+
+/// Recomputes the hourly rollup of every (entry function, hour) touched by the given transaction
+/// versions from `transaction_gas_breakdown`. Recomputing rather than incrementing keeps the rollup
+/// correct when a batch is replayed or processed out of order.
+const RECOMPUTE_ENTRY_FUNCTION_GAS_HOURLY_QUERY: &str = "
+    WITH buckets AS (
+        SELECT DISTINCT entry_function_id_str, date_trunc('hour', transaction_timestamp) AS hour
+        FROM transaction_gas_breakdown
+        WHERE transaction_version = ANY($1)
+    )
+    INSERT INTO entry_function_gas_hourly (
+        entry_function_id_str,
+        hour,
+        num_transactions,
+        num_failed_transactions,
+        total_gas_used,
+        total_execution_gas_units,
+        total_io_gas_units,
+        total_storage_fee_octas,
+        total_storage_fee_refund_octas,
+        total_gas_fee_octas,
+        last_transaction_version
+    )
+    SELECT
+        b.entry_function_id_str,
+        b.hour,
+        COUNT(*),
+        COUNT(*) FILTER (WHERE NOT g.is_transaction_success),
+        SUM(g.gas_used),
+        SUM(g.execution_gas_units),
+        SUM(g.io_gas_units),
+        SUM(g.storage_fee_octas),
+        SUM(g.storage_fee_refund_octas),
+        SUM(g.gas_used * g.gas_unit_price),
+        MAX(g.transaction_version)
+    FROM buckets b
+    JOIN transaction_gas_breakdown g
+        ON g.entry_function_id_str = b.entry_function_id_str
+        AND g.transaction_timestamp >= b.hour
+        AND g.transaction_timestamp < b.hour + INTERVAL '1 hour'
+    GROUP BY b.entry_function_id_str, b.hour
+    ON CONFLICT (entry_function_id_str, hour) DO UPDATE SET
+        num_transactions = EXCLUDED.num_transactions,
+        num_failed_transactions = EXCLUDED.num_failed_transactions,
+        total_gas_used = EXCLUDED.total_gas_used,
+        total_execution_gas_units = EXCLUDED.total_execution_gas_units,
+        total_io_gas_units = EXCLUDED.total_io_gas_units,
+        total_storage_fee_octas = EXCLUDED.total_storage_fee_octas,
+        total_storage_fee_refund_octas = EXCLUDED.total_storage_fee_refund_octas,
+        total_gas_fee_octas = EXCLUDED.total_gas_fee_octas,
+        last_transaction_version = EXCLUDED.last_transaction_version,
+        inserted_at = NOW()
+";
+
+pub struct TransactionMetadataStorer
+where
+    Self: Sized + Send + 'static,
+{
+    conn_pool: ArcDbPool,
+    processor_config: DefaultProcessorConfig,
+}
+
+impl TransactionMetadataStorer {
+    pub fn new(conn_pool: ArcDbPool, processor_config: DefaultProcessorConfig) -> Self {
+        Self {
+            conn_pool,
+            processor_config,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for TransactionMetadataStorer {
+    type Input = (
+        Vec<TransactionSize>,
+        Vec<EventSize>,
+        Vec<WriteSetSize>,
+        Vec<TransactionGasBreakdown>,
+    );
+    type Output = ();
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        input: TransactionContext<Self::Input>,
+    ) -> Result<Option<TransactionContext<()>>, ProcessorError> {
+        let (transaction_sizes, event_sizes, write_set_sizes, gas_breakdowns) = input.data;
+
+        let per_table_chunk_sizes: AHashMap<String, usize> =
+            self.processor_config.per_table_chunk_sizes.clone();
+
+        let ts_res = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_transaction_sizes_query,
+            &transaction_sizes,
+            get_config_table_chunk_size::<TransactionSize>(
+                "transaction_size_info",
+                &per_table_chunk_sizes,
+            ),
+        );
+        let es_res = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_event_sizes_query,
+            &event_sizes,
+            get_config_table_chunk_size::<EventSize>("event_size_info", &per_table_chunk_sizes),
+        );
+        let ws_res = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_write_set_sizes_query,
+            &write_set_sizes,
+            get_config_table_chunk_size::<WriteSetSize>(
+                "write_set_size_info",
+                &per_table_chunk_sizes,
+            ),
+        );
+        let gb_res = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_transaction_gas_breakdown_query,
+            &gas_breakdowns,
+            get_config_table_chunk_size::<TransactionGasBreakdown>(
+                "transaction_gas_breakdown",
+                &per_table_chunk_sizes,
+            ),
+        );
+
+        futures::try_join!(ts_res, es_res, ws_res, gb_res)?;
+
+        // The rollup reads the gas breakdown rows, so it can only run once they're committed
+        if !gas_breakdowns.is_empty() {
+            let transaction_versions: Vec<i64> = gas_breakdowns
+                .iter()
+                .map(|gas_breakdown| gas_breakdown.transaction_version)
+                .collect();
+            execute_with_better_error(
+                self.conn_pool.clone(),
+                sql_query(RECOMPUTE_ENTRY_FUNCTION_GAS_HOURLY_QUERY)
+                    .bind::<Array<BigInt>, _>(transaction_versions),
+                None,
+            )
+            .await?;
+        }
+
+        Ok(Some(TransactionContext {
+            data: (),
+            metadata: input.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for TransactionMetadataStorer {}
+
+impl NamedStep for TransactionMetadataStorer {
+    fn name(&self) -> String {
+        "TransactionMetadataStorer".to_string()
+    }
+}
+
+fn insert_transaction_gas_breakdown_query(
+    items_to_insert: Vec<TransactionGasBreakdown>,
+) -> (
+    impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
+    Option<&'static str>,
+) {
+    use schema::transaction_gas_breakdown::dsl::*;
+    (
+        diesel::insert_into(schema::transaction_gas_breakdown::table)
+            .values(items_to_insert)
+            .on_conflict(transaction_version)
+            .do_nothing(),
+        None,
+    )
+}
@@ -0,0 +1,86 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::database::{
+    execute_in_chunks, execute_with_better_error, get_config_table_chunk_size, new_db_pool,
+    run_migrations,
+};
+use crate::config::{db_config::DbConfig, indexer_processor_config::IndexerProcessorConfig};
+use ahash::AHashMap;
+use anyhow::{bail, Context, Result};
+use diesel::{
+    pg::{upsert::excluded, Pg},
+    query_builder::QueryFragment,
+    sql_query,
+    sql_types::{Array, Text},
+    ExpressionMethods,
+};
+use processor::{db::postgres::models::address_labels::AddressLabel, schema};
+use tracing::info;
+
+const DELETE_REMOVED_LABELS_QUERY: &str = "
+    DELETE FROM address_labels WHERE address <> ALL($1::TEXT[])
+";
+
+/// Writes the labels of the address label file to `address_labels`.
+///
+/// The table mirrors the file: labels that were removed from it are deleted, so processors sharing a
+/// database should point at the same file. Parquet processors only denormalize the labels onto their
+/// rows, they don't write the table.
+pub async fn store_address_labels(indexer_processor_config: &IndexerProcessorConfig) -> Result<()> {
+    let registry = indexer_processor_config
+        .address_label_registry()?
+        .context("address_labels_config must be present to store address labels")?;
+    // The table mirrors the file, so an empty file would delete every stored label
+    if registry.is_empty() {
+        bail!("The address label file has no labels, refusing to delete every stored label");
+    }
+    let connection_string = match &indexer_processor_config.db_config {
+        DbConfig::PostgresConfig(config) => config.connection_string.clone(),
+        DbConfig::ParquetConfig(_) => return Ok(()),
+    };
+    let conn_pool = new_db_pool(&connection_string, Some(2))
+        .await
+        .context("Failed to create connection pool for address labels")?;
+    run_migrations(connection_string, conn_pool.clone()).await;
+
+    let labels: Vec<AddressLabel> = registry.labels().cloned().collect();
+    execute_in_chunks(
+        conn_pool.clone(),
+        insert_address_labels_query,
+        &labels,
+        get_config_table_chunk_size::<AddressLabel>("address_labels", &AHashMap::new()),
+    )
+    .await?;
+    let addresses: Vec<String> = labels.into_iter().map(|label| label.address).collect();
+    execute_with_better_error(
+        conn_pool,
+        sql_query(DELETE_REMOVED_LABELS_QUERY).bind::<Array<Text>, _>(addresses),
+        None,
+    )
+    .await?;
+    info!(num_labels = registry.len(), "Stored address labels");
+    Ok(())
+}
+
+fn insert_address_labels_query(
+    items_to_insert: Vec<AddressLabel>,
+) -> (
+    impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send,
+    Option<&'static str>,
+) {
+    use schema::address_labels::dsl::*;
+
+    (
+        diesel::insert_into(schema::address_labels::table)
+            .values(items_to_insert)
+            .on_conflict(address)
+            .do_update()
+            .set((
+                label.eq(excluded(label)),
+                category.eq(excluded(category)),
+                inserted_at.eq(excluded(inserted_at)),
+            )),
+        None,
+    )
+}
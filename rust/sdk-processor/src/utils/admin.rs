@@ -0,0 +1,320 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::database::{new_db_pool, ArcDbPool};
+use crate::{
+    config::{
+        db_config::DbConfig,
+        indexer_processor_config::{IndexerProcessorConfig, ProcessorMode},
+    },
+    db::common::models::{
+        backfill_processor_status::{BackfillProcessorStatusQuery, BackfillStatus},
+        processor_status::ProcessorStatusQuery,
+    },
+    steps::common::parquet_buffer_step::PARQUET_BUFFER_STEP_NAME,
+};
+use anyhow::{Context, Result};
+use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl, TextExpressionMethods};
+use diesel_async::RunQueryDsl;
+use processor::schema::processor_status;
+use serde_json::{json, Value};
+use server_framework::admin::{
+    admin_state, AdminState, ProcessorPipelineStatus, ProcessorStatusSource,
+};
+use std::{sync::Arc, time::Duration};
+use tracing::info;
+
+/// How often the pipeline is checked while draining it.
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Reads the `processor_status` rows of the processor, or its `backfill_processor_status` row when
+/// backfilling. Parquet processors have a row per table.
+pub struct ProcessorStatusReader {
+    conn_pool: ArcDbPool,
+    processor_name: String,
+    backfill_id: Option<String>,
+}
+
+impl ProcessorStatusReader {
+    pub async fn new(config: &IndexerProcessorConfig) -> Result<Self> {
+        let connection_string = match &config.db_config {
+            DbConfig::PostgresConfig(config) => &config.connection_string,
+            DbConfig::ParquetConfig(config) => &config.connection_string,
+        };
+        let conn_pool = new_db_pool(connection_string, Some(1))
+            .await
+            .context("Failed to create connection pool for the admin processor status")?;
+        let backfill_id = match (&config.mode, &config.backfill_config) {
+            (ProcessorMode::Backfill, Some(backfill_config)) => {
+                Some(backfill_config.backfill_id.clone())
+            },
+            _ => None,
+        };
+        Ok(Self {
+            conn_pool,
+            processor_name: config.processor_config.name().to_string(),
+            backfill_id,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessorStatusSource for ProcessorStatusReader {
+    async fn processor_status(&self) -> Result<Value> {
+        let mut conn = self.conn_pool.get().await?;
+        if let Some(backfill_id) = &self.backfill_id {
+            let status = BackfillProcessorStatusQuery::get_by_processor(
+                &self.processor_name,
+                backfill_id,
+                &mut conn,
+            )
+            .await?;
+            return Ok(json!(status.map(|status| json!({
+                "backfill_alias": status.backfill_alias,
+                "backfill_status": match status.backfill_status {
+                    BackfillStatus::InProgress => "in_progress",
+                    BackfillStatus::Complete => "complete",
+                },
+                "last_success_version": status.last_success_version,
+                "last_updated": status.last_updated,
+                "last_transaction_timestamp": status.last_transaction_timestamp,
+                "backfill_start_version": status.backfill_start_version,
+                "backfill_end_version": status.backfill_end_version,
+            }))));
+        }
+        let statuses: Vec<ProcessorStatusQuery> = processor_status::table
+            .filter(
+                processor_status::processor
+                    .eq(self.processor_name.as_str())
+                    .or(processor_status::processor.like(format!("{}.%", self.processor_name))),
+            )
+            .order(processor_status::processor)
+            .load(&mut conn)
+            .await?;
+        Ok(json!(statuses
+            .into_iter()
+            .map(|status| json!({
+                "processor": status.processor,
+                "last_success_version": status.last_success_version,
+                "last_updated": status.last_updated,
+                "last_transaction_timestamp": status.last_transaction_timestamp,
+            }))
+            .collect::<Vec<_>>()))
+    }
+}
+
+/// Waits for a shutdown requested on a termination signal or through the admin API. Ingestion is
+/// then paused and every processor of the pipeline drained into processor_status, so that a restart
+/// doesn't reprocess anything. The server framework stops waiting after `shutdown_timeout_secs`.
+pub async fn wait_for_shutdown() -> Result<()> {
+    let admin = admin_state();
+    admin.wait_for_shutdown().await;
+    info!("Shutdown requested, checkpointing the pipeline");
+    checkpoint(&admin).await;
+    for processor in admin.pipeline_status().processors {
+        info!(
+            pipeline_name = processor.name.as_str(),
+            checkpointed_version = ?processor.checkpointed_version,
+            "Processor checkpointed"
+        );
+    }
+    info!("Pipeline checkpointed, shutting down");
+    Ok(())
+}
+
+async fn checkpoint(admin: &AdminState) {
+    admin.pause();
+    let processors = admin.pipeline_status().processors;
+    futures::future::join_all(
+        processors
+            .iter()
+            .map(|processor| checkpoint_processor(admin, processor)),
+    )
+    .await;
+}
+
+async fn checkpoint_processor(admin: &AdminState, processor: &ProcessorPipelineStatus) {
+    // A processor of a multi processor that was fed nothing yet has nothing to checkpoint
+    let (Some(ingested_version), Some(mut checkpoints)) = (
+        processor.ingested_version,
+        admin.subscribe_checkpoint(&processor.name),
+    ) else {
+        return;
+    };
+    if processor.is_parquet {
+        // Parquet tables are checkpointed as their buffers are uploaded, so there is no single
+        // version to wait for. Once the batches in flight are buffered, the buffers are flushed and
+        // the second save of the version tracker comes after it received the flushed uploads.
+        wait_for_step(
+            admin,
+            &processor.name,
+            PARQUET_BUFFER_STEP_NAME,
+            ingested_version,
+        )
+        .await;
+        let generation = admin.request_flush();
+        admin.wait_for_flush(&processor.name, generation).await;
+        let saves = checkpoints.borrow_and_update().saves;
+        let _ = checkpoints.wait_for(|c| c.saves >= saves + 2).await;
+    } else {
+        let _ = checkpoints
+            .wait_for(|c| c.version.is_some_and(|version| version >= ingested_version))
+            .await;
+    }
+}
+
+async fn wait_for_step(admin: &AdminState, pipeline_name: &str, step_name: &str, version: u64) {
+    while !admin
+        .pipeline_status()
+        .processors
+        .iter()
+        .filter(|processor| processor.name == pipeline_name)
+        .flat_map(|processor| &processor.steps)
+        .any(|step| step.name == step_name && step.last_version >= version)
+    {
+        tokio::time::sleep(DRAIN_CHECK_INTERVAL).await;
+    }
+}
+
+/// Shows the processor status of the processor in the admin API.
+pub async fn register_processor_status_source(config: &IndexerProcessorConfig) -> Result<()> {
+    let reader = ProcessorStatusReader::new(config).await?;
+    admin_state().set_processor_status_source(Arc::new(reader));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Saves processor_status periodically like the version tracker, once every flush requested by
+    /// the checkpoint is done.
+    async fn run_parquet_pipeline(admin: &AdminState, name: &str, version: u64) {
+        loop {
+            if let Some(generation) = admin.pending_flush(name) {
+                admin.complete_flush(name, generation);
+            }
+            admin.record_checkpoint(name, version);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    fn start_pipeline(admin: &AdminState, processors: &[(&str, bool, u64)]) {
+        admin.start_pipeline("multi_processor", 10);
+        for (name, is_parquet, ingested_version) in processors {
+            admin.add_processor(name, *is_parquet);
+            admin.record_ingested(*ingested_version);
+            admin.record_processor_ingested(name, *ingested_version);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_drained() {
+        let admin = AdminState::default();
+        start_pipeline(&admin, &[("a", false, 10), ("b", false, 8)]);
+        admin.record_checkpoint("a", 5);
+        admin.record_checkpoint("b", 8);
+
+        let drain = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            admin.record_checkpoint("a", 10);
+        };
+        tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(checkpoint(&admin), drain)
+        })
+        .await
+        .unwrap();
+        assert!(admin.is_paused());
+        assert_eq!(admin.checkpoint("a").unwrap().version, Some(10));
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_stalled() {
+        let admin = AdminState::default();
+        start_pipeline(&admin, &[("a", false, 10), ("b", false, 10)]);
+        admin.record_checkpoint("a", 10);
+        admin.record_checkpoint("b", 5);
+
+        // A processor whose batches never reach processor_status keeps the checkpoint waiting,
+        // until the server framework gives up on it
+        let res = tokio::time::timeout(Duration::from_millis(200), checkpoint(&admin)).await;
+        assert!(res.is_err());
+        assert!(admin.is_paused());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_ignores_other_pipelines() {
+        let admin = AdminState::default();
+        start_pipeline(&admin, &[("a", false, 10)]);
+        admin.record_checkpoint("a", 10);
+        // A gap repair isn't tracked, so its versions don't hold the checkpoint
+        admin.record_processor_ingested("a_gap_20_30", 30);
+        admin.record_step("a_gap_20_30", PARQUET_BUFFER_STEP_NAME, 30);
+
+        tokio::time::timeout(Duration::from_secs(5), checkpoint(&admin))
+            .await
+            .unwrap();
+        assert_eq!(admin.pipeline_status().processors.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_parquet_drained() {
+        let admin = AdminState::default();
+        start_pipeline(&admin, &[("a", true, 10), ("b", true, 10)]);
+        admin.record_step("a", PARQUET_BUFFER_STEP_NAME, 10);
+        admin.record_step("b", PARQUET_BUFFER_STEP_NAME, 10);
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::select! {
+                _ = checkpoint(&admin) => {},
+                _ = run_parquet_pipeline(&admin, "a", 10) => {},
+                _ = run_parquet_pipeline(&admin, "b", 10) => {},
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(admin.pending_flush("a"), None);
+        assert_eq!(admin.pending_flush("b"), None);
+        assert_eq!(admin.checkpoint("b").unwrap().version, Some(10));
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_parquet_stalled() {
+        let admin = AdminState::default();
+        start_pipeline(&admin, &[("a", true, 10), ("b", true, 10)]);
+        admin.record_step("a", PARQUET_BUFFER_STEP_NAME, 10);
+        admin.record_step("b", PARQUET_BUFFER_STEP_NAME, 5);
+
+        // The buffers of b aren't flushed before the batches in flight reach them, while those of a
+        // are
+        let res = tokio::time::timeout(Duration::from_millis(200), async {
+            tokio::select! {
+                _ = checkpoint(&admin) => {},
+                _ = run_parquet_pipeline(&admin, "a", 10) => {},
+                _ = run_parquet_pipeline(&admin, "b", 5) => {},
+            }
+        })
+        .await;
+        assert!(res.is_err());
+        assert_eq!(admin.pending_flush("a"), None);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_mixed() {
+        let admin = AdminState::default();
+        start_pipeline(&admin, &[("a", false, 10), ("b", true, 10)]);
+        admin.record_checkpoint("a", 10);
+        admin.record_step("b", PARQUET_BUFFER_STEP_NAME, 10);
+
+        // The Postgres processor is drained, and the parquet one is flushed
+        tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::select! {
+                _ = checkpoint(&admin) => {},
+                _ = run_parquet_pipeline(&admin, "b", 10) => {},
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(admin.pending_flush("b"), None);
+    }
+}
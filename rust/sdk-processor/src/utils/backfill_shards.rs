@@ -0,0 +1,273 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::database::{
+    execute_with_better_error, new_db_pool, run_migrations, ArcDbPool, MAX_DIESEL_PARAM_SIZE,
+};
+use crate::{
+    config::{
+        db_config::DbConfig,
+        indexer_processor_config::{BackfillConfig, IndexerProcessorConfig},
+    },
+    db::common::models::{
+        backfill_processor_status::{BackfillProcessorStatus, BackfillStatus},
+        backfill_shard::{BackfillShard, BackfillShardQuery},
+    },
+};
+use anyhow::{Context, Result};
+use diesel::{upsert::excluded, ExpressionMethods};
+use field_count::FieldCount;
+use processor::schema::{backfill_processor_status, backfill_shards};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Runs a backfill whose range is split into shards that several workers can process in parallel.
+///
+/// Every worker runs with the same `backfill_config`. The first worker to start records the shards
+/// in `backfill_shards`; inserting them again is a no-op. Each worker then repeatedly claims a shard,
+/// runs the processor over the shard's range as a regular backfill with its own
+/// `backfill_processor_status` row, and marks the shard complete. While a shard is running its lease is
+/// renewed, so if a worker crashes the shard is picked up again, by the same worker after a restart or by
+/// another worker once the lease expires, and resumes from the shard's checkpoint. A worker whose lease
+/// was taken over stops processing the shard.
+///
+/// Once every shard is complete, the parent backfill alias is marked complete in `backfill_processor_status`.
+pub async fn run_sharded_backfill(indexer_processor_config: &IndexerProcessorConfig) -> Result<()> {
+    let backfill_config = indexer_processor_config
+        .backfill_config
+        .clone()
+        .context("backfill_config must be present for a sharded backfill")?;
+    let shard_config = backfill_config
+        .shard_config
+        .clone()
+        .context("shard_config must be present for a sharded backfill")?;
+    let processor_name = indexer_processor_config.processor_config.name();
+    let backfill_alias = format!("{}_{}", processor_name, backfill_config.backfill_id);
+    let worker_id = shard_config.get_worker_id();
+
+    let connection_string = match &indexer_processor_config.db_config {
+        DbConfig::PostgresConfig(config) => config.connection_string.clone(),
+        DbConfig::ParquetConfig(config) => config.connection_string.clone(),
+    };
+    let conn_pool = new_db_pool(&connection_string, Some(2))
+        .await
+        .context("Failed to create connection pool for sharded backfill")?;
+    run_migrations(connection_string, conn_pool.clone()).await;
+
+    let shards = BackfillShard::split_range(
+        &backfill_alias,
+        backfill_config.initial_starting_version,
+        backfill_config.ending_version,
+        shard_config.shard_size,
+    );
+    record_shards(conn_pool.clone(), &shards).await?;
+
+    let lease_duration_secs = shard_config.lease_duration_secs as i64;
+    loop {
+        let shard = {
+            let mut conn = conn_pool.get().await?;
+            BackfillShardQuery::claim(&backfill_alias, &worker_id, lease_duration_secs, &mut conn)
+                .await
+                .context("Failed to claim backfill shard")?
+        };
+        let Some(shard) = shard else {
+            break;
+        };
+        info!(
+            backfill_alias = backfill_alias.as_str(),
+            worker_id = worker_id.as_str(),
+            shard_index = shard.shard_index,
+            shard_start_version = shard.shard_start_version,
+            shard_end_version = shard.shard_end_version,
+            "Claimed backfill shard"
+        );
+
+        let shard_processor_config = shard_processor_config(
+            indexer_processor_config,
+            &backfill_config,
+            shard.shard_index,
+            shard.shard_start_version as u64,
+            shard.shard_end_version as u64,
+        );
+        // Another worker that took over the lease processes the shard from its checkpoint, so this
+        // worker stops processing it and moves on to the next shard.
+        tokio::select! {
+            result = shard_processor_config.run_processor() => result?,
+            _ = hold_lease(
+                conn_pool.clone(),
+                &backfill_alias,
+                shard.shard_index,
+                &worker_id,
+                lease_duration_secs,
+            ) => continue,
+        }
+
+        let mut conn = conn_pool.get().await?;
+        let num_marked = BackfillShardQuery::mark_complete(
+            &backfill_alias,
+            shard.shard_index,
+            &worker_id,
+            &mut conn,
+        )
+        .await
+        .context("Failed to mark backfill shard complete")?;
+        if num_marked == 0 {
+            warn!(
+                backfill_alias = backfill_alias.as_str(),
+                shard_index = shard.shard_index,
+                worker_id = worker_id.as_str(),
+                "Backfill shard lease was taken over before the shard was marked complete"
+            );
+        }
+        log_progress(&backfill_alias, &worker_id, conn_pool.clone()).await?;
+    }
+
+    let all_complete = log_progress(&backfill_alias, &worker_id, conn_pool.clone()).await?;
+    if all_complete {
+        mark_backfill_complete(&backfill_alias, &backfill_config, conn_pool).await?;
+    }
+    Ok(())
+}
+
+/// The config to run a single shard with. Using a dedicated backfill id per shard means the regular
+/// backfill checkpointing in `get_starting_version` and the processor status saver apply as-is.
+fn shard_processor_config(
+    indexer_processor_config: &IndexerProcessorConfig,
+    backfill_config: &BackfillConfig,
+    shard_index: i64,
+    shard_start_version: u64,
+    shard_end_version: u64,
+) -> IndexerProcessorConfig {
+    IndexerProcessorConfig {
+        backfill_config: Some(BackfillConfig {
+            backfill_id: format!("{}_shard_{}", backfill_config.backfill_id, shard_index),
+            initial_starting_version: shard_start_version,
+            ending_version: shard_end_version,
+            overwrite_checkpoint: false,
+            shard_config: None,
+        }),
+        ..indexer_processor_config.clone()
+    }
+}
+
+/// Records the shards of a backfill. Shards that were already recorded, by this or another worker,
+/// are left as they are.
+pub async fn record_shards(conn_pool: ArcDbPool, shards: &[BackfillShard]) -> Result<()> {
+    // Keep each insert under the bind parameter limit of postgres
+    for chunk in shards.chunks(MAX_DIESEL_PARAM_SIZE / BackfillShard::field_count()) {
+        execute_with_better_error(
+            conn_pool.clone(),
+            diesel::insert_into(backfill_shards::table)
+                .values(chunk)
+                .on_conflict((
+                    backfill_shards::backfill_alias,
+                    backfill_shards::shard_index,
+                ))
+                .do_nothing(),
+            None,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Renews the lease on a shard while it is being processed. Returns once the lease has been taken
+/// over by another worker.
+pub async fn hold_lease(
+    conn_pool: ArcDbPool,
+    backfill_alias: &str,
+    shard_index: i64,
+    worker_id: &str,
+    lease_duration_secs: i64,
+) {
+    let renew_interval = Duration::from_secs(std::cmp::max(lease_duration_secs as u64 / 3, 1));
+    loop {
+        tokio::time::sleep(renew_interval).await;
+        let renewed = match conn_pool.get().await {
+            Ok(mut conn) => {
+                BackfillShardQuery::renew_lease(
+                    backfill_alias,
+                    shard_index,
+                    worker_id,
+                    lease_duration_secs,
+                    &mut conn,
+                )
+                .await
+            },
+            Err(e) => {
+                warn!(error = ?e, "Failed to get connection to renew backfill shard lease");
+                continue;
+            },
+        };
+        match renewed {
+            Ok(0) => {
+                warn!(
+                    backfill_alias = backfill_alias,
+                    shard_index = shard_index,
+                    worker_id = worker_id,
+                    "Backfill shard lease was taken over by another worker, stopping the shard"
+                );
+                return;
+            },
+            Ok(_) => {},
+            Err(e) => warn!(error = ?e, "Failed to renew backfill shard lease"),
+        }
+    }
+}
+
+/// Logs the overall progress of the backfill and returns whether every shard is complete.
+async fn log_progress(backfill_alias: &str, worker_id: &str, conn_pool: ArcDbPool) -> Result<bool> {
+    let mut conn = conn_pool.get().await?;
+    let shards = BackfillShardQuery::get_by_backfill_alias(backfill_alias, &mut conn)
+        .await
+        .context("Failed to query backfill shards")?;
+    let num_complete = shards
+        .iter()
+        .filter(|shard| shard.shard_status == BackfillStatus::Complete)
+        .count();
+    info!(
+        backfill_alias = backfill_alias,
+        worker_id = worker_id,
+        num_shards = shards.len(),
+        num_complete_shards = num_complete,
+        "Sharded backfill progress"
+    );
+    Ok(num_complete == shards.len())
+}
+
+async fn mark_backfill_complete(
+    backfill_alias: &str,
+    backfill_config: &BackfillConfig,
+    conn_pool: ArcDbPool,
+) -> Result<()> {
+    let status = BackfillProcessorStatus {
+        backfill_alias: backfill_alias.to_string(),
+        backfill_status: BackfillStatus::Complete,
+        last_success_version: backfill_config.ending_version as i64,
+        last_transaction_timestamp: None,
+        backfill_start_version: backfill_config.initial_starting_version as i64,
+        backfill_end_version: backfill_config.ending_version as i64,
+    };
+    execute_with_better_error(
+        conn_pool,
+        diesel::insert_into(backfill_processor_status::table)
+            .values(&status)
+            .on_conflict(backfill_processor_status::backfill_alias)
+            .do_update()
+            .set((
+                backfill_processor_status::backfill_status
+                    .eq(excluded(backfill_processor_status::backfill_status)),
+                backfill_processor_status::last_success_version
+                    .eq(excluded(backfill_processor_status::last_success_version)),
+                backfill_processor_status::last_updated
+                    .eq(excluded(backfill_processor_status::last_updated)),
+            )),
+        None,
+    )
+    .await?;
+    info!(
+        backfill_alias = backfill_alias,
+        "Sharded backfill is complete"
+    );
+    Ok(())
+}
@@ -0,0 +1,214 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    database::{new_db_pool, ArcDbPool},
+    starting_version::get_starting_version,
+};
+use crate::{
+    config::{
+        db_config::DbConfig,
+        indexer_processor_config::{BackfillConfig, IndexerProcessorConfig, ProcessorMode},
+    },
+    db::common::models::backfill_processor_status::{BackfillProcessorStatusQuery, BackfillStatus},
+    processors::build_pipeline,
+};
+use anyhow::{Context, Result};
+use aptos_indexer_processor_sdk::{
+    aptos_indexer_transaction_stream::TransactionStreamConfig, builder::ProcessorBuilder,
+    common_steps::TransactionStreamStep, traits::IntoRunnableStep,
+};
+use processor::db::postgres::models::processor_gaps::{GapRepairStatus, ProcessorGapQuery};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Size of the channel between the transaction stream of a repair and the processor steps.
+const GAP_REPAIR_CHANNEL_SIZE: usize = 10;
+
+/// Repairs the gaps recorded for the processor in `processor_gaps`.
+///
+/// Gaps are recorded by the gap detectors and the parquet buffer step, which then skip past them so
+/// the live processor keeps making progress. The Postgres processors of the sdk don't skip gaps, so
+/// they never record any. Each gap is repaired by streaming the gap's range through
+/// the steps of the processor, built by `build_pipeline` on a db pool shared by every repair. The
+/// live processor runs the migrations and checks the chain id, so a repair doesn't. The repair tracks
+/// its progress in `backfill_processor_status` under the alias stored on the gap, so a repair
+/// interrupted by a restart resumes where it left off. A gap that fails to be repaired
+/// `max_repair_attempts` times in a row is marked as failed and isn't retried.
+pub async fn run_gap_repair(indexer_processor_config: &IndexerProcessorConfig) -> Result<()> {
+    let gap_repair_config = indexer_processor_config
+        .gap_repair_config
+        .clone()
+        .context("gap_repair_config must be present to repair gaps")?;
+    let processor_name = indexer_processor_config.processor_config.name();
+
+    let (connection_string, db_pool_size) = match &indexer_processor_config.db_config {
+        DbConfig::PostgresConfig(config) => (&config.connection_string, config.db_pool_size),
+        DbConfig::ParquetConfig(config) => (&config.connection_string, config.db_pool_size),
+    };
+    let conn_pool = new_db_pool(connection_string, Some(db_pool_size))
+        .await
+        .context("Failed to create connection pool for gap repair")?;
+
+    let poll_interval = Duration::from_secs(gap_repair_config.poll_interval_secs);
+    loop {
+        tokio::time::sleep(poll_interval).await;
+        let gaps = {
+            let mut conn = conn_pool.get().await?;
+            match ProcessorGapQuery::get_unrepaired_by_processor(processor_name, &mut conn).await {
+                Ok(gaps) => gaps,
+                Err(e) => {
+                    // The table may not exist yet if the live processor is still running migrations
+                    warn!(
+                        processor_name = processor_name,
+                        error = ?e,
+                        "Failed to query processor gaps, will retry",
+                    );
+                    continue;
+                },
+            }
+        };
+        let num_gaps = gaps.len();
+        for (gap_index, gap) in gaps.into_iter().enumerate() {
+            let backfill_config = BackfillConfig {
+                backfill_id: format!("gap_{}_{}", gap.gap_start_version, gap.gap_end_version),
+                initial_starting_version: gap.gap_start_version as u64,
+                ending_version: gap.gap_end_version as u64,
+                overwrite_checkpoint: false,
+                shard_config: None,
+            };
+            let backfill_alias = format!("{}_{}", processor_name, backfill_config.backfill_id);
+            info!(
+                processor_name = processor_name,
+                gap_start_version = gap.gap_start_version,
+                gap_end_version = gap.gap_end_version,
+                backfill_alias = backfill_alias.as_str(),
+                remaining_gaps = num_gaps - gap_index,
+                "Repairing gap",
+            );
+
+            let mut conn = conn_pool.get().await?;
+            ProcessorGapQuery::update_repair_status(
+                processor_name,
+                gap.gap_start_version,
+                GapRepairStatus::Repairing,
+                &backfill_alias,
+                &mut conn,
+            )
+            .await
+            .context("Failed to update gap repair status")?;
+            drop(conn);
+
+            let repair_config = IndexerProcessorConfig {
+                backfill_config: Some(backfill_config),
+                gap_repair_config: None,
+                mode: ProcessorMode::Backfill,
+                ..indexer_processor_config.clone()
+            };
+            let mut repair_status = GapRepairStatus::Failed;
+            for attempt in 1..=gap_repair_config.max_repair_attempts {
+                match repair_gap(&repair_config, conn_pool.clone()).await {
+                    Ok(()) => {
+                        repair_status = GapRepairStatus::Repaired;
+                        break;
+                    },
+                    Err(e) if attempt < gap_repair_config.max_repair_attempts => {
+                        warn!(
+                            processor_name = processor_name,
+                            backfill_alias = backfill_alias.as_str(),
+                            attempt = attempt,
+                            error = ?e,
+                            "Failed to repair gap, will retry",
+                        );
+                        tokio::time::sleep(poll_interval).await;
+                    },
+                    Err(e) => {
+                        error!(
+                            processor_name = processor_name,
+                            backfill_alias = backfill_alias.as_str(),
+                            attempt = attempt,
+                            error = ?e,
+                            "Failed to repair gap, giving up",
+                        );
+                    },
+                }
+            }
+
+            let mut conn = conn_pool.get().await?;
+            ProcessorGapQuery::update_repair_status(
+                processor_name,
+                gap.gap_start_version,
+                repair_status,
+                &backfill_alias,
+                &mut conn,
+            )
+            .await
+            .context("Failed to update gap repair status")?;
+            if repair_status == GapRepairStatus::Repaired {
+                info!(
+                    processor_name = processor_name,
+                    gap_start_version = gap.gap_start_version,
+                    gap_end_version = gap.gap_end_version,
+                    remaining_gaps = num_gaps - gap_index - 1,
+                    "Repaired gap",
+                );
+            }
+        }
+    }
+}
+
+/// Streams the range of the backfill in `repair_config` through the steps of the processor,
+/// resuming from the checkpoint of an earlier attempt.
+async fn repair_gap(repair_config: &IndexerProcessorConfig, conn_pool: ArcDbPool) -> Result<()> {
+    let backfill_config = repair_config
+        .backfill_config
+        .as_ref()
+        .context("backfill_config must be present to repair a gap")?;
+    let starting_version = get_starting_version(repair_config, conn_pool.clone()).await?;
+
+    let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+        starting_version: Some(starting_version),
+        request_ending_version: Some(backfill_config.ending_version),
+        ..repair_config.transaction_stream_config.clone()
+    })
+    .await?;
+    let (_, transaction_receiver) =
+        ProcessorBuilder::new_with_inputless_first_step(transaction_stream.into_runnable_step())
+            .end_and_return_output_receiver(GAP_REPAIR_CHANNEL_SIZE);
+    let output_receiver = build_pipeline(
+        repair_config.clone(),
+        conn_pool.clone(),
+        transaction_receiver,
+    )
+    .await?;
+
+    loop {
+        match output_receiver.recv().await {
+            Ok(txn_context) => {
+                debug!(
+                    "Repaired versions [{:?}, {:?}]",
+                    txn_context.metadata.start_version, txn_context.metadata.end_version,
+                );
+            },
+            Err(_) => break,
+        }
+    }
+
+    // The pipeline also stops when one of its steps fails, so the repair is only done once the
+    // backfill is checkpointed as complete.
+    let mut conn = conn_pool.get().await?;
+    let status = BackfillProcessorStatusQuery::get_by_processor(
+        repair_config.processor_config.name(),
+        &backfill_config.backfill_id,
+        &mut conn,
+    )
+    .await
+    .context("Failed to query backfill_processor_status table.")?;
+    match status {
+        Some(status) if status.backfill_status == BackfillStatus::Complete => Ok(()),
+        _ => Err(anyhow::anyhow!(
+            "Gap repair stopped before reaching version {}",
+            backfill_config.ending_version
+        )),
+    }
+}
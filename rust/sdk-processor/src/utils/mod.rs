@@ -1,5 +1,9 @@
+pub mod admin;
+pub mod address_labels;
+pub mod backfill_shards;
 pub mod chain_id;
 pub mod database;
+pub mod gap_repair;
 pub mod parquet_extractor_helper;
 pub mod parquet_processor_table_mapping;
 pub mod starting_version;
@@ -282,6 +282,7 @@ mod tests {
             per_table_chunk_sizes: AHashMap::new(),
             channel_size: 100,
             deprecated_tables: HashSet::new(),
+            cdc_sink: None,
         };
         let processor_config = ProcessorConfig::EventsProcessor(default_processor_config);
         let postgres_config = PostgresConfig {
@@ -314,6 +315,8 @@ mod tests {
             backfill_config,
             bootstrap_config,
             testing_config,
+            gap_repair_config: None,
+            address_labels_config: None,
             mode,
         }
     }
@@ -397,6 +400,7 @@ mod tests {
                 initial_starting_version: 0,
                 ending_version: 10,
                 overwrite_checkpoint: false,
+                shard_config: None,
             }),
             None,
             None,
@@ -438,6 +442,7 @@ mod tests {
                 initial_starting_version: 0,
                 ending_version: 10,
                 overwrite_checkpoint: false,
+                shard_config: None,
             }),
             None,
             None,
@@ -479,6 +484,7 @@ mod tests {
                 initial_starting_version: 3,
                 ending_version: 10,
                 overwrite_checkpoint: true,
+                shard_config: None,
             }),
             None,
             None,
@@ -520,6 +526,7 @@ mod tests {
                 initial_starting_version: 3,
                 ending_version: 10,
                 overwrite_checkpoint: false,
+                shard_config: None,
             }),
             None,
             Some(3),
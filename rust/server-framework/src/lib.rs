@@ -1,5 +1,6 @@
 // Copyright © Aptos Foundation
 
+use admin::{admin_routes, admin_state, AdminConfig, AdminState};
 use anyhow::{Context, Result};
 #[cfg(target_os = "linux")]
 use aptos_system_utils::profiling::start_cpu_profiling;
@@ -11,12 +12,16 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::convert::Infallible;
 // TODO: remove deprecated lint when new clippy nightly is released
 #[allow(deprecated)]
-use std::{fs::File, io::Read, panic::PanicInfo, path::PathBuf, process};
+use std::{fs::File, io::Read, panic::PanicInfo, path::PathBuf, process, sync::Arc, time::Duration};
 use tokio::runtime::Handle;
-use tracing::error;
+#[cfg(unix)]
+use tokio::signal::unix::SignalKind;
+use tracing::{error, info};
 use tracing_subscriber::EnvFilter;
 use warp::{http::Response, Filter};
 
+pub mod admin;
+
 /// ServerArgs bootstraps a server with all common pieces. And then triggers the run method for
 /// the specific service.
 #[derive(Parser)]
@@ -41,23 +46,80 @@ impl ServerArgs {
 /// Run a server and the necessary probes. For spawning these tasks, the user must
 /// provide a handle to a runtime they already have.
 pub async fn run_server_with_config<C>(config: GenericConfig<C>, handle: Handle) -> Result<()>
+where
+    C: RunnableConfig,
+{
+    run_server_with_admin(config, handle, admin_state()).await
+}
+
+async fn run_server_with_admin<C>(
+    config: GenericConfig<C>,
+    handle: Handle,
+    admin: Arc<AdminState>,
+) -> Result<()>
 where
     C: RunnableConfig,
 {
     let health_port = config.health_check_port;
+    let shutdown_timeout = Duration::from_secs(config.shutdown_timeout_secs);
+    let control_token = config.admin_config.control_token()?;
+    admin.set_config(
+        serde_json::to_value(&config).context("Failed to serialize the config for /admin")?,
+    );
     // Start liveness and readiness probes.
+    let probes_admin = admin.clone();
     let task_handler = handle.spawn(async move {
-        register_probes_and_metrics_handler(health_port).await;
+        register_probes_and_metrics_handler(health_port, probes_admin, control_token).await;
         anyhow::Ok(())
     });
-    let main_task_handler = handle.spawn(async move { config.run().await });
+    let signal_admin = admin.clone();
+    handle.spawn(async move {
+        match wait_for_termination_signal().await {
+            Ok(()) => {
+                info!("Received termination signal");
+                signal_admin.request_shutdown();
+            },
+            Err(e) => error!(error = ?e, "Failed to listen for termination signals"),
+        }
+    });
+    let handles_shutdown = config.handles_shutdown();
+    let mut main_task_handler = handle.spawn(async move { config.run().await });
     tokio::select! {
         res = task_handler => {
             res.expect("Probes and metrics handler unexpectedly exited")
         },
-        res = main_task_handler => {
+        res = &mut main_task_handler => {
             res.expect("Main task handler unexpectedly exited")
         },
+        _ = admin.wait_for_shutdown() => {
+            if !handles_shutdown {
+                return Ok(());
+            }
+            // Give the service time to finish what it's doing before exiting
+            match tokio::time::timeout(shutdown_timeout, main_task_handler).await {
+                Ok(res) => res.expect("Main task handler unexpectedly exited"),
+                Err(_) => anyhow::bail!(
+                    "Did not shut down within shutdown_timeout_secs ({}s)",
+                    shutdown_timeout.as_secs()
+                ),
+            }
+        },
+    }
+}
+
+/// Returns on SIGTERM or Ctrl-C.
+async fn wait_for_termination_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate = tokio::signal::unix::signal(SignalKind::terminate())?;
+        tokio::select! {
+            _ = terminate.recv() => Ok(()),
+            res = tokio::signal::ctrl_c() => Ok(res?),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        Ok(tokio::signal::ctrl_c().await?)
     }
 }
 
@@ -65,6 +127,13 @@ where
 pub struct GenericConfig<T> {
     // Shared configuration among all services.
     pub health_check_port: u16,
+    // How long a service that handles shutdowns has to exit after a termination signal or an
+    // admin shutdown request. Keep it below the termination grace period of the pod.
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+    // Admin endpoints served on the health check port
+    #[serde(default)]
+    pub admin_config: AdminConfig,
 
     // Specific configuration for each service.
     pub server_config: T,
@@ -82,13 +151,27 @@ where
     fn get_server_name(&self) -> String {
         self.server_config.get_server_name()
     }
+
+    fn handles_shutdown(&self) -> bool {
+        self.server_config.handles_shutdown()
+    }
+}
+
+pub const fn default_shutdown_timeout_secs() -> u64 {
+    25
 }
 
 /// RunnableConfig is a trait that all services must implement for their configuration.
 #[async_trait::async_trait]
-pub trait RunnableConfig: DeserializeOwned + Send + Sync + 'static {
+pub trait RunnableConfig: DeserializeOwned + Serialize + Send + Sync + 'static {
     async fn run(&self) -> Result<()>;
     fn get_server_name(&self) -> String;
+
+    /// Whether `run` returns by itself after a shutdown is requested through `admin_state()`, on
+    /// a termination signal or from the admin API. Other services are stopped right away.
+    fn handles_shutdown(&self) -> bool {
+        false
+    }
 }
 
 /// Parse a yaml file into a struct.
@@ -154,8 +237,13 @@ pub fn setup_logging() {
         .init();
 }
 
-/// Register readiness and liveness probes and set up metrics endpoint.
-async fn register_probes_and_metrics_handler(port: u16) {
+/// Register readiness and liveness probes and set up metrics and admin endpoints.
+async fn register_probes_and_metrics_handler(
+    port: u16,
+    admin: Arc<AdminState>,
+    control_token: Option<String>,
+) {
+    let admin = admin_routes(admin, control_token);
     let readiness = warp::path("readiness")
         .map(move || warp::reply::with_status("ready", warp::http::StatusCode::OK));
     let metrics_endpoint = warp::path("metrics").map(|| {
@@ -201,11 +289,11 @@ async fn register_probes_and_metrics_handler(port: u16) {
             })
         });
         #[cfg(target_os = "linux")]
-        warp::serve(readiness.or(metrics_endpoint).or(profilez))
+        warp::serve(readiness.or(metrics_endpoint).or(profilez).or(admin))
             .run(([0, 0, 0, 0], port))
             .await;
     } else {
-        warp::serve(readiness.or(metrics_endpoint))
+        warp::serve(readiness.or(metrics_endpoint).or(admin))
             .run(([0, 0, 0, 0], port))
             .await;
     }
@@ -253,10 +341,79 @@ mod tests {
 
         let config = load::<GenericConfig<TestConfig>>(&file_path).unwrap();
         assert_eq!(config.health_check_port, 12345);
+        assert_eq!(
+            config.shutdown_timeout_secs,
+            default_shutdown_timeout_secs()
+        );
         assert_eq!(config.server_config.test, 123);
         assert_eq!(config.server_config.test_name, "test");
     }
 
+    /// Waits for a shutdown, then returns if `drains` or never returns otherwise.
+    #[derive(Deserialize, Serialize)]
+    struct ShutdownTestConfig {
+        drains: bool,
+        #[serde(skip)]
+        admin: Arc<AdminState>,
+    }
+
+    #[async_trait::async_trait]
+    impl RunnableConfig for ShutdownTestConfig {
+        async fn run(&self) -> Result<()> {
+            self.admin.wait_for_shutdown().await;
+            if !self.drains {
+                std::future::pending::<()>().await;
+            }
+            Ok(())
+        }
+
+        fn get_server_name(&self) -> String {
+            "shutdown_test".to_string()
+        }
+
+        fn handles_shutdown(&self) -> bool {
+            true
+        }
+    }
+
+    async fn run_until_shutdown(drains: bool) -> (Result<()>, Duration) {
+        let admin = Arc::new(AdminState::default());
+        let config = GenericConfig {
+            health_check_port: 0,
+            shutdown_timeout_secs: 1,
+            admin_config: AdminConfig::default(),
+            server_config: ShutdownTestConfig {
+                drains,
+                admin: admin.clone(),
+            },
+        };
+        let server = tokio::spawn(run_server_with_admin(
+            config,
+            Handle::current(),
+            admin.clone(),
+        ));
+        let start = std::time::Instant::now();
+        admin.request_shutdown();
+        (server.await.unwrap(), start.elapsed())
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drained() {
+        let (res, elapsed) = run_until_shutdown(true).await;
+        assert!(res.is_ok());
+        assert!(elapsed < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stalled() {
+        let (res, elapsed) = run_until_shutdown(false).await;
+        assert!(res
+            .unwrap_err()
+            .to_string()
+            .contains("Did not shut down within shutdown_timeout_secs (1s)"));
+        assert!(elapsed >= Duration::from_secs(1));
+    }
+
     #[test]
     fn verify_tool() {
         use clap::CommandFactory;